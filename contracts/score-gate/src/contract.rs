@@ -0,0 +1,177 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use example_terra_contract::helpers::CwTemplateContract;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MemberResponse, QueryMsg};
+use crate::state::{Config, CONFIG, MEMBERS};
+
+// Consumes example-terra-contract through its `library` helpers only: scores are read with
+// CwTemplateContract::score and ::has_min_score, and the hook is registered with ::add_hook.
+
+/// Registers this contract as a hook of the scores contract, so AddHook must be open to it:
+/// the scores owner either grants it with SetPolicy or adds the hook themselves, in which
+/// case instantiate fails and should be retried after.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(deps: DepsMut, env: Env, _info: MessageInfo, msg: InstantiateMsg) -> Result<Response, ContractError> {
+    let scores = CwTemplateContract(deps.api.addr_validate(&msg.scores)?);
+    let add_hook = scores.add_hook(env.contract.address)?;
+    CONFIG.save(deps.storage, &Config { scores, min_score: msg.min_score })?;
+
+    Ok(Response::new()
+        .add_message(add_hook)
+        .add_attribute("method", "instantiate")
+        .add_attribute("min_score", msg.min_score.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Enter {} => try_enter(deps, info),
+        ExecuteMsg::ScoreChanged { user, .. } => try_score_changed(deps, info, user),
+    }
+}
+
+/// Reads scores as the public does, so users hidden by the scores contract can't enter.
+pub fn try_enter(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.scores.has_min_score(&deps.querier, &info.sender, config.min_score)? {
+        return Err(ContractError::ScoreTooLow { min_score: config.min_score });
+    }
+    let score = config.scores.score(&deps.querier, &info.sender)?;
+    MEMBERS.save(deps.storage, &info.sender, &score)?;
+
+    Ok(Response::new().add_attribute("method", "enter").add_attribute("member", info.sender))
+}
+
+/// Hooks fire for every user; only members are tracked. The hook carries the raw stored
+/// score, before the scores contract's formula and bucketing, so membership is checked
+/// again through HasMinScore, as on entry. Members whose score is no longer public leave
+/// too. A failure here never blocks the score update, since the scores contract sends
+/// hooks with reply_on_error.
+pub fn try_score_changed(deps: DepsMut, info: MessageInfo, user: String) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.scores.addr() {
+        return Err(ContractError::Unauthorized {});
+    }
+    let user = deps.api.addr_validate(&user)?;
+    if !MEMBERS.has(deps.storage, &user) {
+        return Ok(Response::new().add_attribute("method", "score_changed"));
+    }
+
+    let res = Response::new().add_attribute("method", "score_changed").add_attribute("member", &user);
+    if !config.scores.has_min_score(&deps.querier, &user, config.min_score).unwrap_or(false) {
+        MEMBERS.remove(deps.storage, &user);
+        return Ok(res.add_attribute("removed", "true"));
+    }
+    let score = config.scores.score(&deps.querier, &user)?;
+    MEMBERS.save(deps.storage, &user, &score)?;
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Member { user } => {
+            let user = deps.api.addr_validate(&user)?;
+            to_binary(&MemberResponse { score: MEMBERS.may_load(deps.storage, &user)? })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        from_binary, from_slice, ContractResult, CosmosMsg, OwnedDeps, SystemResult, WasmMsg, WasmQuery
+    };
+    use example_terra_contract::msg::{HasMinScoreResponse, QueryMsg as ScoresQueryMsg, ScoreResponse};
+    use example_terra_contract::notifier::NotifierExecuteMsg;
+
+    const SCORES: &str = "scores";
+
+    // The scores contract, answering with `score_of` as its GetScore would
+    fn answer_scores(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, score_of: fn(&str) -> u32) {
+        deps.querier.update_wasm(move |query| {
+            let res = match query {
+                WasmQuery::Smart { contract_addr, msg } if contract_addr == SCORES => match from_binary(msg).unwrap() {
+                    ScoresQueryMsg::GetScore { user, .. } => to_binary(&ScoreResponse { score: score_of(&user), expires_at: None }),
+                    ScoresQueryMsg::HasMinScore { user, min_score, .. } => {
+                        to_binary(&HasMinScoreResponse { has_min_score: score_of(&user) >= min_score })
+                    }
+                    _ => panic!("unexpected query"),
+                },
+                _ => panic!("unexpected contract"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+    }
+
+    // Alice has 42 and everyone else 5
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        answer_scores(&mut deps, |user| if user == "alice" { 42 } else { 5 });
+
+        let msg = InstantiateMsg { scores: SCORES.to_string(), min_score: 20 };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                assert_eq!(SCORES, contract_addr);
+                assert_eq!(r#"{"add_hook":{"addr":"cosmos2contract"}}"#, std::str::from_utf8(msg).unwrap());
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+        deps
+    }
+
+    fn member(deps: Deps, user: &str) -> Option<u32> {
+        let res: MemberResponse = from_binary(&query(deps, mock_env(), QueryMsg::Member { user: user.to_string() }).unwrap()).unwrap();
+        res.score
+    }
+
+    fn notify(deps: DepsMut, sender: &str, user: &str, new_score: u32) -> Result<Response, ContractError> {
+        // Sent exactly as the scores contract sends it to hooks
+        let hook = NotifierExecuteMsg::ScoreChanged { user: user.to_string(), old_score: 42, new_score };
+        let msg: ExecuteMsg = from_slice(&to_binary(&hook).unwrap()).unwrap();
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn enter_needs_min_score() {
+        let mut deps = setup();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::Enter {}).unwrap_err();
+        assert!(matches!(err, ContractError::ScoreTooLow { min_score: 20 }));
+        assert_eq!(None, member(deps.as_ref(), "bob"));
+
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::Enter {}).unwrap();
+        assert_eq!(Some(42), member(deps.as_ref(), "alice"));
+    }
+
+    #[test]
+    fn hook_tracks_members() {
+        let mut deps = setup();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::Enter {}).unwrap();
+
+        let err = notify(deps.as_mut(), "alice", "alice", 0).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // Non-members are ignored
+        notify(deps.as_mut(), SCORES, "bob", 50).unwrap();
+        assert_eq!(None, member(deps.as_ref(), "bob"));
+
+        // Membership follows the score as the scores contract reports it, not the raw
+        // stored value the hook carries, which its Config::score_formula may scale
+        answer_scores(&mut deps, |_| 30);
+        notify(deps.as_mut(), SCORES, "alice", 15).unwrap();
+        assert_eq!(Some(30), member(deps.as_ref(), "alice"));
+
+        answer_scores(&mut deps, |_| 19);
+        let res = notify(deps.as_mut(), SCORES, "alice", 38).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "removed"));
+        assert_eq!(None, member(deps.as_ref(), "alice"));
+    }
+}
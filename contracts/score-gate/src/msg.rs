@@ -0,0 +1,34 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    // The example-terra-contract instance keeping the scores
+    pub scores: String,
+    pub min_score: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Joins as a member, for senders with at least the configured score.
+    Enter {},
+    /// Hook notification from the scores contract, mirroring its NotifierExecuteMsg.
+    /// Members whose score, read back as on entry, drops below the minimum are removed.
+    ScoreChanged { user: String, old_score: u32, new_score: u32 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    // Returns the Config
+    Config {},
+    // Returns MemberResponse
+    Member { user: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberResponse {
+    // The score last seen for the user; None when they are not a member
+    pub score: Option<u32>,
+}
@@ -0,0 +1,16 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use example_terra_contract::helpers::CwTemplateContract;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub scores: CwTemplateContract,
+    pub min_score: u32,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// Members and their score as last seen, on entry or from a hook notification
+pub const MEMBERS: Map<&Addr, u32> = Map::new("members");
@@ -3,7 +3,7 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use example_terra_contract::msg::{OwnerResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use example_terra_contract::msg::{ConfigResponse, OwnerResponse, MirrorResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use example_terra_contract::state::State;
 
 fn main() {
@@ -15,6 +15,9 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(State), &out_dir);
     export_schema(&schema_for!(OwnerResponse), &out_dir);
+    export_schema(&schema_for!(MirrorResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
 }
@@ -0,0 +1,108 @@
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::error::ContractError;
+use crate::msg::{AddrInfo, OriginAddr};
+use crate::state::ADDR_ORIGINS;
+
+// longest accepted bech32 prefix for an address origin
+pub const MAX_ADDR_PREFIX_LEN: u32 = 16;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+
+/// Prefixes are the human-readable part of a bech32 address, e.g. "osmo" or "juno".
+pub fn validate_addr_prefix(prefix: &str) -> Result<(), ContractError> {
+    let valid = !prefix.is_empty()
+        && prefix.len() <= MAX_ADDR_PREFIX_LEN as usize
+        && prefix.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    if !valid {
+        return Err(ContractError::InvalidAddrPrefix { max: MAX_ADDR_PREFIX_LEN });
+    }
+    Ok(())
+}
+
+/// `user` as shown in responses: the address as stored here, plus the same account
+/// under the prefix of the chain it came from when the user has set one.
+pub fn addr_info(deps: Deps, user: String) -> StdResult<AddrInfo> {
+    let origin = match ADDR_ORIGINS.may_load(deps.storage, user.clone())? {
+        Some(prefix) => {
+            let canonical = deps.api.addr_canonicalize(&user)?;
+            let address = bech32_encode(&prefix, canonical.as_slice());
+            Some(OriginAddr { prefix, address })
+        }
+        None => None,
+    };
+    Ok(AddrInfo { address: user, origin })
+}
+
+pub fn query_addr_info(deps: Deps, address: String) -> StdResult<AddrInfo> {
+    addr_info(deps, address)
+}
+
+/// Encodes `data` as bech32 (BIP 173) under `prefix`.
+pub fn bech32_encode(prefix: &str, data: &[u8]) -> String {
+    encode_words(prefix, &to_words(data))
+}
+
+// Regroups bytes into 5-bit words, zero-padding the last one
+fn to_words(data: &[u8]) -> Vec<u8> {
+    let mut words = Vec::with_capacity((data.len() * 8 + 4) / 5);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for byte in data {
+        acc = (acc << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            words.push(((acc >> bits) & 31) as u8);
+        }
+    }
+    if bits > 0 {
+        words.push(((acc << (5 - bits)) & 31) as u8);
+    }
+    words
+}
+
+fn encode_words(prefix: &str, words: &[u8]) -> String {
+    let mut values: Vec<u8> = prefix.bytes().map(|b| b >> 5).collect();
+    values.push(0);
+    values.extend(prefix.bytes().map(|b| b & 31));
+    values.extend_from_slice(words);
+    values.extend_from_slice(&[0; 6]);
+    let checksum = polymod(&values) ^ 1;
+
+    let mut encoded = format!("{}1", prefix);
+    let checksum_words = (0..6).map(|i| ((checksum >> (5 * (5 - i))) & 31) as u8);
+    encoded.extend(words.iter().copied().chain(checksum_words).map(|word| CHARSET[word as usize] as char));
+    encoded
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk = 1u32;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ff_ffff) << 5) ^ u32::from(*value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bech32_encode, encode_words};
+
+    #[test]
+    // Vectors from BIP 173
+    fn encodes_bech32() {
+        assert_eq!("a12uel5l", encode_words("a", &[]));
+        let words: Vec<u8> = (0..32).collect();
+        assert_eq!("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", encode_words("abcdef", &words));
+        // A 20-byte account hash comes out as the usual 39-character data part
+        let encoded = bech32_encode("osmo", &[0; 20]);
+        assert_eq!("osmo1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqmcn030", encoded);
+    }
+}
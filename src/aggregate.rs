@@ -0,0 +1,42 @@
+use cosmwasm_std::{Deps, Env, StdResult, Uint128};
+
+use crate::msg::{AggregatedScoreResponse, QueryMsg, ScoreResponse, SourceScore};
+use crate::state::{CombineMode, AGGREGATION};
+
+/// Label used for this contract's own entry in the breakdown.
+pub const LOCAL_SOURCE: &str = "local";
+
+/// Combines the local score with weighted scores smart-queried from every configured
+/// source. Sources are expected to speak this contract's `GetScore` query, and answer
+/// it as they would any public query. Unless `exact`, the local score is redacted too.
+pub fn query_aggregated_score(deps: Deps, env: Env, user: String, exact: bool) -> StdResult<AggregatedScoreResponse> {
+    let local = crate::contract::query_score(deps, env, user.clone(), exact)?.score;
+    let mut breakdown = vec![SourceScore {
+        source: LOCAL_SOURCE.to_string(),
+        raw: local,
+        weighted: Uint128::from(local),
+    }];
+
+    let aggregation = match AGGREGATION.may_load(deps.storage)? {
+        Some(aggregation) => aggregation,
+        None => return Ok(AggregatedScoreResponse { score: Uint128::from(local), breakdown }),
+    };
+
+    for source in aggregation.sources.iter() {
+        let res: ScoreResponse = deps
+            .querier
+            .query_wasm_smart(source.addr.to_string(), &QueryMsg::GetScore { user: user.clone(), auth: None })?;
+        breakdown.push(SourceScore {
+            source: source.addr.to_string(),
+            raw: res.score,
+            weighted: Uint128::from(res.score) * source.weight,
+        });
+    }
+
+    let weighted = breakdown.iter().map(|entry| entry.weighted);
+    let score = match aggregation.mode {
+        CombineMode::Sum => weighted.fold(Uint128::zero(), |acc, value| acc + value),
+        CombineMode::Max => weighted.max().unwrap_or_default(),
+    };
+    Ok(AggregatedScoreResponse { score, breakdown })
+}
@@ -0,0 +1,48 @@
+use cosmwasm_std::{StdResult, Storage};
+
+use crate::leaderboard::{index_key, IndexKey};
+use crate::state::{AllTimeScore, Totals, ALL_TIME_INDEX, ALL_TIME_SCORES};
+
+/// Current position of `user` on the all-time board, if they have an all-time score.
+pub fn all_time_key(storage: &dyn Storage, user: &str) -> StdResult<Option<IndexKey>> {
+    let entry = ALL_TIME_SCORES.may_load(storage, user.to_string())?;
+    Ok(entry.map(|entry| index_key(entry.score, entry.achieved_at, user)))
+}
+
+/// Carries a change of `user`'s live score from `old` to `new` over to their all-time
+/// score. Archiving a season leaves the all-time score alone, so it adds up every
+/// season's final standing plus the current one. `achieved_at` defaults to the height
+/// the all-time score last changed at.
+pub fn record_all_time(
+    storage: &mut dyn Storage,
+    totals: &mut Totals,
+    user: &str,
+    old: u32,
+    new: u32,
+    achieved_at: Option<u64>,
+) -> StdResult<()> {
+    let existing = ALL_TIME_SCORES.may_load(storage, user.to_string())?;
+    let current = existing.as_ref().map_or(0, |entry| entry.score);
+    let score = current.saturating_sub(old).saturating_add(new);
+    if existing.is_some() && score == current {
+        return Ok(());
+    }
+
+    if let Some(entry) = &existing {
+        ALL_TIME_INDEX.remove(storage, index_key(entry.score, entry.achieved_at, user));
+    }
+    // Users only drop off the all-time board when nothing is left of it
+    if score == 0 {
+        ALL_TIME_SCORES.remove(storage, user.to_string());
+        if existing.is_some() {
+            totals.all_time_users -= 1;
+        }
+        return Ok(());
+    }
+    if existing.is_none() {
+        totals.all_time_users += 1;
+    }
+    let achieved_at = achieved_at.or_else(|| existing.map(|entry| entry.achieved_at)).unwrap_or_default();
+    ALL_TIME_SCORES.save(storage, user.to_string(), &AllTimeScore { score, achieved_at })?;
+    ALL_TIME_INDEX.save(storage, index_key(score, achieved_at, user), &())
+}
@@ -0,0 +1,31 @@
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::limits::page_limit;
+use crate::msg::{ProvisionalEntry, ProvisionalUpdatesResponse};
+use crate::state::{Totals, CONFIG, PROVISIONAL};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Whether `score` is more than `multiple` times the user's previous score, or the mean
+/// over all users. A user's first score only has the mean to go by, and the first score
+/// on the board nothing at all.
+pub fn is_anomalous(multiple: u32, totals: &Totals, previous: u32, score: u32) -> bool {
+    let score = u64::from(score);
+    let multiple = u64::from(multiple);
+    let above_previous = previous > 0 && score > u64::from(previous) * multiple;
+    let above_mean = totals.users > 0 && score > totals.total_score / totals.users * multiple;
+    above_previous || above_mean
+}
+
+/// Updates held for review, by user.
+pub fn query_provisional_updates(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<ProvisionalUpdatesResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let updates = PROVISIONAL
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(user, update)| ProvisionalEntry { user, update }))
+        .collect::<StdResult<_>>()?;
+    Ok(ProvisionalUpdatesResponse { updates })
+}
@@ -0,0 +1,33 @@
+use cosmwasm_std::{Deps, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::limits::page_limit;
+use crate::msg::{AppealCase, OpenAppealsResponse};
+use crate::state::{CONFIG, OPEN_APPEALS, PENALTIES};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+fn load_case(deps: Deps, case_id: u64, user: String) -> StdResult<AppealCase> {
+    let penalty = PENALTIES.load(deps.storage, user.clone())?;
+    Ok(AppealCase { case_id, user, penalty })
+}
+
+/// Appeals awaiting a decision, oldest first.
+pub fn query_open_appeals(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<OpenAppealsResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let cases = OPEN_APPEALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.and_then(|(case_id, user)| load_case(deps, case_id, user)))
+        .collect::<StdResult<_>>()?;
+    Ok(OpenAppealsResponse { cases })
+}
+
+/// Details of an open case; resolved cases are gone.
+pub fn query_appeal_case(deps: Deps, case_id: u64) -> StdResult<AppealCase> {
+    let user = OPEN_APPEALS
+        .may_load(deps.storage, case_id)?
+        .ok_or_else(|| StdError::generic_err(format!("No open appeal with case id {}", case_id)))?;
+    load_case(deps, case_id, user)
+}
@@ -0,0 +1,34 @@
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::limits::page_limit;
+use crate::msg::{AttestationEntry, AttestationsResponse};
+use crate::state::{ATTESTATIONS, CONFIG};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Third-party attestations about a user under one tag, by attester address. They are
+/// kept apart from SCORES and never touch the leaderboard.
+pub fn query_attestations(
+    deps: Deps,
+    user: String,
+    tag: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AttestationsResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let attestations = ATTESTATIONS
+        .prefix((user, tag))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(attester, attestation)| AttestationEntry {
+                attester,
+                value: attestation.value,
+                height: attestation.height,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(AttestationsResponse { attestations })
+}
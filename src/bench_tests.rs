@@ -0,0 +1,234 @@
+// Storage-op budgets for hot paths, run through a cw-multi-test App. Gas in wasmd is
+// dominated by storage access, so the contract is registered with entry points that count
+// every read and write against its own storage, which catches index regressions long
+// before they show up as failed transactions on chain.
+use std::cell::Cell;
+
+use cosmwasm_std::{Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Record, StdResult, Storage};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use serde::de::DeserializeOwned;
+
+use crate::contract::{commitment_hash, execute, instantiate, query};
+use crate::custom::Response;
+use crate::msg::{ConfigUpdate, ExecuteMsg, InstantiateMsg, LeaderboardResponse, QueryMsg, ScoreResponse, ScoreUpdate};
+use crate::ContractError;
+
+thread_local! {
+    // (reads, writes) the contract has made since the last take()
+    static OPS: Cell<(u64, u64)> = Cell::new((0, 0));
+}
+
+fn count(reads: u64, writes: u64) {
+    OPS.with(|ops| {
+        let (r, w) = ops.get();
+        ops.set((r + reads, w + writes));
+    });
+}
+
+// Returns (reads, writes) since the last call
+fn take() -> (u64, u64) {
+    OPS.with(|ops| ops.replace((0, 0)))
+}
+
+// The contract's storage as the App hands it to a call. Queries only get to read it.
+enum CountingStorage<'a> {
+    Read(&'a dyn Storage),
+    Write(&'a mut dyn Storage),
+}
+
+impl CountingStorage<'_> {
+    fn inner(&self) -> &dyn Storage {
+        match self {
+            CountingStorage::Read(storage) => *storage,
+            CountingStorage::Write(storage) => &**storage,
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn Storage {
+        match self {
+            CountingStorage::Read(_) => unreachable!("queries can't write"),
+            CountingStorage::Write(storage) => &mut **storage,
+        }
+    }
+}
+
+impl Storage for CountingStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        count(1, 0);
+        self.inner().get(key)
+    }
+
+    fn range<'a>(&'a self, start: Option<&[u8]>, end: Option<&[u8]>, order: Order) -> Box<dyn Iterator<Item = Record> + 'a> {
+        // Every item pulled from the iterator costs a read
+        Box::new(self.inner().range(start, end, order).inspect(|_| count(1, 0)))
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        count(0, 1);
+        self.inner_mut().set(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        count(0, 1);
+        self.inner_mut().remove(key)
+    }
+}
+
+fn counted_instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg) -> Result<Response, ContractError> {
+    let mut storage = CountingStorage::Write(deps.storage);
+    instantiate(DepsMut { storage: &mut storage, api: deps.api, querier: deps.querier }, env, info, msg)
+}
+
+fn counted_execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    let mut storage = CountingStorage::Write(deps.storage);
+    execute(DepsMut { storage: &mut storage, api: deps.api, querier: deps.querier }, env, info, msg)
+}
+
+fn counted_query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let storage = CountingStorage::Read(deps.storage);
+    query(Deps { storage: &storage, api: deps.api, querier: deps.querier }, env, msg)
+}
+
+struct Bench {
+    app: App,
+    contract: Addr,
+}
+
+impl Bench {
+    fn execute(&mut self, msg: ExecuteMsg) {
+        self.app.execute_contract(Addr::unchecked("creator"), self.contract.clone(), &msg, &[]).unwrap();
+    }
+
+    fn query<T: DeserializeOwned>(&self, msg: QueryMsg) -> T {
+        self.app.wrap().query_wasm_smart(self.contract.clone(), &msg).unwrap()
+    }
+
+    fn update(&mut self, user: &str, score: u32) {
+        self.execute(ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None });
+    }
+}
+
+fn setup() -> Bench {
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(ContractWrapper::new(counted_execute, counted_instantiate, counted_query)));
+    let creator = Addr::unchecked("creator");
+    let contract = app.instantiate_contract(code_id, creator, &InstantiateMsg::default(), &[], "leaderboard", None).unwrap();
+    take();
+    Bench { app, contract }
+}
+
+#[test]
+fn single_update_budget() {
+    let mut bench = setup();
+
+    // New entry                                          reads  writes
+    //   successor, auth policy, state, config x2, pause,
+    //   maintenance, freeze, journal toggle                  9      0
+    //   reward pool, streamed and settled                    2      0
+    //   merged, provisional, archiving, expiry               4      0
+    //   score, achieved height, index entry                  1      3
+    //   totals                                               1      1
+    //   all-time score and index entry                       1      2
+    //   rank counts for score and block                      2      2
+    //   podium set                                           1      1
+    //   journal toggle, deltas ring head and slot            2      2
+    //   timeline, time-weighted average                      0      2
+    //   retention, mirror, hooks, notifier                   4      0
+    //   sender's activity report                             1      1
+    bench.update("player", 10);
+    let (reads, writes) = take();
+    assert!(reads <= 28, "reads: {}", reads);
+    assert!(writes <= 14, "writes: {}", writes);
+
+    // Overwrite adds 6 reads and 4 writes: the old achieved height, index entry and rank
+    // counts, the last timeline entry and observation, and dropping the old index entries
+    bench.update("player", 20);
+    let (reads, writes) = take();
+    assert!(reads <= 34, "reads: {}", reads);
+    assert!(writes <= 18, "writes: {}", writes);
+}
+
+#[test]
+fn hundred_updates_budget() {
+    let mut bench = setup();
+
+    for i in 0..100 {
+        bench.update(&format!("player{:03}", i), i);
+    }
+    let (reads, writes) = take();
+    assert!(reads <= 2800, "reads: {}", reads);
+    assert!(writes <= 1400, "writes: {}", writes);
+}
+
+#[test]
+fn score_query_budget() {
+    let mut bench = setup();
+    bench.update("player", 10);
+    take();
+
+    let _: ScoreResponse = bench.query(QueryMsg::GetScore { user: "player".to_string(), auth: None });
+    let (reads, writes) = take();
+    // Besides the entry: the config and the user's visibility for the privacy check, whether
+    // a season is being archived, and the config again for the score formula
+    assert!(reads <= 6, "reads: {}", reads);
+    assert_eq!(0, writes);
+}
+
+#[test]
+fn leaderboard_page_budget() {
+    let mut bench = setup();
+    for i in 0..100 {
+        bench.update(&format!("player{:03}", i), i % 10);
+    }
+    take();
+
+    let msg = QueryMsg::Leaderboard { cursor: None, limit: Some(30), scope: None };
+    let res: LeaderboardResponse = bench.query(msg);
+    take();
+
+    // A page costs four reads per entry (the entry, the user's visibility and expiry, and
+    // their address origin), plus the config, the archive state and a look-ahead entry
+    let msg = QueryMsg::Leaderboard { cursor: res.next_cursor, limit: Some(30), scope: None };
+    let _: LeaderboardResponse = bench.query(msg);
+    let (reads, writes) = take();
+    assert!(reads <= 125, "reads: {}", reads);
+    assert_eq!(0, writes);
+}
+
+#[test]
+fn batch_update_budget() {
+    let mut bench = setup();
+
+    let updates: Vec<_> = (0..100).map(|i| ScoreUpdate { user: Addr::unchecked(format!("player{:03}", i)), score: i }).collect();
+    let salt = Binary::from(b"salt");
+    let msg = ExecuteMsg::CommitScores { hash: commitment_hash(&updates, &salt).unwrap() };
+    bench.execute(msg);
+    take();
+
+    let msg = ExecuteMsg::RevealScores { updates, salt };
+    bench.execute(msg);
+    let (reads, writes) = take();
+    // One message pays for the config, totals, policy and pause checks once, so each
+    // entry costs about half the reads of a lone update; writes per entry stay the same
+    assert!(reads <= 1600, "reads: {}", reads);
+    assert!(writes <= 1300, "writes: {}", writes);
+}
+
+#[test]
+fn leaderboard_fifty_budget() {
+    let mut bench = setup();
+    let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { max_query_limit: Some(50), ..ConfigUpdate::default() });
+    bench.execute(msg);
+    for i in 0..100 {
+        bench.update(&format!("player{:03}", i), i % 10);
+    }
+    take();
+
+    // Four reads per entry as for a page of 30, plus the same handful of fixed reads
+    let msg = QueryMsg::Leaderboard { cursor: None, limit: Some(50), scope: None };
+    let res: LeaderboardResponse = bench.query(msg);
+    let (reads, writes) = take();
+    assert_eq!(50, res.entries.len());
+    assert!(reads <= 205, "reads: {}", reads);
+    assert_eq!(0, writes);
+}
@@ -0,0 +1,62 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Deps, StdError, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::state::{BOARD_ADMINS, STATE};
+
+// The contract runs a single board under this name. Admins are kept per board so that
+// splitting off sub-leaderboards later doesn't change how they are assigned or queried.
+pub const DEFAULT_BOARD: &str = "default";
+
+// upper bound on admins per board besides the owner
+pub const MAX_BOARD_ADMINS: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoardAdminsResponse {
+    pub board: String,
+    pub admins: Vec<String>,
+}
+
+pub fn validate_board(board: &str) -> Result<(), ContractError> {
+    if board != DEFAULT_BOARD {
+        return Err(ContractError::UnknownBoard { board: board.to_string() });
+    }
+    Ok(())
+}
+
+/// Removes `remove` from the board's admins, then adds `add`; addresses already present
+/// are kept once.
+pub fn update_board_admins(storage: &mut dyn Storage, board: &str, add: Vec<Addr>, remove: Vec<Addr>) -> Result<(), ContractError> {
+    validate_board(board)?;
+    let mut admins = BOARD_ADMINS.may_load(storage, board.to_string())?.unwrap_or_default();
+    admins.retain(|admin| !remove.contains(admin));
+    for addr in add {
+        if !admins.contains(&addr) {
+            admins.push(addr);
+        }
+    }
+    if admins.len() as u32 > MAX_BOARD_ADMINS {
+        return Err(ContractError::TooManyBoardAdmins { max: MAX_BOARD_ADMINS });
+    }
+    if admins.is_empty() {
+        BOARD_ADMINS.remove(storage, board.to_string());
+        return Ok(());
+    }
+    Ok(BOARD_ADMINS.save(storage, board.to_string(), &admins)?)
+}
+
+/// Whether `addr` may manage `board`: the owner or one of the board's admins.
+pub fn is_board_admin(storage: &dyn Storage, board: &str, addr: &Addr) -> StdResult<bool> {
+    if *addr == STATE.load(storage)?.owner {
+        return Ok(true);
+    }
+    Ok(BOARD_ADMINS.may_load(storage, board.to_string())?.unwrap_or_default().contains(addr))
+}
+
+pub fn query_board_admins(deps: Deps, board: String) -> StdResult<BoardAdminsResponse> {
+    validate_board(&board).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let admins = BOARD_ADMINS.may_load(deps.storage, board.clone())?.unwrap_or_default();
+    Ok(BoardAdminsResponse { board, admins: admins.into_iter().map(String::from).collect() })
+}
@@ -0,0 +1,152 @@
+use cosmwasm_std::{Deps, Env, Order, StdError, StdResult, Timestamp};
+use cw_storage_plus::Bound;
+
+use crate::addr::addr_info;
+use crate::error::ContractError;
+use crate::leaderboard::{current_key, index_key, is_expired, is_public, season_floor, shown_score, IndexKey};
+use crate::limits::{page_limit, ScanBudget};
+use crate::msg::{BracketAssignmentResponse, BracketMembersResponse, LeaderboardEntry};
+use crate::state::{Bracket, Config, BRACKETS, CONFIG, SCORE_INDEX};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+// Members are found by walking the index, so capacities stay small
+pub const MAX_BRACKET_CAPACITY: u32 = 1000;
+pub const MAX_BRACKETS: u32 = 20;
+pub const MAX_BRACKET_NAME_LEN: u32 = 32;
+
+/// Checks a full bracket table: unique names, sane capacities and disjoint score ranges.
+pub fn validate_brackets(mut brackets: Vec<Bracket>) -> Result<Vec<Bracket>, ContractError> {
+    if brackets.len() as u32 > MAX_BRACKETS {
+        return Err(ContractError::TooManyBrackets { max: MAX_BRACKETS });
+    }
+    for (i, bracket) in brackets.iter().enumerate() {
+        let duplicate = brackets[..i].iter().any(|other| other.name == bracket.name);
+        let bad_name = bracket.name.is_empty() || bracket.name.len() > MAX_BRACKET_NAME_LEN as usize;
+        if duplicate || bad_name || bracket.min_score > bracket.max_score || bracket.capacity == 0 || bracket.capacity > MAX_BRACKET_CAPACITY {
+            return Err(ContractError::InvalidBracket { name: bracket.name.clone() });
+        }
+    }
+    brackets.sort_by_key(|bracket| bracket.min_score);
+    for pair in brackets.windows(2) {
+        if pair[1].min_score <= pair[0].max_score {
+            return Err(ContractError::BracketOverlap { first: pair[0].name.clone(), second: pair[1].name.clone() });
+        }
+    }
+    Ok(brackets)
+}
+
+// Index positions from the top of the bracket's range down to its bottom
+fn range_bounds(bracket: &Bracket) -> (IndexKey, Option<IndexKey>) {
+    let top = index_key(bracket.max_score, 0, "");
+    // A range down to zero runs to the end of the index
+    let bottom = bracket.min_score.checked_sub(1).map(|below| index_key(below, 0, ""));
+    (top, bottom)
+}
+
+// Expired entries and those left over from a season being archived read as absent, as
+// on the boards, so they neither hold a place nor show up
+fn is_live(deps: Deps, now: Timestamp, floor: u64, key: &IndexKey) -> StdResult<bool> {
+    Ok(key.1 >= floor && !is_expired(deps.storage, &key.2, now)?)
+}
+
+// How many live entries rank above `key` within the bracket, counting at most `capacity`
+fn count_ahead(deps: Deps, now: Timestamp, config: &Config, bracket: &Bracket, key: &IndexKey) -> StdResult<u32> {
+    let floor = season_floor(deps.storage)?;
+    let (top, _) = range_bounds(bracket);
+    let mut ahead = 0;
+    let mut budget = ScanBudget::new(config);
+    for other in SCORE_INDEX.keys(deps.storage, Some(Bound::inclusive(top)), Some(Bound::exclusive(key.clone())), Order::Ascending) {
+        if ahead == bracket.capacity {
+            break;
+        }
+        budget.spend()?;
+        if is_live(deps, now, floor, &other?)? {
+            ahead += 1;
+        }
+    }
+    Ok(ahead)
+}
+
+/// Which bracket the user's score falls in, and their 1-based position in it. Users past
+/// a bracket's capacity, hidden users and users outside every range have none.
+pub fn query_bracket(deps: Deps, env: Env, user: String) -> StdResult<BracketAssignmentResponse> {
+    let unassigned = BracketAssignmentResponse { bracket: None, position: None };
+    let config = CONFIG.load(deps.storage)?;
+    let key = match current_key(deps.storage, &user)? {
+        Some(key) if is_public(deps.storage, &config, &user)? => key,
+        _ => return Ok(unassigned),
+    };
+    if !is_live(deps, env.block.time, season_floor(deps.storage)?, &key)? {
+        return Ok(unassigned);
+    }
+    let score = u32::MAX - key.0;
+    let brackets = BRACKETS.may_load(deps.storage)?.unwrap_or_default();
+    let bracket = match brackets.into_iter().find(|bracket| bracket.min_score <= score && score <= bracket.max_score) {
+        Some(bracket) => bracket,
+        None => return Ok(unassigned),
+    };
+
+    let ahead = count_ahead(deps, env.block.time, &config, &bracket, &key)?;
+    if ahead >= bracket.capacity {
+        return Ok(unassigned);
+    }
+    Ok(BracketAssignmentResponse { bracket: Some(bracket.name), position: Some(ahead + 1) })
+}
+
+/// Members of a bracket in leaderboard order, up to its capacity. Hidden users hold
+/// their place but are left out. Fails if the `start_after` user's score has since left
+/// the bracket's range.
+pub fn query_list_bracket(
+    deps: Deps,
+    env: Env,
+    name: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BracketMembersResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let brackets = BRACKETS.may_load(deps.storage)?.unwrap_or_default();
+    let bracket = brackets
+        .into_iter()
+        .find(|bracket| bracket.name == name)
+        .ok_or_else(|| StdError::generic_err(format!("No bracket named {}", name)))?;
+
+    let limit = page_limit(&config, limit, DEFAULT_LIMIT)?;
+    let (top, bottom) = range_bounds(&bracket);
+    let (start, seen) = match start_after {
+        Some(user) => {
+            let key = current_key(deps.storage, &user)?
+                .ok_or_else(|| StdError::generic_err(format!("No score for start_after user {}", user)))?;
+            let score = u32::MAX - key.0;
+            if score < bracket.min_score || score > bracket.max_score {
+                return Err(StdError::generic_err(format!("start_after user {} is no longer in bracket {}", user, name)));
+            }
+            let live = is_live(deps, env.block.time, season_floor(deps.storage)?, &key)?;
+            let seen = count_ahead(deps, env.block.time, &config, &bracket, &key)? + u32::from(live);
+            (Bound::exclusive(key), seen)
+        }
+        None => (Bound::inclusive(top), 0),
+    };
+
+    let floor = season_floor(deps.storage)?;
+    let mut members = vec![];
+    let mut room = bracket.capacity.saturating_sub(seen);
+    let mut budget = ScanBudget::new(&config);
+    for key in SCORE_INDEX.keys(deps.storage, Some(start), bottom.map(Bound::exclusive), Order::Ascending) {
+        if room == 0 || members.len() == limit {
+            break;
+        }
+        budget.spend()?;
+        let key = key?;
+        if !is_live(deps, env.block.time, floor, &key)? {
+            continue;
+        }
+        room -= 1;
+        let (inverted, achieved_at, user) = key;
+        if !is_public(deps.storage, &config, &user)? {
+            continue;
+        }
+        members.push(LeaderboardEntry { user: addr_info(deps, user)?, score: shown_score(&config, u32::MAX - inverted), achieved_at });
+    }
+    Ok(BracketMembersResponse { name: bracket.name, members })
+}
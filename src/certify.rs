@@ -0,0 +1,72 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_binary, Addr, Deps, StdResult, WasmMsg};
+
+use crate::custom::SubMsg;
+use crate::leaderboard::redact;
+use crate::msg::CertificationResponse;
+use crate::state::{Certification, CERTIFICATIONS, CONFIG, CURRENT_SEASON};
+
+// Minimal subset of the cw721-base message types, serializing identically to upstream.
+// The certifier must let this contract mint, and accept the extension below; transfers
+// return NFTs sent here by mistake.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw721MintMsg {
+    pub token_id: String,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: CertificateExtension,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CertificateExtension {
+    pub score: u32,
+    pub season: u32,
+    pub rank: u32,
+    // Block height the score and rank were read at
+    pub height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721ExecuteMsg {
+    Mint(Cw721MintMsg),
+    TransferNft { recipient: String, token_id: String },
+}
+
+/// One certificate per user and season, so the id is unique within the certifier.
+pub fn certificate_token_id(season: u32, user: &str) -> String {
+    format!("season-{}-{}", season, user)
+}
+
+/// Mints `certification` to `owner`. Failure propagates, reverting the certification.
+pub fn mint_msg(certifier: &Addr, owner: &str, certification: &Certification) -> StdResult<SubMsg> {
+    let mint = Cw721MintMsg {
+        token_id: certification.token_id.clone(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: CertificateExtension {
+            score: certification.score,
+            season: certification.season,
+            rank: certification.rank,
+            height: certification.height,
+        },
+    };
+    let exec = WasmMsg::Execute { contract_addr: certifier.to_string(), msg: to_binary(&Cw721ExecuteMsg::Mint(mint))?, funds: vec![] };
+    Ok(SubMsg::new(exec))
+}
+
+/// The user's certificate for `season`, the current one by default, with the score
+/// redacted unless `exact`.
+pub fn query_certification(deps: Deps, user: String, season: Option<u32>, exact: bool) -> StdResult<CertificationResponse> {
+    let season = match season {
+        Some(season) => season,
+        None => CURRENT_SEASON.load(deps.storage)?,
+    };
+    let mut certification = CERTIFICATIONS.may_load(deps.storage, (season, user))?;
+    if let Some(certification) = certification.as_mut().filter(|_| !exact) {
+        certification.score = redact(&CONFIG.load(deps.storage)?, certification.score);
+    }
+    Ok(CertificationResponse { certification })
+}
@@ -1,182 +1,7355 @@
+use std::convert::TryFrom;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw2::set_contract_version;
+use cosmwasm_std::{coin, from_binary, to_binary, to_vec, Addr, Api, BankMsg, Binary, BlockInfo, Coin, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, Reply, StdError, StdResult, Storage, Timestamp, Uint128};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use sha2::{Digest, Sha256};
 
+use crate::addr::{query_addr_info, validate_addr_prefix};
+use crate::aggregate::query_aggregated_score;
+use crate::all_time::record_all_time;
+use crate::anomaly::{is_anomalous, query_provisional_updates};
+use crate::appeals::{query_appeal_case, query_open_appeals};
+use crate::attestations::query_attestations;
+use crate::boards::{query_board_admins, update_board_admins, validate_board};
+use crate::brackets::{query_bracket, query_list_bracket, validate_brackets};
+use crate::certify::{certificate_token_id, mint_msg, query_certification};
+use crate::custom::{Response, SubMsg};
+use crate::controllers::{add_hook, query_admin, query_claims, query_hooks, remove_hook};
+use crate::deltas::{query_deltas_since, record_delta};
 use crate::error::ContractError;
-use crate::msg::{OwnerResponse, ScoreResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{State, STATE, SCORES};
+use crate::events::{
+    evt_anomaly, evt_anomaly_reviewed, evt_appeal_resolved, evt_config_updated, evt_journal, evt_merge_identities, evt_penalty,
+    evt_raffle_claimed, evt_raffle_resolved, evt_read_only_forwarding, evt_rebate, evt_raffle_started, evt_score_changed, evt_score_removed, evt_score_restored,
+    evt_score_rollback, evt_spend, evt_wager, evt_weight_table_published, method_response,
+};
+use crate::faucet::query_faucet_status;
+use crate::federation::{query_federated_score, query_peers, sync_from_peer, update_peers};
+use crate::gatekeeper::assert_eligible;
+use crate::idempotency::{claim_key, query_idempotency_key};
+use crate::invariants::query_check_invariants;
+use crate::journal::{latest_sequence, query_changes_since, query_latest_sequence, record_change};
+use crate::maintenance::{active_freeze, assert_not_forwarding, cancel_window, is_paused, query_freeze_status, query_next_maintenance, schedule_window};
+use crate::ledger::{credit, debit, query_reconcile, raffle_funds, stage_funds, FAUCET_FUNDS, REBATE_FUNDS, REWARD_POOL_FUNDS};
+use crate::limits::page_limit;
+use crate::leaderboard::{current_key, index_key, is_public, query_cross_rank, query_leaderboard, query_leaderboard_page, redact, redact_down, IndexKey};
+use crate::merkle::{decode_hash, is_claimed, leaf_hash, query_is_claimed, query_merkle_stages, set_claimed, verify_proof};
+use crate::mirror::{instantiate_mirror_msg, mirror_msgs, parse_mirror_address, settle_burn, INSTANTIATE_MIRROR_REPLY_ID, MIRROR_BURN_REPLY_ID};
+use crate::msg::{
+    BoardConfigUpdate, ConfigInit, ConfigResponse, ConfigUpdate, Cw20FeeInit, Cw20ReceiveMsg, DecimalScoreResponse, ExecuteMsg,
+    FeeRouteInit, GlobalGainResponse, HasMinScoreResponse, InfoResponse, InstantiateMsg, MigrateMsg, MirrorResponse,
+    NotifierResponse, OwnerHistoryResponse, OwnerResponse, PeerInit, QueryMsg, RawEntry, RawRangeResponse, ReceiveMsg, Rounding,
+    ScoreResponse, ScoreUpdate, SignedScore, SourceInit, ViewerAuth, Voucher,
+};
+use crate::notifier::{notify_msgs, NOTIFIER_REPLY_ID};
+use crate::operators::{is_writer, query_operator_report, query_operator_reports, query_operators, record_activity, update_operators};
+use crate::podium::{query_podium, rebuild_top_k, set_top_k, update_top_k};
+use crate::policy::{authorize, msg_name, query_policy, set_policy};
+use crate::profiles::{assert_enabled, assert_msg_enabled, profile_features, query_enabled_features};
+use crate::raffle::{draw, query_raffle, raffle_seed};
+use crate::ranks::{index_add, index_remove, query_rank_slice, rank_of};
+use crate::rebate::{query_rebate_status, take_rebate};
+use crate::rescue::{assert_unmanaged_cw20, recover_cw20_msg, recover_nft_msg};
+use crate::recovery::{purge_tombstones, query_removed_score, record_tombstone, take_tombstone};
+use crate::rewards::{accrue, checkpoint, query_claimable, settle};
+use crate::retention::{query_retention, trim_history, validate_retention, TRIM_PER_WRITE};
+use crate::season::{
+    query_epoch_summary, query_list_seasons, query_season_leaderboard, query_season_score, query_snapshot_digest,
+    summarize_season,
+};
+use crate::shadow::{shadow_msg, SHADOW_REPLY_ID};
+use crate::shop::{query_spend_history, receive_spend_msg, shop_msg};
+use crate::signing::{query_submission_keys, rotate_key, verify_submission};
+use crate::simulate::query_simulate;
+use crate::timeline::{query_user_timeline, record_timeline};
+use crate::twas::{query_average_score, record_observation};
+use crate::splitter::{query_fee_routes, split_cw20_msgs, split_msgs, TOTAL_BPS};
+use crate::state::{
+    AggregationConfig, Appeal, ArchivedScore, Archiving, Attestation, Bracket, CapPolicy, Certification, CombineMode, Config, Cw20Fee,
+    EpochCap, EpochGain, Feature, FeeRoute, Freeze, MerkleStage, MirrorConfig, OwnerChange, Penalty, PendingReset, PreviousScore,
+    ProvisionalUpdate, Raffle, RemovedScore, Retention, Role, ScoreCommitment, ScoreFormula, ScoreSource, Season, SmallDeltaPolicy,
+    SnapshotDigest, Spend, State, TimelineEvent, TopK, Totals, UserReward, Wager, WagerStatus, ACHIEVED_AT, ADDR_ORIGINS, AGGREGATION, ARCHIVING,
+    ATTESTATIONS, BRACKETS, CERTIFICATIONS, CERTIFIER, COMPACT_CURSOR, CONFIG, CURRENT_SEASON, EXPIRIES, EXPIRY_QUEUE, FAUCET, FAUCET_CLAIMS, FEATURES,
+    FREEZE, GLOBAL_EPOCH_GAIN, JOURNAL, MERGED, MERKLE_STAGES, MIRROR, NEXT_CASE_ID, NEXT_RAFFLE_ID, NEXT_WAGER_ID, NOTIFIER, OPEN_APPEALS,
+    OWNER_HISTORY, PAUSED, PENALTIES, PENDING_RESET, PREVIOUS_SCORES, PROFILE_VISIBILITY, PROVISIONAL, RAFFLES, REBATE_POOL, REINDEX_CURSOR,
+    RETENTION, REWARD_POOL, SCORES, SCORE_COMMITMENT, SCORE_INDEX, SEASONS, SEASON_INDEX, SEASON_SCORES, SHOP, SNAPSHOT_DIGESTS,
+    REDEEMED_VOUCHERS, SPENDS, STATE, SUBMISSION_KEYS, SUBMISSION_NONCES, SUCCESSOR, TOP_K, TOTALS, USER_EPOCH_GAINS, USER_REWARDS, VIEWING_KEYS, VOUCHER_KEY, WAGERS,
+};
+use crate::validation::{query_denylist, update_denylist, validate_msg_text};
+use crate::vouchers::{query_voucher_status, set_voucher_key, verify_voucher};
+use crate::wagers::{assert_status, query_wager};
+use crate::weights::{publish_weight_table, query_weight_table};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:example-terra-contract";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// stored by early deployments, before the name was namespaced like CONTRACT_NAME
+const LEGACY_CONTRACT_NAME: &str = "example-terra-contract";
+
+// interface ids reported by QueryMsg::Info, bumped whenever a message family changes shape,
+// which shows up as changed lines in the golden files
+const INTERFACES: &[&str] = &[
+    "cw2",
+    "cw20-receive/v1",
+    // GetScore and friends took `auth` with private scores
+    "score/v2",
+    "mirror/v1",
+    // Simulate took `funds` and `auth`
+    "simulate/v2",
+    "leaderboard/v1",
+    "season/v1",
+    "hooks/v1",
+    "signed-score/v1",
+    "voucher/v1",
+    "spend/v1",
+    "wager/v1",
+    "raffle/v1",
+    "rewards/v1",
+    "merkle/v1",
+    "appeal/v1",
+    "federation/v1",
+];
+
+// pagination info for queries
+const DEFAULT_LIMIT: u32 = 10;
+
+// page size for maintenance operations walking every score
+const DEFAULT_MAINTENANCE_LIMIT: u32 = 30;
+const MAX_MAINTENANCE_LIMIT: u32 = 100;
+
+// upper bound on smart queries made by QueryMsg::AggregatedScore
+const MAX_AGGREGATION_SOURCES: u32 = 10;
+
+// entries archived per execute call while a season rolls over automatically
+const AUTO_ARCHIVE_BATCH: u32 = 10;
+
+// longest accepted Config::event_prefix
+const MAX_EVENT_PREFIX_LEN: u32 = 32;
+
+// most fractional digits a score can have; u32 scores then still reach 4294.967295
+const MAX_SCORE_DECIMALS: u32 = 6;
+
+// default and largest size of the maintained podium set
+const DEFAULT_TOP_K: u32 = 100;
+const MAX_TOP_K: u32 = 100;
+
+// longest accepted penalty or appeal reason
+const MAX_REASON_LEN: u32 = 256;
+
+// longest accepted SpendScore memo
+const MAX_MEMO_LEN: u32 = 128;
+
+// largest message SpendAndCall forwards
+const MAX_CALL_MSG_LEN: u32 = 4096;
+
+// longest accepted attestation tag
+const MAX_TAG_LEN: u32 = 32;
+
+// longest accepted snapshot digest, room for any multibase CID
+const MAX_DIGEST_LEN: u32 = 128;
+
+// size caps on message inputs, checked up front by validate_bounds
+const MAX_BATCH_SIZE: u32 = 100;
+const MAX_VIEWING_KEY_LEN: u32 = 64;
+const MAX_SALT_LEN: u32 = 64;
+// a proof this long covers a tree of 2^32 leaves
+const MAX_PROOF_LEN: u32 = 32;
+const MAX_RESERVED_ADDRESSES: u32 = 50;
+const MAX_FEE_ROUTES: u32 = 10;
+const MAX_IDEMPOTENCY_KEY_LEN: u32 = 64;
+
+// blocks a used idempotency key is remembered by default, about a day at 6s blocks
+const DEFAULT_IDEMPOTENCY_TTL: u64 = 14_400;
+
+// seconds a removed score stays restorable by default
+const DEFAULT_REMOVAL_GRACE_PERIOD: u64 = 86_400;
+
+// default page size cap and entries a query may read, and the most the owner can set
+const DEFAULT_MAX_QUERY_LIMIT: u32 = 30;
+const MAX_QUERY_LIMIT: u32 = 100;
+const DEFAULT_MAX_QUERY_SCAN: u32 = 1000;
+const MAX_QUERY_SCAN: u32 = 10_000;
+
+// blocks the owner has to confirm a requested reset
+const RESET_CONFIRMATION_BLOCKS: u64 = 100;
+
+// Blocks a score commitment stays open for its reveal
+const REVEAL_WINDOW_BLOCKS: u64 = 100;
+
+// pagination info for raw storage dumps
+const DEFAULT_RAW_LIMIT: u32 = 10;
+const MAX_RAW_LIMIT: u32 = 100;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let state = State {        
         owner: info.sender.clone()
     };
 
+    let mut config = initial_config();
+    if let Some(event_prefix) = msg.event_prefix {
+        config.event_prefix = validate_event_prefix(event_prefix)?;
+    }
+    if let Some(score_decimals) = msg.score_decimals {
+        if score_decimals > MAX_SCORE_DECIMALS {
+            return Err(ContractError::InvalidScoreDecimals { max: MAX_SCORE_DECIMALS });
+        }
+        config.score_decimals = score_decimals;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
+    CONFIG.save(deps.storage, &config)?;
+    TOTALS.save(deps.storage, &Totals::default())?;
+    TOP_K.save(deps.storage, &TopK { k: DEFAULT_TOP_K, ..TopK::default() })?;
+    CURRENT_SEASON.save(deps.storage, &1)?;
+    let first = Season { id: 1, start_height: env.block.height, start_time: env.block.time, end_height: None, archived: false };
+    SEASONS.save(deps.storage, 1, &first)?;
+    record_owner_change(deps.storage, &env, None, info.sender)?;
+    if let Some(profile) = msg.profile {
+        FEATURES.save(deps.storage, &profile_features(profile))?;
+    }
+
+    // Run through the owner's own handlers, so the checks match later changes exactly
+    if let Some(operators) = msg.operators {
+        try_update_operators(deps.branch(), operators, vec![])?;
+    }
+    if let Some(init) = msg.config {
+        apply_config_init(deps.branch(), init)?;
+    }
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut res = Response::default();
+    if let Some(mirror) = msg.mirror {
+        MIRROR.save(deps.storage, &MirrorConfig { token: None, enabled: true })?;
+        res = res.add_submessage(instantiate_mirror_msg(&env.contract.address, mirror)?);
+    }
 
-    Ok(Response::default())
+    Ok(namespace_events(&config, res))
+}
+
+fn initial_config() -> Config {
+    Config {
+        idempotency_ttl: DEFAULT_IDEMPOTENCY_TTL,
+        removal_grace_period: DEFAULT_REMOVAL_GRACE_PERIOD,
+        max_query_limit: DEFAULT_MAX_QUERY_LIMIT,
+        max_query_scan: DEFAULT_MAX_QUERY_SCAN,
+        ..Config::default()
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage).map_err(|_| ContractError::MissingContractVersion {})?;
+    let unsupported = || ContractError::UnsupportedMigration { name: stored.contract.clone(), version: stored.version.clone() };
+    if stored.contract != CONTRACT_NAME && stored.contract != LEGACY_CONTRACT_NAME {
+        return Err(unsupported());
+    }
+
+    // One arm per release this code knows how to upgrade from; anything else, including
+    // a newer release, is refused before any state is touched. A config means a 0.1.0
+    // version string left on a later development build, whose layout isn't 0.1.0's.
+    let released_layout = CONFIG.may_load(deps.storage)?.is_none();
+    match stored.version.as_str() {
+        "0.1.0" if released_layout => upgrade_from_v0_1(deps.storage, &env)?,
+        version if version == CONTRACT_VERSION => {}
+        _ => return Err(unsupported()),
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(method_response("migrate")
+        .add_attribute("from_name", stored.contract)
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// 0.1.0 stored only the owner and the scores. Sets up everything instantiate does now
+/// and indexes the existing scores as achieved at height zero, ahead of later ties.
+fn upgrade_from_v0_1(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    CONFIG.save(storage, &initial_config())?;
+    CURRENT_SEASON.save(storage, &1)?;
+    let first = Season { id: 1, start_height: env.block.height, start_time: env.block.time, end_height: None, archived: false };
+    SEASONS.save(storage, 1, &first)?;
+    let owner = STATE.load(storage)?.owner;
+    record_owner_change(storage, env, None, owner)?;
+
+    let scores = SCORES.range(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    let mut totals = Totals::default();
+    for (user, score) in scores {
+        ACHIEVED_AT.save(storage, user.clone(), &0)?;
+        index_add(storage, &index_key(score, 0, &user))?;
+        record_all_time(storage, &mut totals, &user, 0, score, Some(0))?;
+        totals.total_score += u64::from(score);
+        totals.users += 1;
+    }
+    TOTALS.save(storage, &totals)?;
+    TOP_K.save(storage, &TopK { k: DEFAULT_TOP_K, ..TopK::default() })?;
+    rebuild_top_k(storage, DEFAULT_TOP_K as usize)?;
+    Ok(())
+}
+
+fn apply_config_init(mut deps: DepsMut, init: ConfigInit) -> Result<(), ContractError> {
+    if let Some(settings) = init.settings {
+        try_update_config(deps.branch(), settings)?;
+    }
+    if init.user_gain_cap.is_some() {
+        try_set_user_gain_cap(deps.branch(), init.user_gain_cap)?;
+    }
+    if init.global_gain_cap.is_some() {
+        try_set_global_gain_cap(deps.branch(), init.global_gain_cap)?;
+    }
+    if init.update_fee.is_some() || init.cw20_update_fee.is_some() {
+        assert_enabled(deps.storage, Feature::Fees)?;
+        try_set_update_fees(deps.branch(), init.update_fee, init.cw20_update_fee)?;
+    }
+    for hook in init.hooks.unwrap_or_default() {
+        assert_enabled(deps.storage, Feature::Hooks)?;
+        try_add_hook(deps.branch(), hook)?;
+    }
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    #[cfg(feature = "debug-metrics")]
+    return crate::metrics::metered(deps, |deps| execute_msg(deps, env, info, msg));
+    #[cfg(not(feature = "debug-metrics"))]
+    execute_msg(deps, env, info, msg)
+}
+
+fn execute_msg(mut deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    validate_bounds(&msg)?;
+    let name = msg_name(&msg)?;
+    assert_not_forwarding(deps.storage, &name)?;
+    authorize(deps.storage, &info.sender, &name)?;
+    assert_msg_enabled(deps.storage, &name)?;
+    validate_msg_text(deps.storage, &msg)?;
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(key) = idempotency_key(&msg) {
+        if !claim_key(deps.storage, &env, &config, info.sender.as_str(), key)? {
+            return Ok(namespace_events(&config, duplicate_response(key)));
+        }
+    }
+    let (season_msgs, summary) = advance_season(deps.storage, &env, &config)?;
+    accrue(deps.storage, &env)?;
+    let shadow = shadow_msg(&config, &msg)?;
+    let journaled = JOURNAL.may_load(deps.storage)?.filter(|journal| journal.enabled);
+    let rebate = take_rebate(deps.storage, &env, &info.sender, &name)?;
+    let sender = info.sender.to_string();
+    let mut res = dispatch(deps.branch(), env, info, &config, msg)?;
+    if let Some(rebate) = rebate {
+        res = res.add_event(evt_rebate(&sender, &rebate)).add_message(BankMsg::Send { to_address: sender, amount: vec![rebate] });
+    }
+    // Tells an indexer which journal entries this transaction wrote
+    if let Some(before) = journaled {
+        let latest = latest_sequence(deps.storage)?;
+        if latest > before.sequence {
+            res = res.add_event(evt_journal(before.sequence + 1, latest));
+        }
+    }
+    Ok(namespace_events(&config, res.add_submessages(season_msgs).add_events(summary).add_submessages(shadow)))
+}
+
+fn dispatch(deps: DepsMut, env: Env, info: MessageInfo, config: &Config, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateScore { user, score, expires_at, .. } => {
+            let fee = collect_update_fee(deps.as_ref(), config, &info)?;
+            try_update_score(deps, env, &info.sender, user, score, expires_at).map(|res| res.add_messages(fee))
+        }
+        ExecuteMsg::UpdateScoreDecimal { user, score, expires_at, .. } => {
+            let score = to_fixed_point(score, config.score_decimals)?;
+            let fee = collect_update_fee(deps.as_ref(), config, &info)?;
+            try_update_score(deps, env, &info.sender, user, score, expires_at).map(|res| res.add_messages(fee))
+        }
+        ExecuteMsg::SubmitSignedScore { user, score, nonce, signature } => try_submit_signed_score(deps, env, user, score, nonce, signature),
+        ExecuteMsg::RotateSubmissionKey { new_pubkey, effective_at } => try_rotate_submission_key(deps, env, new_pubkey, effective_at),
+        ExecuteMsg::RedeemVoucher { voucher, signature } => try_redeem_voucher(deps, env, voucher, signature),
+        ExecuteMsg::SetVoucherKey { pubkey } => try_set_voucher_key(deps, pubkey),
+        ExecuteMsg::SetMirrorEnabled { enabled } => try_set_mirror_enabled(deps, enabled),
+        ExecuteMsg::DecoupleMirror {} => try_decouple_mirror(deps),
+        ExecuteMsg::UpdateConfig(update) => try_update_config(deps, update),
+        ExecuteMsg::TransferOwnership { new_owner } => try_transfer_ownership(deps, env, new_owner),
+        ExecuteMsg::SetPaused { paused } => try_set_paused(deps, paused),
+        ExecuteMsg::ReadOnlyForwarding { successor } => try_read_only_forwarding(deps, successor),
+        ExecuteMsg::ScheduleMaintenance { start, end } => try_schedule_maintenance(deps, env, start, end),
+        ExecuteMsg::CancelMaintenance { start } => try_cancel_maintenance(deps, start),
+        ExecuteMsg::FreezeLeaderboard { until } => try_freeze_leaderboard(deps, env, until),
+        ExecuteMsg::UnfreezeLeaderboard {} => try_unfreeze_leaderboard(deps),
+        ExecuteMsg::RescaleScores { numerator, denominator, rounding, start_after, limit } => {
+            try_rescale_scores(deps, env, numerator, denominator, rounding.unwrap_or(Rounding::Down), start_after, limit)
+        }
+        ExecuteMsg::ResetAllScores { confirmation, limit } => try_reset_all_scores(deps, env, info, confirmation, limit),
+        ExecuteMsg::CompactZeroScores { limit } => try_compact_zero_scores(deps, env, limit),
+        ExecuteMsg::PurgeExpired { limit } => try_purge_expired(deps, env, limit),
+        ExecuteMsg::SetUserGainCap { cap } => try_set_user_gain_cap(deps, cap),
+        ExecuteMsg::SetGlobalGainCap { cap } => try_set_global_gain_cap(deps, cap),
+        ExecuteMsg::SetNotifier { addr } => try_set_notifier(deps, addr),
+        ExecuteMsg::SetAggregation { sources, mode } => try_set_aggregation(deps, sources, mode),
+        ExecuteMsg::SetViewingKey { key } => try_set_viewing_key(deps, info, key),
+        ExecuteMsg::CommitScores { hash } => try_commit_scores(deps, env, hash),
+        ExecuteMsg::RevealScores { updates, salt } => try_reveal_scores(deps, env, info, updates, salt),
+        ExecuteMsg::StartNewSeason { limit } => try_start_new_season(deps, env, limit),
+        ExecuteMsg::RemoveScore { user } => try_remove_score(deps, env, user),
+        ExecuteMsg::RestoreScore { user } => try_restore_score(deps, env, user),
+        ExecuteMsg::Rollback { user } => try_rollback(deps, env, vec![user]),
+        ExecuteMsg::RollbackBatch { users } => try_rollback(deps, env, users),
+        ExecuteMsg::SetShadow { addr } => try_set_shadow(deps, addr),
+        ExecuteMsg::SetProfileVisibility { public } => try_set_profile_visibility(deps, info, public),
+        ExecuteMsg::SetAddrOrigin { prefix } => try_set_addr_origin(deps, info, prefix),
+        ExecuteMsg::SetGatekeeper { addr } => try_set_gatekeeper(deps, addr),
+        ExecuteMsg::FundPool {} => try_fund_pool(deps, info),
+        ExecuteMsg::Checkpoint {} => try_checkpoint(deps),
+        ExecuteMsg::ClaimRewards {} => try_claim_rewards(deps, env, info),
+        ExecuteMsg::FundFaucet {} => try_fund_faucet(deps, info),
+        ExecuteMsg::SetFaucet { amount_per_claim, min_score, cooldown } => {
+            try_set_faucet(deps, amount_per_claim, min_score, cooldown)
+        }
+        ExecuteMsg::StartRaffle { reveal_height } => try_start_raffle(deps, env, info, reveal_height),
+        ExecuteMsg::ResolveRaffle { raffle_id } => try_resolve_raffle(deps, env, raffle_id),
+        ExecuteMsg::ClaimRaffle { raffle_id } => try_claim_raffle(deps, info, raffle_id),
+        ExecuteMsg::ClaimFaucet {} => try_claim_faucet(deps, env, info),
+        ExecuteMsg::FundRebatePool {} => try_fund_rebate_pool(deps, info),
+        ExecuteMsg::SetRebate { amount } => try_set_rebate(deps, amount),
+        ExecuteMsg::SpendScore { amount, memo } => try_spend_score(deps, env, info, amount, memo),
+        ExecuteMsg::SpendAndCall { amount, contract, msg } => try_spend_and_call(deps, env, info, amount, contract, msg),
+        ExecuteMsg::OpenWager { opponent, stake, expires_at } => try_open_wager(deps, env, info, opponent, stake, expires_at),
+        ExecuteMsg::AcceptWager { wager_id } => try_accept_wager(deps, env, info, wager_id),
+        ExecuteMsg::ReportWager { wager_id, winner } => try_report_wager(deps, env, wager_id, winner),
+        ExecuteMsg::RefundWager { wager_id } => try_refund_wager(deps, env, info, wager_id),
+        ExecuteMsg::SetShop { addr } => try_set_shop(deps, addr),
+        ExecuteMsg::CertifyScore {} => try_certify_score(deps, env, info),
+        ExecuteMsg::SetCertifier { addr } => try_set_certifier(deps, addr),
+        ExecuteMsg::PublishWeightTable { top_n } => try_publish_weight_table(deps, env, top_n),
+        ExecuteMsg::SetRewardStream { per_second } => try_set_reward_stream(deps, env, per_second),
+        ExecuteMsg::Penalize { user, points, reason, .. } => try_penalize(deps, env, user, points, reason),
+        ExecuteMsg::Appeal { reason } => try_appeal(deps, env, info, reason),
+        ExecuteMsg::ResolveAppeal { user, uphold } => try_resolve_appeal(deps, env, info, user, uphold),
+        ExecuteMsg::SetArbiter { addr } => try_set_arbiter(deps, addr),
+        ExecuteMsg::NormalizeAddresses { start_after, limit } => try_normalize_addresses(deps, env, start_after, limit),
+        ExecuteMsg::RegisterMerkleStage { merkle_root, expires_at } => {
+            try_register_merkle_stage(deps, env, info, merkle_root, expires_at)
+        }
+        ExecuteMsg::ClaimMerkle { stage, index, amount, proof } => try_claim_merkle(deps, env, info, stage, index, amount, proof),
+        ExecuteMsg::ClawbackStage { stage } => try_clawback_stage(deps, env, stage),
+        ExecuteMsg::RecoverCw20 { token, amount, recipient } => try_recover_cw20(deps, token, amount, recipient),
+        ExecuteMsg::RecoverNft { contract, token_id, recipient } => try_recover_nft(deps, contract, token_id, recipient),
+        ExecuteMsg::SetTopK { k } => try_set_top_k(deps, k),
+        ExecuteMsg::RebuildTopK { limit } => try_rebuild_top_k(deps, limit),
+        ExecuteMsg::RebuildIndex { start_after, limit } => try_rebuild_index(deps, env, start_after, limit),
+        ExecuteMsg::SetUpdateFees { native, cw20 } => try_set_update_fees(deps, native, cw20),
+        ExecuteMsg::ReviewAnomaly { user, accept } => try_review_anomaly(deps, env, user, accept),
+        ExecuteMsg::AddHook { addr } => try_add_hook(deps, addr),
+        ExecuteMsg::UpdateOperators { add, remove } => try_update_operators(deps, add, remove),
+        ExecuteMsg::RemoveHook { addr } => try_remove_hook(deps, addr),
+        ExecuteMsg::SetBrackets { brackets } => try_set_brackets(deps, brackets),
+        ExecuteMsg::MergeIdentities { primary, secondary } => try_merge_identities(deps, env, primary, secondary),
+        ExecuteMsg::Attest { user, value, tag } => try_attest(deps, env, info, user, value, tag),
+        ExecuteMsg::SetJournal { enabled } => try_set_journal(deps, enabled),
+        ExecuteMsg::SetRetention { retention } => try_set_retention(deps, retention),
+        ExecuteMsg::TrimHistory { limit } => try_trim_history(deps, env, limit),
+        ExecuteMsg::SetPolicy { msg_name, role } => try_set_policy(deps, msg_name, role),
+        ExecuteMsg::UpdateBoardAdmins { board, add, remove } => try_update_board_admins(deps, board, add, remove),
+        ExecuteMsg::UpdateBoardConfig { board, update } => try_update_board_config(deps, info, board, update),
+        ExecuteMsg::UpdateDenylist { add, remove } => try_update_denylist(deps, add, remove),
+        ExecuteMsg::UpdatePeers { add, remove } => try_update_peers(deps, add, remove),
+        ExecuteMsg::SyncFromPeer { chain_id, updates } => try_sync_from_peer(deps, env, info, chain_id, updates),
+        ExecuteMsg::FinalizeSnapshotDigest { snapshot_id, digest } => try_finalize_snapshot_digest(deps, env, snapshot_id, digest),
+        ExecuteMsg::Receive(wrapper) => try_receive(deps, env, info, wrapper),
+    }
+}
+
+fn idempotency_key(msg: &ExecuteMsg) -> Option<&str> {
     match msg {
-        ExecuteMsg::UpdateScore {user, score} => try_update_score(deps, info, user, score)
+        ExecuteMsg::UpdateScore { idempotency_key, .. }
+        | ExecuteMsg::UpdateScoreDecimal { idempotency_key, .. }
+        | ExecuteMsg::Penalize { idempotency_key, .. } => idempotency_key.as_deref(),
+        _ => None,
     }
 }
 
-pub fn try_update_score(deps: DepsMut, info: MessageInfo, user: Addr, score: u32) -> Result<Response, ContractError> {
-    let state = STATE.load(deps.storage)?;
-    if info.sender != state.owner {
-        return Err(ContractError::Unauthorized {});
+// A repeated key skipped under DuplicateKeyPolicy::Ignore
+fn duplicate_response(key: &str) -> Response {
+    method_response("duplicate").add_attribute("idempotency_key", key)
+}
+
+/// Rejects oversized inputs before anything is read or written: batch sizes, page
+/// limits, string lengths and list lengths all have a fixed cap.
+fn validate_bounds(msg: &ExecuteMsg) -> Result<(), ContractError> {
+    let too_long = |value: &str, max: u32| value.len() > max as usize;
+    let limit = match msg {
+        ExecuteMsg::RescaleScores { limit, .. }
+        | ExecuteMsg::ResetAllScores { limit, .. }
+        | ExecuteMsg::CompactZeroScores { limit }
+        | ExecuteMsg::PurgeExpired { limit }
+        | ExecuteMsg::TrimHistory { limit }
+        | ExecuteMsg::StartNewSeason { limit }
+        | ExecuteMsg::NormalizeAddresses { limit, .. }
+        | ExecuteMsg::RebuildTopK { limit }
+        | ExecuteMsg::RebuildIndex { limit, .. } => *limit,
+        _ => None,
+    };
+    // A batch of nothing would never make progress
+    if limit.map_or(false, |limit| limit == 0 || limit > MAX_MAINTENANCE_LIMIT) {
+        return Err(ContractError::InvalidLimit { max: MAX_MAINTENANCE_LIMIT });
     }
 
-    let current_score = SCORES.may_load(deps.storage, user.to_string())?.unwrap_or_default();
+    match msg {
+        ExecuteMsg::RevealScores { updates, salt } => {
+            if updates.len() > MAX_BATCH_SIZE as usize {
+                return Err(ContractError::BatchTooLarge { max: MAX_BATCH_SIZE });
+            }
+            if salt.len() > MAX_SALT_LEN as usize {
+                return Err(ContractError::SaltTooLong { max: MAX_SALT_LEN });
+            }
+        }
+        ExecuteMsg::RollbackBatch { users } if users.len() > MAX_BATCH_SIZE as usize => {
+            return Err(ContractError::BatchTooLarge { max: MAX_BATCH_SIZE });
+        }
+        ExecuteMsg::SyncFromPeer { updates, .. } if updates.len() > MAX_BATCH_SIZE as usize => {
+            return Err(ContractError::BatchTooLarge { max: MAX_BATCH_SIZE });
+        }
+        ExecuteMsg::Penalize { reason, .. } | ExecuteMsg::Appeal { reason } if too_long(reason, MAX_REASON_LEN) => {
+            return Err(ContractError::ReasonTooLong { max: MAX_REASON_LEN });
+        }
+        ExecuteMsg::SpendScore { memo, .. } if too_long(memo, MAX_MEMO_LEN) => {
+            return Err(ContractError::MemoTooLong { max: MAX_MEMO_LEN });
+        }
+        ExecuteMsg::SpendAndCall { msg, .. } if msg.len() > MAX_CALL_MSG_LEN as usize => {
+            return Err(ContractError::CallMsgTooLong { max: MAX_CALL_MSG_LEN });
+        }
+        ExecuteMsg::Attest { tag, .. } if tag.is_empty() || too_long(tag, MAX_TAG_LEN) => {
+            return Err(ContractError::InvalidTag { max: MAX_TAG_LEN });
+        }
+        ExecuteMsg::FinalizeSnapshotDigest { digest, .. } if digest.is_empty() || too_long(digest, MAX_DIGEST_LEN) => {
+            return Err(ContractError::InvalidDigest { max: MAX_DIGEST_LEN });
+        }
+        ExecuteMsg::SetViewingKey { key } if too_long(key, MAX_VIEWING_KEY_LEN) => {
+            return Err(ContractError::ViewingKeyTooLong { max: MAX_VIEWING_KEY_LEN });
+        }
+        ExecuteMsg::ClaimMerkle { proof, .. } if proof.len() > MAX_PROOF_LEN as usize => {
+            return Err(ContractError::ProofTooLong { max: MAX_PROOF_LEN });
+        }
+        ExecuteMsg::UpdateScore { idempotency_key: Some(key), .. }
+        | ExecuteMsg::UpdateScoreDecimal { idempotency_key: Some(key), .. }
+        | ExecuteMsg::Penalize { idempotency_key: Some(key), .. }
+            if key.is_empty() || too_long(key, MAX_IDEMPOTENCY_KEY_LEN) =>
+        {
+            return Err(ContractError::InvalidIdempotencyKey { max: MAX_IDEMPOTENCY_KEY_LEN });
+        }
+        ExecuteMsg::UpdateConfig(update) => {
+            if update.reserved_addresses.as_ref().map_or(false, |addrs| addrs.len() > MAX_RESERVED_ADDRESSES as usize) {
+                return Err(ContractError::TooManyReservedAddresses { max: MAX_RESERVED_ADDRESSES });
+            }
+            if update.fee_routes.as_ref().map_or(false, |routes| routes.len() > MAX_FEE_ROUTES as usize) {
+                return Err(ContractError::TooManyFeeRoutes { max: MAX_FEE_ROUTES });
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
 
-    if current_score == 0 {
-        SCORES.save(deps.storage, user.to_string(), &score);
-    } else {
-        SCORES.update(deps.storage, user.to_string(), |score: Option<u32>| -> StdResult<_> { 
-            Ok(score.unwrap_or_default())
-        })?;
+/// Prefixes custom event types and attribute keys with the configured namespace.
+fn namespace_events(config: &Config, mut res: Response) -> Response {
+    if config.event_prefix.is_empty() {
+        return res;
     }
-    
-    Ok(Response::new().add_attribute("method", "try_update_score"))
+    let prefix = |name: &str| format!("{}-{}", config.event_prefix, name);
+    for attr in res.attributes.iter_mut() {
+        attr.key = prefix(&attr.key);
+    }
+    for event in res.events.iter_mut() {
+        event.ty = prefix(&event.ty);
+        for attr in event.attributes.iter_mut() {
+            attr.key = prefix(&attr.key);
+        }
+    }
+    res
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetOwner {} => to_binary(&query_owner(deps)?),
-        QueryMsg::GetScore { user } => to_binary(&query_score(deps, user)?)
+fn validate_event_prefix(prefix: String) -> Result<String, ContractError> {
+    let valid = prefix.len() <= MAX_EVENT_PREFIX_LEN as usize
+        && prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(ContractError::InvalidEventPrefix { max: MAX_EVENT_PREFIX_LEN });
     }
+    Ok(prefix)
 }
 
-fn query_owner(deps: Deps) -> StdResult<OwnerResponse> {
+pub fn try_update_score(
+    deps: DepsMut,
+    env: Env,
+    writer: &Addr,
+    user: Addr,
+    score: u32,
+    expires_at: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    let res = method_response("try_update_score");
+    write_score(deps, env, res, Some(writer), user.as_str(), score, expires_at)
+}
+
+/// Relays a score signed by the game server's key; the signature stands in for the
+/// sender being a writer.
+pub fn try_submit_signed_score(
+    deps: DepsMut,
+    env: Env,
+    user: String,
+    score: u32,
+    nonce: u64,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let user = normalize_addr(deps.api, &user)?;
+    let payload = SignedScore { contract: env.contract.address.to_string(), user, score, nonce };
+    verify_submission(deps.as_ref(), &env, &payload, &signature)?;
+    SUBMISSION_NONCES.save(deps.storage, payload.user.clone(), &nonce)?;
+
+    let res = method_response("try_submit_signed_score").add_attribute("nonce", nonce.to_string());
+    // Signed by the game server's key rather than sent by a writer, so nobody's activity is
+    // counted for it
+    write_score(deps, env, res, None, &payload.user, score, None)
+}
+
+/// Stores a score on behalf of an authorized writer, adding to `res`, and counts it in
+/// the writer's activity.
+fn write_score(
+    deps: DepsMut,
+    env: Env,
+    mut res: Response,
+    writer: Option<&Addr>,
+    user: &str,
+    score: u32,
+    expires_at: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    if expires_at.map_or(false, |expires_at| expires_at <= env.block.time) {
+        return Err(ContractError::ExpiryInPast {});
+    }
+
+    let user = normalize_addr(deps.api, user)?;
+    let config = CONFIG.load(deps.storage)?;
+    assert_not_reserved(&env, &config, &user)?;
+    assert_eligible(&deps.querier, &config, &user)?;
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let total_before = totals.total_score;
+    let (written, messages, anomaly) = apply_update(deps.storage, &env, &config, &mut totals, &user, score)?;
+    TOTALS.save(deps.storage, &totals)?;
+    res = res.add_submessages(messages).add_events(anomaly);
+    let written = match written {
+        Some(written) => written,
+        None => return Ok(res.add_attribute("ignored", "true")),
+    };
+    if let Some(writer) = writer {
+        record_activity(deps.storage, writer, 1, totals.total_score.saturating_sub(total_before), env.block.height)?;
+    }
+    if written != score {
+        res = res.add_attribute("truncated_to", written.to_string());
+    }
+    res = res.add_event(evt_score_changed(&user, written));
+    // A pruned zero leaves nothing to expire
+    if written > 0 || !config.prune_zero_scores {
+        set_expiry(deps.storage, &user, expires_at)?;
+    }
+
+    Ok(res)
+}
+
+/// Checks the native update fee is attached and returns the messages routing it.
+fn collect_update_fee(deps: Deps, config: &Config, info: &MessageInfo) -> Result<Vec<BankMsg>, ContractError> {
+    let fee = match &config.update_fee {
+        Some(fee) => fee,
+        None => return Ok(vec![]),
+    };
+    let paid = info.funds.iter().find(|coin| coin.denom == fee.denom).map_or(Uint128::zero(), |coin| coin.amount);
+    if paid < fee.amount {
+        return Err(ContractError::FeeRequired { fee: fee.to_string() });
+    }
     let state = STATE.load(deps.storage)?;
-    Ok(OwnerResponse { owner: state.owner })
+    Ok(split_msgs(config, &state.owner, coin(paid.u128(), &fee.denom)))
+}
+
+/// Runs the update embedded in a cw20 transfer as the token sender, once the transfer
+/// covers Config::cw20_update_fee, and routes the tokens received.
+pub fn try_receive(deps: DepsMut, env: Env, info: MessageInfo, wrapper: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let fee = match &config.cw20_update_fee {
+        Some(fee) if fee.token == info.sender => fee,
+        _ => return Err(ContractError::InvalidFeeToken {}),
+    };
+    if wrapper.amount < fee.amount {
+        return Err(ContractError::FeeRequired { fee: format!("{}{}", fee.amount, fee.token) });
+    }
+
+    let state = STATE.load(deps.storage)?;
+    let routed = split_cw20_msgs(&config, &state.owner, &fee.token, wrapper.amount)?;
+    let sender = MessageInfo { sender: deps.api.addr_validate(&wrapper.sender)?, funds: vec![] };
+    let res = match from_binary(&wrapper.msg)? {
+        ReceiveMsg::UpdateScore { user, score, expires_at, idempotency_key } => {
+            // Keyed by the token sender, like a direct UpdateScore from them
+            if let Some(key) = idempotency_key {
+                if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN as usize {
+                    return Err(ContractError::InvalidIdempotencyKey { max: MAX_IDEMPOTENCY_KEY_LEN });
+                }
+                if !claim_key(deps.storage, &env, &config, sender.sender.as_str(), &key)? {
+                    return Ok(duplicate_response(&key).add_messages(routed));
+                }
+            }
+            authorize(deps.storage, &sender.sender, "update_score")?;
+            try_update_score(deps, env, &sender.sender, user, score, expires_at)?
+        }
+    };
+    Ok(res.add_messages(routed))
+}
+
+pub fn try_set_update_fees(
+    deps: DepsMut,
+    native: Option<Coin>,
+    cw20: Option<Cw20FeeInit>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    // A zero fee is no fee, or any zero-token transfer would pay for a write
+    config.update_fee = native.filter(|fee| !fee.amount.is_zero());
+    config.cw20_update_fee = match cw20.filter(|fee| !fee.amount.is_zero()) {
+        Some(fee) => Some(Cw20Fee { token: deps.api.addr_validate(&fee.token)?, amount: fee.amount }),
+        None => None,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(method_response("try_set_update_fees"))
 }
 
-fn query_score(deps: Deps, user: String) -> StdResult<ScoreResponse>  {
-    let score = SCORES.may_load(deps.storage, user)?.unwrap_or_default();
-    Ok(ScoreResponse{ score })
+/// Stored units for a fractional score, which must fit u32 at the configured precision.
+pub fn to_fixed_point(score: Decimal, decimals: u32) -> Result<u32, ContractError> {
+    let unit = Uint128::from(10u128.pow(score.decimal_places() - decimals));
+    let atomics = score.atomics();
+    if !(atomics % unit).is_zero() {
+        return Err(ContractError::ScorePrecision { decimals });
+    }
+    u32::try_from((atomics / unit).u128()).map_err(|_| ContractError::ScorePrecision { decimals })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+// Score written, None when ignored, plus messages and the event flagging an anomaly
+type Applied = (Option<u32>, Vec<SubMsg>, Option<Event>);
 
-    fn get_score<T: Into<String>>(deps: Deps, address: T) -> u32 {
-        query_score(deps, address.into()).unwrap().score
+/// Runs a score write through the min_delta filter, gain cap and anomaly guard, then
+/// stores it.
+fn apply_update(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    totals: &mut Totals,
+    user: &str,
+    score: u32,
+) -> Result<Applied, ContractError> {
+    if let Some(primary) = MERGED.may_load(storage, user.to_string())? {
+        return Err(ContractError::Merged { user: user.to_string(), primary });
+    }
+    // Frozen even if the guard has since been turned off, until the owner reviews
+    if PROVISIONAL.may_load(storage, user.to_string())?.is_some() {
+        return Err(ContractError::UserFrozen { user: user.to_string() });
+    }
+    // A score left over from a season being archived doesn't count towards the new one
+    let mut messages = archive_if_stale(storage, totals, user, &env.block)?.unwrap_or_default();
+    let mut score = score;
+    // Only read the current score when a filter needs it
+    let capped = config.user_gain_cap.is_some() || config.global_gain_cap.is_some();
+    let current_score = if capped || config.min_delta > 0 || config.anomaly_multiple > 0 {
+        SCORES.may_load(storage, user.to_string())?.unwrap_or_default()
+    } else {
+        0
+    };
+    if config.min_delta > 0 {
+        let delta = current_score.max(score) - current_score.min(score);
+        if delta < config.min_delta {
+            return match config.small_delta_policy {
+                SmallDeltaPolicy::Reject => Err(ContractError::DeltaTooSmall { delta, min_delta: config.min_delta }),
+                SmallDeltaPolicy::Ignore => Ok((None, messages, None)),
+            };
+        }
     }
 
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+    // Applied last so that nothing above can discard an update after its gain is recorded
+    if config.user_gain_cap.is_some() || config.global_gain_cap.is_some() {
+        score = apply_gain_caps(storage, env, config, user, current_score, score)?;
+    }
 
-        // let msg = InstantiateMsg { count: 17 };
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+    let mut anomaly = None;
+    if config.anomaly_multiple > 0 && is_anomalous(config.anomaly_multiple, totals, current_score, score) {
+        let existing = current_key(storage, user)?;
+        let flagged = ProvisionalUpdate {
+            score,
+            previous: existing.as_ref().map(|key| u32::MAX - key.0),
+            previous_achieved_at: existing.map_or(0, |key| key.1),
+            flagged_at: env.block.height,
+        };
+        PROVISIONAL.save(storage, user.to_string(), &flagged)?;
+        anomaly = Some(evt_anomaly(user, current_score, score));
+    }
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+    messages.extend(set_score(storage, config, totals, user, score, Some(env.block.height), &env.block)?);
+    Ok((Some(score), messages, anomaly))
+}
 
-        // Query and validate the owner was set correctly
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
-        let value: OwnerResponse = from_binary(&res).unwrap();
-        assert_eq!("creator", value.owner);
+/// Records the sender's signal about a user. Open to anyone: attestations are only read
+/// back through QueryMsg::Attestations and never affect scores.
+pub fn try_attest(deps: DepsMut, env: Env, info: MessageInfo, user: String, value: i64, tag: String) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+
+    let user = normalize_addr(deps.api, &user)?;
+    let attestation = Attestation { value, height: env.block.height };
+    ATTESTATIONS.save(deps.storage, (user.clone(), tag.clone(), info.sender.to_string()), &attestation)?;
+
+    Ok(method_response("try_attest")
+        .add_attribute("attester", info.sender)
+        .add_attribute("user", user)
+        .add_attribute("tag", tag))
+}
+
+/// Ties a published export of an archived season to the chain. Archived standings never
+/// change, so the digest is recorded once and cannot be replaced.
+pub fn try_finalize_snapshot_digest(
+    deps: DepsMut,
+    env: Env,
+    snapshot_id: u32,
+    digest: String,
+) -> Result<Response, ContractError> {
+    if !SEASONS.may_load(deps.storage, snapshot_id)?.map_or(false, |season| season.archived) {
+        return Err(ContractError::SnapshotNotArchived { snapshot_id });
+    }
+    if SNAPSHOT_DIGESTS.has(deps.storage, snapshot_id) {
+        return Err(ContractError::DigestAlreadyRecorded { snapshot_id });
     }
-    
-    #[test]
-    // Sets a specific user's score
-    fn set_user_score() {
-        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
 
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(2, "token"));
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let recorded = SnapshotDigest { digest, recorded_at: env.block.height };
+    SNAPSHOT_DIGESTS.save(deps.storage, snapshot_id, &recorded)?;
 
-        // Set a user's score
-        let info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::UpdateScore { user: info.sender.clone(), score: 1120 };
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    Ok(method_response("try_finalize_snapshot_digest")
+        .add_attribute("snapshot_id", snapshot_id.to_string())
+        .add_attribute("digest", recorded.digest))
+}
 
-        assert_eq!(get_score(deps.as_ref(), "creator"), 1120);
+pub fn try_set_journal(deps: DepsMut, enabled: bool) -> Result<Response, ContractError> {
+    let mut journal = JOURNAL.may_load(deps.storage)?.unwrap_or_default();
+    journal.enabled = enabled;
+    JOURNAL.save(deps.storage, &journal)?;
 
-        // Attempting to set a user's score with someone other than the owner will fail
-        let info = mock_info("someone_new", &coins(2, "token"));
-        let msg = ExecuteMsg::UpdateScore { user: info.sender.clone(), score: 500 };
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
-        match res {
-            Err(ContractError::Unauthorized {}) => {}
-            _ => panic!("Must return unauthorized error"),
+    Ok(method_response("try_set_journal").add_attribute("enabled", enabled.to_string()))
+}
+
+pub fn try_set_retention(deps: DepsMut, retention: Option<Retention>) -> Result<Response, ContractError> {
+    match &retention {
+        Some(retention) => {
+            validate_retention(retention)?;
+            RETENTION.save(deps.storage, retention)?;
         }
+        None => RETENTION.remove(deps.storage),
     }
 
-    #[test]
-    // Get token balances of users
-    fn get_token_balances_of_users() {
-        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+    let limit = |value: Option<u64>| value.map(|value| value.to_string()).unwrap_or_default();
+    Ok(method_response("try_set_retention")
+        .add_attribute("max_entries", limit(retention.as_ref().and_then(|retention| retention.max_entries)))
+        .add_attribute("max_age", limit(retention.as_ref().and_then(|retention| retention.max_age))))
+}
 
-        let msg = InstantiateMsg {};
-        let instantiate_info = mock_info("creator", &coins(2, "token"));
-        let _res = instantiate(deps.as_mut(), mock_env(), instantiate_info, msg).unwrap();
+pub fn try_set_policy(deps: DepsMut, msg_name: String, role: Role) -> Result<Response, ContractError> {
+    set_policy(deps.storage, &msg_name, role)?;
 
-        // Set creator
-        let creator_info = mock_info("creator", &coins(2, "token"));
-        let msg = ExecuteMsg::UpdateScore { user: creator_info.sender.clone(), score: 123 };
-        let _res = execute(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+    Ok(method_response("try_set_policy")
+        .add_attribute("msg_name", msg_name)
+        .add_attribute("role", format!("{:?}", role)))
+}
 
-        // Set someone else
-        let creator_info = mock_info("creator", &coins(2, "token"));
-        let new_human = mock_info("new_human", &coins(10, "token"));
-        let msg = ExecuteMsg::UpdateScore { user: new_human.sender.clone(), score: 456 };
-        let _res = execute(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
-        
-        // Fetch creator
-        let creator_info = mock_info("creator", &coins(10, "token"));
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetScore {user: creator_info.sender.to_string()}).unwrap();
-        let value: ScoreResponse = from_binary(&res).unwrap();
-        println!("{}", value.score);
-        assert_eq!(123, value.score);
+pub fn try_update_board_admins(deps: DepsMut, board: String, add: Vec<String>, remove: Vec<String>) -> Result<Response, ContractError> {
+    let validate = |addrs: Vec<String>| addrs.iter().map(|addr| deps.api.addr_validate(addr)).collect::<StdResult<Vec<_>>>();
+    let (add, remove) = (validate(add)?, validate(remove)?);
+    update_board_admins(deps.storage, &board, add, remove)?;
 
-        // Fetch new human
-        let new_human = mock_info("new_human", &coins(10, "token"));
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetScore {user: new_human.sender.to_string()}).unwrap();
-        let value: ScoreResponse = from_binary(&res).unwrap();
-        assert_eq!(456, value.score);
+    Ok(method_response("try_update_board_admins").add_attribute("board", board))
+}
+
+/// Until boards are split the board's config is the contract-wide config, so board admins
+/// are refused even when a policy lets them through.
+pub fn try_update_board_config(deps: DepsMut, info: MessageInfo, board: String, update: BoardConfigUpdate) -> Result<Response, ContractError> {
+    validate_board(&board)?;
+    if info.sender != STATE.load(deps.storage)?.owner {
+        return Err(ContractError::SharedBoardConfig { board });
     }
+    let BoardConfigUpdate { min_delta, small_delta_policy, season_duration } = update;
+    let update = ConfigUpdate { min_delta, small_delta_policy, season_duration, ..ConfigUpdate::default() };
 
-    #[test]
-    // Get the owner of the contract
-    fn get_owner() {
-        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+    Ok(try_update_config(deps, update)?.add_attribute("board", board))
+}
 
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(2, "token"));
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+pub fn try_update_denylist(deps: DepsMut, add: Vec<String>, remove: Vec<String>) -> Result<Response, ContractError> {
+    update_denylist(deps.storage, add, remove)?;
 
-        // Fetch here
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
-        let value: OwnerResponse = from_binary(&res).unwrap();
-        assert_eq!("creator", value.owner);
+    Ok(method_response("try_update_denylist"))
+}
+
+pub fn try_trim_history(deps: DepsMut, env: Env, limit: Option<u32>) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT);
+    let trimmed = trim_history(deps.storage, env.block.height, limit)?;
+
+    Ok(method_response("try_trim_history").add_attribute("trimmed", trimmed.to_string()))
+}
+
+/// Combines two addresses of one identity under Config::merge_policy. The secondary's
+/// entry is deleted and tombstoned so that later writes to it fail; the primary keeps its
+/// tie-break height unless its score changes.
+pub fn try_merge_identities(
+    deps: DepsMut,
+    env: Env,
+    primary: String,
+    secondary: String,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+
+    let primary = normalize_addr(deps.api, &primary)?;
+    let secondary = normalize_addr(deps.api, &secondary)?;
+    if primary == secondary {
+        return Err(ContractError::SelfMerge {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    assert_not_reserved(&env, &config, &primary)?;
+    for user in [&primary, &secondary] {
+        if let Some(into) = MERGED.may_load(deps.storage, user.clone())? {
+            return Err(ContractError::Merged { user: user.clone(), primary: into });
+        }
+        if PROVISIONAL.may_load(deps.storage, user.clone())?.is_some() {
+            return Err(ContractError::UserFrozen { user: user.clone() });
+        }
+    }
+    // Overturning the secondary's penalty later would pay its escrow back to a tombstone
+    if penalty_pending(deps.storage, &env, &secondary)? {
+        return Err(ContractError::PenaltyPending { user: secondary });
+    }
+
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let primary_score = SCORES.may_load(deps.storage, primary.clone())?;
+    let secondary_score = SCORES.may_load(deps.storage, secondary.clone())?;
+    let merged = config.merge_policy.merge(primary_score, secondary_score);
+
+    let mut messages = remove_score(deps.storage, &mut totals, &secondary, &env.block)?;
+    if let Some(score) = merged {
+        let achieved_at = if merged == primary_score { None } else { Some(env.block.height) };
+        messages.extend(set_score(deps.storage, &config, &mut totals, &primary, score, achieved_at, &env.block)?);
+    }
+    TOTALS.save(deps.storage, &totals)?;
+    MERGED.save(deps.storage, secondary.clone(), &primary)?;
+
+    let policy = format!("{:?}", config.merge_policy);
+    let event = evt_merge_identities(&primary, &secondary, &policy, primary_score, secondary_score, merged);
+    Ok(method_response("try_merge_identities").add_submessages(messages).add_event(event))
+}
+
+pub fn try_review_anomaly(deps: DepsMut, env: Env, user: String, accept: bool) -> Result<Response, ContractError> {
+    let user = normalize_addr(deps.api, &user)?;
+    let flagged = PROVISIONAL
+        .may_load(deps.storage, user.clone())?
+        .ok_or_else(|| ContractError::NotFlagged { user: user.clone() })?;
+    PROVISIONAL.remove(deps.storage, user.clone());
+
+    let mut messages = vec![];
+    if !accept {
+        let config = CONFIG.load(deps.storage)?;
+        let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+        messages = match flagged.previous {
+            Some(previous) => set_score(deps.storage, &config, &mut totals, &user, previous, Some(flagged.previous_achieved_at), &env.block)?,
+            None => remove_score(deps.storage, &mut totals, &user, &env.block)?,
+        };
+        TOTALS.save(deps.storage, &totals)?;
+    }
+
+    Ok(method_response("try_review_anomaly")
+        .add_submessages(messages)
+        .add_event(evt_anomaly_reviewed(&user, accept)))
+}
+
+/// Keeps the entry about to be overwritten or removed so the owner can undo the change.
+fn record_previous(storage: &mut dyn Storage, block: &BlockInfo, user: &str, existing: Option<&IndexKey>) -> StdResult<()> {
+    let previous = PreviousScore {
+        score: existing.map(|key| u32::MAX - key.0),
+        achieved_at: existing.map_or(0, |key| key.1),
+        expires_at: EXPIRIES.may_load(storage, user.to_string())?,
+        recorded_at: block.height,
+    };
+    PREVIOUS_SCORES.save(storage, user.to_string(), &previous)
+}
+
+/// Records a gain against the user's and the global epoch allowances and returns the
+/// score that may be written, which is lower than `score` when a cap truncates.
+fn apply_gain_caps(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    user: &str,
+    current_score: u32,
+    score: u32,
+) -> Result<u32, ContractError> {
+    if score <= current_score {
+        return Ok(score);
+    }
+
+    let mut gain = score - current_score;
+    let mut user_gains = None;
+    if let Some(cap) = &config.user_gain_cap {
+        let mut gains = USER_EPOCH_GAINS.may_load(storage, user.to_string())?.unwrap_or_default();
+        gain = allowed_gain(cap, &mut gains, env, gain, |remaining| ContractError::EpochCapExceeded { remaining })?;
+        user_gains = Some(gains);
+    }
+    let mut global_gains = None;
+    if let Some(cap) = &config.global_gain_cap {
+        let mut gains = GLOBAL_EPOCH_GAIN.may_load(storage)?.unwrap_or_default();
+        gain = allowed_gain(cap, &mut gains, env, gain, |remaining| ContractError::GlobalCapExceeded { remaining })?;
+        global_gains = Some(gains);
+    }
+
+    // Both allowances are charged the gain that is finally written
+    if let Some(mut gains) = user_gains {
+        gains.gained += gain;
+        USER_EPOCH_GAINS.save(storage, user.to_string(), &gains)?;
+    }
+    if let Some(mut gains) = global_gains {
+        gains.gained += gain;
+        GLOBAL_EPOCH_GAIN.save(storage, &gains)?;
+    }
+    Ok(current_score + gain)
+}
+
+/// How much of `gain` `cap` still allows this epoch, moving `gains` to the current epoch.
+/// Fails with `exceeded(remaining)` when the cap rejects rather than truncates.
+fn allowed_gain(
+    cap: &EpochCap,
+    gains: &mut EpochGain,
+    env: &Env,
+    gain: u32,
+    exceeded: fn(u32) -> ContractError,
+) -> Result<u32, ContractError> {
+    let epoch = cap.epoch(env.block.time);
+    if gains.epoch != epoch {
+        *gains = EpochGain { epoch, gained: 0 };
+    }
+
+    let remaining = cap.amount.saturating_sub(gains.gained);
+    if gain <= remaining {
+        return Ok(gain);
+    }
+    match cap.policy {
+        CapPolicy::Reject => Err(exceeded(remaining)),
+        CapPolicy::Truncate => Ok(remaining),
+    }
+}
+
+/// Writes a score, keeping `totals`, the leaderboard index and the podium set in step, and returns any
+/// mirror token messages. `achieved_at` is the height recorded for tie-breaking; None keeps
+/// the current one, as rescaling doesn't re-achieve a score. `height` is the current block
+/// height, recorded with the change in the deltas ring. Callers load and save `totals`
+/// themselves so batch paths touch it once.
+fn set_score(
+    storage: &mut dyn Storage,
+    config: &Config,
+    totals: &mut Totals,
+    user: &str,
+    score: u32,
+    achieved_at: Option<u64>,
+    block: &BlockInfo,
+) -> StdResult<Vec<SubMsg>> {
+    // Every write path ends up here, so this is the one place the floor is applied
+    let score = score.max(config.score_floor);
+    if score == 0 && config.prune_zero_scores {
+        return remove_score(storage, totals, user, block);
+    }
+
+    let existing = current_key(storage, user)?;
+    let current_score = existing.as_ref().map_or(0, |(inverted, _, _)| u32::MAX - inverted);
+    // Rewriting the same score keeps its place among ties
+    if existing.is_some() && current_score == score {
+        return Ok(vec![]);
+    }
+    let achieved_at = achieved_at.or_else(|| existing.as_ref().map(|(_, height, _)| *height)).unwrap_or_default();
+    // Whatever moved the score, Rollback undoes the latest change
+    if config.rollback_window > 0 {
+        record_previous(storage, block, user, existing.as_ref())?;
+    }
+
+    settle(storage, user, current_score)?;
+    record_all_time(storage, totals, user, current_score, score, Some(achieved_at))?;
+    SCORES.save(storage, user.to_string(), &score)?;
+    ACHIEVED_AT.save(storage, user.to_string(), &achieved_at)?;
+    if let Some(old) = existing.clone() {
+        index_remove(storage, &old)?;
+    }
+    let key = index_key(score, achieved_at, user);
+    index_add(storage, &key)?;
+    update_top_k(storage, existing.as_ref(), Some(&key))?;
+
+    totals.total_score = totals.total_score - u64::from(current_score) + u64::from(score);
+    if existing.is_none() {
+        totals.users += 1;
+    }
+
+    change_msgs(storage, user, current_score, score, block)
+}
+
+/// Deletes a score entry, keeping `totals`, the index and the podium set in step, and returns any mirror
+/// token messages.
+fn remove_score(storage: &mut dyn Storage, totals: &mut Totals, user: &str, block: &BlockInfo) -> StdResult<Vec<SubMsg>> {
+    let key = match current_key(storage, user)? {
+        Some(key) => key,
+        None => return Ok(vec![]),
+    };
+    if CONFIG.load(storage)?.rollback_window > 0 {
+        record_previous(storage, block, user, Some(&key))?;
+    }
+    record_all_time(storage, totals, user, u32::MAX - key.0, 0, None)?;
+    remove_entry(storage, totals, user, key, block)
+}
+
+/// Deletes the live entry at `key` without touching the all-time board.
+fn remove_entry(
+    storage: &mut dyn Storage,
+    totals: &mut Totals,
+    user: &str,
+    key: IndexKey,
+    block: &BlockInfo,
+) -> StdResult<Vec<SubMsg>> {
+    let current_score = u32::MAX - key.0;
+    settle(storage, user, current_score)?;
+    SCORES.remove(storage, user.to_string());
+    ACHIEVED_AT.remove(storage, user.to_string());
+    index_remove(storage, &key)?;
+    update_top_k(storage, Some(&key), None)?;
+    set_expiry(storage, user, None)?;
+
+    totals.total_score -= u64::from(current_score);
+    totals.users -= 1;
+
+    change_msgs(storage, user, current_score, 0, block)
+}
+
+/// Journals a score change, records it in the deltas ring, trims history past retention
+/// and returns the messages announcing it to the mirror token
+/// and the notifier.
+fn change_msgs(storage: &mut dyn Storage, user: &str, old: u32, new: u32, block: &BlockInfo) -> StdResult<Vec<SubMsg>> {
+    let height = block.height;
+    record_change(storage, user, old, new, height)?;
+    record_delta(storage, user, old, new, height)?;
+    record_timeline(storage, user, height, TimelineEvent::ScoreChanged { old, new })?;
+    record_observation(storage, user, new, block.time.seconds())?;
+    trim_history(storage, height, TRIM_PER_WRITE)?;
+    let mut msgs = mirror_msgs(storage, user, old, new)?;
+    msgs.extend(notify_msgs(storage, user, old, new)?);
+    Ok(msgs)
+}
+
+/// Replaces a user's expiry, keeping the purge queue in step.
+fn set_expiry(storage: &mut dyn Storage, user: &str, expires_at: Option<Timestamp>) -> StdResult<()> {
+    let old = EXPIRIES.may_load(storage, user.to_string())?;
+    if old == expires_at {
+        return Ok(());
+    }
+    if let Some(old) = old {
+        EXPIRY_QUEUE.remove(storage, (old.nanos(), user.to_string()));
+    }
+    match expires_at {
+        Some(expires_at) => {
+            EXPIRIES.save(storage, user.to_string(), &expires_at)?;
+            EXPIRY_QUEUE.save(storage, (expires_at.nanos(), user.to_string()), &())?;
+        }
+        None => EXPIRIES.remove(storage, user.to_string()),
+    }
+    Ok(())
+}
+
+fn assert_not_paused(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
+    if is_paused(storage, env)? {
+        return Err(ContractError::Paused {});
+    }
+    if active_freeze(storage, env)?.is_some() {
+        return Err(ContractError::Frozen {});
+    }
+    Ok(())
+}
+
+pub fn try_freeze_leaderboard(deps: DepsMut, env: Env, until: Option<Timestamp>) -> Result<Response, ContractError> {
+    if until.map_or(false, |until| until <= env.block.time) {
+        return Err(ContractError::InvalidFreeze {});
+    }
+
+    FREEZE.save(deps.storage, &Freeze { until })?;
+
+    Ok(method_response("try_freeze_leaderboard")
+        .add_attribute("until", until.map(|until| until.to_string()).unwrap_or_default()))
+}
+
+pub fn try_unfreeze_leaderboard(deps: DepsMut) -> Result<Response, ContractError> {
+    FREEZE.remove(deps.storage);
+
+    Ok(method_response("try_unfreeze_leaderboard"))
+}
+
+pub fn try_schedule_maintenance(
+    deps: DepsMut,
+    env: Env,
+    start: Timestamp,
+    end: Timestamp,
+) -> Result<Response, ContractError> {
+    schedule_window(deps.storage, &env, start, end)?;
+
+    Ok(method_response("try_schedule_maintenance")
+        .add_attribute("start", start.to_string())
+        .add_attribute("end", end.to_string()))
+}
+
+pub fn try_cancel_maintenance(deps: DepsMut, start: Timestamp) -> Result<Response, ContractError> {
+    cancel_window(deps.storage, start)?;
+
+    Ok(method_response("try_cancel_maintenance").add_attribute("start", start.to_string()))
+}
+
+pub fn try_rotate_submission_key(
+    deps: DepsMut,
+    env: Env,
+    new_pubkey: Binary,
+    effective_at: Timestamp,
+) -> Result<Response, ContractError> {
+    rotate_key(deps.storage, &env, new_pubkey.clone(), effective_at)?;
+
+    Ok(method_response("try_rotate_submission_key")
+        .add_attribute("pubkey", new_pubkey.to_base64())
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Credits a voucher's points to its user, once per voucher id.
+pub fn try_redeem_voucher(deps: DepsMut, env: Env, voucher: Voucher, signature: Binary) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    verify_voucher(deps.as_ref(), &env, &voucher, &signature)?;
+    let user = normalize_addr(deps.api, &voucher.user)?;
+    assert_not_reserved(&env, &CONFIG.load(deps.storage)?, &user)?;
+    REDEEMED_VOUCHERS.save(deps.storage, voucher.id, &env.block.height)?;
+
+    let messages = credit_points(deps.storage, &env, &user, voucher.points)?;
+    Ok(method_response("try_redeem_voucher")
+        .add_attribute("voucher_id", voucher.id.to_string())
+        .add_attribute("user", user)
+        .add_attribute("points", voucher.points.to_string())
+        .add_submessages(messages))
+}
+
+pub fn try_set_voucher_key(deps: DepsMut, pubkey: Option<Binary>) -> Result<Response, ContractError> {
+    set_voucher_key(deps.storage, pubkey.clone())?;
+    let pubkey = pubkey.map_or_else(|| "none".to_string(), |pubkey| pubkey.to_base64());
+    Ok(method_response("try_set_voucher_key").add_attribute("pubkey", pubkey))
+}
+
+pub fn try_set_paused(deps: DepsMut, paused: bool) -> Result<Response, ContractError> {
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(method_response("try_set_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn try_read_only_forwarding(deps: DepsMut, successor: Option<String>) -> Result<Response, ContractError> {
+    let successor = successor.map(|successor| deps.api.addr_validate(&successor)).transpose()?;
+    match &successor {
+        Some(successor) => SUCCESSOR.save(deps.storage, successor)?,
+        None => SUCCESSOR.remove(deps.storage),
+    }
+    Ok(method_response("try_read_only_forwarding").add_event(evt_read_only_forwarding(successor.as_ref().map(Addr::as_str))))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_rescale_scores(
+    deps: DepsMut,
+    env: Env,
+    numerator: u32,
+    denominator: u32,
+    rounding: Rounding,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if !is_paused(deps.storage, &env)? {
+        return Err(ContractError::NotPaused {});
+    }
+    if denominator == 0 {
+        return Err(ContractError::ZeroDenominator {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let page = SCORES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let more = page.len() > limit;
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let mut messages = vec![];
+    let mut last = None;
+    for (user, score) in page.into_iter().take(limit) {
+        let rescaled = rescale(score, numerator, denominator, rounding);
+        messages.extend(set_score(deps.storage, &config, &mut totals, &user, rescaled, None, &env.block)?);
+        last = Some(user);
+    }
+    TOTALS.save(deps.storage, &totals)?;
+
+    let mut res = method_response("try_rescale_scores").add_submessages(messages);
+    // Resume from here on the next call
+    if let (true, Some(last)) = (more, last) {
+        res = res.add_attribute("next_start_after", last);
+    }
+    Ok(res)
+}
+
+/// Moves score entries stored under a non-normalized address to the normalized one, one
+/// page at a time while paused. When both forms have an entry, the higher score stays.
+/// Entries that aren't valid addresses at all are left alone.
+pub fn try_normalize_addresses(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if !is_paused(deps.storage, &env)? {
+        return Err(ContractError::NotPaused {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let page = SCORES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let more = page.len() > limit;
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let mut messages = vec![];
+    let mut moved = 0u32;
+    let mut last = None;
+    for (user, score) in page.into_iter().take(limit) {
+        let normalized = match normalize_addr(deps.api, &user) {
+            Ok(normalized) if normalized != user => normalized,
+            _ => {
+                last = Some(user);
+                continue;
+            }
+        };
+        let achieved_at = ACHIEVED_AT.may_load(deps.storage, user.clone())?;
+        let expires_at = EXPIRIES.may_load(deps.storage, user.clone())?;
+        messages.extend(remove_score(deps.storage, &mut totals, &user, &env.block)?);
+        if SCORES.may_load(deps.storage, normalized.clone())?.map_or(true, |existing| existing < score) {
+            messages.extend(set_score(deps.storage, &config, &mut totals, &normalized, score, achieved_at, &env.block)?);
+            set_expiry(deps.storage, &normalized, expires_at)?;
+        }
+        moved += 1;
+        last = Some(user);
+    }
+    TOTALS.save(deps.storage, &totals)?;
+
+    let mut res = method_response("try_normalize_addresses")
+        .add_submessages(messages)
+        .add_attribute("moved", moved.to_string());
+    // Resume from here on the next call
+    if let (true, Some(last)) = (more, last) {
+        res = res.add_attribute("next_start_after", last);
+    }
+    Ok(res)
+}
+
+/// Re-derives SCORE_INDEX from SCORES and ACHIEVED_AT, a page at a time while paused.
+/// Each call writes the right key for a page of users and sweeps a page of the index,
+/// dropping keys that don't match their user's entry. The index isn't ordered by user,
+/// so the sweep keeps its own cursor; a call without `start_after` starts both over.
+pub fn try_rebuild_index(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if !is_paused(deps.storage, &env)? {
+        return Err(ContractError::NotPaused {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT) as usize;
+    if start_after.is_none() {
+        REINDEX_CURSOR.remove(deps.storage);
+    }
+
+    let start = start_after.clone().map(Bound::exclusive);
+    let page = SCORES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let more_users = page.len() > limit;
+    let mut added = 0u32;
+    let mut last = None;
+    for (user, score) in page.into_iter().take(limit) {
+        let achieved_at = ACHIEVED_AT.may_load(deps.storage, user.clone())?.unwrap_or_default();
+        let key = index_key(score, achieved_at, &user);
+        if SCORE_INDEX.may_load(deps.storage, key.clone())?.is_none() {
+            index_add(deps.storage, &key)?;
+            added += 1;
+        }
+        last = Some(user);
+    }
+
+    let start = REINDEX_CURSOR.may_load(deps.storage)?.map(Bound::exclusive);
+    let mut keys = SCORE_INDEX
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let more_keys = keys.len() > limit;
+    keys.truncate(limit);
+    let mut removed = 0u32;
+    for key in &keys {
+        if current_key(deps.storage, &key.2)?.as_ref() != Some(key) {
+            index_remove(deps.storage, key)?;
+            removed += 1;
+        }
+    }
+    // Stays at the last key once the sweep is through, so later calls only check keys
+    // the user pass adds
+    if let Some(key) = keys.last() {
+        REINDEX_CURSOR.save(deps.storage, key)?;
+    }
+
+    let done = !more_users && !more_keys;
+    let mut res = method_response("try_rebuild_index")
+        .add_attribute("added", added.to_string())
+        .add_attribute("removed", removed.to_string())
+        .add_attribute("done", done.to_string());
+    // Resume from here on the next call; once users are through it stays put while the
+    // sweep catches up
+    if let (false, Some(next)) = (done, last.or(start_after)) {
+        res = res.add_attribute("next_start_after", next);
+    }
+    Ok(res)
+}
+
+fn rescale(score: u32, numerator: u32, denominator: u32, rounding: Rounding) -> u32 {
+    let product = u64::from(score) * u64::from(numerator);
+    let denominator = u64::from(denominator);
+    let scaled = match rounding {
+        Rounding::Down => product / denominator,
+        Rounding::Up => (product + denominator - 1) / denominator,
+        Rounding::Nearest => (product + denominator / 2) / denominator,
+    };
+    scaled.min(u64::from(u32::MAX)) as u32
+}
+
+pub fn try_reset_all_scores(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    confirmation: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_RESET.may_load(deps.storage)?;
+    let confirmation = match confirmation {
+        Some(confirmation) => confirmation,
+        None => {
+            // Phase one: hand out a fresh token, replacing any unconfirmed request
+            if pending.map_or(false, |pending| pending.confirmed) {
+                return Err(ContractError::ResetInProgress {});
+            }
+            let token = reset_token(&env, &info);
+            let expires_at = env.block.height + RESET_CONFIRMATION_BLOCKS;
+            PENDING_RESET.save(deps.storage, &PendingReset { token: token.clone(), expires_at, confirmed: false })?;
+            return Ok(method_response("try_reset_all_scores")
+                .add_attribute("confirmation_token", token)
+                .add_attribute("expires_at", expires_at.to_string()));
+        }
+    };
+
+    // Phase two: confirm, then delete a batch per call
+    let mut pending = pending.ok_or(ContractError::NoPendingReset {})?;
+    if pending.token != confirmation {
+        return Err(ContractError::InvalidConfirmation {});
+    }
+    if !pending.confirmed && env.block.height > pending.expires_at {
+        return Err(ContractError::ResetExpired { expires_at: pending.expires_at });
+    }
+    pending.confirmed = true;
+
+    // Deleted entries drop out of the range, so every batch starts from the beginning
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT) as usize;
+    let users = SCORES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let done = users.len() <= limit;
+
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let mut messages = vec![];
+    for user in users.iter().take(limit) {
+        messages.extend(remove_score(deps.storage, &mut totals, user, &env.block)?);
+    }
+    TOTALS.save(deps.storage, &totals)?;
+
+    if done {
+        PENDING_RESET.remove(deps.storage);
+    } else {
+        PENDING_RESET.save(deps.storage, &pending)?;
+    }
+
+    Ok(method_response("try_reset_all_scores")
+        .add_submessages(messages)
+        .add_attribute("removed", users.len().min(limit).to_string())
+        .add_attribute("done", done.to_string()))
+}
+
+pub fn try_rollback(deps: DepsMut, env: Env, users: Vec<String>) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let config = CONFIG.load(deps.storage)?;
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let mut res = method_response("try_rollback");
+    for user in users {
+        let user = normalize_addr(deps.api, &user)?;
+        assert_not_reserved(&env, &config, &user)?;
+        let previous = PREVIOUS_SCORES
+            .may_load(deps.storage, user.clone())?
+            .ok_or_else(|| ContractError::NothingToRollBack { user: user.clone() })?;
+        let closed_at = previous.recorded_at + config.rollback_window;
+        if env.block.height > closed_at {
+            return Err(ContractError::RollbackExpired { user, closed_at });
+        }
+
+        let from = SCORES.may_load(deps.storage, user.clone())?;
+        // Restores the old tie-break position too; undoing isn't itself undoable
+        let messages = match previous.score {
+            Some(score) => set_score(deps.storage, &config, &mut totals, &user, score, Some(previous.achieved_at), &env.block)?,
+            None => remove_score(deps.storage, &mut totals, &user, &env.block)?,
+        };
+        if SCORES.has(deps.storage, user.clone()) {
+            set_expiry(deps.storage, &user, previous.expires_at)?;
+        }
+        PREVIOUS_SCORES.remove(deps.storage, user.clone());
+
+        res = res.add_submessages(messages).add_event(evt_score_rollback(&user, from, previous.score));
+    }
+    TOTALS.save(deps.storage, &totals)?;
+
+    Ok(res)
+}
+
+/// Deletes a user's entry. Within Config::removal_grace_period RestoreScore can bring
+/// it back as it was; the tombstone is purged lazily afterwards.
+pub fn try_remove_score(deps: DepsMut, env: Env, user: String) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let user = normalize_addr(deps.api, &user)?;
+    let key = current_key(deps.storage, &user)?.ok_or_else(|| ContractError::NoScore { user: user.clone() })?;
+    let config = CONFIG.load(deps.storage)?;
+    purge_tombstones(deps.storage, &env)?;
+    if config.removal_grace_period > 0 {
+        let removed = RemovedScore {
+            score: u32::MAX - key.0,
+            achieved_at: key.1,
+            expires_at: EXPIRIES.may_load(deps.storage, user.clone())?,
+            restorable_until: env.block.time.plus_seconds(config.removal_grace_period),
+        };
+        record_tombstone(deps.storage, &user, &removed)?;
+    }
+
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let messages = remove_score(deps.storage, &mut totals, &user, &env.block)?;
+    TOTALS.save(deps.storage, &totals)?;
+
+    Ok(method_response("try_remove_score")
+        .add_submessages(messages)
+        .add_event(evt_score_removed(&user, u32::MAX - key.0)))
+}
+
+pub fn try_restore_score(deps: DepsMut, env: Env, user: String) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let user = normalize_addr(deps.api, &user)?;
+    // Anything written since the removal is newer than the tombstone, so it wins
+    if SCORES.has(deps.storage, user.clone()) {
+        return Err(ContractError::ScoreExists { user });
+    }
+    let removed = take_tombstone(deps.storage, &user)?.ok_or_else(|| ContractError::NothingToRestore { user: user.clone() })?;
+    if env.block.time >= removed.restorable_until {
+        return Err(ContractError::GracePeriodOver { user, closed_at: removed.restorable_until });
+    }
+    purge_tombstones(deps.storage, &env)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let messages = set_score(deps.storage, &config, &mut totals, &user, removed.score, Some(removed.achieved_at), &env.block)?;
+    set_expiry(deps.storage, &user, removed.expires_at)?;
+    TOTALS.save(deps.storage, &totals)?;
+
+    Ok(method_response("try_restore_score")
+        .add_submessages(messages)
+        .add_event(evt_score_restored(&user, removed.score)))
+}
+
+/// Whether the user's penalty is under appeal or may still be appealed.
+fn penalty_pending(storage: &dyn Storage, env: &Env, user: &str) -> StdResult<bool> {
+    let penalty = PENALTIES.may_load(storage, user.to_string())?;
+    Ok(penalty.map_or(false, |penalty| penalty.appeal.is_some() || env.block.time <= penalty.appeal_deadline))
+}
+
+pub fn try_penalize(deps: DepsMut, env: Env, user: String, points: u32, reason: String) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let user = normalize_addr(deps.api, &user)?;
+    // A flagged user's score may still be reverted, which would erase the deduction
+    assert_points_movable(deps.storage, &env, &CONFIG.load(deps.storage)?, &user)?;
+    // One case per user at a time; a closed, unappealed penalty is simply final
+    if penalty_pending(deps.storage, &env, &user)? {
+        return Err(ContractError::PenaltyPending { user });
+    }
+    PENALTIES.remove(deps.storage, user.clone());
+
+    let config = CONFIG.load(deps.storage)?;
+    let current = SCORES.may_load(deps.storage, user.clone())?.ok_or_else(|| ContractError::NoScore { user: user.clone() })?;
+    let score = current.saturating_sub(points).max(config.score_floor).min(current);
+    let deducted = current - score;
+    // Nothing to take at or below the floor
+    if deducted == 0 {
+        return Ok(method_response("try_penalize").add_attribute("points", "0"));
+    }
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let messages = set_score(deps.storage, &config, &mut totals, &user, score, Some(env.block.height), &env.block)?;
+    TOTALS.save(deps.storage, &totals)?;
+
+    let event = TimelineEvent::Penalized { points: deducted, reason: reason.clone() };
+    record_timeline(deps.storage, &user, env.block.height, event)?;
+    if config.appeal_window > 0 {
+        let penalty = Penalty {
+            points: deducted,
+            reason: reason.clone(),
+            applied_at: env.block.time,
+            appeal_deadline: env.block.time.plus_seconds(config.appeal_window),
+            appeal: None,
+        };
+        PENALTIES.save(deps.storage, user.clone(), &penalty)?;
+    }
+
+    Ok(method_response("try_penalize")
+        .add_submessages(messages)
+        .add_event(evt_penalty(&user, deducted, &reason)))
+}
+
+pub fn try_appeal(deps: DepsMut, env: Env, info: MessageInfo, reason: String) -> Result<Response, ContractError> {
+    let user = info.sender.to_string();
+    let mut penalty = PENALTIES
+        .may_load(deps.storage, user.clone())?
+        .ok_or_else(|| ContractError::NoPenalty { user: user.clone() })?;
+    if penalty.appeal.is_some() {
+        return Err(ContractError::AlreadyAppealed {});
+    }
+    if env.block.time > penalty.appeal_deadline {
+        return Err(ContractError::AppealWindowClosed { closed_at: penalty.appeal_deadline });
+    }
+    let case_id = NEXT_CASE_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_CASE_ID.save(deps.storage, &(case_id + 1))?;
+    OPEN_APPEALS.save(deps.storage, case_id, &user)?;
+    penalty.appeal = Some(Appeal { case_id, reason, filed_at: env.block.time });
+    PENALTIES.save(deps.storage, user.clone(), &penalty)?;
+
+    Ok(method_response("try_appeal")
+        .add_attribute("user", user)
+        .add_attribute("case_id", case_id.to_string()))
+}
+
+pub fn try_resolve_appeal(deps: DepsMut, env: Env, info: MessageInfo, user: String, uphold: bool) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let arbiter = match config.arbiter.clone() {
+        Some(arbiter) => arbiter,
+        None => STATE.load(deps.storage)?.owner,
+    };
+    if info.sender != arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+    let user = normalize_addr(deps.api, &user)?;
+    assert_not_reserved(&env, &config, &user)?;
+
+    let penalty = PENALTIES
+        .may_load(deps.storage, user.clone())?
+        .ok_or_else(|| ContractError::NoPenalty { user: user.clone() })?;
+    let case_id = match &penalty.appeal {
+        Some(appeal) => appeal.case_id,
+        None => return Err(ContractError::NotAppealed { user }),
+    };
+    PENALTIES.remove(deps.storage, user.clone());
+    OPEN_APPEALS.remove(deps.storage, case_id);
+
+    // Overturning hands the escrowed points back on top of the current score
+    let mut messages = vec![];
+    if !uphold {
+        messages = release_points(deps.storage, &env, &user, penalty.points)?;
+    }
+    record_timeline(deps.storage, &user, env.block.height, TimelineEvent::AppealResolved { case_id, upheld: uphold })?;
+
+    Ok(method_response("try_resolve_appeal")
+        .add_submessages(messages)
+        .add_event(evt_appeal_resolved(case_id, &user, uphold, penalty.points)))
+}
+
+pub fn try_set_arbiter(deps: DepsMut, addr: Option<String>) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+
+    let arbiter = addr.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    if arbiter.as_ref() == Some(&state.owner) {
+        return Err(ContractError::ArbiterIsOwner {});
+    }
+    let mut config = CONFIG.load(deps.storage)?;
+    config.arbiter = arbiter;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(method_response("try_set_arbiter"))
+}
+
+pub fn try_start_new_season(deps: DepsMut, env: Env, limit: Option<u32>) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    // The first call closes the season; it and any follow-up calls archive a batch each
+    let (season, summary) = match ARCHIVING.may_load(deps.storage)? {
+        Some(archiving) => (archiving.season, vec![]),
+        None => end_season(deps.storage, &env)?,
+    };
+
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT);
+    let (archived, done, messages) = archive_batch(deps.storage, limit, &env.block)?;
+
+    Ok(method_response("try_start_new_season")
+        .add_submessages(messages)
+        .add_events(summary)
+        .add_attribute("season", season.to_string())
+        .add_attribute("archived", archived.to_string())
+        .add_attribute("done", done.to_string()))
+}
+
+/// Rolls an elapsed season over and archives a batch of the previous one, so seasons
+/// advance with whatever traffic the contract gets. Does nothing in manual mode or
+/// while paused or frozen. A contract idle for several seasons' worth of time only rolls over
+/// once; the next season starts at the rollover. Returns the archive's messages and the
+/// summary events of a rollover.
+fn advance_season(storage: &mut dyn Storage, env: &Env, config: &Config) -> Result<(Vec<SubMsg>, Vec<Event>), ContractError> {
+    if config.season_duration == 0 || is_paused(storage, env)? || active_freeze(storage, env)?.is_some() {
+        return Ok((vec![], vec![]));
+    }
+
+    let mut summary = vec![];
+    if ARCHIVING.may_load(storage)?.is_none() {
+        let current = SEASONS.load(storage, CURRENT_SEASON.load(storage)?)?;
+        if env.block.time < current.start_time.plus_seconds(config.season_duration) {
+            return Ok((vec![], vec![]));
+        }
+        summary = end_season(storage, env)?.1;
+    }
+    let (_, _, messages) = archive_batch(storage, AUTO_ARCHIVE_BATCH, &env.block)?;
+    Ok((messages, summary))
+}
+
+/// Closes the current season and opens the next, returning the ended season's id and
+/// summary events. Its scores stay live until archived.
+fn end_season(storage: &mut dyn Storage, env: &Env) -> StdResult<(u32, Vec<Event>)> {
+    let season = CURRENT_SEASON.load(storage)?;
+    let mut ended = SEASONS.load(storage, season)?;
+    ended.end_height = Some(env.block.height);
+    SEASONS.save(storage, season, &ended)?;
+    let totals = TOTALS.may_load(storage)?.unwrap_or_default();
+    let summary = summarize_season(storage, &ended, &totals, env.block.time)?;
+
+    let next = season + 1;
+    let started = Season { id: next, start_height: env.block.height, start_time: env.block.time, end_height: None, archived: false };
+    SEASONS.save(storage, next, &started)?;
+    CURRENT_SEASON.save(storage, &next)?;
+    ARCHIVING.save(storage, &Archiving { season, end_height: env.block.height, cursor: None })?;
+    Ok((season, summary))
+}
+
+/// Moves up to `limit` stale entries into the archive, returning how many were moved and
+/// whether the season is now completely archived.
+fn archive_batch(storage: &mut dyn Storage, limit: u32, block: &BlockInfo) -> StdResult<(u32, bool, Vec<SubMsg>)> {
+    let mut archiving = match ARCHIVING.may_load(storage)? {
+        Some(archiving) => archiving,
+        None => return Ok((0, true, vec![])),
+    };
+
+    // Entries written since the rollover stay, so the scan resumes from a cursor
+    let limit = limit as usize;
+    let start = archiving.cursor.clone().map(Bound::exclusive);
+    let users = SCORES
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let done = users.len() <= limit;
+
+    let mut totals = TOTALS.may_load(storage)?.unwrap_or_default();
+    let mut messages = vec![];
+    let mut archived = 0u32;
+    for user in users.iter().take(limit) {
+        if let Some(moved) = archive_if_stale(storage, &mut totals, user, block)? {
+            messages.extend(moved);
+            archived += 1;
+        }
+    }
+    TOTALS.save(storage, &totals)?;
+
+    if done {
+        ARCHIVING.remove(storage);
+        SEASONS.update(storage, archiving.season, |ended| -> StdResult<_> {
+            let mut ended = ended.ok_or_else(|| StdError::not_found("Season"))?;
+            ended.archived = true;
+            Ok(ended)
+        })?;
+    } else {
+        archiving.cursor = users.get(limit - 1).cloned();
+        ARCHIVING.save(storage, &archiving)?;
+    }
+    Ok((archived, done, messages))
+}
+
+/// Archives and removes `user`'s live entry if it belongs to the season being archived,
+/// returning the removal's messages, or None when there was nothing to archive.
+fn archive_if_stale(storage: &mut dyn Storage, totals: &mut Totals, user: &str, block: &BlockInfo) -> StdResult<Option<Vec<SubMsg>>> {
+    let archiving = match ARCHIVING.may_load(storage)? {
+        Some(archiving) => archiving,
+        None => return Ok(None),
+    };
+    let key = match current_key(storage, user)? {
+        Some(key) if key.1 < archiving.end_height => key,
+        _ => return Ok(None),
+    };
+
+    let archived = ArchivedScore { score: u32::MAX - key.0, achieved_at: key.1 };
+    SEASON_SCORES.save(storage, (archiving.season, user.to_string()), &archived)?;
+    SEASON_INDEX.save(storage, (archiving.season, key.clone()), &())?;
+    // The final score stays part of the all-time score
+    remove_entry(storage, totals, user, key, block).map(Some)
+}
+
+pub fn try_compact_zero_scores(deps: DepsMut, env: Env, limit: Option<u32>) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT) as usize;
+    let start = COMPACT_CURSOR.may_load(deps.storage)?.map(Bound::exclusive);
+    let page = SCORES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let mut removed = 0u32;
+    let mut messages = vec![];
+    for (user, _) in page.iter().filter(|(_, score)| *score == 0) {
+        messages.extend(remove_score(deps.storage, &mut totals, user, &env.block)?);
+        removed += 1;
+    }
+    TOTALS.save(deps.storage, &totals)?;
+
+    // A short page means we reached the end; the next run starts over
+    let done = page.len() < limit;
+    match page.last() {
+        Some((user, _)) if !done => COMPACT_CURSOR.save(deps.storage, user)?,
+        _ => COMPACT_CURSOR.remove(deps.storage),
+    }
+
+    Ok(method_response("try_compact_zero_scores")
+        .add_submessages(messages)
+        .add_attribute("scanned", page.len().to_string())
+        .add_attribute("removed", removed.to_string())
+        .add_attribute("done", done.to_string()))
+}
+
+pub fn try_purge_expired(deps: DepsMut, env: Env, limit: Option<u32>) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT) as usize;
+    let now = env.block.time.nanos();
+    let due = EXPIRY_QUEUE
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take_while(|key| key.as_ref().map_or(true, |(expires_at, _)| *expires_at <= now))
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let mut messages = vec![];
+    for (_, user) in due.iter() {
+        messages.extend(remove_score(deps.storage, &mut totals, user, &env.block)?);
+    }
+    TOTALS.save(deps.storage, &totals)?;
+
+    Ok(method_response("try_purge_expired")
+        .add_submessages(messages)
+        .add_attribute("purged", due.len().to_string()))
+}
+
+// Not secret, just unguessable enough that a retried script can't confirm its own request
+fn reset_token(env: &Env, info: &MessageInfo) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(env.contract.address.as_bytes());
+    hasher.update(info.sender.as_bytes());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    hex::encode(&hasher.finalize()[..8])
+}
+
+pub fn try_commit_scores(deps: DepsMut, env: Env, hash: Binary) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    // An open commitment can't be swapped for another once results are known
+    if let Some(pending) = SCORE_COMMITMENT.may_load(deps.storage)? {
+        if env.block.height <= pending.expires_at {
+            return Err(ContractError::CommitmentPending { expires_at: pending.expires_at });
+        }
+    }
+
+    let expires_at = env.block.height + REVEAL_WINDOW_BLOCKS;
+    SCORE_COMMITMENT.save(deps.storage, &ScoreCommitment { hash, expires_at })?;
+
+    Ok(method_response("try_commit_scores")
+        .add_attribute("expires_at", expires_at.to_string()))
+}
+
+pub fn try_reveal_scores(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    updates: Vec<ScoreUpdate>,
+    salt: Binary,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let pending = SCORE_COMMITMENT.may_load(deps.storage)?.ok_or(ContractError::NoCommitment {})?;
+    if env.block.height > pending.expires_at {
+        return Err(ContractError::CommitmentExpired { expires_at: pending.expires_at });
+    }
+    if commitment_hash(&updates, &salt)? != pending.hash {
+        return Err(ContractError::InvalidReveal {});
+    }
+    SCORE_COMMITMENT.remove(deps.storage);
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let total_before = totals.total_score;
+    let mut messages = vec![];
+    let mut events = vec![];
+    let mut applied = 0u32;
+    for update in updates.iter() {
+        let user = normalize_addr(deps.api, update.user.as_str())?;
+        assert_not_reserved(&env, &config, &user)?;
+        assert_eligible(&deps.querier, &config, &user)?;
+        let (written, msgs, anomaly) = apply_update(deps.storage, &env, &config, &mut totals, &user, update.score)?;
+        messages.extend(msgs);
+        events.extend(anomaly);
+        if written.is_some() {
+            applied += 1;
+        }
+    }
+    TOTALS.save(deps.storage, &totals)?;
+    if applied > 0 {
+        let points = totals.total_score.saturating_sub(total_before);
+        record_activity(deps.storage, &info.sender, applied.into(), points, env.block.height)?;
+    }
+
+    Ok(method_response("try_reveal_scores")
+        .add_submessages(messages)
+        .add_attribute("applied", applied.to_string())
+        .add_events(events))
+}
+
+/// Hash to pass to `CommitScores`: sha256 over the JSON-encoded updates followed by the salt.
+pub fn commitment_hash(updates: &[ScoreUpdate], salt: &Binary) -> StdResult<Binary> {
+    let mut hasher = Sha256::new();
+    hasher.update(to_vec(updates)?);
+    hasher.update(salt.as_slice());
+    Ok(Binary::from(hasher.finalize().as_slice()))
+}
+
+pub fn try_set_mirror_enabled(deps: DepsMut, enabled: bool) -> Result<Response, ContractError> {
+    let mut mirror = MIRROR.may_load(deps.storage)?.ok_or(ContractError::MirrorNotConfigured {})?;
+    mirror.enabled = enabled;
+    MIRROR.save(deps.storage, &mirror)?;
+
+    Ok(method_response("try_set_mirror_enabled")
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+pub fn try_decouple_mirror(deps: DepsMut) -> Result<Response, ContractError> {
+    if MIRROR.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::MirrorNotConfigured {});
+    }
+    MIRROR.remove(deps.storage);
+
+    Ok(method_response("try_decouple_mirror"))
+}
+
+pub fn try_update_config(deps: DepsMut, update: ConfigUpdate) -> Result<Response, ContractError> {
+    let event = evt_config_updated(&update)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(raw_queries) = update.raw_queries {
+        config.raw_queries = raw_queries;
+    }
+    if let Some(prune_zero_scores) = update.prune_zero_scores {
+        config.prune_zero_scores = prune_zero_scores;
+    }
+    if let Some(min_delta) = update.min_delta {
+        config.min_delta = min_delta;
+    }
+    if let Some(small_delta_policy) = update.small_delta_policy {
+        config.small_delta_policy = small_delta_policy;
+    }
+    if let Some(private_scores) = update.private_scores {
+        config.private_scores = private_scores;
+    }
+    if let Some(season_duration) = update.season_duration {
+        if season_duration > 0 {
+            assert_enabled(deps.storage, Feature::Seasons)?;
+        }
+        config.season_duration = season_duration;
+    }
+    if let Some(rollback_window) = update.rollback_window {
+        config.rollback_window = rollback_window;
+    }
+    if let Some(appeal_window) = update.appeal_window {
+        config.appeal_window = appeal_window;
+    }
+    if let Some(score_formula) = update.score_formula {
+        if let ScoreFormula::Multiplier { denominator: 0, .. } = score_formula {
+            return Err(ContractError::ZeroDenominator {});
+        }
+        config.score_formula = score_formula;
+    }
+    if let Some(reserved_addresses) = update.reserved_addresses {
+        config.reserved_addresses = reserved_addresses
+            .iter()
+            .map(|addr| deps.api.addr_validate(addr))
+            .collect::<StdResult<_>>()?;
+    }
+    if let Some(event_prefix) = update.event_prefix {
+        config.event_prefix = validate_event_prefix(event_prefix)?;
+    }
+    if let Some(score_floor) = update.score_floor {
+        config.score_floor = score_floor;
+    }
+    if let Some(anomaly_multiple) = update.anomaly_multiple {
+        config.anomaly_multiple = anomaly_multiple;
+    }
+    if let Some(merge_policy) = update.merge_policy {
+        config.merge_policy = merge_policy;
+    }
+    if let Some(idempotency_ttl) = update.idempotency_ttl {
+        if idempotency_ttl == 0 {
+            return Err(ContractError::ZeroIdempotencyTtl {});
+        }
+        config.idempotency_ttl = idempotency_ttl;
+    }
+    if let Some(duplicate_key_policy) = update.duplicate_key_policy {
+        config.duplicate_key_policy = duplicate_key_policy;
+    }
+    if let Some(removal_grace_period) = update.removal_grace_period {
+        config.removal_grace_period = removal_grace_period;
+    }
+    if let Some(score_bucket) = update.score_bucket {
+        config.score_bucket = score_bucket;
+    }
+    if let Some(max_query_limit) = update.max_query_limit {
+        if max_query_limit == 0 || max_query_limit > MAX_QUERY_LIMIT {
+            return Err(ContractError::InvalidQueryLimit { max: MAX_QUERY_LIMIT });
+        }
+        config.max_query_limit = max_query_limit;
+    }
+    if let Some(max_query_scan) = update.max_query_scan {
+        if max_query_scan == 0 || max_query_scan > MAX_QUERY_SCAN {
+            return Err(ContractError::InvalidQueryLimit { max: MAX_QUERY_SCAN });
+        }
+        config.max_query_scan = max_query_scan;
+    }
+    if let Some(fee_routes) = update.fee_routes {
+        config.fee_routes = validate_fee_routes(deps.api, fee_routes)?;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(method_response("try_update_config").add_event(event))
+}
+
+fn validate_fee_routes(api: &dyn Api, routes: Vec<FeeRouteInit>) -> Result<Vec<FeeRoute>, ContractError> {
+    let total: u32 = routes.iter().map(|route| u32::from(route.bps)).sum();
+    if total > u32::from(TOTAL_BPS) || routes.iter().any(|route| route.bps == 0) {
+        return Err(ContractError::InvalidFeeRoutes {});
+    }
+    routes
+        .into_iter()
+        .map(|route| {
+            let addr = route.addr.map(|addr| api.addr_validate(&addr)).transpose()?;
+            Ok(FeeRoute { addr, bps: route.bps })
+        })
+        .collect()
+}
+
+pub fn try_transfer_ownership(deps: DepsMut, env: Env, new_owner: String) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let old_owner = std::mem::replace(&mut state.owner, new_owner.clone());
+    STATE.save(deps.storage, &state)?;
+    record_owner_change(deps.storage, &env, Some(old_owner), new_owner.clone())?;
+
+    Ok(method_response("try_transfer_ownership")
+        .add_attribute("new_owner", new_owner))
+}
+
+fn record_owner_change(storage: &mut dyn Storage, env: &Env, old: Option<Addr>, new: Addr) -> StdResult<()> {
+    let id = OWNER_HISTORY
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last| last + 1);
+    let change = OwnerChange { id, old, new, height: env.block.height, time: env.block.time };
+    OWNER_HISTORY.save(storage, id, &change)?;
+    trim_history(storage, env.block.height, TRIM_PER_WRITE).map(|_| ())
+}
+
+pub fn try_set_user_gain_cap(deps: DepsMut, cap: Option<EpochCap>) -> Result<Response, ContractError> {
+    if cap.as_ref().map_or(false, |cap| cap.epoch_seconds == 0) {
+        return Err(ContractError::InvalidEpoch {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.user_gain_cap = cap;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(method_response("try_set_user_gain_cap"))
+}
+
+pub fn try_set_global_gain_cap(deps: DepsMut, cap: Option<EpochCap>) -> Result<Response, ContractError> {
+    if cap.as_ref().map_or(false, |cap| cap.epoch_seconds == 0) {
+        return Err(ContractError::InvalidEpoch {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.global_gain_cap = cap;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(method_response("try_set_global_gain_cap"))
+}
+
+pub fn try_set_notifier(deps: DepsMut, addr: Option<String>) -> Result<Response, ContractError> {
+    let res = method_response("try_set_notifier");
+    match addr {
+        Some(addr) => {
+            let notifier = deps.api.addr_validate(&addr)?;
+            NOTIFIER.save(deps.storage, &notifier)?;
+            Ok(res.add_attribute("notifier", notifier))
+        }
+        None => {
+            NOTIFIER.remove(deps.storage);
+            Ok(res)
+        }
+    }
+}
+
+pub fn try_add_hook(deps: DepsMut, addr: String) -> Result<Response, ContractError> {
+    let hook = deps.api.addr_validate(&addr)?;
+    add_hook(deps.storage, hook.clone())?;
+
+    Ok(method_response("try_add_hook").add_attribute("hook", hook))
+}
+
+pub fn try_update_operators(deps: DepsMut, add: Vec<String>, remove: Vec<String>) -> Result<Response, ContractError> {
+    let validate = |addrs: Vec<String>| addrs.iter().map(|addr| deps.api.addr_validate(addr)).collect::<StdResult<Vec<_>>>();
+    let (add, remove) = (validate(add)?, validate(remove)?);
+    update_operators(deps.storage, add, remove)?;
+
+    Ok(method_response("try_update_operators"))
+}
+
+pub fn try_remove_hook(deps: DepsMut, addr: String) -> Result<Response, ContractError> {
+    let hook = deps.api.addr_validate(&addr)?;
+    remove_hook(deps.storage, hook.clone())?;
+
+    Ok(method_response("try_remove_hook").add_attribute("hook", hook))
+}
+
+pub fn try_set_brackets(deps: DepsMut, brackets: Vec<Bracket>) -> Result<Response, ContractError> {
+    let brackets = validate_brackets(brackets)?;
+    let count = brackets.len();
+    BRACKETS.save(deps.storage, &brackets)?;
+
+    Ok(method_response("try_set_brackets").add_attribute("brackets", count.to_string()))
+}
+
+pub fn try_set_shadow(deps: DepsMut, addr: Option<String>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.shadow = addr.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(method_response("try_set_shadow"))
+}
+
+pub fn try_set_gatekeeper(deps: DepsMut, addr: Option<String>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.gatekeeper = addr.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(method_response("try_set_gatekeeper"))
+}
+
+pub fn try_fund_pool(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let coin = match (info.funds.as_slice(), &pool.denom) {
+        ([coin], None) => coin,
+        ([coin], Some(denom)) if &coin.denom == denom => coin,
+        _ => return Err(ContractError::InvalidRewardFunds {}),
+    };
+    if coin.amount.is_zero() {
+        return Err(ContractError::InvalidRewardFunds {});
+    }
+    pool.denom = Some(coin.denom.clone());
+    pool.unallocated += coin.amount;
+    REWARD_POOL.save(deps.storage, &pool)?;
+    credit(deps.storage, REWARD_POOL_FUNDS, coin)?;
+
+    Ok(method_response("try_fund_pool")
+        .add_attribute("amount", coin.to_string()))
+}
+
+pub fn try_checkpoint(deps: DepsMut) -> Result<Response, ContractError> {
+    let mut pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let total_score = TOTALS.may_load(deps.storage)?.unwrap_or_default().total_score;
+    if pool.unallocated.is_zero() || total_score == 0 {
+        return Err(ContractError::NothingToDistribute {});
+    }
+    let allocated = checkpoint(&mut pool, total_score);
+    REWARD_POOL.save(deps.storage, &pool)?;
+
+    Ok(method_response("try_checkpoint")
+        .add_attribute("allocated", allocated)
+        .add_attribute("total_score", total_score.to_string()))
+}
+
+pub fn try_set_reward_stream(deps: DepsMut, env: Env, per_second: Uint128) -> Result<Response, ContractError> {
+    // execute() has already accrued at the old rate up to now
+    let mut pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+    pool.stream_per_second = per_second;
+    pool.last_accrual = env.block.time;
+    REWARD_POOL.save(deps.storage, &pool)?;
+
+    Ok(method_response("try_set_reward_stream")
+        .add_attribute("per_second", per_second))
+}
+
+pub fn try_claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let user = info.sender.to_string();
+    let claimable = query_claimable(deps.as_ref(), env.clone(), user.clone())?;
+    let denom = match claimable.denom {
+        Some(denom) if !claimable.amount.is_zero() => denom,
+        _ => return Err(ContractError::NothingToClaim {}),
+    };
+    let per_point = REWARD_POOL.load(deps.storage)?.per_point;
+    USER_REWARDS.save(deps.storage, user.clone(), &UserReward { index: per_point, pending: Uint128::zero() })?;
+    let amount = coin(claimable.amount.u128(), denom);
+    debit(deps.storage, REWARD_POOL_FUNDS, &amount)?;
+    record_timeline(deps.storage, &user, env.block.height, TimelineEvent::RewardsClaimed { amount: amount.clone() })?;
+
+    Ok(method_response("try_claim_rewards")
+        .add_attribute("amount", claimable.amount)
+        .add_message(BankMsg::Send { to_address: user, amount: vec![amount] }))
+}
+
+/// Deducts points the sender spends, recording the spend in their ledger. The score
+/// floor is not spendable. With a shop set, the shop must accept the spend too.
+pub fn try_spend_score(deps: DepsMut, env: Env, info: MessageInfo, amount: u32, memo: String) -> Result<Response, ContractError> {
+    let user = info.sender.to_string();
+    let (spend_id, messages) = spend(deps.storage, &env, &user, amount, &memo, None)?;
+
+    Ok(method_response("try_spend_score")
+        .add_submessages(messages)
+        .add_event(evt_spend(&user, spend_id, amount).add_attribute("memo", memo)))
+}
+
+/// Spends like SpendScore and hands `msg` to `contract` with proof of the spend, all in
+/// one transaction: if the contract rejects the call, the spend is reverted.
+pub fn try_spend_and_call(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: u32,
+    contract: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let user = info.sender.to_string();
+    let contract = deps.api.addr_validate(&contract)?;
+    let (spend_id, mut messages) = spend(deps.storage, &env, &user, amount, "", Some(contract.clone()))?;
+    messages.push(receive_spend_msg(&contract, &user, spend_id, amount, msg)?);
+
+    Ok(method_response("try_spend_and_call")
+        .add_submessages(messages)
+        .add_event(evt_spend(&user, spend_id, amount).add_attribute("contract", contract)))
+}
+
+/// Deducts and records a spend of `amount` by `user`, returning its id and the
+/// messages for the change and the shop.
+fn spend(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &str,
+    amount: u32,
+    memo: &str,
+    contract: Option<Addr>,
+) -> Result<(u64, Vec<SubMsg>), ContractError> {
+    assert_not_paused(storage, env)?;
+    if amount == 0 {
+        return Err(ContractError::InvalidSpendAmount {});
+    }
+    let mut messages = deduct_points(storage, env, user, amount)?;
+
+    let spend_id = SPENDS
+        .prefix(user.to_string())
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |last| last + 1);
+    let record = Spend { amount, memo: memo.to_string(), height: env.block.height, contract };
+    SPENDS.save(storage, (user.to_string(), spend_id), &record)?;
+    record_timeline(storage, user, env.block.height, TimelineEvent::Spent { spend_id, amount })?;
+    if let Some(shop) = SHOP.may_load(storage)? {
+        messages.push(shop_msg(&shop, user, spend_id, amount, memo)?);
+    }
+    Ok((spend_id, messages))
+}
+
+/// Fails for users whose points may not move: merged away, reserved or frozen.
+fn assert_points_movable(storage: &dyn Storage, env: &Env, config: &Config, user: &str) -> Result<(), ContractError> {
+    if let Some(primary) = MERGED.may_load(storage, user.to_string())? {
+        return Err(ContractError::Merged { user: user.to_string(), primary });
+    }
+    assert_not_reserved(env, config, user)?;
+    if PROVISIONAL.may_load(storage, user.to_string())?.is_some() {
+        return Err(ContractError::UserFrozen { user: user.to_string() });
+    }
+    Ok(())
+}
+
+/// Takes `amount` off the user's score, never below Config::score_floor.
+fn deduct_points(storage: &mut dyn Storage, env: &Env, user: &str, amount: u32) -> Result<Vec<SubMsg>, ContractError> {
+    let config = CONFIG.load(storage)?;
+    assert_points_movable(storage, env, &config, user)?;
+    let current = SCORES.may_load(storage, user.to_string())?.unwrap_or_default();
+    let available = current.saturating_sub(config.score_floor);
+    if amount > available {
+        return Err(ContractError::InsufficientScore { available, amount });
+    }
+    let mut totals = TOTALS.may_load(storage)?.unwrap_or_default();
+    let messages = set_score(storage, &config, &mut totals, user, current - amount, Some(env.block.height), &env.block)?;
+    TOTALS.save(storage, &totals)?;
+    Ok(messages)
+}
+
+/// Adds `amount` onto the user's score, e.g. points from a voucher.
+fn credit_points(storage: &mut dyn Storage, env: &Env, user: &str, amount: u32) -> Result<Vec<SubMsg>, ContractError> {
+    let config = CONFIG.load(storage)?;
+    assert_points_movable(storage, env, &config, user)?;
+    add_points(storage, env, &config, user, amount)
+}
+
+/// Pays out points held in escrow, e.g. wager stakes. Unlike credit_points this never
+/// refuses, or the stake would be stuck: a merged user is paid through whoever absorbed
+/// them, and a frozen user's pending update is rebased so rejecting it keeps the payout.
+fn release_points(storage: &mut dyn Storage, env: &Env, user: &str, amount: u32) -> Result<Vec<SubMsg>, ContractError> {
+    let mut recipient = user.to_string();
+    while let Some(primary) = MERGED.may_load(storage, recipient.clone())? {
+        recipient = primary;
+    }
+    if let Some(mut flagged) = PROVISIONAL.may_load(storage, recipient.clone())? {
+        flagged.previous = Some(flagged.previous.unwrap_or_default().saturating_add(amount));
+        PROVISIONAL.save(storage, recipient.clone(), &flagged)?;
+    }
+    let config = CONFIG.load(storage)?;
+    add_points(storage, env, &config, &recipient, amount)
+}
+
+fn add_points(storage: &mut dyn Storage, env: &Env, config: &Config, user: &str, amount: u32) -> Result<Vec<SubMsg>, ContractError> {
+    let current = SCORES.may_load(storage, user.to_string())?.unwrap_or_default();
+    let mut totals = TOTALS.may_load(storage)?.unwrap_or_default();
+    let messages = set_score(storage, config, &mut totals, user, current.saturating_add(amount), Some(env.block.height), &env.block)?;
+    TOTALS.save(storage, &totals)?;
+    Ok(messages)
+}
+
+/// Locks the sender's stake in a new wager against `opponent`.
+pub fn try_open_wager(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    opponent: String,
+    stake: u32,
+    expires_at: Timestamp,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let opponent = Addr::unchecked(normalize_addr(deps.api, &opponent)?);
+    if stake == 0 || opponent == info.sender || expires_at <= env.block.time {
+        return Err(ContractError::InvalidWager {});
+    }
+    // An opponent who can never hold points could never accept
+    assert_not_reserved(&env, &CONFIG.load(deps.storage)?, opponent.as_str())?;
+    if let Some(primary) = MERGED.may_load(deps.storage, opponent.to_string())? {
+        return Err(ContractError::Merged { user: opponent.into_string(), primary });
+    }
+    let messages = deduct_points(deps.storage, &env, info.sender.as_str(), stake)?;
+
+    let id = NEXT_WAGER_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_WAGER_ID.save(deps.storage, &(id + 1))?;
+    let wager = Wager { id, proposer: info.sender, opponent, stake, expires_at, status: WagerStatus::Open, winner: None };
+    WAGERS.save(deps.storage, id, &wager)?;
+
+    Ok(method_response("try_open_wager").add_submessages(messages).add_event(evt_wager(&wager)?))
+}
+
+pub fn try_accept_wager(deps: DepsMut, env: Env, info: MessageInfo, wager_id: u64) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let mut wager = WAGERS.load(deps.storage, wager_id)?;
+    assert_status(&wager, &[WagerStatus::Open])?;
+    if info.sender != wager.opponent {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time >= wager.expires_at {
+        return Err(ContractError::WagerExpired { wager_id });
+    }
+    let messages = deduct_points(deps.storage, &env, wager.opponent.as_str(), wager.stake)?;
+    wager.status = WagerStatus::Accepted;
+    WAGERS.save(deps.storage, wager_id, &wager)?;
+
+    Ok(method_response("try_accept_wager").add_submessages(messages).add_event(evt_wager(&wager)?))
+}
+
+/// Hands both stakes to the winner. Results must come in before the wager expires, after
+/// which it can only be refunded.
+pub fn try_report_wager(deps: DepsMut, env: Env, wager_id: u64, winner: String) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let mut wager = WAGERS.load(deps.storage, wager_id)?;
+    assert_status(&wager, &[WagerStatus::Accepted])?;
+    if env.block.time >= wager.expires_at {
+        return Err(ContractError::WagerExpired { wager_id });
+    }
+    let winner = Addr::unchecked(normalize_addr(deps.api, &winner)?);
+    if winner != wager.proposer && winner != wager.opponent {
+        return Err(ContractError::InvalidWagerWinner {});
+    }
+    let messages = release_points(deps.storage, &env, winner.as_str(), wager.stake.saturating_mul(2))?;
+    wager.status = WagerStatus::Settled;
+    wager.winner = Some(winner);
+    WAGERS.save(deps.storage, wager_id, &wager)?;
+
+    Ok(method_response("try_report_wager").add_submessages(messages).add_event(evt_wager(&wager)?))
+}
+
+/// Returns every locked stake: the proposer may withdraw a wager nobody accepted yet,
+/// and once it expires anyone may refund it.
+pub fn try_refund_wager(deps: DepsMut, env: Env, info: MessageInfo, wager_id: u64) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env)?;
+    let mut wager = WAGERS.load(deps.storage, wager_id)?;
+    assert_status(&wager, &[WagerStatus::Open, WagerStatus::Accepted])?;
+    let expired = env.block.time >= wager.expires_at;
+    let mut messages = match wager.status {
+        WagerStatus::Open if expired || info.sender == wager.proposer => vec![],
+        WagerStatus::Accepted if expired => release_points(deps.storage, &env, wager.opponent.as_str(), wager.stake)?,
+        _ => return Err(ContractError::WagerNotExpired { wager_id }),
+    };
+    messages.extend(release_points(deps.storage, &env, wager.proposer.as_str(), wager.stake)?);
+    wager.status = WagerStatus::Refunded;
+    WAGERS.save(deps.storage, wager_id, &wager)?;
+
+    Ok(method_response("try_refund_wager").add_submessages(messages).add_event(evt_wager(&wager)?))
+}
+
+pub fn try_set_shop(deps: DepsMut, addr: Option<String>) -> Result<Response, ContractError> {
+    let res = method_response("try_set_shop");
+    match addr {
+        Some(addr) => {
+            let shop = deps.api.addr_validate(&addr)?;
+            SHOP.save(deps.storage, &shop)?;
+            Ok(res.add_attribute("shop", shop))
+        }
+        None => {
+            SHOP.remove(deps.storage);
+            Ok(res)
+        }
+    }
+}
+
+pub fn try_set_certifier(deps: DepsMut, addr: Option<String>) -> Result<Response, ContractError> {
+    let res = method_response("try_set_certifier");
+    match addr {
+        Some(addr) => {
+            let certifier = deps.api.addr_validate(&addr)?;
+            CERTIFIER.save(deps.storage, &certifier)?;
+            Ok(res.add_attribute("certifier", certifier))
+        }
+        None => {
+            CERTIFIER.remove(deps.storage);
+            Ok(res)
+        }
+    }
+}
+
+/// Records the sender's score and rank on the current board and mints them a certificate
+/// of it, once a season.
+pub fn try_certify_score(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let certifier = CERTIFIER.may_load(deps.storage)?.ok_or(ContractError::CertifierNotConfigured {})?;
+    // Ranks still count the entries of a season being archived
+    if let Some(archiving) = ARCHIVING.may_load(deps.storage)? {
+        return Err(ContractError::SeasonArchiving { season: archiving.season });
+    }
+    let user = info.sender.to_string();
+    let expired = EXPIRIES.may_load(deps.storage, user.clone())?.map_or(false, |expires_at| expires_at <= env.block.time);
+    let key = match current_key(deps.storage, &user)? {
+        Some(key) if !expired => key,
+        _ => return Err(ContractError::NoScore { user }),
+    };
+    let season = CURRENT_SEASON.load(deps.storage)?;
+    if CERTIFICATIONS.may_load(deps.storage, (season, user.clone()))?.is_some() {
+        return Err(ContractError::AlreadyCertified { user, season });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let certification = Certification {
+        token_id: certificate_token_id(season, &user),
+        // As GetScore shows it to the user
+        score: config.score_formula.apply(u32::MAX - key.0),
+        season,
+        rank: rank_of(deps.storage, &key)?,
+        height: env.block.height,
+    };
+    CERTIFICATIONS.save(deps.storage, (season, user.clone()), &certification)?;
+
+    Ok(method_response("try_certify_score")
+        .add_attribute("token_id", &certification.token_id)
+        .add_attribute("rank", certification.rank.to_string())
+        .add_submessage(mint_msg(&certifier, &user, &certification)?))
+}
+
+pub fn try_publish_weight_table(deps: DepsMut, env: Env, top_n: u32) -> Result<Response, ContractError> {
+    let table = publish_weight_table(deps.storage, &env, top_n)?;
+
+    let event = evt_weight_table_published(table.version, table.season, table.entries.len());
+    Ok(method_response("try_publish_weight_table").add_event(event))
+}
+
+pub fn try_fund_faucet(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut faucet = FAUCET.may_load(deps.storage)?.unwrap_or_default();
+    let coin = match (info.funds.as_slice(), &faucet.denom) {
+        ([coin], None) => coin,
+        ([coin], Some(denom)) if &coin.denom == denom => coin,
+        _ => return Err(ContractError::InvalidFaucetFunds {}),
+    };
+    if coin.amount.is_zero() {
+        return Err(ContractError::InvalidFaucetFunds {});
+    }
+    faucet.denom = Some(coin.denom.clone());
+    faucet.balance += coin.amount;
+    FAUCET.save(deps.storage, &faucet)?;
+    credit(deps.storage, FAUCET_FUNDS, coin)?;
+
+    Ok(method_response("try_fund_faucet")
+        .add_attribute("amount", coin.to_string())
+        .add_attribute("balance", faucet.balance))
+}
+
+pub fn try_set_faucet(
+    deps: DepsMut,
+    amount_per_claim: Uint128,
+    min_score: u32,
+    cooldown: u64,
+) -> Result<Response, ContractError> {
+    if amount_per_claim.is_zero() {
+        return Err(ContractError::InvalidFaucetAmount {});
+    }
+
+    let mut faucet = FAUCET.may_load(deps.storage)?.unwrap_or_default();
+    faucet.amount_per_claim = amount_per_claim;
+    faucet.min_score = min_score;
+    faucet.cooldown = cooldown;
+    FAUCET.save(deps.storage, &faucet)?;
+
+    Ok(method_response("try_set_faucet")
+        .add_attribute("amount_per_claim", amount_per_claim)
+        .add_attribute("min_score", min_score.to_string())
+        .add_attribute("cooldown", cooldown.to_string()))
+}
+
+/// Pays one faucet claim to the sender, judged by their current score.
+pub fn try_claim_faucet(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let user = info.sender.to_string();
+    let mut faucet = FAUCET.may_load(deps.storage)?.unwrap_or_default();
+    let denom = match &faucet.denom {
+        Some(denom) if !faucet.amount_per_claim.is_zero() && faucet.balance >= faucet.amount_per_claim => denom.clone(),
+        _ => return Err(ContractError::FaucetEmpty {}),
+    };
+    // The claimer's own score, so exact, and absent once expired or left in an ended season
+    let score = query_score(deps.as_ref(), env.clone(), user.clone(), true)?.score;
+    if score < faucet.min_score {
+        return Err(ContractError::ScoreTooLow { score, min_score: faucet.min_score });
+    }
+    let mut claim = FAUCET_CLAIMS.may_load(deps.storage, user.clone())?.unwrap_or_default();
+    if let Some(next_claim_at) = claim.last_claimed.map(|last| last.plus_seconds(faucet.cooldown)) {
+        if env.block.time < next_claim_at {
+            return Err(ContractError::FaucetCooldown { next_claim_at });
+        }
+    }
+
+    let amount = faucet.amount_per_claim;
+    debit(deps.storage, FAUCET_FUNDS, &coin(amount.u128(), &denom))?;
+    faucet.balance -= amount;
+    FAUCET.save(deps.storage, &faucet)?;
+    claim.last_claimed = Some(env.block.time);
+    claim.claims += 1;
+    claim.claimed += amount;
+    FAUCET_CLAIMS.save(deps.storage, user.clone(), &claim)?;
+
+    Ok(method_response("try_claim_faucet")
+        .add_attribute("amount", amount)
+        .add_message(BankMsg::Send { to_address: user, amount: vec![coin(amount.u128(), denom)] }))
+}
+
+pub fn try_fund_rebate_pool(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut pool = REBATE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let coin = match (info.funds.as_slice(), &pool.denom) {
+        ([coin], None) => coin,
+        ([coin], Some(denom)) if &coin.denom == denom => coin,
+        _ => return Err(ContractError::InvalidRebateFunds {}),
+    };
+    if coin.amount.is_zero() {
+        return Err(ContractError::InvalidRebateFunds {});
+    }
+    pool.denom = Some(coin.denom.clone());
+    pool.balance += coin.amount;
+    REBATE_POOL.save(deps.storage, &pool)?;
+    credit(deps.storage, REBATE_FUNDS, coin)?;
+
+    Ok(method_response("try_fund_rebate_pool")
+        .add_attribute("amount", coin.to_string())
+        .add_attribute("balance", pool.balance))
+}
+
+pub fn try_set_rebate(deps: DepsMut, amount: Uint128) -> Result<Response, ContractError> {
+    let mut pool = REBATE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    pool.amount = amount;
+    REBATE_POOL.save(deps.storage, &pool)?;
+
+    Ok(method_response("try_set_rebate").add_attribute("amount", amount))
+}
+
+pub fn try_start_raffle(deps: DepsMut, env: Env, info: MessageInfo, reveal_height: u64) -> Result<Response, ContractError> {
+    let prize = match info.funds.as_slice() {
+        [coin] if !coin.amount.is_zero() => coin.clone(),
+        _ => return Err(ContractError::InvalidRafflePrize {}),
+    };
+    if reveal_height <= env.block.height {
+        return Err(ContractError::InvalidRevealHeight {});
+    }
+
+    let id = NEXT_RAFFLE_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_RAFFLE_ID.save(deps.storage, &(id + 1))?;
+    let raffle = Raffle { id, prize: prize.clone(), reveal_height, resolved_at: None, seed: None, winner: None, claimed: false };
+    RAFFLES.save(deps.storage, id, &raffle)?;
+    credit(deps.storage, &raffle_funds(id), &prize)?;
+
+    let event = evt_raffle_started(id, &prize.to_string(), reveal_height);
+    Ok(method_response("try_start_raffle").add_event(event))
+}
+
+/// Draws the winner from the board as it stands in the resolving block. With no
+/// eligible entrant the prize goes back to the owner.
+pub fn try_resolve_raffle(deps: DepsMut, env: Env, raffle_id: u64) -> Result<Response, ContractError> {
+    let mut raffle = RAFFLES.load(deps.storage, raffle_id)?;
+    if raffle.resolved_at.is_some() {
+        return Err(ContractError::RaffleResolved {});
+    }
+    if env.block.height < raffle.reveal_height {
+        return Err(ContractError::RaffleNotReady { reveal_height: raffle.reveal_height });
+    }
+
+    let draw = draw(deps.storage, env.block.time, raffle_seed(&env, raffle_id))?;
+    raffle.resolved_at = Some(env.block.height);
+    raffle.seed = Some(draw.seed.clone());
+    raffle.winner = draw.winner.map(Addr::unchecked);
+
+    let mut res = method_response("try_resolve_raffle");
+    if raffle.winner.is_none() {
+        let state = STATE.load(deps.storage)?;
+        raffle.claimed = true;
+        debit(deps.storage, &raffle_funds(raffle_id), &raffle.prize)?;
+        res = res.add_message(BankMsg::Send { to_address: state.owner.into(), amount: vec![raffle.prize.clone()] });
+    }
+    RAFFLES.save(deps.storage, raffle_id, &raffle)?;
+
+    let winner = raffle.winner.as_ref().map(Addr::as_str);
+    let event = evt_raffle_resolved(raffle_id, &draw.seed, draw.entrants, draw.total_weight, winner);
+    Ok(res.add_event(event))
+}
+
+pub fn try_claim_raffle(deps: DepsMut, info: MessageInfo, raffle_id: u64) -> Result<Response, ContractError> {
+    let mut raffle = RAFFLES.load(deps.storage, raffle_id)?;
+    if raffle.winner.as_ref() != Some(&info.sender) {
+        return Err(ContractError::NotRaffleWinner {});
+    }
+    if raffle.claimed {
+        return Err(ContractError::RaffleClaimed {});
+    }
+    raffle.claimed = true;
+    RAFFLES.save(deps.storage, raffle_id, &raffle)?;
+    debit(deps.storage, &raffle_funds(raffle_id), &raffle.prize)?;
+
+    let event = evt_raffle_claimed(raffle_id, info.sender.as_str(), &raffle.prize.to_string());
+    Ok(method_response("try_claim_raffle")
+        .add_event(event)
+        .add_message(BankMsg::Send { to_address: info.sender.into(), amount: vec![raffle.prize] }))
+}
+
+pub fn try_register_merkle_stage(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root: String,
+    expires_at: Timestamp,
+) -> Result<Response, ContractError> {
+    if expires_at <= env.block.time {
+        return Err(ContractError::ExpiryInPast {});
+    }
+    decode_hash(&merkle_root)?;
+    let funds = match info.funds.as_slice() {
+        [coin] if !coin.amount.is_zero() => coin,
+        _ => return Err(ContractError::InvalidStageFunds {}),
+    };
+
+    let id = MERKLE_STAGES
+        .keys(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |last| last + 1);
+    let stage = MerkleStage {
+        id,
+        merkle_root,
+        denom: funds.denom.clone(),
+        total: funds.amount,
+        claimed: Uint128::zero(),
+        expires_at,
+        clawed_back: false,
+    };
+    MERKLE_STAGES.save(deps.storage, id, &stage)?;
+    credit(deps.storage, &stage_funds(id), funds)?;
+
+    Ok(method_response("try_register_merkle_stage")
+        .add_attribute("stage", id.to_string())
+        .add_attribute("total", funds.to_string()))
+}
+
+pub fn try_claim_merkle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage: u32,
+    index: u64,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut record = MERKLE_STAGES.load(deps.storage, stage)?;
+    if record.clawed_back {
+        return Err(ContractError::StageClosed { stage });
+    }
+    if env.block.time > record.expires_at {
+        return Err(ContractError::StageExpired { stage, expires_at: record.expires_at });
+    }
+    let user = info.sender.to_string();
+    if is_claimed(deps.storage, stage, index)? {
+        return Err(ContractError::AlreadyClaimed { stage, index });
+    }
+    let root = decode_hash(&record.merkle_root)?;
+    if !verify_proof(&root, leaf_hash(index, &user, amount), &proof)? {
+        return Err(ContractError::InvalidProof {});
+    }
+
+    // A tree promising more than was funded mustn't pay out of other stages' funds
+    record.claimed = record.claimed.checked_add(amount).map_err(StdError::from)?;
+    if record.claimed > record.total {
+        return Err(ContractError::InvalidStageFunds {});
+    }
+    set_claimed(deps.storage, stage, index)?;
+    MERKLE_STAGES.save(deps.storage, stage, &record)?;
+    debit(deps.storage, &stage_funds(stage), &coin(amount.u128(), &record.denom))?;
+
+    Ok(method_response("try_claim_merkle")
+        .add_attribute("stage", stage.to_string())
+        .add_attribute("amount", amount)
+        .add_message(BankMsg::Send { to_address: user, amount: vec![coin(amount.u128(), record.denom)] }))
+}
+
+pub fn try_clawback_stage(deps: DepsMut, env: Env, stage: u32) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let mut record = MERKLE_STAGES.load(deps.storage, stage)?;
+    if record.clawed_back {
+        return Err(ContractError::StageClosed { stage });
+    }
+    if env.block.time <= record.expires_at {
+        return Err(ContractError::StageNotExpired { stage, expires_at: record.expires_at });
+    }
+    record.clawed_back = true;
+    MERKLE_STAGES.save(deps.storage, stage, &record)?;
+
+    let unclaimed = record.total - record.claimed;
+    let mut res = method_response("try_clawback_stage")
+        .add_attribute("stage", stage.to_string())
+        .add_attribute("unclaimed", unclaimed);
+    if !unclaimed.is_zero() {
+        let unclaimed = coin(unclaimed.u128(), record.denom);
+        debit(deps.storage, &stage_funds(stage), &unclaimed)?;
+        res = res.add_message(BankMsg::Send { to_address: state.owner.into(), amount: vec![unclaimed] });
+    }
+    Ok(res)
+}
+
+pub fn try_recover_cw20(deps: DepsMut, token: String, amount: Uint128, recipient: String) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidRecoveryAmount {});
+    }
+    let token = deps.api.addr_validate(&token)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    assert_unmanaged_cw20(deps.storage, &token)?;
+
+    Ok(method_response("try_recover_cw20")
+        .add_attribute("token", token.as_str())
+        .add_attribute("amount", amount)
+        .add_attribute("recipient", recipient.as_str())
+        .add_message(recover_cw20_msg(&token, amount, &recipient)?))
+}
+
+pub fn try_recover_nft(deps: DepsMut, contract: String, token_id: String, recipient: String) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_validate(&contract)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    Ok(method_response("try_recover_nft")
+        .add_attribute("contract", contract.as_str())
+        .add_attribute("token_id", token_id.as_str())
+        .add_attribute("recipient", recipient.as_str())
+        .add_message(recover_nft_msg(&contract, token_id, &recipient)?))
+}
+
+pub fn try_set_top_k(deps: DepsMut, k: u32) -> Result<Response, ContractError> {
+    if k > MAX_TOP_K {
+        return Err(ContractError::TopKTooLarge { max: MAX_TOP_K });
+    }
+    set_top_k(deps.storage, k)?;
+
+    Ok(method_response("try_set_top_k").add_attribute("k", k.to_string()))
+}
+
+pub fn try_rebuild_top_k(deps: DepsMut, limit: Option<u32>) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_MAINTENANCE_LIMIT).min(MAX_MAINTENANCE_LIMIT) as usize;
+    let (added, done) = rebuild_top_k(deps.storage, limit)?;
+
+    Ok(method_response("try_rebuild_top_k")
+        .add_attribute("added", added.to_string())
+        .add_attribute("done", done.to_string()))
+}
+
+pub fn try_set_aggregation(deps: DepsMut, sources: Vec<SourceInit>, mode: CombineMode) -> Result<Response, ContractError> {
+    if sources.len() > MAX_AGGREGATION_SOURCES as usize {
+        return Err(ContractError::TooManySources { max: MAX_AGGREGATION_SOURCES });
+    }
+
+    if sources.is_empty() {
+        AGGREGATION.remove(deps.storage);
+    } else {
+        let sources = sources
+            .into_iter()
+            .map(|source| Ok(ScoreSource { addr: deps.api.addr_validate(&source.addr)?, weight: source.weight }))
+            .collect::<StdResult<_>>()?;
+        AGGREGATION.save(deps.storage, &AggregationConfig { sources, mode })?;
+    }
+
+    Ok(method_response("try_set_aggregation"))
+}
+
+pub fn try_update_peers(deps: DepsMut, add: Vec<PeerInit>, remove: Vec<String>) -> Result<Response, ContractError> {
+    update_peers(deps.storage, deps.api, add, remove)?;
+
+    Ok(method_response("try_update_peers"))
+}
+
+pub fn try_sync_from_peer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    chain_id: String,
+    updates: Vec<ScoreUpdate>,
+) -> Result<Response, ContractError> {
+    let updates = updates
+        .into_iter()
+        .map(|update| Ok((normalize_addr(deps.api, update.user.as_str())?, update.score)))
+        .collect::<StdResult<Vec<_>>>()?;
+    sync_from_peer(deps.storage, &info.sender, &chain_id, &updates, env.block.height)?;
+
+    Ok(method_response("try_sync_from_peer")
+        .add_attribute("chain_id", chain_id)
+        .add_attribute("synced", updates.len().to_string()))
+}
+
+pub fn try_set_viewing_key(deps: DepsMut, info: MessageInfo, key: String) -> Result<Response, ContractError> {
+    let hashed = Binary::from(Sha256::digest(key.as_bytes()).as_slice());
+    VIEWING_KEYS.save(deps.storage, info.sender.to_string(), &hashed)?;
+
+    // Never echo the key itself
+    Ok(method_response("try_set_viewing_key"))
+}
+
+pub fn try_set_profile_visibility(deps: DepsMut, info: MessageInfo, public: bool) -> Result<Response, ContractError> {
+    PROFILE_VISIBILITY.save(deps.storage, info.sender.to_string(), &public)?;
+
+    Ok(method_response("try_set_profile_visibility")
+        .add_attribute("public", public.to_string()))
+}
+
+pub fn try_set_addr_origin(deps: DepsMut, info: MessageInfo, prefix: Option<String>) -> Result<Response, ContractError> {
+    match &prefix {
+        Some(prefix) => {
+            validate_addr_prefix(prefix)?;
+            ADDR_ORIGINS.save(deps.storage, info.sender.to_string(), prefix)?;
+        }
+        None => ADDR_ORIGINS.remove(deps.storage, info.sender.to_string()),
+    }
+
+    Ok(method_response("try_set_addr_origin")
+        .add_attribute("prefix", prefix.unwrap_or_default()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let res = match msg.id {
+        INSTANTIATE_MIRROR_REPLY_ID => {
+            let token = deps.api.addr_validate(&parse_mirror_address(msg)?)?;
+            MIRROR.update(deps.storage, |mut mirror| -> StdResult<_> {
+                mirror.token = Some(token.clone());
+                Ok(mirror)
+            })?;
+            Ok(method_response("instantiate_mirror")
+                .add_attribute("token", token))
+        }
+        // The score change stands even if the holder revoked the burn allowance; what
+        // wasn't burned is owed and settled with their next change
+        MIRROR_BURN_REPLY_ID => {
+            let failed = msg.result.is_err();
+            let (user, shortfall) = settle_burn(deps.storage, &msg.result)?;
+            let res = method_response(if failed { "mirror_burn_failed" } else { "mirror_burn" });
+            Ok(res.add_attribute("user", user).add_attribute("shortfall", shortfall))
+        }
+        // Notifications are best effort
+        NOTIFIER_REPLY_ID => Ok(method_response("notifier_failed")),
+        // A failing shadow is exactly what shadow mode is meant to surface, not to prevent
+        SHADOW_REPLY_ID => Ok(method_response("shadow_failed")),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }?;
+    Ok(namespace_events(&config, res))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetOwner {} => to_binary(&query_owner(deps)?),
+        QueryMsg::GetScore { user, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            to_binary(&query_score(deps, env, user, exact)?)
+        }
+        QueryMsg::HasMinScore { user, min_score, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            let score = query_score(deps, env, user, true)?.score;
+            // Rounding to the nearest bucket would let a score just short of it through
+            let score = if exact { score } else { redact_down(&CONFIG.load(deps.storage)?, score) };
+            to_binary(&HasMinScoreResponse { has_min_score: score >= min_score })
+        }
+        QueryMsg::GetScoreDecimal { user, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            let res = query_score(deps, env, user, exact)?;
+            let decimals = CONFIG.load(deps.storage)?.score_decimals;
+            let score = Decimal::from_atomics(res.score, decimals).map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&DecimalScoreResponse { score, expires_at: res.expires_at })
+        }
+        QueryMsg::GetMirror {} => to_binary(&query_mirror(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
+        QueryMsg::RawRange { start, end, limit, auth } => to_binary(&query_raw_range(deps, start, end, limit, auth)?),
+        QueryMsg::CheckInvariants { cursor, limit } => to_binary(&query_check_invariants(deps, cursor, limit)?),
+        QueryMsg::Reconcile {} => to_binary(&query_reconcile(deps, env)?),
+        QueryMsg::Simulate { msg, sender, funds, auth } => to_binary(&query_simulate(deps, env, *msg, sender, funds, auth)?),
+        QueryMsg::AverageScore { user, window, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            to_binary(&query_average_score(deps, env, user, window, exact)?)
+        }
+        QueryMsg::Info {} => to_binary(&query_info(deps)?),
+        QueryMsg::OwnerHistory { start_after, limit } => to_binary(&query_owner_history(deps, start_after, limit)?),
+        QueryMsg::GetNotifier {} => to_binary(&query_notifier(deps)?),
+        QueryMsg::Leaderboard { cursor, limit, scope } => {
+            to_binary(&query_leaderboard(deps, env, cursor, limit, scope.unwrap_or_default())?)
+        }
+        QueryMsg::LeaderboardPage { limit, cursor, viewer, scope } => {
+            let viewer = viewer.map(|viewer| normalize_addr(deps.api, &viewer)).transpose()?;
+            to_binary(&query_leaderboard_page(deps, env, cursor, limit, viewer, scope.unwrap_or_default())?)
+        }
+        QueryMsg::CrossRank { user, boards, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            to_binary(&query_cross_rank(deps, env.block.time, user, boards, exact)?)
+        }
+        QueryMsg::SeasonLeaderboard { season, limit, start_after } => {
+            let start_after = start_after.map(|user| normalize_addr(deps.api, &user)).transpose()?;
+            to_binary(&query_season_leaderboard(deps, season, limit, start_after)?)
+        }
+        QueryMsg::SeasonScore { season, user, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            let mut res = query_season_score(deps, season, user)?;
+            if !exact {
+                res.score = redact(&CONFIG.load(deps.storage)?, res.score);
+            }
+            to_binary(&res)
+        }
+        QueryMsg::ListSeasons { start_after, limit } => to_binary(&query_list_seasons(deps, start_after, limit)?),
+        QueryMsg::Claimable { user } => to_binary(&query_claimable(deps, env, normalize_addr(deps.api, &user)?)?),
+        QueryMsg::SpendHistory { user, auth, start_after, limit } => {
+            let user = normalize_addr(deps.api, &user)?;
+            assert_can_view(deps, &user, auth)?;
+            to_binary(&query_spend_history(deps, user, start_after, limit)?)
+        }
+        QueryMsg::UserTimeline { user, auth, start_after, limit } => {
+            let user = normalize_addr(deps.api, &user)?;
+            assert_can_view(deps, &user, auth)?;
+            to_binary(&query_user_timeline(deps, user, start_after, limit)?)
+        }
+        QueryMsg::RemovedScore { user } => to_binary(&query_removed_score(deps, env, normalize_addr(deps.api, &user)?)?),
+        QueryMsg::Raffle { raffle_id } => to_binary(&query_raffle(deps, raffle_id)?),
+        QueryMsg::Wager { wager_id } => to_binary(&query_wager(deps, wager_id)?),
+        QueryMsg::Certification { user, season, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            to_binary(&query_certification(deps, user, season, exact)?)
+        }
+        QueryMsg::WeightTable { version } => to_binary(&query_weight_table(deps, version)?),
+        QueryMsg::FaucetStatus { user, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = is_authorized_viewer(deps, &user, auth)?;
+            let score = match exact || is_public(deps.storage, &CONFIG.load(deps.storage)?, &user)? {
+                true => Some(query_score(deps, env.clone(), user.clone(), exact)?.score),
+                false => None,
+            };
+            to_binary(&query_faucet_status(deps, env, user, score)?)
+        }
+        QueryMsg::RebateStatus { user } => to_binary(&query_rebate_status(deps, normalize_addr(deps.api, &user)?)?),
+        QueryMsg::OpenAppeals { start_after, limit } => to_binary(&query_open_appeals(deps, start_after, limit)?),
+        QueryMsg::AppealCase { case_id } => to_binary(&query_appeal_case(deps, case_id)?),
+        QueryMsg::MerkleStages { start_after, limit } => to_binary(&query_merkle_stages(deps, start_after, limit)?),
+        QueryMsg::IsClaimed { stage, index } => to_binary(&query_is_claimed(deps, stage, index)?),
+        QueryMsg::Podium { k } => to_binary(&query_podium(deps, env.block.time, k)?),
+        QueryMsg::RankSlice { from_rank, to_rank } => to_binary(&query_rank_slice(deps, env.block.time, from_rank, to_rank)?),
+        QueryMsg::FeeRoutes {} => to_binary(&query_fee_routes(deps)?),
+        QueryMsg::ProvisionalUpdates { start_after, limit } => to_binary(&query_provisional_updates(deps, start_after, limit)?),
+        QueryMsg::GlobalGain {} => to_binary(&query_global_gain(deps, env)?),
+        QueryMsg::Admin {} => to_binary(&query_admin(deps)?),
+        QueryMsg::Hooks {} => to_binary(&query_hooks(deps)?),
+        QueryMsg::Operators {} => to_binary(&query_operators(deps)?),
+        QueryMsg::OperatorReport { addr } => to_binary(&query_operator_report(deps, normalize_addr(deps.api, &addr)?)?),
+        QueryMsg::ListOperatorReports { start_after, limit } => to_binary(&query_operator_reports(deps, start_after, limit)?),
+        QueryMsg::SubmissionKeys {} => to_binary(&query_submission_keys(deps, env)?),
+        QueryMsg::VoucherStatus { id } => to_binary(&query_voucher_status(deps, id)?),
+        QueryMsg::Claims { address } => to_binary(&query_claims(deps, env, normalize_addr(deps.api, &address)?)?),
+        QueryMsg::NextMaintenance {} => to_binary(&query_next_maintenance(deps, env)?),
+        QueryMsg::AddrInfo { address } => to_binary(&query_addr_info(deps, normalize_addr(deps.api, &address)?)?),
+        QueryMsg::FreezeStatus {} => to_binary(&query_freeze_status(deps, env)?),
+        QueryMsg::IdempotencyKey { sender, key } => to_binary(&query_idempotency_key(deps, env, normalize_addr(deps.api, &sender)?, key)?),
+        QueryMsg::EpochSummary { season } => to_binary(&query_epoch_summary(deps, season)?),
+        QueryMsg::SnapshotDigest { snapshot_id } => to_binary(&query_snapshot_digest(deps, snapshot_id)?),
+        QueryMsg::LatestSequence {} => to_binary(&query_latest_sequence(deps)?),
+        QueryMsg::ChangesSince { seq, limit } => to_binary(&query_changes_since(deps, seq, limit)?),
+        QueryMsg::DeltasSince { height, start_after, limit } => to_binary(&query_deltas_since(deps, height, start_after, limit)?),
+        QueryMsg::Retention {} => to_binary(&query_retention(deps)?),
+        QueryMsg::Policy { msg_name } => to_binary(&query_policy(deps, msg_name)?),
+        QueryMsg::BoardAdmins { board } => to_binary(&query_board_admins(deps, board)?),
+        QueryMsg::Denylist {} => to_binary(&query_denylist(deps)?),
+        QueryMsg::EnabledFeatures {} => to_binary(&query_enabled_features(deps)?),
+        QueryMsg::Attestations { user, tag, start_after, limit } => {
+            to_binary(&query_attestations(deps, normalize_addr(deps.api, &user)?, tag, start_after, limit)?)
+        }
+        QueryMsg::Bracket { user } => to_binary(&query_bracket(deps, env, normalize_addr(deps.api, &user)?)?),
+        QueryMsg::ListBracket { name, start_after, limit } => {
+            let start_after = start_after.map(|user| normalize_addr(deps.api, &user)).transpose()?;
+            to_binary(&query_list_bracket(deps, env, name, start_after, limit)?)
+        }
+        QueryMsg::AggregatedScore { user, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            to_binary(&query_aggregated_score(deps, env, user, exact)?)
+        }
+        QueryMsg::FederatedScore { user, auth } => {
+            let user = normalize_addr(deps.api, &user)?;
+            let exact = assert_can_view(deps, &user, auth)?;
+            to_binary(&query_federated_score(deps, env, user, exact)?)
+        }
+        QueryMsg::Peers {} => to_binary(&query_peers(deps)?),
+    }
+}
+
+fn query_owner(deps: Deps) -> StdResult<OwnerResponse> {
+    let state = STATE.load(deps.storage)?;
+    Ok(OwnerResponse { owner: state.owner })
+}
+
+/// Rejects the contract's own address and the configured reserved addresses as score holders.
+fn assert_not_reserved(env: &Env, config: &Config, user: &str) -> Result<(), ContractError> {
+    if user == env.contract.address.as_str() || config.reserved_addresses.iter().any(|addr| addr.as_str() == user) {
+        return Err(ContractError::ReservedAddress { addr: user.to_string() });
+    }
+    Ok(())
+}
+
+/// The one stored form of an address, whatever casing it was given in. Invalid addresses
+/// are an error rather than an empty entry.
+pub fn normalize_addr(api: &dyn Api, input: &str) -> StdResult<String> {
+    Ok(api.addr_humanize(&api.addr_canonicalize(input)?)?.into_string())
+}
+
+/// Fails unless `user`'s score is public or `auth` proves the querier is the user, the
+/// owner or an operator. Returns whether it does, in which case exact values may be shown.
+pub fn assert_can_view(deps: Deps, user: &str, auth: Option<ViewerAuth>) -> StdResult<bool> {
+    let authorized = is_authorized_viewer(deps, user, auth)?;
+    if !authorized && !is_public(deps.storage, &CONFIG.load(deps.storage)?, user)? {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+    Ok(authorized)
+}
+
+pub fn is_authorized_viewer(deps: Deps, user: &str, auth: Option<ViewerAuth>) -> StdResult<bool> {
+    match authenticate(deps, auth)? {
+        Some(address) => Ok(address == user || is_writer(deps.storage, &Addr::unchecked(address))?),
+        None => Ok(false),
+    }
+}
+
+/// The address `auth` proves the querier holds the viewing key of, if it does.
+fn authenticate(deps: Deps, auth: Option<ViewerAuth>) -> StdResult<Option<String>> {
+    let auth = match auth {
+        Some(auth) => auth,
+        None => return Ok(None),
+    };
+    let address = normalize_addr(deps.api, &auth.address)?;
+    let stored = match VIEWING_KEYS.may_load(deps.storage, address.clone())? {
+        Some(stored) => stored,
+        None => return Ok(None),
+    };
+    if stored.as_slice() != Sha256::digest(auth.key.as_bytes()).as_slice() {
+        return Ok(None);
+    }
+    Ok(Some(address))
+}
+
+/// The user's score after Config::score_formula, redacted unless `exact`.
+pub fn query_score(deps: Deps, env: Env, user: String, exact: bool) -> StdResult<ScoreResponse>  {
+    // Expired entries read as absent until someone purges them
+    let expires_at = EXPIRIES.may_load(deps.storage, user.clone())?;
+    if expires_at.map_or(false, |expires_at| expires_at <= env.block.time) {
+        return Ok(ScoreResponse { score: 0, expires_at: None });
+    }
+    // So are scores from a season still being archived
+    if let Some(archiving) = ARCHIVING.may_load(deps.storage)? {
+        if ACHIEVED_AT.may_load(deps.storage, user.clone())?.map_or(false, |height| height < archiving.end_height) {
+            return Ok(ScoreResponse { score: 0, expires_at: None });
+        }
+    }
+    let points = SCORES.may_load(deps.storage, user)?.unwrap_or_default();
+    let config = CONFIG.load(deps.storage)?;
+    let score = config.score_formula.apply(points);
+    let score = if exact { score } else { redact(&config, score) };
+    Ok(ScoreResponse{ score, expires_at })
+}
+
+fn query_mirror(deps: Deps) -> StdResult<MirrorResponse> {
+    let mirror = MIRROR.may_load(deps.storage)?.unwrap_or(MirrorConfig { token: None, enabled: false });
+    Ok(MirrorResponse { token: mirror.token, enabled: mirror.enabled })
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    CONFIG.load(deps.storage)
+}
+
+/// Raw storage holds every private score and viewing key hash, so only the owner may
+/// read it, and only while Config::raw_queries is on.
+fn query_raw_range(deps: Deps, start: Option<Binary>, end: Option<Binary>, limit: Option<u32>, auth: ViewerAuth) -> StdResult<RawRangeResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.raw_queries {
+        return Err(StdError::generic_err("Raw storage queries are disabled"));
+    }
+    let owner = STATE.load(deps.storage)?.owner;
+    if authenticate(deps, Some(auth))?.map_or(true, |address| address != owner.as_str()) {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_RAW_LIMIT);
+    if limit > MAX_RAW_LIMIT {
+        return Err(StdError::generic_err(format!("Limit {} is above the maximum of {}", limit, MAX_RAW_LIMIT)));
+    }
+    let entries = deps
+        .storage
+        .range(start.as_deref(), end.as_deref(), Order::Ascending)
+        .take(limit as usize)
+        .map(|(key, value)| RawEntry { key: key.into(), value: value.into() })
+        .collect();
+    Ok(RawRangeResponse { entries })
+}
+
+fn query_info(deps: Deps) -> StdResult<InfoResponse> {
+    let version = get_contract_version(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    // The subsystems the profile and feature flags leave on, then what is switched on at runtime
+    let flags = FEATURES.may_load(deps.storage)?.unwrap_or_default();
+    let mut features: Vec<_> = flags.enabled_features.iter().map(|feature| feature.as_str().to_string()).collect();
+    let runtime = [
+        ("mirror", MIRROR.may_load(deps.storage)?.map_or(false, |mirror| mirror.enabled)),
+        ("raw_queries", config.raw_queries),
+        ("private_scores", config.private_scores),
+        ("signed_submissions", !SUBMISSION_KEYS.may_load(deps.storage)?.unwrap_or_default().is_empty()),
+        ("vouchers", VOUCHER_KEY.may_load(deps.storage)?.is_some()),
+    ];
+    features.extend(runtime.iter().filter(|(_, on)| *on).map(|(name, _)| name.to_string()));
+
+    Ok(InfoResponse {
+        contract: version.contract,
+        version: version.version,
+        features,
+        interfaces: INTERFACES.iter().map(|id| id.to_string()).collect(),
+        successor: SUCCESSOR.may_load(deps.storage)?.map(String::from),
+    })
+}
+
+fn query_owner_history(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<OwnerHistoryResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let changes = OWNER_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, change)| change))
+        .collect::<StdResult<_>>()?;
+    Ok(OwnerHistoryResponse { changes })
+}
+
+fn query_global_gain(deps: Deps, env: Env) -> StdResult<GlobalGainResponse> {
+    let cap = CONFIG.load(deps.storage)?.global_gain_cap;
+    let gains = GLOBAL_EPOCH_GAIN.may_load(deps.storage)?.unwrap_or_default();
+    // Gains of an earlier epoch no longer count
+    let gained = match &cap {
+        Some(cap) if cap.epoch(env.block.time) == gains.epoch => gains.gained,
+        _ => 0,
+    };
+    let remaining = cap.as_ref().map(|cap| cap.amount.saturating_sub(gained));
+    Ok(GlobalGainResponse { cap, gained, remaining })
+}
+
+fn query_notifier(deps: Deps) -> StdResult<NotifierResponse> {
+    Ok(NotifierResponse { notifier: NOTIFIER.may_load(deps.storage)? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{
+        coins, from_binary, to_vec, Attribute, ContractResult, Decimal, Event, SubMsgExecutionResponse, SubMsgResult, SystemResult, Uint128,
+        WasmMsg, WasmQuery,
+    };
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::{Signature, SigningKey};
+    use crate::controllers::HooksResponse;
+    use crate::deltas::DELTA_CAPACITY;
+    use crate::all_time::all_time_key;
+    use crate::events::evt_score_changed;
+    use crate::boards::BoardAdminsResponse;
+    use crate::validation::DenylistResponse;
+    use crate::profiles::EnabledFeaturesResponse;
+    use crate::operators::{OperatorReportResponse, OperatorReportsResponse, OperatorsResponse};
+    use crate::federation::{FederatedScoreResponse, PeersResponse};
+    use crate::addr::bech32_encode;
+    use crate::gatekeeper::{EligibleResponse, GatekeeperQueryMsg};
+    use crate::state::{DuplicateKeyPolicy, EpochSummary, MaintenanceWindow, MergePolicy, Profile, SummaryEntry, WeightEntry, ALL_TIME_INDEX, IDEMPOTENCY_KEYS, LEDGER, RANK_COUNTS, MERKLE_CLAIM_BITS, REMOVED_SCORES};
+    use crate::certify::Cw721ExecuteMsg;
+    use crate::mirror::Cw20ExecuteMsg;
+    use crate::notifier::NotifierExecuteMsg;
+    use crate::shop::{ShopExecuteMsg, SpendReceiveMsg, SpendReceiverExecuteMsg};
+    use crate::msg::{
+        AddrInfo, AggregatedScoreResponse, AppealCase, DeltasResponse, PolicyResponse, RetentionResponse, WagerResponse, AverageScoreResponse, RebateStatusResponse, RankSliceResponse, CertificationResponse, SignedVoucher, VoucherStatusResponse, DenomReconciliation, LedgerEntry, ReconcileResponse, WeightTableResponse, BoardRank, CrossRankResponse, TimelineResponse, EpochSummaryResponse, RaffleResponse, SubmissionKeysResponse, FaucetStatusResponse, OriginAddr, FreezeResponse, RemovedScoreResponse, SpendEntry, SpendHistoryResponse, MaintenanceResponse, Scope, IdempotencyKeyResponse, SnapshotDigestResponse, ChangesResponse, SequenceResponse, AttestationsResponse, BracketAssignmentResponse, BracketMembersResponse, ClaimableResponse, DecimalScoreResponse, FeeRoutesResponse, GlobalGainResponse, IsClaimedResponse, PodiumResponse, ProvisionalUpdatesResponse, MerkleStagesResponse, LeaderboardPageResponse, LeaderboardResponse, MirrorTokenInit, SeasonLeaderboardResponse,
+        InvariantsResponse, OpenAppealsResponse, SeasonsResponse, SimulateResponse, ViewerRank,
+    };
+
+    fn get_score<T: Into<String>>(deps: Deps, address: T) -> u32 {
+        query_score(deps, mock_env(), address.into(), true).unwrap().score
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // Query and validate the owner was set correctly
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
+        let value: OwnerResponse = from_binary(&res).unwrap();
+        assert_eq!("creator", value.owner);
+    }
+    
+    #[test]
+    // Sets a specific user's score
+    fn set_user_score() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Set a user's score
+        let info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::UpdateScore { user: info.sender.clone(), score: 1120, expires_at: None, idempotency_key: None };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(get_score(deps.as_ref(), "creator"), 1120);
+
+        // Attempting to set a user's score with someone other than the owner will fail
+        let info = mock_info("someone_new", &coins(2, "token"));
+        let msg = ExecuteMsg::UpdateScore { user: info.sender.clone(), score: 500, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+    }
+
+    #[test]
+    // Get token balances of users
+    fn get_token_balances_of_users() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let instantiate_info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), instantiate_info, msg).unwrap();
+
+        // Set creator
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        let msg = ExecuteMsg::UpdateScore { user: creator_info.sender.clone(), score: 123, expires_at: None, idempotency_key: None };
+        let _res = execute(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+
+        // Set someone else
+        let creator_info = mock_info("creator", &coins(2, "token"));
+        let new_human = mock_info("new_human", &coins(10, "token"));
+        let msg = ExecuteMsg::UpdateScore { user: new_human.sender.clone(), score: 456, expires_at: None, idempotency_key: None };
+        let _res = execute(deps.as_mut(), mock_env(), creator_info, msg).unwrap();
+        
+        // Fetch creator
+        let creator_info = mock_info("creator", &coins(10, "token"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetScore { user: creator_info.sender.to_string(), auth: None }).unwrap();
+        let value: ScoreResponse = from_binary(&res).unwrap();
+        println!("{}", value.score);
+        assert_eq!(123, value.score);
+
+        // Fetch new human
+        let new_human = mock_info("new_human", &coins(10, "token"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetScore { user: new_human.sender.to_string(), auth: None }).unwrap();
+        let value: ScoreResponse = from_binary(&res).unwrap();
+        assert_eq!(456, value.score);
+    }
+
+    #[test]
+    // Get the owner of the contract
+    fn get_owner() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Fetch here
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
+        let value: OwnerResponse = from_binary(&res).unwrap();
+        assert_eq!("creator", value.owner);
+    }
+
+    #[test]
+    // Mirror token is minted on increments and burned on decrements
+    fn mirror_score_token() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg {
+            mirror: Some(MirrorTokenInit {
+                code_id: 7,
+                name: "Score Points".to_string(),
+                symbol: "PTS".to_string(),
+                decimals: 0,
+                label: "score mirror".to_string(),
+            }),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(INSTANTIATE_MIRROR_REPLY_ID, res.messages[0].id);
+
+        // Simulate the token instantiation reply
+        let reply_msg = Reply {
+            id: INSTANTIATE_MIRROR_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgExecutionResponse {
+                events: vec![Event::new("instantiate").add_attribute("_contract_address", "pointstoken")],
+                data: None,
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetMirror {}).unwrap();
+        let value: MirrorResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(Addr::unchecked("pointstoken")), value.token);
+        assert!(value.enabled);
+
+        // Increment mints the difference
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 100, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "pointstoken".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Mint { recipient: "player".to_string(), amount: Uint128::new(100) }).unwrap(),
+                funds: vec![],
+            })]
+        );
+
+        // Decrement burns the difference without blocking on failure
+        let burn = |amount: u128| {
+            SubMsg::reply_always(
+                WasmMsg::Execute {
+                    contract_addr: "pointstoken".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::BurnFrom { owner: "player".to_string(), amount: Uint128::new(amount) }).unwrap(),
+                    funds: vec![],
+                },
+                MIRROR_BURN_REPLY_ID,
+            )
+        };
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 40, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, vec![burn(60)]);
+        assert_eq!(get_score(deps.as_ref(), "player"), 40);
+
+        // A failed burn is owed: burned with the next decrement, or held back from a mint
+        let failure = Reply { id: MIRROR_BURN_REPLY_ID, result: SubMsgResult::Err("no allowance".to_string()) };
+        let res = reply(deps.as_mut(), mock_env(), failure).unwrap();
+        assert_eq!(vec![Attribute::new("method", "mirror_burn_failed"), Attribute::new("user", "player"), Attribute::new("shortfall", "60")], res.attributes);
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 30, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, vec![burn(70)]);
+        reply(deps.as_mut(), mock_env(), Reply { id: MIRROR_BURN_REPLY_ID, result: SubMsgResult::Err("no allowance".to_string()) }).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 80, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 70, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages, vec![burn(30)]);
+        let success = Reply { id: MIRROR_BURN_REPLY_ID, result: SubMsgResult::Ok(SubMsgExecutionResponse { events: vec![], data: None }) };
+        let res = reply(deps.as_mut(), mock_env(), success).unwrap();
+        assert_eq!(Attribute::new("shortfall", "0"), res.attributes[2]);
+
+        // Only the owner may toggle mirroring
+        let msg = ExecuteMsg::SetMirrorEnabled { enabled: false };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("player", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 90, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        // Decoupling forgets the token entirely
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::DecoupleMirror {}).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetMirrorEnabled { enabled: true });
+        assert!(matches!(res, Err(ContractError::MirrorNotConfigured {})));
+    }
+
+    #[test]
+    // Raw storage can only be dumped by the owner, once they enable it
+    fn raw_range_query() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 5, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        for sender in ["creator", "player"] {
+            let msg = ExecuteMsg::SetViewingKey { key: format!("{}-key", sender) };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+        let auth = |address: &str| ViewerAuth { address: address.to_string(), key: format!("{}-key", address) };
+        let range = QueryMsg::RawRange { start: None, end: None, limit: None, auth: auth("creator") };
+        assert!(query(deps.as_ref(), mock_env(), range.clone()).is_err());
+
+        // Only the owner can flip the switch
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { raw_queries: Some(true), ..ConfigUpdate::default() });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("player", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Even then only the owner may read it
+        let msg = QueryMsg::RawRange { start: None, end: None, limit: None, auth: auth("player") };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let msg = QueryMsg::RawRange { start: None, end: None, limit: None, auth: ViewerAuth { address: "creator".to_string(), key: "guess".to_string() } };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        let res = query(deps.as_ref(), mock_env(), range).unwrap();
+        let value: RawRangeResponse = from_binary(&res).unwrap();
+        let score_key = SCORES.key("player".to_string()).to_vec();
+        let entry = value.entries.iter().find(|entry| entry.key.as_slice() == score_key.as_slice()).unwrap();
+        assert_eq!(b"5", entry.value.as_slice());
+
+        // Bounds and limits are respected
+        let range = QueryMsg::RawRange { start: Some(score_key.clone().into()), end: None, limit: Some(1), auth: auth("creator") };
+        let res = query(deps.as_ref(), mock_env(), range).unwrap();
+        let value: RawRangeResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.entries.len());
+        assert_eq!(score_key.as_slice(), value.entries[0].key.as_slice());
+    }
+
+    #[test]
+    // Maintained totals agree with the score map, and drift is reported
+    fn check_invariants() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (user, score) in [("alice", 10), ("bob", 20), ("alice", 5)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CheckInvariants { cursor: None, limit: None }).unwrap();
+        let value: InvariantsResponse = from_binary(&res).unwrap();
+        // Two scores, then two all-time scores
+        assert_eq!(4, value.checked);
+        assert!(value.complete);
+        assert_eq!(None, value.next_cursor);
+        assert!(value.discrepancies.is_empty());
+
+        // One entry a batch, carrying the sums across
+        let mut cursor = None;
+        let mut batches = 0;
+        loop {
+            let msg = QueryMsg::CheckInvariants { cursor, limit: Some(1) };
+            let value: InvariantsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert!(value.discrepancies.is_empty(), "{:?}", value.discrepancies);
+            batches += 1;
+            if value.complete {
+                break;
+            }
+            cursor = value.next_cursor;
+        }
+        assert_eq!(4, batches);
+        assert_eq!(Totals { total_score: 25, users: 2, all_time_users: 2 }, TOTALS.load(&deps.storage).unwrap());
+
+        // Write around the aggregates to simulate a botched migration
+        SCORES.save(&mut deps.storage, "carol".to_string(), &7).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CheckInvariants { cursor: None, limit: None }).unwrap();
+        let value: InvariantsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                "score_index: no entry for carol".to_string(),
+                "all_time_scores: carol has 0, below the live 7".to_string(),
+                "total_score: stored 25, computed 32".to_string(),
+                "users: stored 2, computed 3".to_string(),
+                "score_index: 2 entries, 3 scores".to_string(),
+            ],
+            value.discrepancies
+        );
+        SCORES.remove(&mut deps.storage, "carol".to_string());
+
+        // Index entries that disagree with the rank counts, podium and all-time board
+        RANK_COUNTS.save(&mut deps.storage, u32::MAX - 20, &2).unwrap();
+        TOP_K.update(&mut deps.storage, |mut top| -> StdResult<_> {
+            top.entries.reverse();
+            Ok(top)
+        })
+        .unwrap();
+        let key = all_time_key(&deps.storage, "alice").unwrap().unwrap();
+        ALL_TIME_INDEX.remove(&mut deps.storage, key);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CheckInvariants { cursor: None, limit: None }).unwrap();
+        let value: InvariantsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                "top_k: 2 entries, not the 2 at the head of the index".to_string(),
+                "rank_counts: score 20 counted 2, indexed 1".to_string(),
+                "all_time_index: no entry for alice".to_string(),
+            ],
+            value.discrepancies
+        );
+
+        let msg = QueryMsg::CheckInvariants { cursor: Some(Binary::from(b"nope")), limit: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    // Simulation reports outcomes without touching state
+    fn simulate_update() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for sender in ["creator", "player"] {
+            let msg = ExecuteMsg::SetViewingKey { key: format!("{}-key", sender) };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+        let auth = |address: &str| ViewerAuth { address: address.to_string(), key: format!("{}-key", address) };
+        let simulate = |deps: Deps, msg: &ExecuteMsg, sender: &str, funds: Vec<Coin>, auth: ViewerAuth| {
+            let msg = QueryMsg::Simulate { msg: Box::new(msg.clone()), sender: sender.to_string(), funds, auth };
+            query(deps, mock_env(), msg).map(|res| from_binary::<SimulateResponse>(&res).unwrap())
+        };
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 42, expires_at: None, idempotency_key: None };
+        let value = simulate(deps.as_ref(), &msg, "creator", vec![], auth("creator")).unwrap();
+        assert!(value.success);
+        assert_eq!(Some(42), value.score);
+        assert_eq!(get_score(deps.as_ref(), "player"), 0);
+
+        // Failures come back as a report rather than a query error
+        let value = simulate(deps.as_ref(), &msg, "player", vec![], auth("player")).unwrap();
+        assert!(!value.success);
+        assert_eq!(Some("Unauthorized".to_string()), value.error);
+
+        // Nobody may simulate as someone else, or the errors would read out their score
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone()).unwrap();
+        let spend = ExecuteMsg::SpendScore { amount: u32::MAX, memo: String::new() };
+        assert!(simulate(deps.as_ref(), &spend, "player", vec![], auth("mallory")).is_err());
+        let value = simulate(deps.as_ref(), &spend, "player", vec![], auth("player")).unwrap();
+        assert_eq!(Some(format!("Not enough spendable score to spend {} points", u32::MAX)), value.error);
+        let value = simulate(deps.as_ref(), &spend, "player", vec![], auth("creator")).unwrap();
+        assert_eq!(Some(format!("Cannot spend {} points with 42 spendable", u32::MAX)), value.error);
+
+        // Fee-gated updates simulate with the funds given and report the fee
+        let msg = ExecuteMsg::SetUpdateFees { native: Some(coin(10, "uluna")), cw20: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let update = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 50, expires_at: None, idempotency_key: None };
+        let value = simulate(deps.as_ref(), &update, "creator", vec![], auth("creator")).unwrap();
+        assert_eq!(Some(ContractError::FeeRequired { fee: "10uluna".to_string() }.to_string()), value.error);
+        let value = simulate(deps.as_ref(), &update, "creator", coins(10, "uluna"), auth("creator")).unwrap();
+        assert!(value.success);
+        assert_eq!(coins(10, "uluna"), value.fees);
+
+        // The score is shown as GetScore shows it
+        let formula = ScoreFormula::Multiplier { numerator: 2, denominator: 1 };
+        let settings = ConfigUpdate { score_formula: Some(formula), ..ConfigUpdate::default() };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::UpdateConfig(settings)).unwrap();
+        let spend = ExecuteMsg::SpendScore { amount: 1, memo: String::new() };
+        let value = simulate(deps.as_ref(), &spend, "player", vec![], auth("player")).unwrap();
+        assert_eq!(Some(82), value.score);
+    }
+
+    #[test]
+    // Info reports identity and toggled features
+    fn info_query() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Info {}).unwrap();
+        let value: InfoResponse = from_binary(&res).unwrap();
+        assert_eq!(CONTRACT_NAME, value.contract);
+        assert_eq!(CONTRACT_VERSION, value.version);
+        assert_eq!(vec!["seasons", "fees", "hooks"], value.features);
+        assert!(value.interfaces.contains(&"score/v2".to_string()));
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { raw_queries: Some(true), private_scores: Some(true), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Info {}).unwrap();
+        let value: InfoResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["seasons", "fees", "hooks", "raw_queries", "private_scores"], value.features);
+
+        // Profiles that turn subsystems off report them off
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg { profile: Some(Profile::Reputation), ..InstantiateMsg::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let value: InfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Info {}).unwrap()).unwrap();
+        assert_eq!(vec!["hooks"], value.features);
+    }
+
+    #[test]
+    // Ownership transfers are recorded in order
+    fn owner_history() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::TransferOwnership { new_owner: "multisig".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("someone_new", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::TransferOwnership { new_owner: "dao".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("multisig", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetOwner {}).unwrap();
+        let value: OwnerResponse = from_binary(&res).unwrap();
+        assert_eq!("dao", value.owner);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::OwnerHistory { start_after: None, limit: None }).unwrap();
+        let value: OwnerHistoryResponse = from_binary(&res).unwrap();
+        let owners: Vec<_> = value.changes.iter().map(|change| (change.old.clone(), change.new.to_string())).collect();
+        assert_eq!(
+            vec![
+                (None, "creator".to_string()),
+                (Some(Addr::unchecked("creator")), "multisig".to_string()),
+                (Some(Addr::unchecked("multisig")), "dao".to_string()),
+            ],
+            owners
+        );
+        assert_eq!(mock_env().block.height, value.changes[2].height);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::OwnerHistory { start_after: Some(0), limit: Some(1) }).unwrap();
+        let value: OwnerHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.changes.len());
+        assert_eq!(1, value.changes[0].id);
+    }
+
+    #[test]
+    // Rescaling walks every score in pages and only while paused
+    fn rescale_scores() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (user, score) in [("alice", 10), ("bob", 15), ("carol", 4)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let rescale_msg = |start_after: Option<&str>| ExecuteMsg::RescaleScores {
+            numerator: 1,
+            denominator: 3,
+            rounding: Some(Rounding::Nearest),
+            start_after: start_after.map(|s| s.to_string()),
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rescale_msg(None));
+        assert!(matches!(res, Err(ContractError::NotPaused {})));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+
+        // Writes are blocked while paused
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 1, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::Paused {})));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rescale_msg(None)).unwrap();
+        assert_eq!(Attribute::new("next_start_after", "bob"), res.attributes[1]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rescale_msg(Some("bob"))).unwrap();
+        assert!(!res.attributes.iter().any(|attr| attr.key == "next_start_after"));
+
+        assert_eq!(get_score(deps.as_ref(), "alice"), 3);
+        assert_eq!(get_score(deps.as_ref(), "bob"), 5);
+        assert_eq!(get_score(deps.as_ref(), "carol"), 1);
+        assert_eq!(9, TOTALS.load(&deps.storage).unwrap().total_score);
+
+        assert_eq!(2, rescale(5, 1, 2, Rounding::Down));
+        assert_eq!(3, rescale(5, 1, 2, Rounding::Up));
+        assert_eq!(3, rescale(5, 1, 2, Rounding::Nearest));
+        assert_eq!(u32::MAX, rescale(u32::MAX, 2, 1, Rounding::Down));
+    }
+
+    #[test]
+    // Resetting needs the echoed token within the window, then runs in batches
+    fn reset_all_scores() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (user, score) in [("alice", 10), ("bob", 15), ("carol", 4)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let reset = |confirmation: Option<&str>| ExecuteMsg::ResetAllScores { confirmation: confirmation.map(|c| c.to_string()), limit: Some(2) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reset(Some("guess")));
+        assert!(matches!(res, Err(ContractError::NoPendingReset {})));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reset(None)).unwrap();
+        let token = res.attributes.iter().find(|attr| attr.key == "confirmation_token").unwrap().value.clone();
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reset(Some("guess")));
+        assert!(matches!(res, Err(ContractError::InvalidConfirmation {})));
+
+        // Too late to confirm
+        let mut late = mock_env();
+        late.block.height += RESET_CONFIRMATION_BLOCKS + 1;
+        let res = execute(deps.as_mut(), late, mock_info("creator", &[]), reset(Some(&token)));
+        assert!(matches!(res, Err(ContractError::ResetExpired { .. })));
+
+        // Confirmed in time, first batch
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reset(Some(&token))).unwrap();
+        assert_eq!(Attribute::new("done", "false"), res.attributes[2]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reset(None));
+        assert!(matches!(res, Err(ContractError::ResetInProgress {})));
+
+        // Later batches don't care about the confirmation window
+        let mut later = mock_env();
+        later.block.height += RESET_CONFIRMATION_BLOCKS + 1;
+        let res = execute(deps.as_mut(), later, mock_info("creator", &[]), reset(Some(&token))).unwrap();
+        assert_eq!(Attribute::new("done", "true"), res.attributes[2]);
+
+        assert_eq!(get_score(deps.as_ref(), "carol"), 0);
+        assert_eq!(Totals::default(), TOTALS.load(&deps.storage).unwrap());
+        assert!(PENDING_RESET.may_load(&deps.storage).unwrap().is_none());
+    }
+
+    #[test]
+    // Zero scores are pruned on write when enabled, and old zeros can be compacted
+    fn prune_and_compact_zero_scores() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Historical zeros written before pruning was switched on
+        for (user, score) in [("alice", 0), ("bob", 15), ("carol", 0), ("dave", 3)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        assert_eq!(4, TOTALS.load(&deps.storage).unwrap().users);
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { prune_zero_scores: Some(true), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("dave"), score: 0, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(SCORES.may_load(&deps.storage, "dave".to_string()).unwrap().is_none());
+
+        // Compaction resumes across calls
+        let compact = ExecuteMsg::CompactZeroScores { limit: Some(2) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), compact.clone()).unwrap();
+        assert_eq!(Attribute::new("done", "false"), res.attributes[3]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), compact).unwrap();
+        assert_eq!(Attribute::new("done", "true"), res.attributes[3]);
+
+        let users: Vec<String> = SCORES.keys(&deps.storage, None, None, Order::Ascending).collect::<StdResult<_>>().unwrap();
+        assert_eq!(vec!["bob".to_string()], users);
+        assert_eq!(Totals { total_score: 15, users: 1, all_time_users: 1 }, TOTALS.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    // Updates below min_delta are rejected or ignored per policy
+    fn min_delta_filter() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { min_delta: Some(5), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 100, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 97, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::DeltaTooSmall { delta: 3, min_delta: 5 })));
+
+        let update = ConfigUpdate { small_delta_policy: Some(SmallDeltaPolicy::Ignore), ..ConfigUpdate::default() };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::UpdateConfig(update)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(Attribute::new("ignored", "true"), res.attributes[1]);
+        assert_eq!(get_score(deps.as_ref(), "player"), 100);
+
+        // Large enough changes still go through
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 95, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(get_score(deps.as_ref(), "player"), 95);
+    }
+
+    #[test]
+    // Expired scores read as zero and can be purged by anyone
+    fn expiring_scores() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let now = mock_env().block.time;
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: Some(now), idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::ExpiryInPast {})));
+
+        for (user, score, ttl) in [("alice", 10, 100), ("bob", 20, 200), ("carol", 30, 100)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: Some(now.plus_seconds(ttl)), idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        // Re-submitting without an expiry makes carol's score permanent
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 30, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let mut later = mock_env();
+        later.block.time = now.plus_seconds(150);
+        let res = query(deps.as_ref(), later.clone(), QueryMsg::GetScore { user: "alice".to_string(), auth: None }).unwrap();
+        let value: ScoreResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.score);
+        let res = query(deps.as_ref(), later.clone(), QueryMsg::GetScore { user: "bob".to_string(), auth: None }).unwrap();
+        let value: ScoreResponse = from_binary(&res).unwrap();
+        assert_eq!(ScoreResponse { score: 20, expires_at: Some(now.plus_seconds(200)) }, value);
+        // The leaderboard agrees, before any purge
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), later.clone(), msg).unwrap()).unwrap();
+        let users: Vec<_> = res.entries.iter().map(|entry| entry.user.address.as_str()).collect();
+        assert_eq!(vec!["carol", "bob"], users);
+
+        let res = execute(deps.as_mut(), later, mock_info("anyone", &[]), ExecuteMsg::PurgeExpired { limit: None }).unwrap();
+        assert_eq!(Attribute::new("purged", "1"), res.attributes[1]);
+        assert!(SCORES.may_load(&deps.storage, "alice".to_string()).unwrap().is_none());
+        assert_eq!(Totals { total_score: 50, users: 2, all_time_users: 2 }, TOTALS.load(&deps.storage).unwrap());
+        assert_eq!(1, EXPIRY_QUEUE.keys(&deps.storage, None, None, Order::Ascending).count());
+    }
+
+    #[test]
+    // Gains above the per-epoch cap are truncated or rejected, and the allowance resets each epoch
+    fn user_epoch_gain_cap() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let cap = EpochCap { amount: 100, epoch_seconds: 3600, policy: CapPolicy::Reject };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetUserGainCap { cap: Some(EpochCap { epoch_seconds: 0, ..cap.clone() }) });
+        assert!(matches!(res, Err(ContractError::InvalidEpoch {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetUserGainCap { cap: Some(cap.clone()) }).unwrap();
+
+        let update = |score| ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(60)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(150));
+        assert!(matches!(res, Err(ContractError::EpochCapExceeded { remaining: 40 })));
+
+        // Decreases never count against the cap
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(10)).unwrap();
+
+        let cap = EpochCap { policy: CapPolicy::Truncate, ..cap };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetUserGainCap { cap: Some(cap) }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(150)).unwrap();
+        assert_eq!(Attribute::new("truncated_to", "50"), res.attributes[1]);
+        assert_eq!(get_score(deps.as_ref(), "player"), 50);
+
+        // Next epoch starts with a fresh allowance
+        let mut next = mock_env();
+        next.block.time = next.block.time.plus_seconds(3600);
+        execute(deps.as_mut(), next, mock_info("creator", &[]), update(150)).unwrap();
+        assert_eq!(get_score(deps.as_ref(), "player"), 150);
+    }
+
+    #[test]
+    // The global cap limits gains summed over all users, alongside the per-user cap
+    fn global_epoch_gain_cap() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let cap = EpochCap { amount: 100, epoch_seconds: 3600, policy: CapPolicy::Reject };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("player", &[]), ExecuteMsg::SetGlobalGainCap { cap: Some(cap.clone()) });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetGlobalGainCap { cap: Some(cap.clone()) }).unwrap();
+        let user_cap = EpochCap { amount: 50, policy: CapPolicy::Truncate, ..cap.clone() };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetUserGainCap { cap: Some(user_cap) }).unwrap();
+
+        let update = |user: &str, score| ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+        // Truncated to the user allowance, and only that much counts globally
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("alice", 80)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("bob", 40)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("carol", 20));
+        assert!(matches!(res, Err(ContractError::GlobalCapExceeded { remaining: 10 })));
+
+        let res: GlobalGainResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GlobalGain {}).unwrap()).unwrap();
+        assert_eq!((90, Some(10)), (res.gained, res.remaining));
+
+        let cap = EpochCap { policy: CapPolicy::Truncate, ..cap };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetGlobalGainCap { cap: Some(cap) }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("carol", 20)).unwrap();
+        assert_eq!(10, get_score(deps.as_ref(), "carol"));
+
+        let mut next = mock_env();
+        next.block.time = next.block.time.plus_seconds(3600);
+        let res: GlobalGainResponse = from_binary(&query(deps.as_ref(), next.clone(), QueryMsg::GlobalGain {}).unwrap()).unwrap();
+        assert_eq!((0, Some(100)), (res.gained, res.remaining));
+        execute(deps.as_mut(), next, mock_info("creator", &[]), update("carol", 30)).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "carol"));
+    }
+
+    #[test]
+    // Every change is announced to the notifier without depending on it succeeding
+    fn notifier_receives_changes() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetNotifier { addr: Some("questengine".to_string()) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("player", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 12, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "questengine".to_string(),
+                    msg: to_binary(&NotifierExecuteMsg::ScoreChanged { user: "player".to_string(), old_score: 0, new_score: 12 }).unwrap(),
+                    funds: vec![],
+                },
+                NOTIFIER_REPLY_ID
+            )]
+        );
+
+        // A failing notifier is swallowed
+        let failure = Reply { id: NOTIFIER_REPLY_ID, result: SubMsgResult::Err("out of gas".to_string()) };
+        reply(deps.as_mut(), mock_env(), failure).unwrap();
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetNotifier { addr: None }).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 20, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    // Aggregation sums or maxes weighted scores from other score contracts
+    fn aggregated_score() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, .. } => {
+                let score = if contract_addr == "season1" { 100 } else { 40 };
+                let res = ScoreResponse { score, expires_at: None };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 60, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let sources = vec![
+            SourceInit { addr: "season1".to_string(), weight: Decimal::percent(50) },
+            SourceInit { addr: "season2".to_string(), weight: Decimal::percent(200) },
+        ];
+        let msg = ExecuteMsg::SetAggregation { sources: sources.clone(), mode: CombineMode::Sum };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let aggregated = || QueryMsg::AggregatedScore { user: "player".to_string(), auth: None };
+        let res = query(deps.as_ref(), mock_env(), aggregated()).unwrap();
+        let value: AggregatedScoreResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::new(60 + 50 + 80), value.score);
+        assert_eq!(vec!["local", "season1", "season2"], value.breakdown.iter().map(|s| s.source.as_str()).collect::<Vec<_>>());
+
+        let msg = ExecuteMsg::SetAggregation { sources, mode: CombineMode::Max };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), aggregated()).unwrap();
+        let value: AggregatedScoreResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::new(80), value.score);
+    }
+
+    #[test]
+    // Private mode needs a viewing key for the user or the owner
+    fn private_scores_with_viewing_keys() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 7, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { private_scores: Some(true), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        for (who, key) in [("player", "player-secret"), ("creator", "owner-secret"), ("rival", "rival-secret")] {
+            let msg = ExecuteMsg::SetViewingKey { key: key.to_string() };
+            execute(deps.as_mut(), mock_env(), mock_info(who, &[]), msg).unwrap();
+        }
+
+        let get = |address: &str, key: &str| QueryMsg::GetScore {
+            user: "player".to_string(),
+            auth: Some(ViewerAuth { address: address.to_string(), key: key.to_string() }),
+        };
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::GetScore { user: "player".to_string(), auth: None }).is_err());
+        assert!(query(deps.as_ref(), mock_env(), get("player", "wrong")).is_err());
+        assert!(query(deps.as_ref(), mock_env(), get("rival", "rival-secret")).is_err());
+
+        for (address, key) in [("player", "player-secret"), ("creator", "owner-secret")] {
+            let res = query(deps.as_ref(), mock_env(), get(address, key)).unwrap();
+            let value: ScoreResponse = from_binary(&res).unwrap();
+            assert_eq!(7, value.score);
+        }
+    }
+
+    #[test]
+    // Revealed updates must match the commitment and arrive before the deadline
+    fn commit_reveal_scores() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let updates = vec![
+            ScoreUpdate { user: Addr::unchecked("alice"), score: 30 },
+            ScoreUpdate { user: Addr::unchecked("bob"), score: 20 },
+        ];
+        let salt = Binary::from(b"pepper".as_slice());
+        let hash = commitment_hash(&updates, &salt).unwrap();
+
+        let reveal = |updates: Vec<ScoreUpdate>, salt: &Binary| ExecuteMsg::RevealScores { updates, salt: salt.clone() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reveal(updates.clone(), &salt));
+        assert!(matches!(res, Err(ContractError::NoCommitment {})));
+
+        let msg = ExecuteMsg::CommitScores { hash: hash.clone() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::CommitmentPending { .. })));
+
+        // Tampered batch or salt
+        let mut tampered = updates.clone();
+        tampered[1].score = 25;
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reveal(tampered, &salt));
+        assert!(matches!(res, Err(ContractError::InvalidReveal {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reveal(updates.clone(), &Binary::default()));
+        assert!(matches!(res, Err(ContractError::InvalidReveal {})));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reveal(updates.clone(), &salt)).unwrap();
+        assert_eq!(Attribute::new("applied", "2"), res.attributes[1]);
+        assert_eq!(30, get_score(deps.as_ref(), "alice"));
+        assert_eq!(20, get_score(deps.as_ref(), "bob"));
+
+        // A commitment can't be revealed twice
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), reveal(updates.clone(), &salt));
+        assert!(matches!(res, Err(ContractError::NoCommitment {})));
+
+        // Past the window the commitment expires and can be replaced
+        let msg = ExecuteMsg::CommitScores { hash };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone()).unwrap();
+        let mut env = mock_env();
+        env.block.height += REVEAL_WINDOW_BLOCKS + 1;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), reveal(updates, &salt));
+        assert!(matches!(res, Err(ContractError::CommitmentExpired { .. })));
+        execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap();
+    }
+
+    #[test]
+    // Equal scores are ordered by the height they were reached at, then by address
+    fn leaderboard_breaks_ties() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        for (user, score) in [("dave", 10), ("erin", 30), ("carol", 20), ("bob", 20), ("alice", 20)] {
+            env.block.height += 1;
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        }
+        // Same height as alice's update, so the address decides
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("aaron"), score: 20, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        // Rewriting an unchanged score keeps carol ahead
+        env.block.height += 1;
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 20, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let page = |cursor: Option<Binary>, limit: u32| -> LeaderboardResponse {
+            let msg = QueryMsg::Leaderboard { cursor, limit: Some(limit), scope: None };
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap()
+        };
+        let users = |res: &LeaderboardResponse| -> Vec<String> { res.entries.iter().map(|entry| entry.user.address.clone()).collect() };
+        let expected = ["erin", "carol", "bob", "aaron", "alice", "dave"];
+        assert_eq!(expected.to_vec(), users(&page(None, 10)));
+
+        // Pages of two never repeat or skip across the tie
+        let mut seen = vec![];
+        let mut cursor = None;
+        loop {
+            let res = page(cursor, 2);
+            seen.extend(users(&res));
+            cursor = match res.next_cursor {
+                Some(next) => Some(next),
+                None => break,
+            };
+        }
+        assert_eq!(expected.to_vec(), seen);
+
+        // Private mode only lists users who opted in
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { private_scores: Some(true), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert!(res.entries.is_empty());
+        let msg = ExecuteMsg::SetProfileVisibility { public: true };
+        execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap();
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(vec!["bob"], users(&res));
+    }
+
+    #[test]
+    // Scores changing between pages neither duplicate nor reorder what was already seen
+    fn leaderboard_cursor_survives_updates() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        for (user, score) in [("alice", 40), ("bob", 30), ("carol", 20), ("dave", 10)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: Some(2), scope: None };
+        let first: LeaderboardResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["alice", "bob"], first.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+
+        // alice drops below the cursor before the second page is read
+        env.block.height += 1;
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 5, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = QueryMsg::Leaderboard { cursor: first.next_cursor, limit: Some(2), scope: None };
+        let second: LeaderboardResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["carol", "dave"], second.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+        assert_eq!(None, second.next_cursor);
+
+        let msg = QueryMsg::Leaderboard { cursor: Some(Binary::from(b"garbage".as_slice())), limit: None, scope: None };
+        assert!(query(deps.as_ref(), env, msg).is_err());
+    }
+
+    #[test]
+    // One query returns the page, the board size and the viewer's standing
+    fn leaderboard_page_with_viewer() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (user, score) in [("alice", 40), ("bob", 30), ("carol", 20), ("dave", 10)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::LeaderboardPage { limit: Some(2), cursor: None, viewer: Some("carol".to_string()), scope: None };
+        let res: LeaderboardPageResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["alice", "bob"], res.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+        assert!(res.next_cursor.is_some());
+        assert_eq!(4, res.total);
+        assert_eq!(Some(ViewerRank { user: AddrInfo { address: "carol".to_string(), origin: None }, score: 20, rank: Some(3) }), res.viewer);
+
+        // Viewers without a score get no standing
+        let msg = QueryMsg::LeaderboardPage { limit: None, cursor: res.next_cursor, viewer: Some("erin".to_string()), scope: None };
+        let res: LeaderboardPageResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["carol", "dave"], res.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+        assert_eq!(None, res.viewer);
+    }
+
+    #[test]
+    // Ending a season archives its standings, which stay queryable afterwards
+    fn season_archive() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (user, score) in [("alice", 10), ("bob", 30), ("carol", 20)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        // Not archived yet
+        let msg = QueryMsg::SeasonLeaderboard { season: 1, limit: None, start_after: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        let mut env = mock_env();
+        env.block.height += 10;
+        let msg = ExecuteMsg::StartNewSeason { limit: Some(2) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg.clone()).unwrap();
+        assert_eq!(Attribute::new("done", "false"), res.attributes[3]);
+
+        // carol isn't archived yet: her old score reads as zero, and writing archives it first
+        assert_eq!(0, get_score(deps.as_ref(), "carol"));
+        let update = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 1, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update).unwrap();
+        assert_eq!(1, get_score(deps.as_ref(), "carol"));
+
+        // The new-season entry is left alone by the archiver
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(Attribute::new("archived", "0"), res.attributes[2]);
+        assert_eq!(Attribute::new("done", "true"), res.attributes[3]);
+        assert_eq!(1, get_score(deps.as_ref(), "carol"));
+
+        let msg = QueryMsg::SeasonLeaderboard { season: 1, limit: Some(2), start_after: None };
+        let res: SeasonLeaderboardResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["bob", "carol"], res.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+        let msg = QueryMsg::SeasonLeaderboard { season: 1, limit: None, start_after: Some("carol".to_string()) };
+        let res: SeasonLeaderboardResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["alice"], res.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+
+        let msg = QueryMsg::SeasonScore { season: 1, user: "carol".to_string(), auth: None };
+        let res: ScoreResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(20, res.score);
+
+        let res: SeasonsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ListSeasons { start_after: None, limit: None }).unwrap()).unwrap();
+        let (start, time) = (mock_env().block.height, mock_env().block.time);
+        assert_eq!(
+            vec![
+                Season { id: 1, start_height: start, start_time: time, end_height: Some(start + 10), archived: true },
+                Season { id: 2, start_height: start + 10, start_time: time, end_height: None, archived: false },
+            ],
+            res.seasons
+        );
+    }
+
+    #[test]
+    // With a season duration set, any execute call rolls the season over and archives lazily
+    fn automatic_season_rollover() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { season_duration: Some(3600), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let users: Vec<String> = (0..15).map(|i| format!("player{:02}", i)).collect();
+        for user in users.iter() {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score: 5, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        // Not yet elapsed
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3599);
+        execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::PurgeExpired { limit: None }).unwrap();
+        assert_eq!(1, CURRENT_SEASON.load(&deps.storage).unwrap());
+
+        // Anyone's call rolls over and archives a first batch
+        env.block.height += 1;
+        env.block.time = env.block.time.plus_seconds(1);
+        execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::PurgeExpired { limit: None }).unwrap();
+        assert_eq!(2, CURRENT_SEASON.load(&deps.storage).unwrap());
+        assert_eq!(5, TOTALS.load(&deps.storage).unwrap().users);
+        for user in users.iter() {
+            assert_eq!(0, get_score(deps.as_ref(), user));
+        }
+
+        // The next call finishes the archive
+        execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::PurgeExpired { limit: None }).unwrap();
+        assert!(ARCHIVING.may_load(&deps.storage).unwrap().is_none());
+        assert_eq!(0, TOTALS.load(&deps.storage).unwrap().users);
+        let msg = QueryMsg::SeasonScore { season: 1, user: "player14".to_string(), auth: None };
+        let res: ScoreResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(5, res.score);
+
+        // Season 2 is timed from the rollover
+        let season = SEASONS.load(&deps.storage, 2).unwrap();
+        assert_eq!(env.block.time, season.start_time);
+    }
+
+    #[test]
+    // The owner can undo the latest score change within the window, restoring the previous entry
+    fn rollback_score_updates() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { rollback_window: Some(10), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        for (user, score) in [("alice", 10), ("alice", 999), ("bob", 7)] {
+            env.block.height += 1;
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let rollback = ExecuteMsg::RollbackBatch { users: vec!["alice".to_string(), "bob".to_string()] };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), rollback.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), rollback.clone()).unwrap();
+        assert_eq!(2, res.events.len());
+        assert_eq!("score_rollback", res.events[0].ty);
+        assert_eq!(
+            vec![Attribute::new("user", "alice"), Attribute::new("from", "999"), Attribute::new("to", "10")],
+            res.events[0].attributes
+        );
+        assert_eq!(10, get_score(deps.as_ref(), "alice"));
+        // bob's entry didn't exist before
+        assert!(SCORES.may_load(&deps.storage, "bob".to_string()).unwrap().is_none());
+        assert_eq!(Totals { total_score: 10, users: 1, all_time_users: 1 }, TOTALS.load(&deps.storage).unwrap());
+
+        // Spends are recorded too, so Rollback undoes the spend rather than the older update
+        env.block.height += 1;
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 40, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let spend = ExecuteMsg::SpendScore { amount: 15, memo: String::new() };
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), spend).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::Rollback { user: "alice".to_string() }).unwrap();
+        assert_eq!(
+            vec![Attribute::new("user", "alice"), Attribute::new("from", "25"), Attribute::new("to", "40")],
+            res.events[0].attributes
+        );
+        assert_eq!(40, get_score(deps.as_ref(), "alice"));
+
+        // Only one level of undo
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::Rollback { user: "alice".to_string() });
+        assert!(matches!(res, Err(ContractError::NothingToRollBack { .. })));
+
+        env.block.height += 1;
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 50, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        env.block.height += 11;
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), ExecuteMsg::Rollback { user: "alice".to_string() });
+        assert!(matches!(res, Err(ContractError::RollbackExpired { .. })));
+    }
+
+    #[test]
+    // Every execute message is copied to the shadow contract without affecting local results
+    fn shadow_receives_copies() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetShadow { addr: Some("next".to_string()) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        let res: ConfigResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap()).unwrap();
+        assert_eq!(Some(Addr::unchecked("next")), res.shadow);
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 3, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone()).unwrap();
+        let exec = WasmMsg::Execute { contract_addr: "next".to_string(), msg: to_binary(&msg).unwrap(), funds: vec![] };
+        assert_eq!(vec![SubMsg::reply_on_error(exec, SHADOW_REPLY_ID)], res.messages);
+        assert_eq!(3, get_score(deps.as_ref(), "player"));
+
+        // Failed local calls send nothing
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        // A failing shadow is reported, not propagated
+        let reply = Reply { id: SHADOW_REPLY_ID, result: SubMsgResult::Err("boom".to_string()) };
+        let res = super::reply(deps.as_mut(), mock_env(), reply).unwrap();
+        assert_eq!(Attribute::new("method", "shadow_failed"), res.attributes[0]);
+    }
+
+    #[test]
+    // A configured prefix namespaces every attribute key and event type
+    fn event_prefix() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg { event_prefix: Some("arcade-1".to_string()), ..InstantiateMsg::default() };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidEventPrefix { .. })));
+        let msg = InstantiateMsg { event_prefix: Some("arcade1".to_string()), ..InstantiateMsg::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { rollback_window: Some(5), ..ConfigUpdate::default() });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(Attribute::new("arcade1-method", "try_update_config"), res.attributes[0]);
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 3, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Rollback { user: "player".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!("arcade1-score_rollback", res.events[0].ty);
+        assert_eq!(Attribute::new("arcade1-user", "player"), res.events[0].attributes[0]);
+
+        // An empty prefix switches namespacing off from the next message on
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { event_prefix: Some(String::new()), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetPaused { paused: false };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(Attribute::new("method", "try_set_paused"), res.attributes[0]);
+    }
+
+    #[test]
+    // Users who hide their profile drop off the board, and only they or the owner can read their score
+    fn profile_visibility() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (user, score) in [("alice", 30), ("bob", 20), ("carol", 10)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        for (who, msg) in [
+            ("bob", ExecuteMsg::SetProfileVisibility { public: false }),
+            ("bob", ExecuteMsg::SetViewingKey { key: "bob-key".to_string() }),
+        ] {
+            execute(deps.as_mut(), mock_env(), mock_info(who, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::LeaderboardPage { limit: None, cursor: None, viewer: Some("bob".to_string()), scope: None };
+        let res: LeaderboardPageResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["alice", "carol"], res.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+        assert_eq!(None, res.viewer);
+
+        let msg = QueryMsg::GetScore { user: "bob".to_string(), auth: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let auth = ViewerAuth { address: "bob".to_string(), key: "bob-key".to_string() };
+        let msg = QueryMsg::GetScore { user: "bob".to_string(), auth: Some(auth) };
+        let res: ScoreResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(20, res.score);
+
+        // Other users stay readable without a key
+        let msg = QueryMsg::GetScore { user: "carol".to_string(), auth: None };
+        query(deps.as_ref(), mock_env(), msg).unwrap();
+    }
+
+    #[test]
+    // Writes below the configured floor are clamped up to it, whichever path they take
+    fn score_floor_clamps() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_floor: Some(10), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let score = |deps: Deps, user: &str| -> u32 {
+            let msg = QueryMsg::GetScore { user: user.to_string(), auth: None };
+            let res: ScoreResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.score
+        };
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 4, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(10, score(deps.as_ref(), "alice"));
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("bob"), score: 30, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Rescaling down stops at the floor too
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+        let msg = ExecuteMsg::RescaleScores { numerator: 1, denominator: 10, rounding: None, start_after: None, limit: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(10, score(deps.as_ref(), "alice"));
+        assert_eq!(10, score(deps.as_ref(), "bob"));
+    }
+
+    #[test]
+    // Updates for users the gatekeeper rejects fail, others go through
+    fn gatekeeper_blocks_ineligible() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "kyc" => {
+                let GatekeeperQueryMsg::IsEligible { user } = from_binary(msg).unwrap();
+                let res = EligibleResponse { eligible: user != "mallory" };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetGatekeeper { addr: Some("kyc".to_string()) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("player", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("mallory"), score: 10, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::Ineligible { user }) if user == "mallory"));
+
+        // Without a gatekeeper nothing is queried
+        let msg = ExecuteMsg::SetGatekeeper { addr: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("mallory"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    #[test]
+    // Each checkpoint splits newly funded rewards by the scores held at that point
+    fn reward_pool_claims() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let update = |deps: DepsMut, user: &str, score: u32| {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        };
+        let claimable = |deps: Deps, user: &str| -> Uint128 {
+            let msg = QueryMsg::Claimable { user: user.to_string() };
+            let res: ClaimableResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.amount
+        };
+        update(deps.as_mut(), "alice", 30);
+        update(deps.as_mut(), "bob", 10);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::Checkpoint {});
+        assert!(matches!(res, Err(ContractError::NothingToDistribute {})));
+        execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(100, "uluna")), ExecuteMsg::FundPool {}).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(100, "uusd")), ExecuteMsg::FundPool {});
+        assert!(matches!(res, Err(ContractError::InvalidRewardFunds {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("sponsor", &[]), ExecuteMsg::Checkpoint {});
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::Checkpoint {}).unwrap();
+        assert_eq!(Uint128::new(75), claimable(deps.as_ref(), "alice"));
+        assert_eq!(Uint128::new(25), claimable(deps.as_ref(), "bob"));
+
+        // Score changes after a checkpoint only count towards later ones
+        update(deps.as_mut(), "alice", 90);
+        assert_eq!(Uint128::new(75), claimable(deps.as_ref(), "alice"));
+        execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(100, "uluna")), ExecuteMsg::FundPool {}).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::Checkpoint {}).unwrap();
+        assert_eq!(Uint128::new(165), claimable(deps.as_ref(), "alice"));
+        assert_eq!(Uint128::new(35), claimable(deps.as_ref(), "bob"));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimRewards {}).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send { to_address: "alice".to_string(), amount: coins(165, "uluna") })],
+            res.messages
+        );
+        assert_eq!(Uint128::zero(), claimable(deps.as_ref(), "alice"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimRewards {});
+        assert!(matches!(res, Err(ContractError::NothingToClaim {})));
+    }
+
+    #[test]
+    // A streaming pool pays out by score and elapsed time, settled lazily on each change
+    fn reward_stream_accrues() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let mut env = mock_env();
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        for (user, score) in [("alice", 30), ("bob", 10)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        }
+        execute(deps.as_mut(), env.clone(), mock_info("sponsor", &coins(1000, "uluna")), ExecuteMsg::FundPool {}).unwrap();
+        let msg = ExecuteMsg::SetRewardStream { per_second: Uint128::new(4) };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let claimable = |deps: Deps, env: &Env, user: &str| -> Uint128 {
+            let msg = QueryMsg::Claimable { user: user.to_string() };
+            let res: ClaimableResponse = from_binary(&query(deps, env.clone(), msg).unwrap()).unwrap();
+            res.amount
+        };
+
+        // 40 released over 10 seconds, split 3:1
+        env.block.time = env.block.time.plus_seconds(10);
+        assert_eq!(Uint128::new(30), claimable(deps.as_ref(), &env, "alice"));
+        assert_eq!(Uint128::new(10), claimable(deps.as_ref(), &env, "bob"));
+
+        // Bob catches up; the next 60 released are split evenly
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("bob"), score: 30, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(15);
+        assert_eq!(Uint128::new(60), claimable(deps.as_ref(), &env, "alice"));
+        assert_eq!(Uint128::new(40), claimable(deps.as_ref(), &env, "bob"));
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), ExecuteMsg::ClaimRewards {}).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send { to_address: "bob".to_string(), amount: coins(40, "uluna") })],
+            res.messages
+        );
+
+        // Streaming stops when the pool runs dry
+        env.block.time = env.block.time.plus_seconds(1000);
+        assert_eq!(Uint128::new(60 + 450), claimable(deps.as_ref(), &env, "alice"));
+        assert_eq!(Uint128::new(450), claimable(deps.as_ref(), &env, "bob"));
+    }
+
+    #[test]
+    // Penalized points stay in escrow until an appeal is decided or the window closes
+    fn penalty_appeals() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let mut env = mock_env();
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { appeal_window: Some(3600), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        for user in ["alice", "bob"] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score: 50, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::Penalize { user: user.to_string(), points: 20, reason: "smurfing".to_string(), idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+            assert_eq!(30, get_score(deps.as_ref(), user));
+        }
+
+        // One open case per user
+        let msg = ExecuteMsg::Penalize { user: "alice".to_string(), points: 5, reason: "again".to_string(), idempotency_key: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::PenaltyPending { .. })));
+
+        let appeal = || ExecuteMsg::Appeal { reason: "lag".to_string() };
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), appeal()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), appeal());
+        assert!(matches!(res, Err(ContractError::AlreadyAppealed {})));
+        execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), appeal()).unwrap();
+        // An open case keeps the address from being merged away before it's decided
+        let msg = ExecuteMsg::MergeIdentities { primary: "alice".to_string(), secondary: "bob".to_string() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::PenaltyPending { user }) if user == "bob"));
+
+        let msg = ExecuteMsg::ResolveAppeal { user: "alice".to_string(), uphold: false };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(50, get_score(deps.as_ref(), "alice"));
+        let msg = ExecuteMsg::ResolveAppeal { user: "bob".to_string(), uphold: true };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "bob"));
+
+        // Appeals are only taken within the window
+        let msg = ExecuteMsg::Penalize { user: "alice".to_string(), points: 5, reason: "again".to_string(), idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(3601);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), appeal());
+        assert!(matches!(res, Err(ContractError::AppealWindowClosed { .. })));
+
+        // Penalties never create a score, nor lift one to the floor
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_floor: Some(40), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let penalize = |user: &str| ExecuteMsg::Penalize { user: user.to_string(), points: 5, reason: "again".to_string(), idempotency_key: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), penalize("carol"));
+        assert!(matches!(res, Err(ContractError::NoScore { .. })));
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), penalize("bob")).unwrap();
+        assert!(res.events.is_empty());
+        assert_eq!(30, get_score(deps.as_ref(), "bob"));
+        assert_eq!(2, TOTALS.load(deps.as_ref().storage).unwrap().users);
+    }
+
+    #[test]
+    // A dedicated arbiter works through the queue of open appeals instead of the owner
+    fn arbiter_case_queue() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetArbiter { addr: Some("creator".to_string()) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::ArbiterIsOwner {})));
+        let msg = ExecuteMsg::SetArbiter { addr: Some("court".to_string()) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { appeal_window: Some(3600), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for user in ["carol", "alice", "bob"] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score: 50, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::Penalize { user: user.to_string(), points: 10, reason: "smurfing".to_string(), idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::Appeal { reason: format!("{} disagrees", user) };
+            execute(deps.as_mut(), mock_env(), mock_info(user, &[]), msg).unwrap();
+        }
+
+        let open = |deps: Deps, start_after: Option<u64>| -> Vec<(u64, String)> {
+            let msg = QueryMsg::OpenAppeals { start_after, limit: Some(2) };
+            let res: OpenAppealsResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.cases.into_iter().map(|case| (case.case_id, case.user)).collect()
+        };
+        assert_eq!(vec![(0, "carol".to_string()), (1, "alice".to_string())], open(deps.as_ref(), None));
+        assert_eq!(vec![(2, "bob".to_string())], open(deps.as_ref(), Some(1)));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AppealCase { case_id: 1 }).unwrap();
+        let case: AppealCase = from_binary(&res).unwrap();
+        assert_eq!("alice disagrees", case.penalty.appeal.unwrap().reason);
+        assert_eq!(10, case.penalty.points);
+
+        // The owner no longer decides appeals
+        let msg = ExecuteMsg::ResolveAppeal { user: "alice".to_string(), uphold: false };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("court", &[]), msg).unwrap();
+        assert_eq!(50, get_score(deps.as_ref(), "alice"));
+        assert_eq!(vec![(0, "carol".to_string()), (2, "bob".to_string())], open(deps.as_ref(), None));
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::AppealCase { case_id: 1 }).is_err());
+    }
+
+    #[test]
+    // Differently-cased addresses reach the same entry; invalid ones are an error
+    fn addresses_are_normalized() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("Alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(10, SCORES.load(&deps.storage, "alice".to_string()).unwrap());
+        for user in ["alice", "ALICE"] {
+            let msg = QueryMsg::GetScore { user: user.to_string(), auth: None };
+            let res: ScoreResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(10, res.score);
+        }
+        let msg = QueryMsg::GetScore { user: "x".to_string(), auth: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Entries written before normalization are moved over
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let mut totals = TOTALS.load(&deps.storage).unwrap();
+        let block = BlockInfo { height: 1, ..mock_env().block };
+        set_score(&mut deps.storage, &config, &mut totals, "Bob", 20, Some(1), &block).unwrap();
+        set_score(&mut deps.storage, &config, &mut totals, "ALICE", 5, Some(1), &block).unwrap();
+        TOTALS.save(&mut deps.storage, &totals).unwrap();
+
+        let msg = ExecuteMsg::NormalizeAddresses { start_after: None, limit: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::NotPaused {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(Attribute::new("moved", "2"), res.attributes[1]);
+
+        let users = SCORES.keys(&deps.storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>().unwrap();
+        assert_eq!(vec!["alice".to_string(), "bob".to_string()], users);
+        assert_eq!(10, get_score(deps.as_ref(), "alice"));
+        assert_eq!(20, get_score(deps.as_ref(), "bob"));
+        let report = query_check_invariants(deps.as_ref(), None, None).unwrap();
+        assert!(report.discrepancies.is_empty(), "{:?}", report.discrepancies);
+    }
+
+    #[test]
+    // Reserved addresses and the contract itself can never be given a score
+    fn reserved_addresses_rejected() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let update = ConfigUpdate { reserved_addresses: Some(vec!["treasury".to_string()]), ..ConfigUpdate::default() };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::UpdateConfig(update)).unwrap();
+
+        for user in ["treasury", "TREASURY", MOCK_CONTRACT_ADDR] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score: 10, expires_at: None, idempotency_key: None };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+            assert!(matches!(res, Err(ContractError::ReservedAddress { .. })), "{}", user);
+        }
+        let msg = ExecuteMsg::Penalize { user: "treasury".to_string(), points: 1, reason: "".to_string(), idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::ReservedAddress { .. })));
+        assert!(!SCORES.has(&deps.storage, "treasury".to_string()));
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    #[test]
+    // Parallel stages each pay out proven allocations until expiry, then claw back the rest
+    fn merkle_stages() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let mut env = mock_env();
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Two-leaf tree: each leaf's proof is the other leaf
+        let alice = leaf_hash(0, "alice", Uint128::new(30));
+        let bob = leaf_hash(1, "bob", Uint128::new(50));
+        let (first, second) = if alice <= bob { (alice, bob) } else { (bob, alice) };
+        let root = hex::encode(Sha256::new().chain(first).chain(second).finalize());
+
+        let expires_at = env.block.time.plus_seconds(100);
+        for funds in [100, 80] {
+            let msg = ExecuteMsg::RegisterMerkleStage { merkle_root: root.clone(), expires_at };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &coins(funds, "uluna")), msg).unwrap();
+        }
+
+        let claim = |stage: u32, amount: u128, proof: [u8; 32]| ExecuteMsg::ClaimMerkle {
+            stage,
+            // Alice sits at leaf 0 and is proven by Bob's leaf, and the other way round
+            index: if proof == bob { 0 } else { 1 },
+            amount: Uint128::new(amount),
+            proof: vec![hex::encode(proof)],
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim(1, 30, bob)).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send { to_address: "alice".to_string(), amount: coins(30, "uluna") })],
+            res.messages
+        );
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim(1, 30, bob));
+        assert!(matches!(res, Err(ContractError::AlreadyClaimed { stage: 1, index: 0 })));
+        let is_claimed = |deps: Deps, stage: u32, index: u64| -> bool {
+            let msg = QueryMsg::IsClaimed { stage, index };
+            let res: IsClaimedResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.claimed
+        };
+        assert!(is_claimed(deps.as_ref(), 1, 0));
+        assert!(!is_claimed(deps.as_ref(), 1, 1));
+        assert!(!is_claimed(deps.as_ref(), 2, 0));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim(2, 50, bob));
+        assert!(matches!(res, Err(ContractError::InvalidProof {})));
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim(2, 30, bob)).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), claim(2, 50, alice)).unwrap();
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::ClawbackStage { stage: 1 });
+        assert!(matches!(res, Err(ContractError::StageNotExpired { stage: 1, .. })));
+
+        env.block.time = env.block.time.plus_seconds(101);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), claim(1, 50, alice));
+        assert!(matches!(res, Err(ContractError::StageExpired { stage: 1, .. })));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::ClawbackStage { stage: 1 }).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send { to_address: "creator".to_string(), amount: coins(70, "uluna") })],
+            res.messages
+        );
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::ClawbackStage { stage: 1 });
+        assert!(matches!(res, Err(ContractError::StageClosed { stage: 1 })));
+
+        let msg = QueryMsg::MerkleStages { start_after: None, limit: None };
+        let res: MerkleStagesResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(vec![(1, 30, true), (2, 80, false)], res.stages.iter().map(|s| (s.id, s.claimed.u128(), s.clawed_back)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    // Claim flags pack into shared words without disturbing their neighbours
+    fn merkle_claim_bitmap() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        for index in [0, 5, 63, 64, 1000] {
+            set_claimed(&mut deps.storage, 1, index).unwrap();
+        }
+        for index in [0, 5, 63, 64, 1000] {
+            assert!(is_claimed(&deps.storage, 1, index).unwrap());
+        }
+        for index in [1, 62, 65, 999] {
+            assert!(!is_claimed(&deps.storage, 1, index).unwrap());
+        }
+        assert!(!is_claimed(&deps.storage, 2, 0).unwrap());
+        // Indexes 0 to 127 share two entries
+        assert_eq!(3, MERKLE_CLAIM_BITS.keys(&deps.storage, None, None, Order::Ascending).count());
+    }
+
+    #[test]
+    // GetScore reports stored points through the configured formula
+    fn derived_score_formula() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 1000, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let cases = [
+            (ScoreFormula::Raw, 1000),
+            (ScoreFormula::Sqrt, 31),
+            (ScoreFormula::Log2 { scale: 10 }, 90),
+            (ScoreFormula::Multiplier { numerator: 3, denominator: 2 }, 1500),
+        ];
+        for (formula, expected) in cases {
+            let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_formula: Some(formula), ..ConfigUpdate::default() });
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            assert_eq!(expected, get_score(deps.as_ref(), "player"), "{:?}", formula);
+
+            // Board rows agree with GetScore
+            let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+            let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(expected, res.entries[0].score, "{:?}", formula);
+            let msg = QueryMsg::LeaderboardPage { limit: None, cursor: None, viewer: Some("player".to_string()), scope: None };
+            let res: LeaderboardPageResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(expected, res.viewer.unwrap().score, "{:?}", formula);
+            let res: PodiumResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Podium { k: 1 }).unwrap()).unwrap();
+            assert_eq!(expected, res.entries[0].score, "{:?}", formula);
+            let msg = QueryMsg::CrossRank { user: "player".to_string(), boards: vec!["default".to_string()], auth: None };
+            let res: CrossRankResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(expected, res.boards[0].score, "{:?}", formula);
+        }
+        // Stored points are untouched
+        assert_eq!(1000, SCORES.load(&deps.storage, "player".to_string()).unwrap());
+
+        let formula = ScoreFormula::Multiplier { numerator: 1, denominator: 0 };
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_formula: Some(formula), ..ConfigUpdate::default() });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::ZeroDenominator {})));
+
+        for (points, root) in [(0, 0), (1, 1), (3, 1), (4, 2), (u32::MAX, 65535)] {
+            assert_eq!(root, ScoreFormula::Sqrt.apply(points));
+        }
+        assert_eq!(0, ScoreFormula::Log2 { scale: 10 }.apply(0));
+        assert_eq!(u32::MAX, ScoreFormula::Multiplier { numerator: 2, denominator: 1 }.apply(u32::MAX));
+    }
+
+    #[test]
+    // Fractional scores are stored exactly as fixed-point units and read back as decimals
+    fn decimal_scores() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg { score_decimals: Some(7), ..InstantiateMsg::default() };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidScoreDecimals { max: 6 })));
+        let msg = InstantiateMsg { score_decimals: Some(2), ..InstantiateMsg::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let decimal = |value: &str| -> Decimal { value.parse().unwrap() };
+        for (user, score) in [("alice", "12.5"), ("bob", "12.25"), ("carol", "3")] {
+            let msg = ExecuteMsg::UpdateScoreDecimal { user: Addr::unchecked(user), score: decimal(score), expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        assert_eq!(1250, get_score(deps.as_ref(), "alice"));
+
+        let msg = QueryMsg::GetScoreDecimal { user: "bob".to_string(), auth: None };
+        let res: DecimalScoreResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(decimal("12.25"), res.score);
+
+        // Fixed-point units keep the board ordered by the fractional value
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec![1250, 1225, 300], res.entries.iter().map(|entry| entry.score).collect::<Vec<_>>());
+
+        for score in ["1.005", "42949673"] {
+            let msg = ExecuteMsg::UpdateScoreDecimal { user: Addr::unchecked("dave"), score: decimal(score), expires_at: None, idempotency_key: None };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+            assert!(matches!(res, Err(ContractError::ScorePrecision { decimals: 2 })), "{}", score);
+        }
+    }
+
+    #[test]
+    // The podium set follows every write and matches the head of the leaderboard
+    fn podium_tracks_top_k() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::SetTopK { k: 101 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::TopKTooLarge { max: 100 })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetTopK { k: 3 }).unwrap();
+
+        let podium = |deps: Deps, k: u32| -> Vec<(String, u32)> {
+            let res: PodiumResponse = from_binary(&query(deps, mock_env(), QueryMsg::Podium { k }).unwrap()).unwrap();
+            res.entries.into_iter().map(|entry| (entry.user.address, entry.score)).collect()
+        };
+        let update = |deps: DepsMut, user: &str, score: u32| {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        };
+
+        for (user, score) in [("alice", 50), ("bob", 40), ("carol", 30), ("dave", 20)] {
+            update(deps.as_mut(), user, score);
+        }
+        assert_eq!(vec![("alice".to_string(), 50), ("bob".to_string(), 40)], podium(deps.as_ref(), 2));
+
+        // A member dropping off the set is replaced by the next entry of the index
+        update(deps.as_mut(), "alice", 10);
+        let expected: Vec<_> = [("bob", 40), ("carol", 30), ("dave", 20)].iter().map(|(u, s)| (u.to_string(), *s)).collect();
+        assert_eq!(expected, podium(deps.as_ref(), 3));
+
+        // An outsider climbing in pushes the last member out
+        update(deps.as_mut(), "erin", 35);
+        let expected: Vec<_> = [("bob", 40), ("erin", 35), ("carol", 30)].iter().map(|(u, s)| (u.to_string(), *s)).collect();
+        assert_eq!(expected, podium(deps.as_ref(), 3));
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::Podium { k: 4 }).is_err());
+
+        // Growing a full set needs a rebuild before the new size can be read; it carries on
+        // from the entries already held
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetTopK { k: 5 }).unwrap();
+        assert_eq!(3, podium(deps.as_ref(), 3).len());
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::Podium { k: 5 }).is_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::RebuildTopK { limit: None });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let rebuild = ExecuteMsg::RebuildTopK { limit: Some(1) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rebuild.clone()).unwrap();
+        assert!(res.attributes.contains(&Attribute::new("done", "false")));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rebuild).unwrap();
+        assert!(res.attributes.contains(&Attribute::new("done", "true")));
+
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: Some(5), scope: None };
+        let board: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let board: Vec<_> = board.entries.into_iter().map(|entry| (entry.user.address, entry.score)).collect();
+        assert_eq!(board, podium(deps.as_ref(), 5));
+    }
+
+    #[test]
+    // A damaged index is repaired in resumable batches while paused
+    fn rebuild_index_repairs_damage() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (user, score) in [("alice", 30), ("bob", 20), ("carol", 10)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        // Lose bob's key and leave stale ones behind
+        let height = mock_env().block.height;
+        SCORE_INDEX.remove(&mut deps.storage, index_key(20, height, "bob"));
+        SCORE_INDEX.save(&mut deps.storage, index_key(99, height, "carol"), &()).unwrap();
+        SCORE_INDEX.save(&mut deps.storage, index_key(5, height, "nobody"), &()).unwrap();
+
+        let rebuild = |start_after: Option<String>| ExecuteMsg::RebuildIndex { start_after, limit: Some(2) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rebuild(None));
+        assert!(matches!(res, Err(ContractError::NotPaused {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), rebuild(None));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        let mut start_after = None;
+        let (mut added, mut removed) = (0, 0);
+        for _ in 0..5 {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rebuild(start_after)).unwrap();
+            let value = |key: &str| res.attributes.iter().find(|attr| attr.key == key).map(|attr| attr.value.clone());
+            added += value("added").unwrap().parse::<u32>().unwrap();
+            removed += value("removed").unwrap().parse::<u32>().unwrap();
+            if value("done").unwrap() == "true" {
+                break;
+            }
+            start_after = value("next_start_after");
+        }
+        assert_eq!((1, 2), (added, removed));
+
+        let keys = SCORE_INDEX.keys(&deps.storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>().unwrap();
+        let expected = vec![index_key(30, height, "alice"), index_key(20, height, "bob"), index_key(10, height, "carol")];
+        assert_eq!(expected, keys);
+    }
+
+    #[test]
+    // Collected funds are split by basis points, the owner keeping the rest
+    fn fee_routes_split_collections() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let mut env = mock_env();
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let routes = |routes: &[(Option<&str>, u16)]| {
+            let routes = routes.iter().map(|(addr, bps)| FeeRouteInit { addr: addr.map(String::from), bps: *bps }).collect();
+            ExecuteMsg::UpdateConfig(ConfigUpdate { fee_routes: Some(routes), ..ConfigUpdate::default() })
+        };
+        for invalid in [routes(&[(Some("treasury"), 9_000), (None, 1_001)]), routes(&[(Some("treasury"), 0)])] {
+            let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), invalid);
+            assert!(matches!(res, Err(ContractError::InvalidFeeRoutes {})));
+        }
+        let msg = routes(&[(Some("treasury"), 5_000), (Some("devfund"), 2_500), (None, 1_000)]);
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let res: FeeRoutesResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::FeeRoutes {}).unwrap()).unwrap();
+        assert_eq!(3, res.routes.len());
+        assert_eq!(None, res.routes[2].addr);
+        assert_eq!("creator", res.remainder.as_str());
+
+        // An expired stage nobody claimed from goes back to the owner whole, not through the routes
+        let msg = ExecuteMsg::RegisterMerkleStage { merkle_root: "00".repeat(32), expires_at: env.block.time.plus_seconds(10) };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &coins(1001, "uluna")), msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(11);
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), ExecuteMsg::ClawbackStage { stage: 1 }).unwrap();
+        assert_eq!(
+            vec![SubMsg::new(BankMsg::Send { to_address: "creator".to_string(), amount: coins(1001, "uluna") })],
+            res.messages
+        );
+    }
+
+    #[test]
+    // Updates can be paid for natively or with the configured cw20, and fees are routed
+    fn update_fees() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let routes = vec![FeeRouteInit { addr: Some("treasury".to_string()), bps: 5_000 }];
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { fee_routes: Some(routes), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let cw20 = Cw20FeeInit { token: "project".to_string(), amount: Uint128::new(40) };
+        let msg = ExecuteMsg::SetUpdateFees { native: Some(coin(10, "uluna")), cw20: Some(cw20) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let update = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 5, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(9, "uluna")), update.clone());
+        assert!(matches!(res, Err(ContractError::FeeRequired { .. })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(10, "uluna")), update).unwrap();
+        assert_eq!(
+            vec![
+                SubMsg::new(BankMsg::Send { to_address: "treasury".to_string(), amount: coins(5, "uluna") }),
+                SubMsg::new(BankMsg::Send { to_address: "creator".to_string(), amount: coins(5, "uluna") }),
+            ],
+            res.messages
+        );
+
+        let receive = |sender: &str, amount: u128| {
+            let msg = ReceiveMsg::UpdateScore { user: Addr::unchecked("bob"), score: 7, expires_at: None, idempotency_key: None };
+            ExecuteMsg::Receive(Cw20ReceiveMsg { sender: sender.to_string(), amount: Uint128::new(amount), msg: to_binary(&msg).unwrap() })
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("other_token", &[]), receive("creator", 40));
+        assert!(matches!(res, Err(ContractError::InvalidFeeToken {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("project", &[]), receive("creator", 39));
+        assert!(matches!(res, Err(ContractError::FeeRequired { .. })));
+        // Paying doesn't grant write access
+        let res = execute(deps.as_mut(), mock_env(), mock_info("project", &[]), receive("mallory", 40));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("project", &[]), receive("creator", 40)).unwrap();
+        assert_eq!(7, get_score(deps.as_ref(), "bob"));
+        let transfer = |recipient: &str| {
+            let msg = Cw20ExecuteMsg::Transfer { recipient: recipient.to_string(), amount: Uint128::new(20) };
+            SubMsg::new(WasmMsg::Execute { contract_addr: "project".to_string(), msg: to_binary(&msg).unwrap(), funds: vec![] })
+        };
+        assert_eq!(vec![transfer("treasury"), transfer("creator")], res.messages);
+
+        // A zero cw20 fee is waived, so zero-token transfers don't count as paid writes
+        let cw20 = Cw20FeeInit { token: "project".to_string(), amount: Uint128::zero() };
+        let msg = ExecuteMsg::SetUpdateFees { native: None, cw20: Some(cw20) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(None, CONFIG.load(&deps.storage).unwrap().cw20_update_fee);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("project", &[]), receive("creator", 0));
+        assert!(matches!(res, Err(ContractError::InvalidFeeToken {})));
+    }
+
+    #[test]
+    // Admin, Hooks and Claims answer in the cw-controllers wire format
+    fn controller_queries() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let raw = |deps: Deps, msg: QueryMsg| String::from_utf8(query(deps, mock_env(), msg).unwrap().to_vec()).unwrap();
+        assert_eq!(r#"{"admin":"creator"}"#, raw(deps.as_ref(), QueryMsg::Admin {}));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::AddHook { addr: "listener".to_string() });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::AddHook { addr: "listener".to_string() }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::AddHook { addr: "listener".to_string() });
+        assert!(matches!(res, Err(ContractError::HookAlreadyRegistered {})));
+        assert_eq!(r#"{"hooks":["listener"]}"#, raw(deps.as_ref(), QueryMsg::Hooks {}));
+
+        // Hooks hear about every change, like the notifier
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 10, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let notify = NotifierExecuteMsg::ScoreChanged { user: "player".to_string(), old_score: 0, new_score: 10 };
+        let exec = WasmMsg::Execute { contract_addr: "listener".to_string(), msg: to_binary(&notify).unwrap(), funds: vec![] };
+        assert_eq!(vec![SubMsg::reply_on_error(exec, NOTIFIER_REPLY_ID)], res.messages);
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::RemoveHook { addr: "listener".to_string() }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::RemoveHook { addr: "listener".to_string() });
+        assert!(matches!(res, Err(ContractError::HookNotRegistered {})));
+
+        // Pending rewards show up as one claim releasable now
+        assert_eq!(r#"{"claims":[]}"#, raw(deps.as_ref(), QueryMsg::Claims { address: "player".to_string() }));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(50, "uluna")), ExecuteMsg::FundPool {}).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::Checkpoint {}).unwrap();
+        let expected = format!(r#"{{"claims":[{{"amount":"50","release_at":{{"at_height":{}}}}}]}}"#, mock_env().block.height);
+        assert_eq!(expected, raw(deps.as_ref(), QueryMsg::Claims { address: "player".to_string() }));
+    }
+
+    #[test]
+    // Outsized jumps are written provisionally and freeze the user until reviewed
+    fn anomaly_guard_freezes_user() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { anomaly_multiple: Some(10), appeal_window: Some(100), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let update = |user: &str, score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+        for (user, score) in [("alice", 10), ("bob", 30), ("alice", 100)] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(user, score)).unwrap();
+            assert_eq!(vec![evt_score_changed(user, score)], res.events);
+        }
+
+        // Ten times the mean of 65 is fine for a newcomer, but not ten times bob's own 30
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("carol", 650)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("bob", 301)).unwrap();
+        assert_eq!(
+            vec![
+                Event::new("anomaly").add_attribute("user", "bob").add_attribute("previous", "30").add_attribute("score", "301"),
+                evt_score_changed("bob", 301),
+            ],
+            res.events
+        );
+        assert_eq!(301, get_score(deps.as_ref(), "bob"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("bob", 40));
+        assert!(matches!(res, Err(ContractError::UserFrozen { .. })));
+        // Nor can a penalty land, which a rejection would silently undo
+        let penalize = ExecuteMsg::Penalize { user: "bob".to_string(), points: 100, reason: "spam".to_string(), idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), penalize.clone());
+        assert!(matches!(res, Err(ContractError::UserFrozen { .. })));
+
+        let msg = QueryMsg::ProvisionalUpdates { start_after: None, limit: None };
+        let res: ProvisionalUpdatesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec![("bob".to_string(), Some(30))], res.updates.iter().map(|e| (e.user.clone(), e.update.previous)).collect::<Vec<_>>());
+
+        // Rejecting puts the replaced score back and unfreezes
+        let review = |user: &str, accept: bool| ExecuteMsg::ReviewAnomaly { user: user.to_string(), accept };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), review("bob", false));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), review("bob", false)).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "bob"));
+        assert_eq!(None, PENALTIES.may_load(&deps.storage, "bob".to_string()).unwrap());
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), penalize).unwrap();
+        assert_eq!(0, get_score(deps.as_ref(), "bob"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), review("bob", true));
+        assert!(matches!(res, Err(ContractError::NotFlagged { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("bob", 40)).unwrap();
+
+        // Accepting keeps it
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("dave", 100_000)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), review("dave", true)).unwrap();
+        assert_eq!(100_000, get_score(deps.as_ref(), "dave"));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("dave", 100_001)).unwrap();
+    }
+
+    #[test]
+    // Members fill a bracket in leaderboard order up to its capacity
+    fn brackets_assign_by_score() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (user, score) in [("alice", 150), ("bob", 120), ("carol", 180), ("dave", 50), ("erin", 300)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let bracket = |name: &str, min_score: u32, max_score: u32, capacity: u32| Bracket { name: name.to_string(), min_score, max_score, capacity };
+        let msg = ExecuteMsg::SetBrackets { brackets: vec![bracket("gold", 100, 199, 2), bracket("bronze", 0, 99, 10)] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let overlapping = ExecuteMsg::SetBrackets { brackets: vec![bracket("gold", 100, 199, 2), bracket("silver", 50, 100, 2)] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), overlapping);
+        assert!(matches!(res, Err(ContractError::BracketOverlap { .. })));
+        let empty = ExecuteMsg::SetBrackets { brackets: vec![bracket("gold", 100, 199, 0)] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), empty);
+        assert!(matches!(res, Err(ContractError::InvalidBracket { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let assignment = |deps: Deps, user: &str| {
+            let res: BracketAssignmentResponse = from_binary(&query(deps, mock_env(), QueryMsg::Bracket { user: user.to_string() }).unwrap()).unwrap();
+            (res.bracket, res.position)
+        };
+        assert_eq!((Some("gold".to_string()), Some(1)), assignment(deps.as_ref(), "carol"));
+        assert_eq!((Some("gold".to_string()), Some(2)), assignment(deps.as_ref(), "alice"));
+        // Over capacity, out of every range, or without a score
+        assert_eq!((None, None), assignment(deps.as_ref(), "bob"));
+        assert_eq!((None, None), assignment(deps.as_ref(), "erin"));
+        assert_eq!((None, None), assignment(deps.as_ref(), "frank"));
+        assert_eq!((Some("bronze".to_string()), Some(1)), assignment(deps.as_ref(), "dave"));
+
+        let list = |deps: Deps, start_after: Option<&str>| {
+            let msg = QueryMsg::ListBracket { name: "gold".to_string(), start_after: start_after.map(String::from), limit: Some(1) };
+            let res: BracketMembersResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.members.into_iter().map(|entry| entry.user.address).collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["carol"], list(deps.as_ref(), None));
+        assert_eq!(vec!["alice"], list(deps.as_ref(), Some("carol")));
+        assert!(list(deps.as_ref(), Some("alice")).is_empty());
+        let msg = QueryMsg::ListBracket { name: "platinum".to_string(), start_after: None, limit: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Losing points moves carol down a bracket and lets bob in
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 90, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!((Some("gold".to_string()), Some(2)), assignment(deps.as_ref(), "bob"));
+        assert_eq!((Some("bronze".to_string()), Some(1)), assignment(deps.as_ref(), "carol"));
+        // ...so she can't carry a page of gold on, which would run into the brackets above
+        let msg = QueryMsg::ListBracket { name: "gold".to_string(), start_after: Some("carol".to_string()), limit: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // An expired score neither holds a place nor shows
+        let expires_at = Some(mock_env().block.time.plus_seconds(10));
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 150, expires_at, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(10);
+        let res: BracketAssignmentResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Bracket { user: "alice".to_string() }).unwrap()).unwrap();
+        assert_eq!(None, res.bracket);
+        let res: BracketAssignmentResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Bracket { user: "bob".to_string() }).unwrap()).unwrap();
+        assert_eq!(Some(1), res.position);
+        let msg = QueryMsg::ListBracket { name: "gold".to_string(), start_after: None, limit: None };
+        let res: BracketMembersResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(vec!["bob"], res.members.into_iter().map(|entry| entry.user.address).collect::<Vec<_>>());
+    }
+
+    #[test]
+    // The merge policy decides the primary's score and the secondary is tombstoned
+    fn merge_identities_policies() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let update = |user: &str, score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+        for (user, score) in [("alice", 10), ("alt1", 25), ("alt2", 40), ("alt3", 5)] {
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(user, score)).unwrap();
+        }
+        let merge = |secondary: &str| ExecuteMsg::MergeIdentities { primary: "alice".to_string(), secondary: secondary.to_string() };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), merge("alt1"));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), merge("ALICE"));
+        assert!(matches!(res, Err(ContractError::SelfMerge {})));
+
+        // Sum by default
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), merge("alt1")).unwrap();
+        let expected = Event::new("merge_identities")
+            .add_attribute("primary", "alice")
+            .add_attribute("secondary", "alt1")
+            .add_attribute("policy", "Sum")
+            .add_attribute("primary_score", "10")
+            .add_attribute("secondary_score", "25")
+            .add_attribute("score", "35");
+        assert_eq!(vec![expected], res.events);
+        assert_eq!(35, get_score(deps.as_ref(), "alice"));
+        assert_eq!(0, get_score(deps.as_ref(), "alt1"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("alt1", 5));
+        assert!(matches!(res, Err(ContractError::Merged { .. })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), merge("alt1"));
+        assert!(matches!(res, Err(ContractError::Merged { .. })));
+        // Nor can wagers, spends or vouchers move the merged address's points
+        let wager = ExecuteMsg::OpenWager { opponent: "alice".to_string(), stake: 1, expires_at: mock_env().block.time.plus_seconds(60) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alt1", &[]), wager);
+        assert!(matches!(res, Err(ContractError::Merged { .. })));
+        // A score can't be merged into a reserved address
+        let msg = ExecuteMsg::MergeIdentities { primary: MOCK_CONTRACT_ADDR.to_string(), secondary: "alt2".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::ReservedAddress { .. })));
+
+        let policy = |merge_policy| ExecuteMsg::UpdateConfig(ConfigUpdate { merge_policy: Some(merge_policy), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), policy(MergePolicy::Max)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), merge("alt2")).unwrap();
+        assert_eq!(40, get_score(deps.as_ref(), "alice"));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), policy(MergePolicy::KeepPrimary)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), merge("alt3")).unwrap();
+        assert_eq!(40, get_score(deps.as_ref(), "alice"));
+        assert_eq!(1, TOTALS.load(deps.as_ref().storage).unwrap().users);
+    }
+
+    #[test]
+    // Anyone may attest; attestations are listed per tag and leave scores alone
+    fn attestations_are_separate() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let attest = |value: i64, tag: &str| ExecuteMsg::Attest { user: "Player".to_string(), value, tag: tag.to_string() };
+
+        execute(deps.as_mut(), mock_env(), mock_info("guild", &[]), attest(5, "teamwork")).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("arena", &[]), attest(-2, "teamwork")).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("arena", &[]), attest(-4, "teamwork")).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("arena", &[]), attest(1, "skill")).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("arena", &[]), attest(1, ""));
+        assert!(matches!(res, Err(ContractError::InvalidTag { .. })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("arena", &[]), attest(1, &"x".repeat(33)));
+        assert!(matches!(res, Err(ContractError::InvalidTag { .. })));
+
+        let list = |deps: Deps, start_after: Option<&str>| {
+            let msg = QueryMsg::Attestations { user: "player".to_string(), tag: "teamwork".to_string(), start_after: start_after.map(String::from), limit: None };
+            let res: AttestationsResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.attestations.into_iter().map(|entry| (entry.attester, entry.value)).collect::<Vec<_>>()
+        };
+        assert_eq!(vec![("arena".to_string(), -4), ("guild".to_string(), 5)], list(deps.as_ref(), None));
+        assert_eq!(vec![("guild".to_string(), 5)], list(deps.as_ref(), Some("arena")));
+        assert_eq!(0, get_score(deps.as_ref(), "player"));
+        assert_eq!(0, TOTALS.may_load(deps.as_ref().storage).unwrap().unwrap_or_default().users);
+    }
+
+    #[test]
+    // Every score change gets the next sequence number while the journal is on
+    fn journal_sequences_changes() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let update = |user: &str, score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+        let latest = |deps: Deps| {
+            let res: SequenceResponse = from_binary(&query(deps, mock_env(), QueryMsg::LatestSequence {}).unwrap()).unwrap();
+            res.sequence
+        };
+
+        // Nothing is numbered until the owner turns it on
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("alice", 10)).unwrap();
+        assert_eq!(0, latest(deps.as_ref()));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::SetJournal { enabled: true });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetJournal { enabled: true }).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("alice", 20)).unwrap();
+        let expected = Event::new("journal").add_attribute("first_sequence", "1").add_attribute("last_sequence", "1");
+        assert_eq!(vec![evt_score_changed("alice", 20), expected], res.events);
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("bob", 5)).unwrap();
+        // A merge removes one entry and rewrites another
+        let merge = ExecuteMsg::MergeIdentities { primary: "carol".to_string(), secondary: "bob".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), merge).unwrap();
+        let expected = Event::new("journal").add_attribute("first_sequence", "3").add_attribute("last_sequence", "4");
+        assert_eq!(expected, res.events[1]);
+        // Rewriting the same score changes nothing and takes no number
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("alice", 20)).unwrap();
+        assert_eq!(vec![evt_score_changed("alice", 20)], res.events);
+        assert_eq!(4, latest(deps.as_ref()));
+
+        let changes = |deps: Deps, seq: u64| {
+            let res: ChangesResponse = from_binary(&query(deps, mock_env(), QueryMsg::ChangesSince { seq, limit: None }).unwrap()).unwrap();
+            res.changes.into_iter().map(|change| (change.seq, change.user, change.old, change.new)).collect::<Vec<_>>()
+        };
+        assert_eq!(vec![(3, "bob".to_string(), 5, 0), (4, "carol".to_string(), 0, 5)], changes(deps.as_ref(), 2));
+        assert_eq!(4, changes(deps.as_ref(), 0).len());
+
+        // Numbers pick up where they left off after a pause in journaling
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetJournal { enabled: false }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("alice", 30)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetJournal { enabled: true }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update("alice", 40)).unwrap();
+        assert_eq!(vec![(5, "alice".to_string(), 30, 40)], changes(deps.as_ref(), 4));
+    }
+
+    #[test]
+    // A digest can only be recorded once, for an archived season
+    fn snapshot_digest_recorded_once() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let finalize = |digest: &str| ExecuteMsg::FinalizeSnapshotDigest { snapshot_id: 1, digest: digest.to_string() };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), finalize("bafy1"));
+        assert!(matches!(res, Err(ContractError::SnapshotNotArchived { snapshot_id: 1 })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::StartNewSeason { limit: None }).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), finalize("bafy1"));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), finalize(""));
+        assert!(matches!(res, Err(ContractError::InvalidDigest { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), finalize("bafy1")).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), finalize("bafy2"));
+        assert!(matches!(res, Err(ContractError::DigestAlreadyRecorded { snapshot_id: 1 })));
+
+        let digest = |deps: Deps, snapshot_id: u32| {
+            let res: SnapshotDigestResponse = from_binary(&query(deps, mock_env(), QueryMsg::SnapshotDigest { snapshot_id }).unwrap()).unwrap();
+            (res.digest, res.recorded_at)
+        };
+        assert_eq!((Some("bafy1".to_string()), Some(mock_env().block.height)), digest(deps.as_ref(), 1));
+        assert_eq!((None, None), digest(deps.as_ref(), 2));
+    }
+
+    #[test]
+    // Oversized inputs are turned away before any state is touched
+    fn input_bounds_rejected() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let run = |deps: DepsMut, msg: ExecuteMsg| execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+
+        let users = vec!["alice".to_string(); 101];
+        assert!(matches!(run(deps.as_mut(), ExecuteMsg::RollbackBatch { users }), ContractError::BatchTooLarge { max: 100 }));
+        let updates = vec![ScoreUpdate { user: Addr::unchecked("alice"), score: 1 }; 101];
+        let msg = ExecuteMsg::RevealScores { updates, salt: Binary::from(b"salt".as_slice()) };
+        assert!(matches!(run(deps.as_mut(), msg), ContractError::BatchTooLarge { .. }));
+        let msg = ExecuteMsg::RevealScores { updates: vec![], salt: Binary::from(vec![0u8; 65]) };
+        assert!(matches!(run(deps.as_mut(), msg), ContractError::SaltTooLong { max: 64 }));
+        let msg = ExecuteMsg::PurgeExpired { limit: Some(101) };
+        assert!(matches!(run(deps.as_mut(), msg), ContractError::InvalidLimit { max: 100 }));
+        let score = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 1, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), score).unwrap();
+        let msg = ExecuteMsg::StartNewSeason { limit: Some(0) };
+        assert!(matches!(run(deps.as_mut(), msg), ContractError::InvalidLimit { max: 100 }));
+        let msg = ExecuteMsg::SetViewingKey { key: "k".repeat(65) };
+        assert!(matches!(run(deps.as_mut(), msg), ContractError::ViewingKeyTooLong { max: 64 }));
+        let msg = ExecuteMsg::ClaimMerkle { stage: 1, index: 0, amount: Uint128::new(1), proof: vec![String::new(); 33] };
+        assert!(matches!(run(deps.as_mut(), msg), ContractError::ProofTooLong { max: 32 }));
+        let msg = ExecuteMsg::Penalize { user: "alice".to_string(), points: 1, reason: "r".repeat(257), idempotency_key: None };
+        assert!(matches!(run(deps.as_mut(), msg), ContractError::ReasonTooLong { max: 256 }));
+        let update = ConfigUpdate { reserved_addresses: Some(vec!["treasury".to_string(); 51]), ..ConfigUpdate::default() };
+        assert!(matches!(run(deps.as_mut(), ExecuteMsg::UpdateConfig(update)), ContractError::TooManyReservedAddresses { max: 50 }));
+        let routes = vec![FeeRouteInit { addr: None, bps: 1 }; 11];
+        let update = ConfigUpdate { fee_routes: Some(routes), ..ConfigUpdate::default() };
+        assert!(matches!(run(deps.as_mut(), ExecuteMsg::UpdateConfig(update)), ContractError::TooManyFeeRoutes { max: 10 }));
+
+        // At the caps everything goes through
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::PurgeExpired { limit: Some(100) }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::SetViewingKey { key: "k".repeat(64) }).unwrap();
+    }
+
+    #[test]
+    // A retried message with a live key is rejected, or skipped under the Ignore policy
+    fn idempotency_keys_dedupe_retries() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let penalize = |key: &str| ExecuteMsg::Penalize {
+            user: "alice".to_string(),
+            points: 5,
+            reason: "spam".to_string(),
+            idempotency_key: Some(key.to_string()),
+        };
+        let update = |score: u32, key: &str| ExecuteMsg::UpdateScore {
+            user: Addr::unchecked("alice"),
+            score,
+            expires_at: None,
+            idempotency_key: Some(key.to_string()),
+        };
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(50, "tx-1")).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(60, "tx-1"));
+        assert!(matches!(res, Err(ContractError::DuplicateIdempotencyKey { .. })));
+        assert_eq!(50, get_score(deps.as_ref(), "alice"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(60, ""));
+        assert!(matches!(res, Err(ContractError::InvalidIdempotencyKey { .. })));
+
+        let status = |deps: Deps, env: Env| {
+            let msg = QueryMsg::IdempotencyKey { sender: "creator".to_string(), key: "tx-1".to_string() };
+            let res: IdempotencyKeyResponse = from_binary(&query(deps, env, msg).unwrap()).unwrap();
+            (res.used, res.expires_at)
+        };
+        let height = mock_env().block.height;
+        assert_eq!((true, Some(height + DEFAULT_IDEMPOTENCY_TTL)), status(deps.as_ref(), mock_env()));
+
+        // Skipped without an error under Ignore
+        let msg = ConfigUpdate { duplicate_key_policy: Some(DuplicateKeyPolicy::Ignore), idempotency_ttl: Some(10), ..ConfigUpdate::default() };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::UpdateConfig(msg)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), penalize("tx-2")).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), penalize("tx-2")).unwrap();
+        assert_eq!(vec![Attribute::new("method", "duplicate"), Attribute::new("idempotency_key", "tx-2")], res.attributes[..2]);
+        assert_eq!(45, get_score(deps.as_ref(), "alice"));
+
+        // Once expired a key is free again, and pruned when another key is used
+        let mut env = mock_env();
+        env.block.height += 10;
+        let msg = ConfigUpdate { idempotency_ttl: Some(0), ..ConfigUpdate::default() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::UpdateConfig(msg));
+        assert!(matches!(res, Err(ContractError::ZeroIdempotencyTtl {})));
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), penalize("tx-2")).unwrap();
+        assert_eq!(40, get_score(deps.as_ref(), "alice"));
+        assert!(IDEMPOTENCY_KEYS.has(deps.as_ref().storage, ("creator".to_string(), "tx-1".to_string())));
+        env.block.height = height + DEFAULT_IDEMPOTENCY_TTL;
+        assert_eq!((false, None), status(deps.as_ref(), env.clone()));
+        execute(deps.as_mut(), env, mock_info("creator", &[]), update(70, "tx-3")).unwrap();
+        assert!(!IDEMPOTENCY_KEYS.has(deps.as_ref().storage, ("creator".to_string(), "tx-1".to_string())));
+    }
+
+    #[test]
+    // A scheduled window blocks writes while it lasts and lifts by itself
+    fn maintenance_window_pauses_and_resumes() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+
+        let now = mock_env().block.time;
+        let schedule = |start: u64, end: u64| ExecuteMsg::ScheduleMaintenance { start: now.plus_seconds(start), end: now.plus_seconds(end) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), schedule(100, 200));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), schedule(200, 100));
+        assert!(matches!(res, Err(ContractError::InvalidMaintenanceWindow {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), schedule(100, 200)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), schedule(150, 300));
+        assert!(matches!(res, Err(ContractError::InvalidMaintenanceWindow {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), schedule(400, 500)).unwrap();
+
+        let next = |deps: Deps, env: Env| -> MaintenanceResponse { from_binary(&query(deps, env, QueryMsg::NextMaintenance {}).unwrap()).unwrap() };
+        let res = next(deps.as_ref(), mock_env());
+        assert_eq!(Some(MaintenanceWindow { start: now.plus_seconds(100), end: now.plus_seconds(200) }), res.window);
+        assert!(!res.active);
+
+        let update = |score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score, expires_at: None, idempotency_key: None };
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(150);
+        assert!(next(deps.as_ref(), env.clone()).active);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update(10));
+        assert!(matches!(res, Err(ContractError::Paused {})));
+
+        // Maintenance ops run inside the window as they would while paused
+        let msg = ExecuteMsg::RebuildIndex { start_after: None, limit: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // Writes resume once the window ends, and the query moves on to the next one
+        env.block.time = now.plus_seconds(200);
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update(10)).unwrap();
+        assert_eq!(10, get_score(deps.as_ref(), "alice"));
+        assert_eq!(Some(now.plus_seconds(400)), next(deps.as_ref(), env.clone()).window.map(|window| window.start));
+
+        let msg = ExecuteMsg::CancelMaintenance { start: now.plus_seconds(400) };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::MaintenanceNotFound {})));
+        assert_eq!(None, next(deps.as_ref(), env).window);
+    }
+
+    #[test]
+    // The all-time board adds archived seasons to the current one
+    fn all_time_board_spans_seasons() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+
+        let mut env = mock_env();
+        let update = |user: &str, score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+        for (user, score) in [("alice", 30), ("bob", 20)] {
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update(user, score)).unwrap();
+        }
+        env.block.height += 1;
+        let msg = ExecuteMsg::StartNewSeason { limit: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        env.block.height += 1;
+        for (user, score) in [("bob", 25), ("carol", 40)] {
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update(user, score)).unwrap();
+        }
+
+        let board = |deps: Deps, scope: Option<Scope>| -> Vec<(String, u32)> {
+            let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope };
+            let res: LeaderboardResponse = from_binary(&query(deps, env.clone(), msg).unwrap()).unwrap();
+            res.entries.into_iter().map(|entry| (entry.user.address, entry.score)).collect()
+        };
+        let pairs = |entries: &[(&str, u32)]| entries.iter().map(|(user, score)| (user.to_string(), *score)).collect::<Vec<_>>();
+        assert_eq!(pairs(&[("carol", 40), ("bob", 25)]), board(deps.as_ref(), None));
+        assert_eq!(pairs(&[("bob", 45), ("carol", 40), ("alice", 30)]), board(deps.as_ref(), Some(Scope::AllTime)));
+
+        // Changes within the season carry over on top of the archived part
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update("bob", 5)).unwrap();
+        let msg = QueryMsg::LeaderboardPage { limit: Some(1), cursor: None, viewer: Some("bob".to_string()), scope: Some(Scope::AllTime) };
+        let res: LeaderboardPageResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(3, res.total);
+        assert_eq!(Some(ViewerRank { user: AddrInfo { address: "bob".to_string(), origin: None }, score: 25, rank: Some(3) }), res.viewer);
+    }
+
+    #[test]
+    // Users at the score threshold claim a fixed amount once per cooldown
+    fn faucet_claims_gated_by_score() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        for (user, score) in [("alice", 50), ("bob", 49)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimFaucet {});
+        assert!(matches!(res, Err(ContractError::FaucetEmpty {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(250, "uluna")), ExecuteMsg::FundFaucet {});
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(250, "uluna")), ExecuteMsg::FundFaucet {}).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(5, "uusd")), ExecuteMsg::FundFaucet {});
+        assert!(matches!(res, Err(ContractError::InvalidFaucetFunds {})));
+        let msg = ExecuteMsg::SetFaucet { amount_per_claim: Uint128::new(100), min_score: 50, cooldown: 3600 };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::ClaimFaucet {});
+        assert!(matches!(res, Err(ContractError::ScoreTooLow { score: 49, min_score: 50 })));
+        // An expired score counts as none, purged or not
+        let expires_at = Some(mock_env().block.time.plus_seconds(1));
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 80, expires_at, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(1);
+        let res = execute(deps.as_mut(), later, mock_info("carol", &[]), ExecuteMsg::ClaimFaucet {});
+        assert!(matches!(res, Err(ContractError::ScoreTooLow { score: 0, min_score: 50 })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimFaucet {}).unwrap();
+        assert_eq!(vec![SubMsg::new(BankMsg::Send { to_address: "alice".to_string(), amount: coins(100, "uluna") })], res.messages);
+
+        // Once per cooldown
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimFaucet {});
+        let next_claim_at = mock_env().block.time.plus_seconds(3600);
+        assert!(matches!(res, Err(ContractError::FaucetCooldown { next_claim_at: at }) if at == next_claim_at));
+        let status = |deps: Deps, env: Env| -> FaucetStatusResponse {
+            from_binary(&query(deps, env, QueryMsg::FaucetStatus { user: "alice".to_string(), auth: None }).unwrap()).unwrap()
+        };
+        let res = status(deps.as_ref(), mock_env());
+        assert_eq!(Some(false), res.eligible);
+        assert_eq!((Some(next_claim_at), 1, Uint128::new(100)), (res.next_claim_at, res.claims, res.claimed));
+
+        let mut env = mock_env();
+        env.block.time = next_claim_at;
+        assert_eq!(Some(true), status(deps.as_ref(), env.clone()).eligible);
+        // Hidden users' eligibility is only shown with their viewing key or the owner's
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::SetProfileVisibility { public: false }).unwrap();
+        assert_eq!(None, status(deps.as_ref(), env.clone()).eligible);
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::SetViewingKey { key: "secret".to_string() }).unwrap();
+        let auth = Some(ViewerAuth { address: "alice".to_string(), key: "secret".to_string() });
+        let msg = QueryMsg::FaucetStatus { user: "alice".to_string(), auth };
+        let res: FaucetStatusResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(Some(true), res.eligible);
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::ClaimFaucet {}).unwrap();
+
+        // 50 left can't cover another claim
+        env.block.time = env.block.time.plus_seconds(3600);
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::SetProfileVisibility { public: true }).unwrap();
+        assert_eq!(Some(false), status(deps.as_ref(), env.clone()).eligible);
+        let res = execute(deps.as_mut(), env, mock_info("alice", &[]), ExecuteMsg::ClaimFaucet {});
+        assert!(matches!(res, Err(ContractError::FaucetEmpty {})));
+    }
+
+    #[test]
+    // Users spend their own points above the floor; the shop is called with each spend
+    fn spend_score_ledger() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_floor: Some(5), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 50, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let spend = |amount: u32, memo: &str| ExecuteMsg::SpendScore { amount, memo: memo.to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend(20, "sword")).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "alice"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend(26, "castle"));
+        assert!(matches!(res, Err(ContractError::InsufficientScore { available: 25, amount: 26 })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), spend(1, "potion"));
+        assert!(matches!(res, Err(ContractError::InsufficientScore { available: 0, amount: 1 })));
+
+        let msg = ExecuteMsg::SetShop { addr: Some("shop".to_string()) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend(25, "shield")).unwrap();
+        let shop = ShopExecuteMsg::ScoreSpent { user: "alice".to_string(), spend_id: 2, amount: 25, memo: "shield".to_string() };
+        let exec = WasmMsg::Execute { contract_addr: "shop".to_string(), msg: to_binary(&shop).unwrap(), funds: vec![] };
+        assert_eq!(Some(&SubMsg::new(exec)), res.messages.last());
+        assert_eq!(5, get_score(deps.as_ref(), "alice"));
+
+        let msg = QueryMsg::SpendHistory { user: "alice".to_string(), auth: None, start_after: Some(1), limit: None };
+        let res: SpendHistoryResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let height = mock_env().block.height;
+        assert_eq!(vec![SpendEntry { id: 2, amount: 25, memo: "shield".to_string(), height, contract: None }], res.spends);
+    }
+
+    #[test]
+    // SpendAndCall deducts points and forwards the message with the spend to the target
+    fn spend_and_call_forwards() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 50, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let payload = to_binary(&"buy_item").unwrap();
+        let msg = ExecuteMsg::SpendAndCall { amount: 30, contract: "market".to_string(), msg: payload.clone() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let receive = SpendReceiverExecuteMsg::ReceiveSpend(SpendReceiveMsg {
+            sender: "alice".to_string(),
+            spend_id: 1,
+            amount: 30,
+            msg: payload.clone(),
+        });
+        let exec = WasmMsg::Execute { contract_addr: "market".to_string(), msg: to_binary(&receive).unwrap(), funds: vec![] };
+        assert_eq!(Some(&SubMsg::new(exec)), res.messages.last());
+        assert_eq!(20, get_score(deps.as_ref(), "alice"));
+
+        let msg = QueryMsg::SpendHistory { user: "alice".to_string(), auth: None, start_after: None, limit: None };
+        let res: SpendHistoryResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(Some(Addr::unchecked("market")), res.spends[0].contract);
+
+        let msg = ExecuteMsg::SpendAndCall { amount: 21, contract: "market".to_string(), msg: payload };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InsufficientScore { available: 20, amount: 21 })));
+    }
+
+    #[test]
+    // A removed score can be restored as it was until the grace period ends
+    fn removed_scores_restorable() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        for (user, score) in [("alice", 40), ("bob", 30)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let remove = |user: &str| ExecuteMsg::RemoveScore { user: user.to_string() };
+        let restore = |user: &str| ExecuteMsg::RestoreScore { user: user.to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), remove("alice"));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), remove("alice")).unwrap();
+        assert_eq!(0, get_score(deps.as_ref(), "alice"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), remove("alice"));
+        assert!(matches!(res, Err(ContractError::NoScore { .. })));
+
+        let msg = QueryMsg::RemovedScore { user: "alice".to_string() };
+        let res: RemovedScoreResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let closed_at = mock_env().block.time.plus_seconds(DEFAULT_REMOVAL_GRACE_PERIOD);
+        assert_eq!((Some(40), Some(closed_at)), (res.score, res.restorable_until));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), restore("alice")).unwrap();
+        assert_eq!(40, get_score(deps.as_ref(), "alice"));
+        assert_eq!(Totals { total_score: 70, users: 2, all_time_users: 2 }, TOTALS.load(&deps.storage).unwrap());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), restore("alice"));
+        assert!(matches!(res, Err(ContractError::ScoreExists { .. })));
+
+        // Past the grace period the tombstone is gone, purged by the next removal
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), remove("bob")).unwrap();
+        let mut env = mock_env();
+        env.block.time = closed_at;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), restore("bob"));
+        assert!(matches!(res, Err(ContractError::GracePeriodOver { .. })));
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), remove("alice")).unwrap();
+        assert!(!REMOVED_SCORES.has(&deps.storage, "bob".to_string()));
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), restore("bob"));
+        assert!(matches!(res, Err(ContractError::NothingToRestore { .. })));
+    }
+
+    #[test]
+    // Public queries see bucketed scores; the user and the owner read exact ones with a key
+    fn public_scores_redacted() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_bucket: Some(100), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (user, score) in [("alice", 1249), ("bob", 1250)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        for viewer in ["alice", "creator"] {
+            let msg = ExecuteMsg::SetViewingKey { key: format!("{}-key", viewer) };
+            execute(deps.as_mut(), mock_env(), mock_info(viewer, &[]), msg).unwrap();
+        }
+
+        let score = |deps: Deps, auth: Option<(&str, &str)>| -> u32 {
+            let auth = auth.map(|(address, key)| ViewerAuth { address: address.to_string(), key: key.to_string() });
+            let msg = QueryMsg::GetScore { user: "alice".to_string(), auth };
+            let res: ScoreResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.score
+        };
+        assert_eq!(1200, score(deps.as_ref(), None));
+        assert_eq!(1249, score(deps.as_ref(), Some(("alice", "alice-key"))));
+        assert_eq!(1249, score(deps.as_ref(), Some(("creator", "creator-key"))));
+        assert_eq!(1200, score(deps.as_ref(), Some(("alice", "wrong"))));
+
+        // Boards keep the exact order but show bucketed scores
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let entries: Vec<(String, u32)> = res.entries.into_iter().map(|entry| (entry.user.address, entry.score)).collect();
+        assert_eq!(vec![("bob".to_string(), 1300), ("alice".to_string(), 1200)], entries);
+    }
+
+    #[test]
+    // A freeze blocks writes but not queries, and is not a pause for maintenance ops
+    fn freeze_blocks_writes_until_deadline() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        let update = |score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(10)).unwrap();
+
+        let now = mock_env().block.time;
+        let freeze = |until: Option<u64>| ExecuteMsg::FreezeLeaderboard { until: until.map(|until| now.plus_seconds(until)) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), freeze(Some(100)));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), freeze(Some(0)));
+        assert!(matches!(res, Err(ContractError::InvalidFreeze {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), freeze(Some(100))).unwrap();
+
+        let status = |deps: Deps, env: Env| -> FreezeResponse { from_binary(&query(deps, env, QueryMsg::FreezeStatus {}).unwrap()).unwrap() };
+        assert_eq!(FreezeResponse { frozen: true, until: Some(now.plus_seconds(100)) }, status(deps.as_ref(), mock_env()));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(20));
+        assert!(matches!(res, Err(ContractError::Frozen {})));
+        assert_eq!(10, get_score(deps.as_ref(), "alice"));
+        let msg = ExecuteMsg::RebuildIndex { start_after: None, limit: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::NotPaused {})));
+
+        // Lifts by itself at the deadline
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(100);
+        assert_eq!(FreezeResponse { frozen: false, until: None }, status(deps.as_ref(), env.clone()));
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update(20)).unwrap();
+
+        // An open-ended freeze holds until lifted
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), freeze(None)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update(30));
+        assert!(matches!(res, Err(ContractError::Frozen {})));
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::UnfreezeLeaderboard {}).unwrap();
+        execute(deps.as_mut(), env, mock_info("creator", &[]), update(30)).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "alice"));
+    }
+
+    #[test]
+    // Nothing deletes or archives live scores while frozen: not maintenance, not a season rollover
+    fn freeze_blocks_removals_and_rollover() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { season_duration: Some(100), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let now = mock_env().block.time;
+        for (user, score, expires_at) in [("alice", 10, Some(now.plus_seconds(10))), ("bob", 0, None)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::FreezeLeaderboard { until: None }).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(200);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::PurgeExpired { limit: None });
+        assert!(matches!(res, Err(ContractError::Frozen {})));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::CompactZeroScores { limit: None });
+        assert!(matches!(res, Err(ContractError::Frozen {})));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::StartNewSeason { limit: None });
+        assert!(matches!(res, Err(ContractError::Frozen {})));
+        // Other traffic doesn't roll the elapsed season over either
+        let msg = ExecuteMsg::SetViewingKey { key: "key".to_string() };
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(1, CURRENT_SEASON.load(&deps.storage).unwrap());
+        assert!(EXPIRIES.has(&deps.storage, "alice".to_string()));
+        assert!(SCORES.has(&deps.storage, "bob".to_string()));
+
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::UnfreezeLeaderboard {}).unwrap();
+        execute(deps.as_mut(), env, mock_info("anyone", &[]), ExecuteMsg::PurgeExpired { limit: None }).unwrap();
+        assert!(!EXPIRIES.has(&deps.storage, "alice".to_string()));
+        assert_eq!(2, CURRENT_SEASON.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    // Boards show a user's address under the prefix of the chain they came from, once set
+    fn addr_origin_shown_on_boards() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let set_origin = |prefix: Option<&str>| ExecuteMsg::SetAddrOrigin { prefix: prefix.map(String::from) };
+        for prefix in ["", "Osmo", "osmo-1"] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), set_origin(Some(prefix)));
+            assert!(matches!(res, Err(ContractError::InvalidAddrPrefix { .. })));
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), set_origin(Some("osmo"))).unwrap();
+
+        let canonical = deps.api.addr_canonicalize("alice").unwrap();
+        let expected = AddrInfo {
+            address: "alice".to_string(),
+            origin: Some(OriginAddr { prefix: "osmo".to_string(), address: bech32_encode("osmo", canonical.as_slice()) }),
+        };
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(expected, res.entries[0].user);
+        let msg = QueryMsg::AddrInfo { address: "alice".to_string() };
+        let res: AddrInfo = from_binary(&query(deps.as_ref(), mock_env(), msg.clone()).unwrap()).unwrap();
+        assert_eq!(expected, res);
+
+        // Clearing it leaves the local form only
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), set_origin(None)).unwrap();
+        let res: AddrInfo = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(AddrInfo { address: "alice".to_string(), origin: None }, res);
+    }
+
+    #[test]
+    // Operators, config, caps and hooks can all be set in the instantiate transaction
+    fn instantiate_with_full_config() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let cap = EpochCap { amount: 100, epoch_seconds: 3600, policy: CapPolicy::Reject };
+        let init = ConfigInit {
+            settings: Some(ConfigUpdate { min_delta: Some(5), score_bucket: Some(10), ..ConfigUpdate::default() }),
+            user_gain_cap: Some(cap.clone()),
+            hooks: Some(vec!["listener".to_string()]),
+            ..ConfigInit::default()
+        };
+        let msg = InstantiateMsg {
+            operators: Some(vec!["relayer".to_string()]),
+            config: Some(init.clone()),
+            ..InstantiateMsg::default()
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res: ConfigResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap()).unwrap();
+        assert_eq!((5, 10, Some(cap.clone())), (res.min_delta, res.score_bucket, res.user_gain_cap));
+        let res: HooksResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Hooks {}).unwrap()).unwrap();
+        assert_eq!(vec!["listener".to_string()], res.hooks);
+        let res: OperatorsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Operators {}).unwrap()).unwrap();
+        assert_eq!(vec!["relayer".to_string()], res.operators);
+
+        // Operators write scores until the owner removes them
+        let update = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), update.clone()).unwrap();
+        assert_eq!(10, get_score(deps.as_ref(), "alice"));
+        let msg = ExecuteMsg::UpdateOperators { add: vec![], remove: vec!["relayer".to_string()] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), update);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        // Settings go through the same checks as later, and a bad one fails the instantiation
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let init = ConfigInit { user_gain_cap: Some(EpochCap { epoch_seconds: 0, ..cap }), ..init };
+        let msg = InstantiateMsg { config: Some(init), ..InstantiateMsg::default() };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidEpoch {})));
+    }
+
+    #[test]
+    // Old and new submission keys both validate during a rotation's overlap
+    fn submission_key_rotation() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+
+        let keys: Vec<SigningKey> = (1..=3).map(|seed| SigningKey::from_bytes(&[seed; 32]).unwrap()).collect();
+        let pubkey = |key: &SigningKey| Binary::from(key.verifying_key().to_bytes().as_slice());
+        let submit = |key: &SigningKey, score: u32, nonce: u64| {
+            let payload = SignedScore { contract: MOCK_CONTRACT_ADDR.to_string(), user: "alice".to_string(), score, nonce };
+            let signature: Signature = key.sign(&to_vec(&payload).unwrap());
+            ExecuteMsg::SubmitSignedScore { user: "alice".to_string(), score, nonce, signature: Binary::from(signature.as_ref()) }
+        };
+        let now = mock_env().block.time;
+        let rotate = |key: &SigningKey, effective_at: u64| ExecuteMsg::RotateSubmissionKey { new_pubkey: pubkey(key), effective_at: now.plus_seconds(effective_at) };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), submit(&keys[0], 10, 1));
+        assert!(matches!(res, Err(ContractError::InvalidSignature {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), rotate(&keys[0], 0));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rotate(&keys[0], 0)).unwrap();
+
+        // Anyone can relay; the nonce stops replays
+        execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), submit(&keys[0], 10, 1)).unwrap();
+        assert_eq!(10, get_score(deps.as_ref(), "alice"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), submit(&keys[0], 10, 1));
+        assert!(matches!(res, Err(ContractError::StaleNonce { last: 1 })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), submit(&keys[1], 20, 2));
+        assert!(matches!(res, Err(ContractError::InvalidSignature {})));
+
+        // Both keys validate until the old one retires
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), rotate(&keys[1], 100)).unwrap();
+        let active = |deps: Deps, env: Env| -> Vec<(Binary, Option<Timestamp>)> {
+            let res: SubmissionKeysResponse = from_binary(&query(deps, env, QueryMsg::SubmissionKeys {}).unwrap()).unwrap();
+            res.keys.into_iter().map(|key| (key.pubkey, key.retires_at)).collect()
+        };
+        assert_eq!(vec![(pubkey(&keys[0]), Some(now.plus_seconds(100))), (pubkey(&keys[1]), None)], active(deps.as_ref(), mock_env()));
+        execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), submit(&keys[0], 20, 2)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), submit(&keys[1], 30, 3)).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(100);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("relayer", &[]), submit(&keys[0], 40, 4));
+        assert!(matches!(res, Err(ContractError::InvalidSignature {})));
+        assert_eq!(vec![(pubkey(&keys[1]), None)], active(deps.as_ref(), env.clone()));
+
+        // An emergency rotation retires the current key right away
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), rotate(&keys[2], 100)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("relayer", &[]), submit(&keys[1], 40, 4));
+        assert!(matches!(res, Err(ContractError::InvalidSignature {})));
+        execute(deps.as_mut(), env, mock_info("relayer", &[]), submit(&keys[2], 40, 4)).unwrap();
+        assert_eq!(40, get_score(deps.as_ref(), "alice"));
+    }
+
+    #[test]
+    // Raffles draw a score-weighted winner at the reveal height, who then claims the prize
+    fn raffle_drawn_by_score() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        for (user, score) in [("alice", 30), ("bob", 70), ("carol", 0)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        // Chances follow the scores, and a zero score never wins
+        let mut wins = std::collections::BTreeMap::new();
+        for i in 0u32..1000 {
+            let seed = Binary::from(Sha256::digest(&i.to_be_bytes()).as_slice());
+            let winner = draw(deps.as_ref().storage, mock_env().block.time, seed).unwrap().winner.unwrap();
+            *wins.entry(winner).or_insert(0) += 1;
+        }
+        assert!(!wins.contains_key("carol"));
+        assert!((600..800).contains(&wins["bob"]), "{:?}", wins);
+
+        // Nor does an expired score, even before it's purged
+        let now = mock_env().block.time;
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("dave"), score: 1000, expires_at: Some(now.plus_seconds(10)), idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let drawn = draw(deps.as_ref().storage, now.plus_seconds(10), Binary::from([0u8; 8].as_slice())).unwrap();
+        assert_eq!((3, 100), (drawn.entrants, drawn.total_weight));
+
+        let height = mock_env().block.height;
+        let start = ExecuteMsg::StartRaffle { reveal_height: height + 10 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), start.clone());
+        assert!(matches!(res, Err(ContractError::InvalidRafflePrize {})));
+        let msg = ExecuteMsg::StartRaffle { reveal_height: height };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(100, "prize")), msg);
+        assert!(matches!(res, Err(ContractError::InvalidRevealHeight {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(100, "prize")), start).unwrap();
+
+        let resolve = ExecuteMsg::ResolveRaffle { raffle_id: 0 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), resolve.clone());
+        assert!(matches!(res, Err(ContractError::RaffleNotReady { .. })));
+        let mut env = mock_env();
+        env.block.height = height + 10;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), resolve.clone()).unwrap();
+        let expected = draw(deps.as_ref().storage, env.block.time, raffle_seed(&env, 0)).unwrap();
+        let winner = expected.winner.clone().unwrap();
+        let event = res.events.iter().find(|event| event.ty == "raffle_resolved").unwrap();
+        assert!(event.attributes.contains(&Attribute::new("winner", winner.clone())));
+        assert!(event.attributes.contains(&Attribute::new("seed", expected.seed.to_base64())));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), resolve);
+        assert!(matches!(res, Err(ContractError::RaffleResolved {})));
+
+        let claim = ExecuteMsg::ClaimRaffle { raffle_id: 0 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), claim.clone());
+        assert!(matches!(res, Err(ContractError::NotRaffleWinner {})));
+        let res = execute(deps.as_mut(), env.clone(), mock_info(&winner, &[]), claim.clone()).unwrap();
+        assert_eq!(vec![SubMsg::new(BankMsg::Send { to_address: winner.clone(), amount: coins(100, "prize") })], res.messages);
+        let res = execute(deps.as_mut(), env.clone(), mock_info(&winner, &[]), claim);
+        assert!(matches!(res, Err(ContractError::RaffleClaimed {})));
+        let raffle: RaffleResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::Raffle { raffle_id: 0 }).unwrap()).unwrap();
+        assert_eq!((Some(Addr::unchecked(winner)), true), (raffle.winner, raffle.claimed));
+
+        // With no one to win, the prize goes back to the owner
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::StartRaffle { reveal_height: height + 1 };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(100, "prize")), msg).unwrap();
+        let mut env = mock_env();
+        env.block.height = height + 1;
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), ExecuteMsg::ResolveRaffle { raffle_id: 0 }).unwrap();
+        assert_eq!(vec![SubMsg::new(BankMsg::Send { to_address: "creator".to_string(), amount: coins(100, "prize") })], res.messages);
+    }
+
+    #[test]
+    // A rollover records the season's totals and top scores and announces them in events
+    fn epoch_summary_at_rollover() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        for (user, score) in [("alice", 30), ("bob", 20)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let summary = QueryMsg::EpochSummary { season: 1 };
+        assert!(query(deps.as_ref(), mock_env(), summary.clone()).is_err());
+
+        let mut env = mock_env();
+        env.block.height += 10;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::StartNewSeason { limit: None }).unwrap();
+        let event = res.events.iter().find(|event| event.ty == "epoch_summary").unwrap();
+        assert!(event.attributes.contains(&Attribute::new("total_score", "50")));
+        assert!(event.attributes.contains(&Attribute::new("users", "2")));
+        let top: Vec<_> = res.events.iter().filter(|event| event.ty == "epoch_top").map(|event| event.attributes[2].value.clone()).collect();
+        assert_eq!(vec!["alice", "bob"], top);
+
+        let res: EpochSummaryResponse = from_binary(&query(deps.as_ref(), env.clone(), summary).unwrap()).unwrap();
+        let expected = EpochSummary {
+            season: 1,
+            start_height: mock_env().block.height,
+            end_height: env.block.height,
+            end_time: env.block.time,
+            total_score: 50,
+            users: 2,
+            top: vec![
+                SummaryEntry { user: "alice".to_string(), score: 30 },
+                SummaryEntry { user: "bob".to_string(), score: 20 },
+            ],
+        };
+        assert_eq!(expected, res);
+    }
+
+    #[test]
+    // The feed returns changes past a height and says when the ring has dropped some of them
+    fn deltas_since_height() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        let start = mock_env().block.height;
+        let mut env = mock_env();
+        for (user, score) in [("alice", 10), ("bob", 5), ("alice", 4)] {
+            env.block.height += 1;
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        }
+        env.block.height += 1;
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::RemoveScore { user: "bob".to_string() }).unwrap();
+
+        let since = |deps: Deps, height: u64, start_after: Option<u64>| -> DeltasResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::DeltasSince { height, start_after, limit: Some(2) }).unwrap()).unwrap()
+        };
+        let res = since(deps.as_ref(), start + 1, None);
+        let records: Vec<_> = res.deltas.iter().map(|d| (d.seq, d.user.as_str(), d.delta, d.height)).collect();
+        assert_eq!(vec![(1, "bob", 5, start + 2), (2, "alice", -6, start + 3)], records);
+        assert!(res.complete);
+        let res = since(deps.as_ref(), start + 1, Some(2));
+        assert_eq!(vec![(3, -5)], res.deltas.iter().map(|d| (d.seq, d.delta)).collect::<Vec<_>>());
+        assert!(since(deps.as_ref(), start + 4, None).deltas.is_empty());
+
+        // Once the ring wraps, only readers caught up past the overwritten changes get a complete feed
+        for i in 0..DELTA_CAPACITY {
+            env.block.height += 1;
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 1 + i as u32 % 2, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let res = since(deps.as_ref(), start, None);
+        assert!(!res.complete);
+        assert_eq!(4, res.deltas[0].seq);
+        let res = since(deps.as_ref(), start + 5, None);
+        assert!(res.complete);
+        assert_eq!((5, start + 6), (res.deltas[0].seq, res.deltas[0].height));
+    }
+
+    #[test]
+    // Retention trims the journal, owner history and deltas ring, lazily and on request
+    fn history_retention() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetJournal { enabled: true }).unwrap();
+        let mut env = mock_env();
+        for score in 1..=6 {
+            env.block.height += 1;
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let retention = Some(Retention { max_entries: Some(0), max_age: None });
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::SetRetention { retention: retention.clone() });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::SetRetention { retention });
+        assert!(matches!(res, Err(ContractError::InvalidRetention {})));
+        let retention = Some(Retention { max_entries: Some(2), max_age: None });
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::SetRetention { retention: retention.clone() }).unwrap();
+        let res: RetentionResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Retention {}).unwrap()).unwrap();
+        assert_eq!(retention, res.retention);
+
+        let seqs = |deps: Deps| -> Vec<u64> {
+            let res: ChangesResponse = from_binary(&query(deps, mock_env(), QueryMsg::ChangesSince { seq: 0, limit: None }).unwrap()).unwrap();
+            res.changes.iter().map(|change| change.seq).collect()
+        };
+        let deltas = |deps: Deps| -> DeltasResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::DeltasSince { height: 0, start_after: None, limit: None }).unwrap()).unwrap()
+        };
+        assert_eq!(vec![1, 2, 3, 4, 5, 6], seqs(deps.as_ref()));
+
+        // A write sheds a couple of the oldest entries of each log on its way past
+        env.block.height += 1;
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 7, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(vec![3, 4, 5, 6, 7], seqs(deps.as_ref()));
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::TrimHistory { limit: None }).unwrap();
+        assert!(res.attributes.contains(&Attribute::new("trimmed", "6")));
+        assert_eq!(vec![6, 7], seqs(deps.as_ref()));
+        let res = deltas(deps.as_ref());
+        assert_eq!(vec![5, 6], res.deltas.iter().map(|delta| delta.seq).collect::<Vec<_>>());
+        assert!(!res.complete);
+
+        // By age the newest entry stays however old, so numbering carries on
+        let retention = Some(Retention { max_entries: None, max_age: Some(10) });
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::SetRetention { retention }).unwrap();
+        env.block.height += 100;
+        execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), ExecuteMsg::TrimHistory { limit: None }).unwrap();
+        assert_eq!(vec![7], seqs(deps.as_ref()));
+        let res: OwnerHistoryResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::OwnerHistory { start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(1, res.changes.len());
+    }
+
+    #[test]
+    // The owner can open up or lock down single messages without touching the rest
+    fn per_message_policy() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+
+        let msg = InstantiateMsg { operators: Some(vec!["operator".to_string()]), ..InstantiateMsg::default() };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(2, "token")), msg).unwrap();
+        let set = |deps: DepsMut, sender: &str, msg_name: &str, role: Role| {
+            execute(deps, mock_env(), mock_info(sender, &[]), ExecuteMsg::SetPolicy { msg_name: msg_name.to_string(), role })
+        };
+
+        let res = set(deps.as_mut(), "operator", "set_paused", Role::Operator);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = set(deps.as_mut(), "creator", "pause_everything", Role::Anyone);
+        assert!(matches!(res, Err(ContractError::UnknownMessage { .. })));
+        let res = set(deps.as_mut(), "creator", "set_policy", Role::Anyone);
+        assert!(matches!(res, Err(ContractError::PolicyLocked {})));
+
+        // Opening up: operators may pause
+        let res = execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), ExecuteMsg::SetPaused { paused: true });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        set(deps.as_mut(), "creator", "set_paused", Role::Operator).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), ExecuteMsg::SetPaused { paused: true }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), ExecuteMsg::SetPaused { paused: false }).unwrap();
+        let res: PolicyResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Policy { msg_name: "set_paused".to_string() }).unwrap()).unwrap();
+        assert_eq!((Role::Operator, true), (res.role, res.overridden));
+
+        // Locking down: only users with a score may trim history
+        set(deps.as_mut(), "creator", "trim_history", Role::AnyRegistered).unwrap();
+        let trim = ExecuteMsg::TrimHistory { limit: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), trim.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 1, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), trim).unwrap();
+
+        // Setting the default again drops the override
+        set(deps.as_mut(), "creator", "trim_history", Role::Anyone).unwrap();
+        let res: PolicyResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Policy { msg_name: "trim_history".to_string() }).unwrap()).unwrap();
+        assert_eq!((Role::Anyone, false), (res.role, res.overridden));
+    }
+
+    #[test]
+    // Known predecessors upgrade in place; anything else is refused untouched
+    fn migrate_from_known_versions() {
+        // The original release under its original name: an owner and bare scores
+        let mut deps = mock_dependencies_with_balance(&[]);
+        set_contract_version(&mut deps.storage, "example-terra-contract", "0.1.0").unwrap();
+        STATE.save(&mut deps.storage, &State { owner: Addr::unchecked("creator") }).unwrap();
+        SCORES.save(&mut deps.storage, "alice".to_string(), &30).unwrap();
+        SCORES.save(&mut deps.storage, "bob".to_string(), &20).unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert!(res.attributes.contains(&Attribute::new("from_version", "0.1.0")));
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!((CONTRACT_NAME, CONTRACT_VERSION), (version.contract.as_str(), version.version.as_str()));
+        let res: InvariantsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::CheckInvariants { cursor: None, limit: None }).unwrap()).unwrap();
+        assert!(res.discrepancies.is_empty(), "{:?}", res.discrepancies);
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: None, scope: None };
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["alice", "bob"], res.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("bob"), score: 40, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Rerunning on the current version changes nothing
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let unsupported = |name: &str, version: &str| {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let msg = InstantiateMsg::default();
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            set_contract_version(&mut deps.storage, name, version).unwrap();
+            let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+            matches!(res, Err(ContractError::UnsupportedMigration { .. }))
+        };
+        assert!(unsupported("crates.io:cw20-base", "0.1.0"));
+        assert!(unsupported(CONTRACT_NAME, "99.0.0"));
+        // A development build that kept the 0.1.0 version has more state than 0.1.0 had
+        assert!(unsupported(CONTRACT_NAME, "0.1.0"));
+
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+        assert!(matches!(res, Err(ContractError::MissingContractVersion {})));
+    }
+
+    #[test]
+    // Board admins manage their board's seasons, and nothing else
+    fn board_admins_manage_their_board() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let admins = |board: &str, add: &[&str], remove: &[&str]| ExecuteMsg::UpdateBoardAdmins {
+            board: board.to_string(),
+            add: add.iter().map(|addr| addr.to_string()).collect(),
+            remove: remove.iter().map(|addr| addr.to_string()).collect(),
+        };
+        let board_config = |board: &str, min_delta: u32| ExecuteMsg::UpdateBoardConfig {
+            board: board.to_string(),
+            update: BoardConfigUpdate { min_delta: Some(min_delta), ..BoardConfigUpdate::default() },
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), board_config("default", 2));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), admins("default", &["moderator"], &[]));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), admins("weekly", &["moderator"], &[]));
+        assert!(matches!(res, Err(ContractError::UnknownBoard { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), admins("default", &["moderator"], &[])).unwrap();
+        let res: BoardAdminsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::BoardAdmins { board: "default".to_string() }).unwrap()).unwrap();
+        assert_eq!(vec!["moderator".to_string()], res.admins);
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::BoardAdmins { board: "weekly".to_string() }).is_err());
+
+        // The board's config is still the contract-wide one, so only the owner edits it, even
+        // with the policy opened up to board admins
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), board_config("default", 2));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::SetPolicy { msg_name: "update_board_config".to_string(), role: Role::BoardAdmin };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), board_config("default", 2));
+        assert!(matches!(res, Err(ContractError::SharedBoardConfig { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), board_config("default", 2)).unwrap();
+        assert_eq!(2, CONFIG.load(&deps.storage).unwrap().min_delta);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), board_config("weekly", 2));
+        assert!(matches!(res, Err(ContractError::UnknownBoard { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), ExecuteMsg::StartNewSeason { limit: None }).unwrap();
+        // Contract-wide config, operators, global controls and admin assignment stay with the owner
+        let config = ExecuteMsg::UpdateConfig(ConfigUpdate { min_delta: Some(3), ..ConfigUpdate::default() });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), config);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::UpdateOperators { add: vec!["moderator".to_string()], remove: vec![] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), ExecuteMsg::SetPaused { paused: true });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), admins("default", &["friend"], &[]));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::SetPolicy { msg_name: "update_board_admins".to_string(), role: Role::BoardAdmin };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::PolicyLocked {})));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), admins("default", &[], &["moderator"])).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("moderator", &[]), ExecuteMsg::StartNewSeason { limit: None });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+    }
+
+    #[test]
+    // Stakes are locked on both sides, paid out to the reported winner or refunded on timeout
+    fn wagers_escrow_points() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg { operators: Some(vec!["referee".to_string()]), ..InstantiateMsg::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (user, score) in [("alice", 50), ("bob", 30)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let expires_at = mock_env().block.time.plus_seconds(100);
+        let open = |opponent: &str, stake: u32| ExecuteMsg::OpenWager { opponent: opponent.to_string(), stake, expires_at };
+        let wager = |deps: Deps, wager_id: u64| -> WagerResponse { from_binary(&query(deps, mock_env(), QueryMsg::Wager { wager_id }).unwrap()).unwrap() };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), open("alice", 20));
+        assert!(matches!(res, Err(ContractError::InvalidWager {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), open("alice", 40));
+        assert!(matches!(res, Err(ContractError::InsufficientScore { .. })));
+
+        // Settled: the winner takes both stakes
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), open("Bob", 20)).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "alice"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), ExecuteMsg::AcceptWager { wager_id: 0 });
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let report = |wager_id: u64, winner: &str| ExecuteMsg::ReportWager { wager_id, winner: winner.to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("referee", &[]), report(0, "bob"));
+        assert!(matches!(res, Err(ContractError::WagerNotInState { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::AcceptWager { wager_id: 0 }).unwrap();
+        assert_eq!(10, get_score(deps.as_ref(), "bob"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), report(0, "bob"));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("referee", &[]), report(0, "carol"));
+        assert!(matches!(res, Err(ContractError::InvalidWagerWinner {})));
+        execute(deps.as_mut(), mock_env(), mock_info("referee", &[]), report(0, "bob")).unwrap();
+        assert_eq!((30, 50), (get_score(deps.as_ref(), "alice"), get_score(deps.as_ref(), "bob")));
+        let settled = wager(deps.as_ref(), 0);
+        assert_eq!((WagerStatus::Settled, Some(Addr::unchecked("bob"))), (settled.status, settled.winner));
+
+        // Refunded: the proposer may withdraw an open wager, anyone may refund an expired one
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), open("bob", 10)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::RefundWager { wager_id: 1 }).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "alice"));
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), open("bob", 10)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::AcceptWager { wager_id: 2 }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::RefundWager { wager_id: 2 });
+        assert!(matches!(res, Err(ContractError::WagerNotExpired { .. })));
+        let mut env = mock_env();
+        env.block.time = expires_at;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("referee", &[]), report(2, "alice"));
+        assert!(matches!(res, Err(ContractError::WagerExpired { .. })));
+        execute(deps.as_mut(), env, mock_info("anyone", &[]), ExecuteMsg::RefundWager { wager_id: 2 }).unwrap();
+        assert_eq!((30, 50), (get_score(deps.as_ref(), "alice"), get_score(deps.as_ref(), "bob")));
+        assert_eq!(WagerStatus::Refunded, wager(deps.as_ref(), 2).status);
+        assert_eq!(Totals { total_score: 80, users: 2, all_time_users: 2 }, TOTALS.load(&deps.storage).unwrap());
+
+        // Stakes still come back to participants merged or frozen since staking
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), open("bob", 10)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::AcceptWager { wager_id: 3 }).unwrap();
+        let merge = ExecuteMsg::MergeIdentities { primary: "carol".to_string(), secondary: "bob".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), merge).unwrap();
+        let carol = get_score(deps.as_ref(), "carol");
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { anomaly_multiple: Some(10), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10_000, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let mut env = mock_env();
+        env.block.time = expires_at;
+        execute(deps.as_mut(), env, mock_info("anyone", &[]), ExecuteMsg::RefundWager { wager_id: 3 }).unwrap();
+        assert_eq!(carol + 10, get_score(deps.as_ref(), "carol"));
+        // Rejecting alice's frozen update restores her score with the refund included
+        let review = ExecuteMsg::ReviewAnomaly { user: "alice".to_string(), accept: false };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), review).unwrap();
+        assert_eq!(30, get_score(deps.as_ref(), "alice"));
+
+        // Nobody can wager against an address that can't hold points
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), open("bob", 5));
+        assert!(matches!(res, Err(ContractError::Merged { .. })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), open(MOCK_CONTRACT_ADDR, 5));
+        assert!(matches!(res, Err(ContractError::ReservedAddress { .. })));
+    }
+
+    #[test]
+    // Score changes, spends and penalties land in one timeline per user, in order
+    fn user_timeline_combines_activity() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 50, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let spend = ExecuteMsg::SpendScore { amount: 10, memo: "potion".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend).unwrap();
+        let msg = ExecuteMsg::Penalize { user: "alice".to_string(), points: 5, reason: "afk".to_string(), idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let timeline = |start_after: Option<u64>, limit: Option<u32>| {
+            let msg = QueryMsg::UserTimeline { user: "alice".to_string(), auth: None, start_after, limit };
+            let res: TimelineResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res.entries.into_iter().map(|entry| (entry.id, entry.event)).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                (1, TimelineEvent::ScoreChanged { old: 0, new: 50 }),
+                (2, TimelineEvent::ScoreChanged { old: 50, new: 40 }),
+                (3, TimelineEvent::Spent { spend_id: 1, amount: 10 }),
+                (4, TimelineEvent::ScoreChanged { old: 40, new: 35 }),
+                (5, TimelineEvent::Penalized { points: 5, reason: "afk".to_string() }),
+            ],
+            timeline(None, None)
+        );
+        assert_eq!(vec![(4, TimelineEvent::ScoreChanged { old: 40, new: 35 })], timeline(Some(3), Some(1)));
+    }
+
+    #[test]
+    // User-supplied text is checked against the owner's denylist and for hidden characters
+    fn user_text_is_validated() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 50, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let spend = |memo: &str| ExecuteMsg::SpendScore { amount: 1, memo: memo.to_string() };
+        let denylist = |add: &[&str], remove: &[&str]| ExecuteMsg::UpdateDenylist {
+            add: add.iter().map(|entry| entry.to_string()).collect(),
+            remove: remove.iter().map(|entry| entry.to_string()).collect(),
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend("bidi\u{202e}trick"));
+        assert!(matches!(res, Err(ContractError::InvalidText { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend("Scam Shield")).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), denylist(&["scam"], &[]));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), denylist(&[""], &[]));
+        assert!(matches!(res, Err(ContractError::InvalidDenylistEntry { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), denylist(&["SCAM"], &[])).unwrap();
+        let res: DenylistResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Denylist {}).unwrap()).unwrap();
+        assert_eq!(vec!["scam".to_string()], res.substrings);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend("Scam Shield"));
+        assert!(matches!(res, Err(ContractError::DeniedText { .. })));
+        let msg = ExecuteMsg::Penalize { user: "alice".to_string(), points: 1, reason: "ran a scam".to_string(), idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::DeniedText { .. })));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), denylist(&[], &["Scam"])).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), spend("Scam Shield")).unwrap();
+    }
+
+    #[test]
+    // One query returns the user's standing on every board asked for
+    fn cross_rank_over_boards() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (user, score) in [("alice", 30), ("bob", 20)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let expires_at = mock_env().block.time.plus_seconds(60);
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 10, expires_at: Some(expires_at), idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let cross_rank = |deps: Deps, env: Env, user: &str, boards: &[&str]| {
+            let msg = QueryMsg::CrossRank { user: user.to_string(), boards: boards.iter().map(|board| board.to_string()).collect(), auth: None };
+            query(deps, env, msg).map(|res| from_binary::<CrossRankResponse>(&res).unwrap().boards)
+        };
+        let standing = |score: u32, rank: Option<u32>| vec![BoardRank { board: "default".to_string(), score, rank }];
+
+        assert_eq!(standing(20, Some(2)), cross_rank(deps.as_ref(), mock_env(), "bob", &["default"]).unwrap());
+        assert_eq!(standing(0, None), cross_rank(deps.as_ref(), mock_env(), "dave", &["default"]).unwrap());
+        assert!(cross_rank(deps.as_ref(), mock_env(), "bob", &["default", "weekly"]).is_err());
+        assert!(cross_rank(deps.as_ref(), mock_env(), "bob", &["default"; 6]).is_err());
+
+        // Expired scores read as absent, as in GetScore
+        assert_eq!(standing(10, Some(3)), cross_rank(deps.as_ref(), mock_env(), "carol", &["default"]).unwrap());
+        let mut later = mock_env();
+        later.block.time = expires_at;
+        assert_eq!(standing(0, None), cross_rank(deps.as_ref(), later, "carol", &["default"]).unwrap());
+
+        // So do scores of a season still being archived, and nobody is ranked meanwhile
+        let mut env = mock_env();
+        env.block.height += 1;
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::StartNewSeason { limit: Some(1) }).unwrap();
+        assert!(ARCHIVING.may_load(&deps.storage).unwrap().is_some());
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("dave"), score: 5, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(standing(0, None), cross_rank(deps.as_ref(), env.clone(), "bob", &["default"]).unwrap());
+        assert_eq!(standing(5, None), cross_rank(deps.as_ref(), env, "dave", &["default"]).unwrap());
+    }
+
+    #[test]
+    fn first_claims_are_rebated_once() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let set_key = |key: &str| ExecuteMsg::SetViewingKey { key: key.to_string() };
+
+        // Nothing is paid back until the pool is funded and a rebate set
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), set_key("a")).unwrap();
+        assert!(res.messages.is_empty());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(50, "uluna")), ExecuteMsg::FundRebatePool {});
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(50, "uluna")), ExecuteMsg::FundRebatePool {}).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(5, "uusd")), ExecuteMsg::FundRebatePool {});
+        assert!(matches!(res, Err(ContractError::InvalidRebateFunds {})));
+        let msg = ExecuteMsg::SetRebate { amount: Uint128::new(20) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Alice's earlier message didn't use up her rebate
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::SetProfileVisibility { public: true }).unwrap();
+        let rebate = BankMsg::Send { to_address: "alice".to_string(), amount: coins(20, "uluna") };
+        assert_eq!(vec![SubMsg::new(rebate)], res.messages);
+        assert!(res.events.iter().any(|event| event.ty == "rebate"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), set_key("b")).unwrap();
+        assert!(res.messages.is_empty());
+
+        let status = |deps: Deps, user: &str| -> RebateStatusResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::RebateStatus { user: user.to_string() }).unwrap()).unwrap()
+        };
+        let alice = status(deps.as_ref(), "alice");
+        assert!(!alice.eligible);
+        assert_eq!(Some(mock_env().block.height), alice.rebated_at);
+        assert_eq!(Uint128::new(30), alice.balance);
+        assert!(status(deps.as_ref(), "bob").eligible);
+
+        execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), set_key("b")).unwrap();
+        // 10 left can't cover carol's rebate; her message still goes through
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), set_key("c")).unwrap();
+        assert!(res.messages.is_empty());
+        assert!(!status(deps.as_ref(), "carol").eligible);
+        assert_eq!(None, status(deps.as_ref(), "carol").rebated_at);
+    }
+
+    #[test]
+    fn profiles_turn_subsystems_off() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+
+        // Hook settings are rejected along with the profile that turns hooks off
+        let init = ConfigInit { hooks: Some(vec!["hook".to_string()]), ..ConfigInit::default() };
+        let msg = InstantiateMsg { config: Some(init), profile: Some(Profile::Simple), ..InstantiateMsg::default() };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::FeatureDisabled { feature }) if feature == "hooks"));
+
+        let msg = InstantiateMsg { profile: Some(Profile::Simple), ..InstantiateMsg::default() };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let features: EnabledFeaturesResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::EnabledFeatures {}).unwrap()).unwrap();
+        assert_eq!(EnabledFeaturesResponse { profile: Profile::Simple, enabled_features: vec![] }, features);
+
+        let disabled = [
+            ExecuteMsg::StartNewSeason { limit: None },
+            ExecuteMsg::UpdateConfig(ConfigUpdate { season_duration: Some(3600), ..ConfigUpdate::default() }),
+            ExecuteMsg::SetUpdateFees { native: Some(coin(5, "uluna")), cw20: None },
+            ExecuteMsg::AddHook { addr: "hook".to_string() },
+            ExecuteMsg::RemoveHook { addr: "hook".to_string() },
+            ExecuteMsg::SetNotifier { addr: Some("notifier".to_string()) },
+        ];
+        for msg in disabled {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+            assert!(matches!(res, Err(ContractError::FeatureDisabled { .. })));
+        }
+        // Scores work as ever
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Contracts without a profile run everything
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let features: EnabledFeaturesResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::EnabledFeatures {}).unwrap()).unwrap();
+        assert_eq!(Profile::Full, features.profile);
+        assert_eq!(vec![Feature::Seasons, Feature::Fees, Feature::Hooks], features.enabled_features);
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::AddHook { addr: "hook".to_string() }).unwrap();
+    }
+
+    #[test]
+    fn operator_reports_count_writes() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+
+        let msg = InstantiateMsg { operators: Some(vec!["relayer".to_string(), "stale".to_string()]), ..InstantiateMsg::default() };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let update = |user: &str, score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+
+        execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), update("alice", 30)).unwrap();
+        let mut env = mock_env();
+        env.block.height += 5;
+        execute(deps.as_mut(), env.clone(), mock_info("relayer", &[]), update("bob", 20)).unwrap();
+        // Lowering a score is a write but issues nothing
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), update("alice", 10)).unwrap();
+
+        let report = |deps: Deps, addr: &str| -> OperatorReportResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::OperatorReport { addr: addr.to_string() }).unwrap()).unwrap()
+        };
+        let relayer = OperatorReportResponse { addr: "relayer".to_string(), updates: 2, points_issued: 50, last_active_height: Some(env.block.height) };
+        assert_eq!(relayer, report(deps.as_ref(), "relayer"));
+        let creator = report(deps.as_ref(), "creator");
+        assert_eq!((1, 0), (creator.updates, creator.points_issued));
+        let stale = OperatorReportResponse { addr: "stale".to_string(), updates: 0, points_issued: 0, last_active_height: None };
+        assert_eq!(stale, report(deps.as_ref(), "stale"));
+
+        let msg = QueryMsg::ListOperatorReports { start_after: None, limit: None };
+        let res: OperatorReportsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec!["creator", "relayer"], res.reports.iter().map(|report| report.addr.as_str()).collect::<Vec<_>>());
+        let msg = QueryMsg::ListOperatorReports { start_after: Some("creator".to_string()), limit: Some(1) };
+        let res: OperatorReportsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(vec![relayer], res.reports);
+    }
+
+    #[test]
+    fn average_score_weights_by_time_held() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let at = |seconds: u64| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            env
+        };
+        for (seconds, score) in [(0, 10), (100, 30)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), at(seconds), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let average = |deps: Deps, window: u64| -> StdResult<AverageScoreResponse> {
+            let msg = QueryMsg::AverageScore { user: "alice".to_string(), window, auth: None };
+            query(deps, at(200), msg).and_then(|res| from_binary(&res))
+        };
+        assert_eq!(30, average(deps.as_ref(), 100).unwrap().score);
+        assert_eq!(20, average(deps.as_ref(), 200).unwrap().score);
+        // Before her first score she held nothing
+        assert_eq!(10, average(deps.as_ref(), 400).unwrap().score);
+        assert!(average(deps.as_ref(), 0).is_err());
+        assert!(average(deps.as_ref(), crate::twas::MAX_AVERAGE_WINDOW + 1).is_err());
+
+        // A last-minute jump barely moves a long average
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 1000, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), at(199), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(24, average(deps.as_ref(), 200).unwrap().score);
+    }
+
+    #[test]
+    fn federated_score_combines_peer_reports() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+
+        let msg = InstantiateMsg::default();
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let peer = |chain_id: &str, address: &str, weight: Decimal| PeerInit { chain_id: chain_id.to_string(), address: address.to_string(), weight };
+        let msg = ExecuteMsg::UpdatePeers { add: vec![peer("phoenix-1", "relayer", Decimal::zero())], remove: vec![] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidPeer { .. })));
+        let add = vec![peer("phoenix-1", "relayer", Decimal::percent(50)), peer("osmosis-1", "osmo", Decimal::percent(200))];
+        let msg = ExecuteMsg::UpdatePeers { add, remove: vec![] };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let sync = |chain_id: &str, score: u32| ExecuteMsg::SyncFromPeer {
+            chain_id: chain_id.to_string(),
+            updates: vec![ScoreUpdate { user: Addr::unchecked("alice"), score }],
+        };
+        // Only the peer's own address may report for it
+        let res = execute(deps.as_mut(), mock_env(), mock_info("osmo", &[]), sync("phoenix-1", 100));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), sync("juno-1", 100));
+        assert!(matches!(res, Err(ContractError::UnknownPeer { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), sync("phoenix-1", 100)).unwrap();
+
+        let federated = |deps: Deps| -> FederatedScoreResponse {
+            let msg = QueryMsg::FederatedScore { user: "alice".to_string(), auth: None };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+        let res = federated(deps.as_ref());
+        assert_eq!(Uint128::new(60), res.score);
+        let sources: Vec<_> = res.breakdown.iter().map(|source| (source.source.as_str(), source.raw, source.synced_at)).collect();
+        assert_eq!(vec![("local", 10, None), ("osmosis-1", 0, None), ("phoenix-1", 100, Some(mock_env().block.height))], sources);
+
+        // A removed peer's reports stop counting
+        let msg = ExecuteMsg::UpdatePeers { add: vec![], remove: vec!["phoenix-1".to_string()] };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(Uint128::new(10), federated(deps.as_ref()).score);
+        let res: PeersResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Peers {}).unwrap()).unwrap();
+        assert_eq!(vec!["osmosis-1"], res.peers.iter().map(|peer| peer.chain_id.as_str()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    // Rank slices agree with the paged leaderboard across score blocks and ties
+    fn rank_slice_jumps_to_a_rank() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Scores far enough apart to land in different count blocks, with some ties
+        let scores = [50_000, 9_000, 9_000, 8_999, 4_096, 4_095, 1, 1, 0];
+        for (i, score) in scores.iter().enumerate() {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(format!("user{}", i)), score: *score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("user3", &[]), ExecuteMsg::SetProfileVisibility { public: false }).unwrap();
+
+        let slice = |deps: Deps, from_rank: u32, to_rank: u32| -> Vec<(u32, String)> {
+            let res: RankSliceResponse = from_binary(&query(deps, mock_env(), QueryMsg::RankSlice { from_rank, to_rank }).unwrap()).unwrap();
+            res.entries.into_iter().map(|entry| (entry.rank, entry.user.address)).collect()
+        };
+        let msg = QueryMsg::Leaderboard { cursor: None, limit: Some(30), scope: None };
+        let board: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let board: Vec<_> = board.entries.into_iter().map(|entry| entry.user.address).collect();
+        assert_eq!(8, board.len());
+
+        // The hidden user keeps rank 4 but isn't shown
+        let all = slice(deps.as_ref(), 1, 9);
+        assert_eq!(board, all.iter().map(|(_, user)| user.clone()).collect::<Vec<_>>());
+        assert!(all.iter().all(|(rank, _)| *rank != 4));
+        for from_rank in 1..=9 {
+            let expected: Vec<_> = all.iter().filter(|(rank, _)| *rank >= from_rank && *rank < from_rank + 2).cloned().collect();
+            assert_eq!(expected, slice(deps.as_ref(), from_rank, from_rank + 1), "{}", from_rank);
+        }
+        assert_eq!(vec![(9, "user8".to_string())], slice(deps.as_ref(), 9, 20));
+        assert!(slice(deps.as_ref(), 10, 12).is_empty());
+
+        // Counts follow moves and removals
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("user6"), score: 60_000, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::RemoveScore { user: "user0".to_string() }).unwrap();
+        assert_eq!(vec![(1, "user6".to_string()), (2, "user1".to_string())], slice(deps.as_ref(), 1, 2));
+        assert_eq!(vec![(8, "user8".to_string())], slice(deps.as_ref(), 7, 8)[1..].to_vec());
+
+        for (from_rank, to_rank) in [(0, 1), (3, 2), (1, 31)] {
+            assert!(query(deps.as_ref(), mock_env(), QueryMsg::RankSlice { from_rank, to_rank }).is_err());
+        }
+    }
+
+    #[test]
+    // A certificate mints once a season, with the rank the sender holds on the board
+    fn certify_score_mints_once_a_season() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (user, score) in [("alice", 30), ("bob", 20), ("carol", 20)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), ExecuteMsg::CertifyScore {});
+        assert!(matches!(res, Err(ContractError::CertifierNotConfigured {})));
+        let msg = ExecuteMsg::SetCertifier { addr: Some("nft".to_string()) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let formula = ScoreFormula::Multiplier { numerator: 2, denominator: 1 };
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_formula: Some(formula), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // carol ties bob and comes after him by address; the score is the one GetScore shows
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), ExecuteMsg::CertifyScore {}).unwrap();
+        let mint = r#"{"mint":{"token_id":"season-1-carol","owner":"carol","token_uri":null,"extension":{"score":40,"season":1,"rank":3,"height":12345}}}"#;
+        let exec = WasmMsg::Execute { contract_addr: "nft".to_string(), msg: Binary::from(mint.as_bytes()), funds: vec![] };
+        assert_eq!(vec![SubMsg::new(exec)], res.messages);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), ExecuteMsg::CertifyScore {});
+        assert!(matches!(res, Err(ContractError::AlreadyCertified { season: 1, .. })));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("dave", &[]), ExecuteMsg::CertifyScore {});
+        assert!(matches!(res, Err(ContractError::NoScore { .. })));
+
+        let certification = |deps: Deps, season: Option<u32>| -> CertificationResponse {
+            let msg = QueryMsg::Certification { user: "CAROL".to_string(), season, auth: None };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+        assert_eq!(Some(3), certification(deps.as_ref(), None).certification.map(|certification| certification.rank));
+
+        // A private user's certificate takes their viewing key to read
+        let msg = ExecuteMsg::SetProfileVisibility { public: false };
+        execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), msg).unwrap();
+        let msg = QueryMsg::Certification { user: "carol".to_string(), season: None, auth: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let msg = ExecuteMsg::SetViewingKey { key: "carol-key".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), msg).unwrap();
+        let auth = Some(ViewerAuth { address: "carol".to_string(), key: "carol-key".to_string() });
+        let msg = QueryMsg::Certification { user: "carol".to_string(), season: None, auth };
+        let res: CertificationResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(Some(40), res.certification.map(|certification| certification.score));
+        let msg = ExecuteMsg::SetProfileVisibility { public: true };
+        execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), msg).unwrap();
+
+        // The next season takes a new certificate of the new standing
+        let mut env = mock_env();
+        env.block.height += 10;
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::StartNewSeason { limit: None }).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), ExecuteMsg::CertifyScore {});
+        assert!(matches!(res, Err(ContractError::NoScore { .. })));
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("carol"), score: 5, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), ExecuteMsg::CertifyScore {}).unwrap();
+        assert!(res.attributes.contains(&Attribute::new("token_id", "season-2-carol")));
+        assert!(res.attributes.contains(&Attribute::new("rank", "1")));
+        assert_eq!(3, certification(deps.as_ref(), Some(1)).certification.unwrap().rank);
+        assert!(certification(deps.as_ref(), Some(3)).certification.is_none());
+    }
+
+    #[test]
+    // Owner-signed vouchers credit their user once, before they expire
+    fn vouchers_redeem_once() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let owner_key = SigningKey::from_bytes(&[1; 32]).unwrap();
+        let other_key = SigningKey::from_bytes(&[2; 32]).unwrap();
+        let now = mock_env().block.time;
+        let voucher = |id: u64, points: u32| Voucher { id, user: "alice".to_string(), points, expires_at: now.plus_seconds(60) };
+        let redeem = |key: &SigningKey, contract: &str, voucher: Voucher| {
+            let payload = SignedVoucher { contract: contract.to_string(), voucher: voucher.clone() };
+            let signature: Signature = key.sign(&to_vec(&payload).unwrap());
+            ExecuteMsg::RedeemVoucher { voucher, signature: Binary::from(signature.as_ref()) }
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), redeem(&owner_key, MOCK_CONTRACT_ADDR, voucher(1, 5)));
+        assert!(matches!(res, Err(ContractError::VouchersNotConfigured {})));
+        let set_key = ExecuteMsg::SetVoucherKey { pubkey: Some(Binary::from(owner_key.verifying_key().to_bytes().as_slice())) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), set_key.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetVoucherKey { pubkey: Some(Binary::from(b"key".to_vec())) });
+        assert!(matches!(res, Err(ContractError::InvalidPubkey {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_key).unwrap();
+
+        // Anyone can relay a voucher; the points go to its user
+        execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), redeem(&owner_key, MOCK_CONTRACT_ADDR, voucher(1, 5))).unwrap();
+        assert_eq!(15, get_score(deps.as_ref(), "alice"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), redeem(&owner_key, MOCK_CONTRACT_ADDR, voucher(1, 5)));
+        assert!(matches!(res, Err(ContractError::VoucherRedeemed { id: 1, height: 12345 })));
+        let status: VoucherStatusResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::VoucherStatus { id: 1 }).unwrap()).unwrap();
+        assert_eq!(VoucherStatusResponse { id: 1, redeemed_at: Some(12345) }, status);
+
+        // Other keys, other contracts, altered vouchers and expired ones are refused
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), redeem(&other_key, MOCK_CONTRACT_ADDR, voucher(2, 5)));
+        assert!(matches!(res, Err(ContractError::InvalidVoucherSignature {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), redeem(&owner_key, "other", voucher(2, 5)));
+        assert!(matches!(res, Err(ContractError::InvalidVoucherSignature {})));
+        let msg = match redeem(&owner_key, MOCK_CONTRACT_ADDR, voucher(2, 5)) {
+            ExecuteMsg::RedeemVoucher { signature, .. } => ExecuteMsg::RedeemVoucher { voucher: voucher(2, 500), signature },
+            _ => unreachable!(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidVoucherSignature {})));
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(60);
+        let res = execute(deps.as_mut(), env, mock_info("alice", &[]), redeem(&owner_key, MOCK_CONTRACT_ADDR, voucher(2, 5)));
+        assert!(matches!(res, Err(ContractError::VoucherExpired { .. })));
+        assert_eq!(15, get_score(deps.as_ref(), "alice"));
+
+        // Even a validly signed voucher can't give a reserved address a score
+        let reserved = Voucher { user: MOCK_CONTRACT_ADDR.to_string(), ..voucher(3, 5) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), redeem(&owner_key, MOCK_CONTRACT_ADDR, reserved));
+        assert!(matches!(res, Err(ContractError::ReservedAddress { .. })));
+    }
+
+    #[test]
+    // After a cutover every write is refused with the successor, while reads carry on
+    fn read_only_forwarding_blocks_writes() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let update = |score: u32| ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(10)).unwrap();
+
+        let forward = |successor: Option<&str>| ExecuteMsg::ReadOnlyForwarding { successor: successor.map(String::from) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), forward(Some("successor")));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), forward(Some("successor"))).unwrap();
+        assert_eq!(vec![Event::new("read_only_forwarding").add_attribute("successor", "successor")], res.events);
+
+        // Writes from anyone, the owner included, point at the successor
+        for (sender, msg) in [("creator", update(20)), ("alice", ExecuteMsg::SetViewingKey { key: "secret".to_string() })] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg);
+            assert!(matches!(res, Err(ContractError::ReadOnly { successor }) if successor == "successor"));
+        }
+        assert_eq!(10, get_score(deps.as_ref(), "alice"));
+        let value: InfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Info {}).unwrap()).unwrap();
+        assert_eq!(Some("successor".to_string()), value.successor);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), forward(None)).unwrap();
+        assert_eq!(vec![Event::new("read_only_forwarding").add_attribute("successor", "none")], res.events);
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update(20)).unwrap();
+        assert_eq!(20, get_score(deps.as_ref(), "alice"));
+        let value: InfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Info {}).unwrap()).unwrap();
+        assert!(value.successor.is_none());
+    }
+
+    #[test]
+    // Stray cw20s and NFTs go back out, but never a token the contract manages itself
+    fn recover_returns_stray_assets() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let cw20 = Cw20FeeInit { token: "project".to_string(), amount: Uint128::new(40) };
+        let msg = ExecuteMsg::SetUpdateFees { native: None, cw20: Some(cw20) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let recover = |token: &str, amount: u128| ExecuteMsg::RecoverCw20 { token: token.to_string(), amount: Uint128::new(amount), recipient: "alice".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), recover("stray", 25));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), recover("project", 25));
+        assert!(matches!(res, Err(ContractError::ManagedAsset { asset }) if asset == "project"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), recover("stray", 0));
+        assert!(matches!(res, Err(ContractError::InvalidRecoveryAmount {})));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), recover("stray", 25)).unwrap();
+        let transfer = Cw20ExecuteMsg::Transfer { recipient: "alice".to_string(), amount: Uint128::new(25) };
+        let expected = WasmMsg::Execute { contract_addr: "stray".to_string(), msg: to_binary(&transfer).unwrap(), funds: vec![] };
+        assert_eq!(vec![SubMsg::new(expected)], res.messages);
+
+        let msg = ExecuteMsg::RecoverNft { contract: "nft".to_string(), token_id: "7".to_string(), recipient: "alice".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let transfer = Cw721ExecuteMsg::TransferNft { recipient: "alice".to_string(), token_id: "7".to_string() };
+        let expected = WasmMsg::Execute { contract_addr: "nft".to_string(), msg: to_binary(&transfer).unwrap(), funds: vec![] };
+        assert_eq!(vec![SubMsg::new(expected)], res.messages);
+    }
+
+    #[test]
+    // Each purpose pays out only what was paid in for it, and the bank balance is checked
+    // against the total
+    fn ledger_attributes_held_funds() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("alice"), score: 10, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(30, "uluna")), ExecuteMsg::FundFaucet {}).unwrap();
+        let msg = ExecuteMsg::SetFaucet { amount_per_claim: Uint128::new(10), min_score: 0, cooldown: 0 };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::StartRaffle { reveal_height: mock_env().block.height + 1 };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(50, "uluna")), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimFaucet {}).unwrap();
+
+        // The bank holds what was paid in less the claim, plus 5uluna nobody accounted for
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(75, "uluna"));
+        let res: ReconcileResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Reconcile {}).unwrap()).unwrap();
+        let entry = |purpose: &str, amount: u128| LedgerEntry { denom: "uluna".to_string(), purpose: purpose.to_string(), amount: Uint128::new(amount) };
+        assert_eq!(vec![entry("faucet", 20), entry("raffle-0", 50)], res.entries);
+        let uluna = DenomReconciliation {
+            denom: "uluna".to_string(),
+            attributed: Uint128::new(70),
+            balance: Uint128::new(75),
+            surplus: Uint128::new(5),
+            shortfall: Uint128::zero(),
+        };
+        assert_eq!(vec![uluna], res.denoms);
+
+        // A payout never reaches past its own purpose's share
+        LEDGER.save(deps.as_mut().storage, ("uluna".to_string(), "faucet".to_string()), &Uint128::new(5)).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimFaucet {});
+        assert!(matches!(res, Err(ContractError::LedgerShortfall { purpose, held, .. }) if purpose == "faucet" && held.u128() == 5));
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(60, "uluna"));
+        let res: ReconcileResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Reconcile {}).unwrap()).unwrap();
+        assert_eq!((Uint128::new(55), Uint128::new(5), Uint128::zero()), (res.denoms[0].attributed, res.denoms[0].surplus, res.denoms[0].shortfall));
+    }
+
+    #[test]
+    // Oversized limits and long filtering walks fail outright, within bounds the owner sets
+    fn queries_enforce_configured_caps() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (user, score) in [("alice", 40), ("bob", 30), ("carol", 20), ("dave", 10)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let leaderboard = |limit: Option<u32>| QueryMsg::Leaderboard { cursor: None, limit, scope: None };
+
+        let err = query(deps.as_ref(), mock_env(), leaderboard(Some(31))).unwrap_err();
+        assert!(err.to_string().contains("Limit 31 is above the maximum of 30"));
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), leaderboard(Some(30))).unwrap()).unwrap();
+        assert_eq!(4, res.entries.len());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::OwnerHistory { start_after: None, limit: Some(31) });
+        assert!(res.is_err());
+
+        for update in [ConfigUpdate { max_query_limit: Some(0), ..ConfigUpdate::default() }, ConfigUpdate { max_query_scan: Some(10_001), ..ConfigUpdate::default() }] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::UpdateConfig(update));
+            assert!(matches!(res, Err(ContractError::InvalidQueryLimit { .. })));
+        }
+        let update = ConfigUpdate { max_query_limit: Some(2), max_query_scan: Some(3), ..ConfigUpdate::default() };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::UpdateConfig(update)).unwrap();
+        // The default page shrinks with the cap rather than failing
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), leaderboard(None)).unwrap()).unwrap();
+        assert_eq!(2, res.entries.len());
+        assert!(query(deps.as_ref(), mock_env(), leaderboard(Some(3))).is_err());
+
+        // Walking past a hidden user to fill the page, and one more to see if there's a next
+        // page, spends the scan budget
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::SetProfileVisibility { public: false }).unwrap();
+        let err = query(deps.as_ref(), mock_env(), leaderboard(Some(2))).unwrap_err();
+        assert!(err.to_string().contains("Query would read more than 3 entries"));
+        let res: LeaderboardResponse = from_binary(&query(deps.as_ref(), mock_env(), leaderboard(Some(1))).unwrap()).unwrap();
+        assert_eq!("bob", res.entries[0].user.address);
+    }
+
+    #[test]
+    // Each publish is a new version of the top public scores as shares of their total,
+    // and earlier versions stay readable
+    fn weight_table_publishes_versions() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let publish = |top_n: u32| ExecuteMsg::PublishWeightTable { top_n };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), publish(2));
+        assert!(matches!(res, Err(ContractError::EmptyWeightTable {})));
+        for (user, score) in [("alice", 30), ("bob", 20), ("carol", 10)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at: None, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), publish(2));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        for top_n in [0, 101] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), publish(top_n));
+            assert!(matches!(res, Err(ContractError::InvalidWeightTableSize { max: 100 })));
+        }
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), publish(2)).unwrap();
+        let expected = Event::new("weight_table_published").add_attribute("version", "1").add_attribute("season", "1").add_attribute("entries", "2");
+        assert_eq!(vec![expected], res.events);
+
+        // Hidden users are left out and the next one down takes their place
+        execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::SetProfileVisibility { public: false }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), publish(2)).unwrap();
+
+        let entry = |user: &str, score: u32, weight: u64| WeightEntry { user: user.to_string(), score, weight: Decimal::percent(weight) };
+        let table = |version: Option<u64>| -> WeightTableResponse {
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::WeightTable { version }).unwrap()).unwrap()
+        };
+        let latest = table(None);
+        assert_eq!(1, latest.schema);
+        let latest = latest.table.unwrap();
+        assert_eq!((2, 40), (latest.version, latest.total_score));
+        assert_eq!(vec![entry("alice", 30, 75), entry("carol", 10, 25)], latest.entries);
+        let first = table(Some(1)).table.unwrap();
+        assert_eq!(vec![entry("alice", 30, 60), entry("bob", 20, 40)], first.entries);
+        assert!(table(Some(3)).table.is_none());
+    }
+
+    #[test]
+    // HasMinScore sees what GetScore would, so thresholds can't probe hidden or exact scores
+    fn has_min_score_respects_visibility() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 7, expires_at: None, idempotency_key: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetViewingKey { key: "player-secret".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("player", &[]), msg).unwrap();
+        let has_min = |deps: Deps, min_score: u32, auth: bool| -> StdResult<bool> {
+            let auth = auth.then(|| ViewerAuth { address: "player".to_string(), key: "player-secret".to_string() });
+            let msg = QueryMsg::HasMinScore { user: "player".to_string(), min_score, auth };
+            Ok(from_binary::<HasMinScoreResponse>(&query(deps, mock_env(), msg)?)?.has_min_score)
+        };
+        assert!(has_min(deps.as_ref(), 7, false).unwrap());
+        assert!(!has_min(deps.as_ref(), 8, false).unwrap());
+
+        // Public reads are bucketed, 7 reading as 5
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_bucket: Some(5), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(!has_min(deps.as_ref(), 6, false).unwrap());
+        assert!(has_min(deps.as_ref(), 6, true).unwrap());
+        // and rounded down, even where GetScore rounds 7 up to 10
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { score_bucket: Some(10), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(!has_min(deps.as_ref(), 10, false).unwrap());
+        assert!(has_min(deps.as_ref(), 0, false).unwrap());
+
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { private_scores: Some(true), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(has_min(deps.as_ref(), 0, false).is_err());
+        assert!(has_min(deps.as_ref(), 7, true).unwrap());
+    }
+
+    #[test]
+    // Podium and rank slices leave out expired scores before they're purged, as GetScore does
+    fn podium_and_ranks_skip_expired() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), ExecuteMsg::SetTopK { k: 3 }).unwrap();
+        let now = mock_env().block.time;
+        for (user, score, expires_at) in [("alice", 30, Some(now.plus_seconds(10))), ("bob", 20, None), ("carol", 10, None)] {
+            let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score, expires_at, idempotency_key: None };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let mut later = mock_env();
+        later.block.time = now.plus_seconds(10);
+        let res: PodiumResponse = from_binary(&query(deps.as_ref(), later.clone(), QueryMsg::Podium { k: 3 }).unwrap()).unwrap();
+        assert_eq!(vec!["bob", "carol"], res.entries.iter().map(|entry| entry.user.address.as_str()).collect::<Vec<_>>());
+        let res: RankSliceResponse = from_binary(&query(deps.as_ref(), later, QueryMsg::RankSlice { from_rank: 1, to_rank: 3 }).unwrap()).unwrap();
+        let ranks: Vec<_> = res.entries.iter().map(|entry| (entry.rank, entry.user.address.as_str())).collect();
+        assert_eq!(vec![(2, "bob"), (3, "carol")], ranks);
     }
 }
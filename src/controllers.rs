@@ -0,0 +1,76 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Deps, Env, StdResult, Storage, Uint128};
+use cw_utils::Expiration;
+
+use crate::error::ContractError;
+use crate::rewards::query_claimable;
+use crate::state::{HOOKS, STATE};
+
+// upper bound on hooks notified of every score change
+pub const MAX_HOOKS: u32 = 10;
+
+// Responses of the cw-controllers Admin, Hooks and Claims queries. They serialize
+// identically to the upstream definitions, so tooling built on those controllers can
+// read this contract without knowing its own query types; golden/cw_controllers.golden
+// pins them to the upstream 0.13 schemas.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminResponse {
+    pub admin: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimsResponse {
+    pub claims: Vec<Claim>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Claim {
+    pub amount: Uint128,
+    pub release_at: Expiration,
+}
+
+pub fn add_hook(storage: &mut dyn Storage, addr: Addr) -> Result<(), ContractError> {
+    let mut hooks = HOOKS.may_load(storage)?.unwrap_or_default();
+    if hooks.contains(&addr) {
+        return Err(ContractError::HookAlreadyRegistered {});
+    }
+    if hooks.len() as u32 >= MAX_HOOKS {
+        return Err(ContractError::TooManyHooks { max: MAX_HOOKS });
+    }
+    hooks.push(addr);
+    Ok(HOOKS.save(storage, &hooks)?)
+}
+
+pub fn remove_hook(storage: &mut dyn Storage, addr: Addr) -> Result<(), ContractError> {
+    let mut hooks = HOOKS.may_load(storage)?.unwrap_or_default();
+    let pos = hooks.iter().position(|hook| *hook == addr).ok_or(ContractError::HookNotRegistered {})?;
+    hooks.remove(pos);
+    Ok(HOOKS.save(storage, &hooks)?)
+}
+
+/// The owner, as the admin of the contract.
+pub fn query_admin(deps: Deps) -> StdResult<AdminResponse> {
+    let state = STATE.load(deps.storage)?;
+    Ok(AdminResponse { admin: Some(state.owner.into()) })
+}
+
+pub fn query_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    let hooks = HOOKS.may_load(deps.storage)?.unwrap_or_default();
+    Ok(HooksResponse { hooks: hooks.into_iter().map(String::from).collect() })
+}
+
+/// Rewards waiting for withdrawal, as a single claim releasable right away. Users
+/// without pending rewards have none.
+pub fn query_claims(deps: Deps, env: Env, address: String) -> StdResult<ClaimsResponse> {
+    let height = env.block.height;
+    let pending = query_claimable(deps, env, address)?.amount;
+    let claims = if pending.is_zero() { vec![] } else { vec![Claim { amount: pending, release_at: Expiration::AtHeight(height) }] };
+    Ok(ClaimsResponse { claims })
+}
@@ -0,0 +1,78 @@
+// Chain-specific message type carried by every response. Plain `Empty` by default; a
+// chain feature swaps in its own type so integrators building on the `library` feature
+// can add chain messages, e.g. a market swap of paid-out rewards, to our responses
+// without forking the crate.
+#[cfg(feature = "terra")]
+use schemars::JsonSchema;
+#[cfg(feature = "terra")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "terra")]
+use cosmwasm_std::{Coin, CosmosMsg};
+
+#[cfg(not(feature = "terra"))]
+pub type CustomMsg = cosmwasm_std::Empty;
+#[cfg(feature = "terra")]
+pub type CustomMsg = TerraMsgWrapper;
+
+pub type Response = cosmwasm_std::Response<CustomMsg>;
+pub type SubMsg = cosmwasm_std::SubMsg<CustomMsg>;
+
+// The subset of terra-cosmwasm's message types reward paths need. They serialize
+// identically to the upstream definitions, which the chain's wasm binding decodes.
+#[cfg(feature = "terra")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TerraMsgWrapper {
+    pub route: TerraRoute,
+    pub msg_data: TerraMsg,
+}
+
+#[cfg(feature = "terra")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerraRoute {
+    Market,
+    Treasury,
+    Oracle,
+    Wasm,
+}
+
+#[cfg(feature = "terra")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerraMsg {
+    Swap { offer_coin: Coin, ask_denom: String },
+    SwapSend { to_address: String, offer_coin: Coin, ask_denom: String },
+}
+
+#[cfg(feature = "terra")]
+impl cosmwasm_std::CustomMsg for TerraMsgWrapper {}
+
+#[cfg(feature = "terra")]
+impl From<TerraMsgWrapper> for CosmosMsg<TerraMsgWrapper> {
+    fn from(msg: TerraMsgWrapper) -> Self {
+        CosmosMsg::Custom(msg)
+    }
+}
+
+#[cfg(feature = "terra")]
+pub fn swap_send_msg(to_address: String, offer_coin: Coin, ask_denom: String) -> CosmosMsg<TerraMsgWrapper> {
+    TerraMsgWrapper { route: TerraRoute::Market, msg_data: TerraMsg::SwapSend { to_address, offer_coin, ask_denom } }.into()
+}
+
+#[cfg(all(test, feature = "terra"))]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, to_binary, BankMsg};
+
+    #[test]
+    fn terra_messages_share_a_response() {
+        let res = Response::new()
+            .add_message(BankMsg::Send { to_address: "alice".to_string(), amount: vec![coin(5, "uluna")] })
+            .add_message(swap_send_msg("alice".to_string(), coin(5, "uluna"), "uusd".to_string()));
+        assert_eq!(
+            r#"{"custom":{"route":"market","msg_data":{"swap_send":{"to_address":"alice","offer_coin":{"denom":"uluna","amount":"5"},"ask_denom":"uusd"}}}}"#,
+            String::from_utf8(to_binary(&res.messages[1].msg).unwrap().to_vec()).unwrap()
+        );
+    }
+}
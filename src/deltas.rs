@@ -0,0 +1,68 @@
+use cosmwasm_std::{Deps, StdResult, Storage};
+
+use crate::limits::page_limit;
+use crate::msg::DeltasResponse;
+use crate::state::{Retention, ScoreDelta, CONFIG, DELTAS, DELTA_HEAD, DELTA_TAIL};
+
+// Changes kept for DeltasSince; the oldest slot is overwritten once the ring is full
+pub const DELTA_CAPACITY: u64 = 1000;
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Records a score change at `height` in the ring buffer of recent changes. Unlike the
+/// journal this is always on, and bounded by DELTA_CAPACITY.
+pub fn record_delta(storage: &mut dyn Storage, user: &str, old: u32, new: u32, height: u64) -> StdResult<()> {
+    let seq = DELTA_HEAD.may_load(storage)?.unwrap_or_default();
+    DELTA_HEAD.save(storage, &(seq + 1))?;
+    let delta = ScoreDelta { seq, user: user.to_string(), delta: i64::from(new) - i64::from(old), height };
+    DELTAS.save(storage, seq % DELTA_CAPACITY, &delta)
+}
+
+/// Drops up to `limit` of the oldest records `retention` no longer keeps, never the newest.
+pub fn trim_deltas(storage: &mut dyn Storage, retention: &Retention, height: u64, limit: u32) -> StdResult<u32> {
+    let head = DELTA_HEAD.may_load(storage)?.unwrap_or_default();
+    let mut tail = DELTA_TAIL.may_load(storage)?.unwrap_or_default().max(head.saturating_sub(DELTA_CAPACITY));
+    let mut trimmed = 0;
+    while trimmed < limit && tail + 1 < head {
+        let slot = tail % DELTA_CAPACITY;
+        if !retention.expired(head - 1 - tail, DELTAS.load(storage, slot)?.height, height) {
+            break;
+        }
+        DELTAS.remove(storage, slot);
+        tail += 1;
+        trimmed += 1;
+    }
+    if trimmed > 0 {
+        DELTA_TAIL.save(storage, &tail)?;
+    }
+    Ok(trimmed)
+}
+
+/// Recorded changes at heights above `height`, oldest first, paged by `start_after` sequence
+/// number. `complete` is false when the ring may already have overwritten or trimmed some of them, in
+/// which case a mirror has to resync from the full leaderboard.
+pub fn query_deltas_since(deps: Deps, height: u64, start_after: Option<u64>, limit: Option<u32>) -> StdResult<DeltasResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)? as u64;
+    let head = DELTA_HEAD.may_load(deps.storage)?.unwrap_or_default();
+    let tail = DELTA_TAIL.may_load(deps.storage)?.unwrap_or_default();
+    let oldest = head.saturating_sub(DELTA_CAPACITY).max(tail);
+    let load = |seq: u64| DELTAS.load(deps.storage, seq % DELTA_CAPACITY);
+
+    // Anything dropped is no newer than the oldest record still held
+    let complete = oldest == 0 || load(oldest)?.height <= height;
+
+    // Heights never decrease along the ring, so the first record past `height` can be bisected
+    let (mut lo, mut hi) = (oldest, head);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if load(mid)?.height > height {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    let start = start_after.map_or(lo, |after| lo.max(after + 1));
+
+    let deltas = (start..head.max(start)).take(limit as usize).map(load).collect::<StdResult<_>>()?;
+    Ok(DeltasResponse { deltas, complete })
+}
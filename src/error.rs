@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +9,417 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("Contract must be paused first")]
+    NotPaused {},
+
+    #[error("Contract is read-only; scores are now kept by {successor}")]
+    ReadOnly { successor: String },
+
+    #[error("Leaderboard is frozen")]
+    Frozen {},
+
+    #[error("A freeze must end in the future")]
+    InvalidFreeze {},
+
+    #[error("Retention limits must be above zero")]
+    InvalidRetention {},
+
+    #[error("Unknown message {name}")]
+    UnknownMessage { name: String },
+
+    #[error("This message's policy can't be changed")]
+    PolicyLocked {},
+
+    #[error("No cw2 contract version is stored, so the code being replaced is unknown")]
+    MissingContractVersion {},
+
+    #[error("Can't migrate from {name} {version}")]
+    UnsupportedMigration { name: String, version: String },
+
+    #[error("Maintenance windows must end after they start, in the future, and not overlap")]
+    InvalidMaintenanceWindow {},
+
+    #[error("At most {max} maintenance windows can be scheduled")]
+    TooManyMaintenanceWindows { max: u32 },
+
+    #[error("No maintenance window starts at that time")]
+    MaintenanceNotFound {},
+
+    #[error("Denominator must not be zero")]
+    ZeroDenominator {},
+
+    #[error("Score change of {delta} is below the minimum of {min_delta}")]
+    DeltaTooSmall { delta: u32, min_delta: u32 },
+
+    #[error("Gain exceeds the per-epoch cap, {remaining} remaining this epoch")]
+    EpochCapExceeded { remaining: u32 },
+
+    #[error("Gain exceeds the global per-epoch cap, {remaining} remaining this epoch")]
+    GlobalCapExceeded { remaining: u32 },
+
+    #[error("Epoch length must be greater than zero")]
+    InvalidEpoch {},
+
+    #[error("At most {max} aggregation sources are allowed")]
+    TooManySources { max: u32 },
+
+    #[error("Peers need a chain id of 1 to {max} bytes and a non-zero weight")]
+    InvalidPeer { max: u32 },
+
+    #[error("At most {max} peers are allowed")]
+    TooManyPeers { max: u32 },
+
+    #[error("No peer is registered for chain {chain_id}")]
+    UnknownPeer { chain_id: String },
+
+    #[error("Expiry must be in the future")]
+    ExpiryInPast {},
+
+    #[error("Signature does not match an active submission key")]
+    InvalidSignature {},
+
+    #[error("Nonce must be above {last}")]
+    StaleNonce { last: u64 },
+
+    #[error("Public key must be a SEC1 encoded secp256k1 point")]
+    InvalidPubkey {},
+
+    #[error("New key must not be active already, and old keys can't retire in the past")]
+    InvalidKeyRotation {},
+
+    #[error("At most {max} submission keys can be active")]
+    TooManySubmissionKeys { max: u32 },
+
+    #[error("No reset has been requested")]
+    NoPendingReset {},
+
+    #[error("Reset confirmation does not match")]
+    InvalidConfirmation {},
+
+    #[error("Reset confirmation expired at height {expires_at}")]
+    ResetExpired { expires_at: u64 },
+
+    #[error("A confirmed reset is still in progress")]
+    ResetInProgress {},
+
+    #[error("No score commitment to reveal")]
+    NoCommitment {},
+
+    #[error("A score commitment is open until height {expires_at}")]
+    CommitmentPending { expires_at: u64 },
+
+    #[error("Score commitment expired at height {expires_at}")]
+    CommitmentExpired { expires_at: u64 },
+
+    #[error("Revealed scores do not match the commitment")]
+    InvalidReveal {},
+
+    #[error("No update to roll back for {user}")]
+    NothingToRollBack { user: String },
+
+    #[error("Rollback window for {user} closed at height {closed_at}")]
+    RollbackExpired { user: String, closed_at: u64 },
+
+    #[error("Event prefix must be at most {max} ASCII letters, digits or underscores")]
+    InvalidEventPrefix { max: u32 },
+
+    #[error("Address prefix must be 1 to {max} lowercase ASCII letters or digits")]
+    InvalidAddrPrefix { max: u32 },
+
+    #[error("{user} is not eligible for score updates")]
+    Ineligible { user: String },
+
+    #[error("{user} has no score")]
+    NoScore { user: String },
+
+    #[error("{user} has no removed score to restore")]
+    NothingToRestore { user: String },
+
+    #[error("Grace period for restoring {user} closed at {closed_at}")]
+    GracePeriodOver { user: String, closed_at: Timestamp },
+
+    #[error("{user} has a score again; remove it before restoring")]
+    ScoreExists { user: String },
+
+    #[error("Send exactly one non-zero coin, in the pool denom once one is set")]
+    InvalidRewardFunds {},
+
+    #[error("Nothing to hand out: the pool or the total score is empty")]
+    NothingToDistribute {},
+
+    #[error("No rewards to claim")]
+    NothingToClaim {},
+
+    #[error("Cannot spend {amount} points with {available} spendable")]
+    InsufficientScore { available: u32, amount: u32 },
+
+    #[error("Spends must be non-zero")]
+    InvalidSpendAmount {},
+
+    #[error("Memo is longer than {max} bytes")]
+    MemoTooLong { max: u32 },
+
+    #[error("Forwarded message is longer than {max} bytes")]
+    CallMsgTooLong { max: u32 },
+
+    #[error("Send exactly one non-zero coin, in the faucet denom once one is set")]
+    InvalidFaucetFunds {},
+
+    #[error("Faucet payouts must be non-zero")]
+    InvalidFaucetAmount {},
+
+    #[error("The faucet cannot cover a claim")]
+    FaucetEmpty {},
+
+    #[error("A score of at least {min_score} is needed to claim, got {score}")]
+    ScoreTooLow { score: u32, min_score: u32 },
+
+    #[error("The next faucet claim opens at {next_claim_at}")]
+    FaucetCooldown { next_claim_at: Timestamp },
+
+    #[error("Send exactly one non-zero coin, in the rebate denom once one is set")]
+    InvalidRebateFunds {},
+
+    #[error("A raffle prize must be a single non-zero coin")]
+    InvalidRafflePrize {},
+
+    #[error("Reveal height must be in the future")]
+    InvalidRevealHeight {},
+
+    #[error("Raffle can't be drawn before height {reveal_height}")]
+    RaffleNotReady { reveal_height: u64 },
+
+    #[error("Raffle has been drawn already")]
+    RaffleResolved {},
+
+    #[error("Only the raffle winner can claim, once drawn")]
+    NotRaffleWinner {},
+
+    #[error("Raffle prize has been claimed already")]
+    RaffleClaimed {},
+
+    #[error("A wager needs a non-zero stake, another user as opponent and an expiry in the future")]
+    InvalidWager {},
+
+    #[error("Wager {wager_id} is not {expected}")]
+    WagerNotInState { wager_id: u64, expected: String },
+
+    #[error("Wager {wager_id} expired")]
+    WagerExpired { wager_id: u64 },
+
+    #[error("Wager {wager_id} can't be refunded before it expires")]
+    WagerNotExpired { wager_id: u64 },
+
+    #[error("The winner of a wager must be one of its two users")]
+    InvalidWagerWinner {},
+
+    #[error("Reasons are limited to {max} bytes")]
+    ReasonTooLong { max: u32 },
+
+    #[error("Idempotency key {key} was already used")]
+    DuplicateIdempotencyKey { key: String },
+
+    #[error("Idempotency keys must be 1 to {max} bytes")]
+    InvalidIdempotencyKey { max: u32 },
+
+    #[error("Idempotency keys must be kept for at least one block")]
+    ZeroIdempotencyTtl {},
+
+    #[error("Batches are limited to {max} entries")]
+    BatchTooLarge { max: u32 },
+
+    #[error("Limits must be between 1 and {max}")]
+    InvalidLimit { max: u32 },
+
+    #[error("Viewing keys are limited to {max} bytes")]
+    ViewingKeyTooLong { max: u32 },
+
+    #[error("Salts are limited to {max} bytes")]
+    SaltTooLong { max: u32 },
+
+    #[error("Merkle proofs are limited to {max} hashes")]
+    ProofTooLong { max: u32 },
+
+    #[error("At most {max} reserved addresses are allowed")]
+    TooManyReservedAddresses { max: u32 },
+
+    #[error("At most {max} fee routes are allowed")]
+    TooManyFeeRoutes { max: u32 },
+
+    #[error("{user} still has a penalty open for appeal")]
+    PenaltyPending { user: String },
+
+    #[error("{user} has no penalty open for appeal")]
+    NoPenalty { user: String },
+
+    #[error("The penalty has already been appealed")]
+    AlreadyAppealed {},
+
+    #[error("The appeal window closed at {closed_at}")]
+    AppealWindowClosed { closed_at: Timestamp },
+
+    #[error("{user} has not appealed their penalty")]
+    NotAppealed { user: String },
+
+    #[error("The arbiter must not be the owner")]
+    ArbiterIsOwner {},
+
+    #[error("{addr} is reserved and cannot hold a score")]
+    ReservedAddress { addr: String },
+
+    #[error("Send exactly one non-zero coin to fund the stage")]
+    InvalidStageFunds {},
+
+    #[error("Merkle proof does not match the stage root")]
+    InvalidProof {},
+
+    #[error("Leaf {index} of stage {stage} already claimed")]
+    AlreadyClaimed { stage: u32, index: u64 },
+
+    #[error("Stage {stage} expired at {expires_at}")]
+    StageExpired { stage: u32, expires_at: Timestamp },
+
+    #[error("Stage {stage} is open until {expires_at}")]
+    StageNotExpired { stage: u32, expires_at: Timestamp },
+
+    #[error("Stage {stage} has already been clawed back")]
+    StageClosed { stage: u32 },
+
+    #[error("Scores have at most {max} decimal places")]
+    InvalidScoreDecimals { max: u32 },
+
+    #[error("Score does not fit a u32 with {decimals} decimal places")]
+    ScorePrecision { decimals: u32 },
+
+    #[error("The podium holds at most {max} entries")]
+    TopKTooLarge { max: u32 },
+
+    #[error("Fee route shares must be non-zero and add up to at most 10000 basis points")]
+    InvalidFeeRoutes {},
+
+    #[error("Updates cost {fee}")]
+    FeeRequired { fee: String },
+
+    #[error("This token is not accepted for update fees")]
+    InvalidFeeToken {},
+
+    #[error("Given address already registered as a hook")]
+    HookAlreadyRegistered {},
+
+    #[error("Given address not registered as a hook")]
+    HookNotRegistered {},
+
+    #[error("At most {max} hooks are allowed")]
+    TooManyHooks { max: u32 },
+
+    #[error("At most {max} operators are allowed")]
+    TooManyOperators { max: u32 },
+
+    #[error("At most {max} admins are allowed per board")]
+    TooManyBoardAdmins { max: u32 },
+
+    #[error("Unknown board {board}")]
+    UnknownBoard { board: String },
+
+    #[error("Board {board} shares the contract-wide config, which only the owner can change")]
+    SharedBoardConfig { board: String },
+
+    #[error("The {field} contains control or invisible characters")]
+    InvalidText { field: String },
+
+    #[error("The {field} contains a denied word")]
+    DeniedText { field: String },
+
+    #[error("Denylist entries must be 1 to {max} bytes")]
+    InvalidDenylistEntry { max: u32 },
+
+    #[error("At most {max} denylist entries are allowed")]
+    TooManyDenylistEntries { max: u32 },
+
+    #[error("Bracket {name} needs a unique name of 1 to 32 bytes, min_score <= max_score and a capacity of 1 to 1000")]
+    InvalidBracket { name: String },
+
+    #[error("Brackets {first} and {second} overlap")]
+    BracketOverlap { first: String, second: String },
+
+    #[error("At most {max} brackets are allowed")]
+    TooManyBrackets { max: u32 },
+
+    #[error("{user} was merged into {primary}")]
+    Merged { user: String, primary: String },
+
+    #[error("Season {snapshot_id} must be archived before its digest is recorded")]
+    SnapshotNotArchived { snapshot_id: u32 },
+
+    #[error("A digest for season {snapshot_id} is already recorded")]
+    DigestAlreadyRecorded { snapshot_id: u32 },
+
+    #[error("Digests must be 1 to {max} bytes")]
+    InvalidDigest { max: u32 },
+
+    #[error("Tags must be 1 to {max} bytes")]
+    InvalidTag { max: u32 },
+
+    #[error("An address cannot be merged into itself")]
+    SelfMerge {},
+
+    #[error("{user} is frozen until their flagged update is reviewed")]
+    UserFrozen { user: String },
+
+    #[error("{user} has no flagged update")]
+    NotFlagged { user: String },
+
+    #[error("Score mirroring is not configured")]
+    MirrorNotConfigured {},
+
+    #[error("The contract's profile has {feature} turned off")]
+    FeatureDisabled { feature: String },
+
+    #[error("Score certificates are not configured")]
+    CertifierNotConfigured {},
+
+    #[error("{user} already holds a certificate for season {season}")]
+    AlreadyCertified { user: String, season: u32 },
+
+    #[error("Season {season} is still being archived")]
+    SeasonArchiving { season: u32 },
+
+    #[error("Voucher redemption is not configured")]
+    VouchersNotConfigured {},
+
+    #[error("Voucher expired at {expired_at}")]
+    VoucherExpired { expired_at: Timestamp },
+
+    #[error("Voucher {id} was redeemed at height {height}")]
+    VoucherRedeemed { id: u64, height: u64 },
+
+    #[error("Signature does not match the voucher key")]
+    InvalidVoucherSignature {},
+
+    #[error("{asset} is managed by the contract and cannot be recovered")]
+    ManagedAsset { asset: String },
+
+    #[error("Recovered amounts must be non-zero")]
+    InvalidRecoveryAmount {},
+
+    #[error("{purpose} holds {held}{denom}, short of {requested}{denom}")]
+    LedgerShortfall { purpose: String, denom: String, held: Uint128, requested: Uint128 },
+
+    #[error("Query limits must be between 1 and {max}")]
+    InvalidQueryLimit { max: u32 },
+
+    #[error("Weight tables hold between 1 and {max} entries")]
+    InvalidWeightTableSize { max: u32 },
+
+    #[error("No public scores to weigh")]
+    EmptyWeightTable {},
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -0,0 +1,236 @@
+use cosmwasm_std::{to_vec, Binary, Coin, Event, StdResult};
+use serde::Serialize;
+
+use crate::custom::Response;
+use crate::msg::ConfigUpdate;
+use crate::state::{EpochSummary, SummaryEntry, Wager};
+
+// Every event and its attributes are built here, so a name or an ordering only changes in
+// one place. Keys are prefixed later by Config::event_prefix, never here.
+
+/// An empty response tagged with the handler that produced it.
+pub fn method_response(method: &str) -> Response {
+    Response::new().add_attribute("method", method)
+}
+
+fn show(score: Option<u32>) -> String {
+    score.map_or_else(|| "none".to_string(), |score| score.to_string())
+}
+
+// Config values as their JSON, minus the quotes around plain strings
+fn show_json<T: Serialize>(value: &T) -> StdResult<String> {
+    let json = String::from_utf8(to_vec(value)?).unwrap_or_default();
+    Ok(json.strip_prefix('"').and_then(|json| json.strip_suffix('"')).map_or(json.clone(), String::from))
+}
+
+/// A score write that went through, with the score as stored.
+pub fn evt_score_changed(user: &str, score: u32) -> Event {
+    Event::new("score_changed").add_attribute("user", user).add_attribute("score", score.to_string())
+}
+
+/// One attribute per field the update sets, in ConfigUpdate's declaration order.
+pub fn evt_config_updated(update: &ConfigUpdate) -> StdResult<Event> {
+    let fields = vec![
+        ("raw_queries", update.raw_queries.map(|value| value.to_string())),
+        ("prune_zero_scores", update.prune_zero_scores.map(|value| value.to_string())),
+        ("min_delta", update.min_delta.map(|value| value.to_string())),
+        ("small_delta_policy", update.small_delta_policy.as_ref().map(show_json).transpose()?),
+        ("private_scores", update.private_scores.map(|value| value.to_string())),
+        ("season_duration", update.season_duration.map(|value| value.to_string())),
+        ("rollback_window", update.rollback_window.map(|value| value.to_string())),
+        ("event_prefix", update.event_prefix.as_ref().map(|prefix| if prefix.is_empty() { "none".to_string() } else { prefix.clone() })),
+        ("score_floor", update.score_floor.map(|value| value.to_string())),
+        ("appeal_window", update.appeal_window.map(|value| value.to_string())),
+        ("reserved_addresses", update.reserved_addresses.as_ref().map(|addrs| addrs.join(","))),
+        ("score_formula", update.score_formula.as_ref().map(show_json).transpose()?),
+        ("fee_routes", update.fee_routes.as_ref().map(show_json).transpose()?),
+        ("anomaly_multiple", update.anomaly_multiple.map(|value| value.to_string())),
+        ("merge_policy", update.merge_policy.as_ref().map(show_json).transpose()?),
+        ("idempotency_ttl", update.idempotency_ttl.map(|value| value.to_string())),
+        ("duplicate_key_policy", update.duplicate_key_policy.as_ref().map(show_json).transpose()?),
+        ("removal_grace_period", update.removal_grace_period.map(|value| value.to_string())),
+        ("score_bucket", update.score_bucket.map(|value| value.to_string())),
+        ("max_query_limit", update.max_query_limit.map(|value| value.to_string())),
+        ("max_query_scan", update.max_query_scan.map(|value| value.to_string())),
+    ];
+    Ok(fields
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .fold(Event::new("config_updated"), |event, (key, value)| event.add_attribute(key, value)))
+}
+
+/// Announces a cutover to `successor`, or "none" when it's lifted.
+pub fn evt_read_only_forwarding(successor: Option<&str>) -> Event {
+    Event::new("read_only_forwarding").add_attribute("successor", successor.unwrap_or("none"))
+}
+
+pub fn evt_journal(first_sequence: u64, last_sequence: u64) -> Event {
+    Event::new("journal")
+        .add_attribute("first_sequence", first_sequence.to_string())
+        .add_attribute("last_sequence", last_sequence.to_string())
+}
+
+pub fn evt_anomaly(user: &str, previous: u32, score: u32) -> Event {
+    Event::new("anomaly")
+        .add_attribute("user", user)
+        .add_attribute("previous", previous.to_string())
+        .add_attribute("score", score.to_string())
+}
+
+pub fn evt_anomaly_reviewed(user: &str, accepted: bool) -> Event {
+    Event::new("anomaly_reviewed").add_attribute("user", user).add_attribute("accepted", accepted.to_string())
+}
+
+pub fn evt_merge_identities(
+    primary: &str,
+    secondary: &str,
+    policy: &str,
+    primary_score: Option<u32>,
+    secondary_score: Option<u32>,
+    merged: Option<u32>,
+) -> Event {
+    Event::new("merge_identities")
+        .add_attribute("primary", primary)
+        .add_attribute("secondary", secondary)
+        .add_attribute("policy", policy)
+        .add_attribute("primary_score", show(primary_score))
+        .add_attribute("secondary_score", show(secondary_score))
+        .add_attribute("score", show(merged))
+}
+
+pub fn evt_score_rollback(user: &str, from: Option<u32>, to: Option<u32>) -> Event {
+    Event::new("score_rollback").add_attribute("user", user).add_attribute("from", show(from)).add_attribute("to", show(to))
+}
+
+pub fn evt_score_removed(user: &str, score: u32) -> Event {
+    Event::new("score_removed").add_attribute("user", user).add_attribute("score", score.to_string())
+}
+
+pub fn evt_score_restored(user: &str, score: u32) -> Event {
+    Event::new("score_restored").add_attribute("user", user).add_attribute("score", score.to_string())
+}
+
+pub fn evt_penalty(user: &str, points: u32, reason: &str) -> Event {
+    Event::new("penalty")
+        .add_attribute("user", user)
+        .add_attribute("points", points.to_string())
+        .add_attribute("reason", reason)
+}
+
+/// Overturning an appeal returns the escrowed points; upholding it returns none.
+pub fn evt_appeal_resolved(case_id: u64, user: &str, upheld: bool, points: u32) -> Event {
+    Event::new("appeal_resolved")
+        .add_attribute("case_id", case_id.to_string())
+        .add_attribute("user", user)
+        .add_attribute("upheld", upheld.to_string())
+        .add_attribute("returned", if upheld { "0".to_string() } else { points.to_string() })
+}
+
+pub fn evt_spend(user: &str, spend_id: u64, amount: u32) -> Event {
+    Event::new("spend")
+        .add_attribute("user", user)
+        .add_attribute("spend_id", spend_id.to_string())
+        .add_attribute("amount", amount.to_string())
+}
+
+pub fn evt_raffle_started(raffle_id: u64, prize: &str, reveal_height: u64) -> Event {
+    Event::new("raffle_started")
+        .add_attribute("raffle_id", raffle_id.to_string())
+        .add_attribute("prize", prize)
+        .add_attribute("reveal_height", reveal_height.to_string())
+}
+
+pub fn evt_raffle_resolved(raffle_id: u64, seed: &Binary, entrants: u32, total_weight: u64, winner: Option<&str>) -> Event {
+    Event::new("raffle_resolved")
+        .add_attribute("raffle_id", raffle_id.to_string())
+        .add_attribute("seed", seed.to_base64())
+        .add_attribute("entrants", entrants.to_string())
+        .add_attribute("total_weight", total_weight.to_string())
+        .add_attribute("winner", winner.unwrap_or_default())
+}
+
+pub fn evt_weight_table_published(version: u64, season: u32, entries: usize) -> Event {
+    Event::new("weight_table_published")
+        .add_attribute("version", version.to_string())
+        .add_attribute("season", season.to_string())
+        .add_attribute("entries", entries.to_string())
+}
+
+pub fn evt_raffle_claimed(raffle_id: u64, winner: &str, prize: &str) -> Event {
+    Event::new("raffle_claimed")
+        .add_attribute("raffle_id", raffle_id.to_string())
+        .add_attribute("winner", winner)
+        .add_attribute("prize", prize)
+}
+
+pub fn evt_rebate(user: &str, amount: &Coin) -> Event {
+    Event::new("rebate").add_attribute("user", user).add_attribute("amount", amount.to_string())
+}
+
+/// Emitted on every change to a wager, with its state afterwards.
+pub fn evt_wager(wager: &Wager) -> StdResult<Event> {
+    Ok(Event::new("wager")
+        .add_attribute("wager_id", wager.id.to_string())
+        .add_attribute("status", show_json(&wager.status)?)
+        .add_attribute("proposer", &wager.proposer)
+        .add_attribute("opponent", &wager.opponent)
+        .add_attribute("stake", wager.stake.to_string())
+        .add_attribute("winner", wager.winner.as_ref().map_or("none", |winner| winner.as_str())))
+}
+
+pub fn evt_epoch_summary(summary: &EpochSummary) -> Event {
+    Event::new("epoch_summary")
+        .add_attribute("season", summary.season.to_string())
+        .add_attribute("start_height", summary.start_height.to_string())
+        .add_attribute("end_height", summary.end_height.to_string())
+        .add_attribute("total_score", summary.total_score.to_string())
+        .add_attribute("users", summary.users.to_string())
+}
+
+/// `rank` counts from 1.
+pub fn evt_epoch_top(season: u32, rank: usize, entry: &SummaryEntry) -> Event {
+    Event::new("epoch_top")
+        .add_attribute("season", season.to_string())
+        .add_attribute("rank", rank.to_string())
+        .add_attribute("user", &entry.user)
+        .add_attribute("score", entry.score.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evt_config_updated, evt_score_rollback, method_response};
+    use crate::msg::ConfigUpdate;
+    use crate::state::MergePolicy;
+    use cosmwasm_std::Attribute;
+
+    #[test]
+    // Only the fields being set show up, in declaration order whatever order they're given in
+    fn config_updated_lists_set_fields() {
+        let update = ConfigUpdate {
+            score_bucket: Some(5),
+            merge_policy: Some(MergePolicy::Max),
+            event_prefix: Some(String::new()),
+            raw_queries: Some(true),
+            ..ConfigUpdate::default()
+        };
+        let event = evt_config_updated(&update).unwrap();
+        assert_eq!("config_updated", event.ty);
+        assert_eq!(
+            vec![
+                Attribute::new("raw_queries", "true"),
+                Attribute::new("event_prefix", "none"),
+                Attribute::new("merge_policy", "max"),
+                Attribute::new("score_bucket", "5"),
+            ],
+            event.attributes
+        );
+        assert!(evt_config_updated(&ConfigUpdate::default()).unwrap().attributes.is_empty());
+    }
+
+    #[test]
+    fn absent_scores_show_as_none() {
+        let event = evt_score_rollback("alice", Some(3), None);
+        assert_eq!(vec![Attribute::new("user", "alice"), Attribute::new("from", "3"), Attribute::new("to", "none")], event.attributes);
+        assert_eq!(vec![Attribute::new("method", "try_x")], method_response("try_x").attributes);
+    }
+}
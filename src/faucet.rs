@@ -0,0 +1,25 @@
+use cosmwasm_std::{Deps, Env, StdResult};
+
+use crate::msg::FaucetStatusResponse;
+use crate::state::{FAUCET, FAUCET_CLAIMS};
+
+/// Whether `user` could claim from the faucet right now, and when they next can. `score` is
+/// the user's score as the querier may see it, None for a hidden user, which leaves
+/// eligibility unknown.
+pub fn query_faucet_status(deps: Deps, env: Env, user: String, score: Option<u32>) -> StdResult<FaucetStatusResponse> {
+    let faucet = FAUCET.may_load(deps.storage)?.unwrap_or_default();
+    let claim = FAUCET_CLAIMS.may_load(deps.storage, user)?.unwrap_or_default();
+
+    let next_claim_at = claim.last_claimed.map(|last| last.plus_seconds(faucet.cooldown));
+    let cooled_down = next_claim_at.map_or(true, |next| next <= env.block.time);
+    let funded = !faucet.amount_per_claim.is_zero() && faucet.balance >= faucet.amount_per_claim;
+    Ok(FaucetStatusResponse {
+        eligible: score.map(|score| funded && cooled_down && score >= faucet.min_score),
+        denom: faucet.denom,
+        amount_per_claim: faucet.amount_per_claim,
+        min_score: faucet.min_score,
+        next_claim_at,
+        claims: claim.claims,
+        claimed: claim.claimed,
+    })
+}
@@ -0,0 +1,108 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Api, Decimal, Deps, Env, Order, StdResult, Storage, Uint128};
+
+use crate::aggregate::LOCAL_SOURCE;
+use crate::error::ContractError;
+use crate::leaderboard::redact;
+use crate::msg::PeerInit;
+use crate::state::{Peer, PeerScore, CONFIG, PEERS, PEER_SCORES};
+
+// upper bounds on the peer registry
+pub const MAX_PEERS: u32 = 10;
+pub const MAX_CHAIN_ID_LEN: u32 = 64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PeerEntry {
+    pub chain_id: String,
+    pub address: String,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PeersResponse {
+    pub peers: Vec<PeerEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FederatedSource {
+    // LOCAL_SOURCE, or the peer's chain id
+    pub source: String,
+    pub raw: u32,
+    pub weighted: Uint128,
+    // Height of the peer's last sync for the user; None for the local score or when the
+    // peer never reported one
+    pub synced_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FederatedScoreResponse {
+    pub score: Uint128,
+    // Local score first, then each registered peer by chain id
+    pub breakdown: Vec<FederatedSource>,
+}
+
+/// Removes the peers under `remove`, then adds or replaces `add`. Scores a removed peer
+/// reported stay cached but no longer count.
+pub fn update_peers(storage: &mut dyn Storage, api: &dyn Api, add: Vec<PeerInit>, remove: Vec<String>) -> Result<(), ContractError> {
+    for chain_id in remove {
+        PEERS.remove(storage, chain_id);
+    }
+    for peer in add {
+        if peer.chain_id.is_empty() || peer.chain_id.len() > MAX_CHAIN_ID_LEN as usize || peer.weight.is_zero() {
+            return Err(ContractError::InvalidPeer { max: MAX_CHAIN_ID_LEN });
+        }
+        let address = api.addr_validate(&peer.address)?;
+        PEERS.save(storage, peer.chain_id, &Peer { address, weight: peer.weight })?;
+    }
+    if PEERS.keys(storage, None, None, Order::Ascending).count() > MAX_PEERS as usize {
+        return Err(ContractError::TooManyPeers { max: MAX_PEERS });
+    }
+    Ok(())
+}
+
+/// Caches the scores a peer reports, replacing earlier reports for the same users. Only
+/// the peer's registered address may report for it.
+pub fn sync_from_peer(storage: &mut dyn Storage, sender: &Addr, chain_id: &str, updates: &[(String, u32)], height: u64) -> Result<(), ContractError> {
+    let peer = PEERS.may_load(storage, chain_id.to_string())?.ok_or_else(|| ContractError::UnknownPeer { chain_id: chain_id.to_string() })?;
+    if *sender != peer.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    for (user, score) in updates {
+        PEER_SCORES.save(storage, (chain_id.to_string(), user.clone()), &PeerScore { score: *score, synced_at: height })?;
+    }
+    Ok(())
+}
+
+/// The local score plus every registered peer's last reported score times its weight.
+/// Unless `exact`, each score is redacted as the local one would be.
+pub fn query_federated_score(deps: Deps, env: Env, user: String, exact: bool) -> StdResult<FederatedScoreResponse> {
+    let local = crate::contract::query_score(deps, env, user.clone(), exact)?.score;
+    let mut breakdown = vec![FederatedSource { source: LOCAL_SOURCE.to_string(), raw: local, weighted: Uint128::from(local), synced_at: None }];
+
+    let config = CONFIG.load(deps.storage)?;
+    for item in PEERS.range(deps.storage, None, None, Order::Ascending) {
+        let (chain_id, peer) = item?;
+        let reported = PEER_SCORES.may_load(deps.storage, (chain_id.clone(), user.clone()))?;
+        let raw = reported.as_ref().map_or(0, |reported| reported.score);
+        let raw = if exact { raw } else { redact(&config, raw) };
+        breakdown.push(FederatedSource {
+            source: chain_id,
+            raw,
+            weighted: Uint128::from(raw) * peer.weight,
+            synced_at: reported.map(|reported| reported.synced_at),
+        });
+    }
+
+    let score = breakdown.iter().fold(Uint128::zero(), |acc, entry| acc + entry.weighted);
+    Ok(FederatedScoreResponse { score, breakdown })
+}
+
+pub fn query_peers(deps: Deps) -> StdResult<PeersResponse> {
+    let peers = PEERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(chain_id, peer)| PeerEntry { chain_id, address: peer.address.to_string(), weight: peer.weight }))
+        .collect::<StdResult<_>>()?;
+    Ok(PeersResponse { peers })
+}
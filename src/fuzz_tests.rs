@@ -0,0 +1,400 @@
+// Randomized sequences of execute messages, checking invariants after every step.
+// proptest draws every choice a step makes, so a failing sequence shrinks to the fewest
+// steps and the smallest choices that still break it.
+use std::collections::{BTreeMap, BTreeSet};
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{from_binary, to_vec, Addr, Binary, Order, OwnedDeps, StdError, Storage};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::contract::{execute, instantiate, query};
+use crate::msg::{ConfigUpdate, ExecuteMsg, InstantiateMsg, InvariantsResponse, QueryMsg, Rounding, ScoreResponse, SignedVoucher, Voucher};
+use crate::state::ALL_TIME_SCORES;
+use crate::ContractError;
+
+const OWNER: &str = "creator";
+const USERS: [&str; 5] = ["alice", "bob", "carol", "dave", "erin"];
+const CASES: u32 = 50;
+const STEPS: usize = 200;
+// More than any step draws
+const DRAWS: usize = 12;
+
+// The choices one step makes; once they run out every further choice is 0
+struct Rng<'a>(std::slice::Iter<'a, u64>);
+
+impl Rng<'_> {
+    fn next(&mut self) -> u64 {
+        self.0.next().copied().unwrap_or_default()
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next() % items.len() as u64) as usize]
+    }
+
+    fn score(&mut self) -> u32 {
+        // Bias towards edge values, which is where arithmetic bugs live
+        match self.next() % 4 {
+            0 => 0,
+            1 => u32::MAX,
+            _ => self.next() as u32,
+        }
+    }
+
+    // One of `items` most of the time, so follow-up messages find something to act on
+    fn pick_from<'a>(&mut self, items: impl Iterator<Item = &'a String>, fallback: String) -> String {
+        let items: Vec<_> = items.collect();
+        match self.next() % 4 {
+            0 => fallback,
+            _ if items.is_empty() => fallback,
+            n => items[(n as usize + self.next() as usize) % items.len()].clone(),
+        }
+    }
+
+    fn points(&mut self) -> u32 {
+        // Mostly amounts a score can cover, so spends and stakes get through
+        match self.next() % 2 {
+            0 => (self.next() % 100) as u32,
+            _ => self.score(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WagerState {
+    Open,
+    Accepted,
+    Closed,
+}
+
+struct ModelWager {
+    proposer: String,
+    opponent: String,
+    stake: u32,
+    state: WagerState,
+}
+
+// What the scores should be, advanced by every message the contract accepted
+#[derive(Default)]
+struct Model {
+    scores: BTreeMap<String, u32>,
+    // Each merged-away user to whoever absorbed them
+    merged: BTreeMap<String, String>,
+    // Scores deleted by RemoveScore, restorable within the grace period
+    removed: BTreeMap<String, u32>,
+    // Points escrowed by the open penalty of each user
+    penalties: BTreeMap<String, u32>,
+    // Users whose open penalty is under appeal
+    appealed: BTreeSet<String>,
+    // Voucher ids already redeemed
+    vouchers: BTreeSet<u64>,
+    // Indexed by wager id, which the contract hands out in sequence
+    wagers: Vec<ModelWager>,
+    paused: bool,
+    frozen: bool,
+}
+
+impl Model {
+    fn credit(&mut self, user: &str, points: u32) {
+        let score = self.scores.entry(user.to_string()).or_default();
+        *score = score.saturating_add(points);
+    }
+
+    // Escrowed points of a merged user go to whoever absorbed them
+    fn release(&mut self, user: &str, points: u32) {
+        let mut payee = user;
+        while let Some(primary) = self.merged.get(payee) {
+            payee = primary;
+        }
+        let payee = payee.to_string();
+        self.credit(&payee, points);
+    }
+
+    fn deduct(&mut self, user: &str, points: u32) {
+        *self.scores.get_mut(user).unwrap() -= points;
+    }
+
+    fn apply(&mut self, sender: &str, msg: &ExecuteMsg) {
+        match msg {
+            ExecuteMsg::UpdateScore { user, score, .. } => {
+                self.scores.insert(user.to_string(), *score);
+            }
+            ExecuteMsg::Penalize { user, points, .. } => {
+                let current = self.scores[user];
+                let deducted = current.min(*points);
+                self.deduct(user, deducted);
+                if deducted > 0 {
+                    self.penalties.insert(user.clone(), deducted);
+                }
+            }
+            ExecuteMsg::Appeal { .. } => {
+                self.appealed.insert(sender.to_string());
+            }
+            ExecuteMsg::ResolveAppeal { user, uphold } => {
+                self.appealed.remove(user);
+                let points = self.penalties.remove(user).unwrap();
+                if !uphold {
+                    self.release(user, points);
+                }
+            }
+            ExecuteMsg::SpendScore { amount, .. } => self.deduct(sender, *amount),
+            ExecuteMsg::OpenWager { opponent, stake, .. } => {
+                self.deduct(sender, *stake);
+                let wager = ModelWager { proposer: sender.to_string(), opponent: opponent.clone(), stake: *stake, state: WagerState::Open };
+                self.wagers.push(wager);
+            }
+            ExecuteMsg::AcceptWager { wager_id } => {
+                let wager = &mut self.wagers[*wager_id as usize];
+                wager.state = WagerState::Accepted;
+                let (opponent, stake) = (wager.opponent.clone(), wager.stake);
+                self.deduct(&opponent, stake);
+            }
+            ExecuteMsg::ReportWager { wager_id, winner } => {
+                let wager = &mut self.wagers[*wager_id as usize];
+                wager.state = WagerState::Closed;
+                let stake = wager.stake;
+                self.release(winner, stake.saturating_mul(2));
+            }
+            ExecuteMsg::RefundWager { wager_id } => {
+                let wager = &mut self.wagers[*wager_id as usize];
+                let accepted = wager.state == WagerState::Accepted;
+                wager.state = WagerState::Closed;
+                let (proposer, opponent, stake) = (wager.proposer.clone(), wager.opponent.clone(), wager.stake);
+                if accepted {
+                    self.release(&opponent, stake);
+                }
+                self.release(&proposer, stake);
+            }
+            ExecuteMsg::MergeIdentities { primary, secondary } => {
+                let secondary_score = self.scores.remove(secondary);
+                let merged = match (self.scores.get(primary), secondary_score) {
+                    (Some(primary), Some(secondary)) => Some(primary.saturating_add(secondary)),
+                    (primary, secondary) => primary.copied().or(secondary),
+                };
+                if let Some(score) = merged {
+                    self.scores.insert(primary.clone(), score);
+                }
+                self.merged.insert(secondary.clone(), primary.clone());
+            }
+            ExecuteMsg::RedeemVoucher { voucher, .. } => {
+                self.vouchers.insert(voucher.id);
+                self.credit(&voucher.user, voucher.points);
+            }
+            ExecuteMsg::RemoveScore { user } => {
+                let score = self.scores.remove(user).unwrap();
+                self.removed.insert(user.clone(), score);
+            }
+            ExecuteMsg::RestoreScore { user } => {
+                let score = self.removed.remove(user).unwrap();
+                self.scores.insert(user.clone(), score);
+            }
+            ExecuteMsg::RescaleScores { numerator, denominator, .. } => {
+                for score in self.scores.values_mut() {
+                    *score = (u64::from(*score) * u64::from(*numerator) / u64::from(*denominator)).min(u64::from(u32::MAX)) as u32;
+                }
+            }
+            ExecuteMsg::SetPaused { paused } => self.paused = *paused,
+            ExecuteMsg::FreezeLeaderboard { .. } => self.frozen = true,
+            ExecuteMsg::UnfreezeLeaderboard {} => self.frozen = false,
+            _ => {}
+        }
+    }
+
+    fn score(&self, user: &str) -> u32 {
+        self.scores.get(user).copied().unwrap_or_default()
+    }
+
+    fn wager(&self, wager_id: u64, states: &[WagerState]) -> bool {
+        self.wagers.get(wager_id as usize).map_or(false, |wager| states.contains(&wager.state))
+    }
+
+    // Whether the model agrees `msg` from `sender` should have been refused with `err`
+    fn expects_failure(&self, sender: &str, msg: &ExecuteMsg, err: &ContractError) -> bool {
+        use ContractError as E;
+        use WagerState::*;
+
+        let missing_wager = |wager_id: u64| wager_id as usize >= self.wagers.len();
+        let merged = |users: &[&str], user: &str| users.contains(&user) && self.merged.contains_key(user);
+        let short = |user: &str, available: u32, amount: u32| available < amount && available <= self.score(user);
+        match (msg, err) {
+            (_, E::Paused {}) => self.paused,
+            (_, E::Frozen {}) => self.frozen,
+            (ExecuteMsg::UpdateScore { user, .. }, E::Merged { user: merged_user, .. }) => merged(&[user.as_str()], merged_user),
+            (ExecuteMsg::SpendScore { amount, .. }, E::InvalidSpendAmount {}) => *amount == 0,
+            (ExecuteMsg::SpendScore { .. }, E::Merged { user, .. }) => merged(&[sender], user),
+            (ExecuteMsg::SpendScore { .. }, E::InsufficientScore { available, amount }) => short(sender, *available, *amount),
+            (ExecuteMsg::OpenWager { opponent, stake, .. }, E::InvalidWager {}) => *stake == 0 || opponent == sender,
+            (ExecuteMsg::OpenWager { opponent, .. }, E::Merged { user, .. }) => merged(&[sender, opponent], user),
+            (ExecuteMsg::OpenWager { .. }, E::InsufficientScore { available, amount }) => short(sender, *available, *amount),
+            (ExecuteMsg::AcceptWager { wager_id }, E::Std(StdError::NotFound { .. })) => missing_wager(*wager_id),
+            (ExecuteMsg::AcceptWager { wager_id }, E::WagerNotInState { .. }) => !self.wager(*wager_id, &[Open]),
+            (ExecuteMsg::AcceptWager { wager_id }, E::Unauthorized {}) => self.wagers[*wager_id as usize].opponent != sender,
+            (ExecuteMsg::AcceptWager { .. }, E::Merged { user, .. }) => merged(&[sender], user),
+            (ExecuteMsg::AcceptWager { .. }, E::InsufficientScore { available, amount }) => short(sender, *available, *amount),
+            (ExecuteMsg::ReportWager { wager_id, .. }, E::Std(StdError::NotFound { .. })) => missing_wager(*wager_id),
+            (ExecuteMsg::ReportWager { wager_id, .. }, E::WagerNotInState { .. }) => !self.wager(*wager_id, &[Accepted]),
+            (ExecuteMsg::RefundWager { wager_id }, E::Std(StdError::NotFound { .. })) => missing_wager(*wager_id),
+            (ExecuteMsg::RefundWager { wager_id }, E::WagerNotInState { .. }) => self.wager(*wager_id, &[Closed]),
+            // The block time never moves, so only the proposer of an unaccepted wager may refund it
+            (ExecuteMsg::RefundWager { wager_id }, E::WagerNotExpired { .. }) => {
+                let wager = &self.wagers[*wager_id as usize];
+                wager.state == Accepted || (wager.state == Open && wager.proposer != sender)
+            }
+            (ExecuteMsg::Penalize { user, .. }, E::Merged { user: merged_user, .. }) => merged(&[user], merged_user),
+            (ExecuteMsg::Penalize { user, .. }, E::NoScore { .. }) => !self.scores.contains_key(user),
+            (ExecuteMsg::Penalize { user, .. }, E::PenaltyPending { .. }) => self.penalties.contains_key(user),
+            (ExecuteMsg::Appeal { .. }, E::NoPenalty { .. }) => !self.penalties.contains_key(sender),
+            (ExecuteMsg::Appeal { .. }, E::AlreadyAppealed {}) => self.appealed.contains(sender),
+            (ExecuteMsg::ResolveAppeal { user, .. }, E::NoPenalty { .. }) => !self.penalties.contains_key(user),
+            (ExecuteMsg::ResolveAppeal { user, .. }, E::NotAppealed { .. }) => !self.appealed.contains(user),
+            (ExecuteMsg::MergeIdentities { primary, secondary }, E::SelfMerge {}) => primary == secondary,
+            (ExecuteMsg::MergeIdentities { primary, secondary }, E::Merged { user, .. }) => merged(&[primary, secondary], user),
+            (ExecuteMsg::MergeIdentities { secondary, .. }, E::PenaltyPending { .. }) => self.penalties.contains_key(secondary),
+            (ExecuteMsg::RedeemVoucher { voucher, .. }, E::Merged { user, .. }) => merged(&[&voucher.user], user),
+            (ExecuteMsg::RedeemVoucher { voucher, .. }, E::VoucherRedeemed { .. }) => self.vouchers.contains(&voucher.id),
+            (ExecuteMsg::RemoveScore { user }, E::NoScore { .. }) => !self.scores.contains_key(user),
+            (ExecuteMsg::RestoreScore { user }, E::NothingToRestore { .. }) => !self.removed.contains_key(user),
+            (ExecuteMsg::RestoreScore { user }, E::ScoreExists { .. }) => self.scores.contains_key(user),
+            (ExecuteMsg::RescaleScores { .. }, E::NotPaused {}) => !self.paused,
+            _ => false,
+        }
+    }
+}
+
+// Picks a message and who sends it. Owner-only messages come from a random user now and
+// then, which must be refused; the rest are sent by users on their own behalf.
+fn random_step(rng: &mut Rng<'_>, model: &Model, voucher_key: &SigningKey) -> (String, ExecuteMsg, bool) {
+    let user = rng.pick(&USERS).to_string();
+    let later = mock_env().block.time.plus_seconds(3600);
+    let msg = match rng.next() % 50 {
+        0 => ExecuteMsg::UpdateConfig(ConfigUpdate { raw_queries: Some(rng.next() % 2 == 0), ..ConfigUpdate::default() }),
+        1 => ExecuteMsg::FreezeLeaderboard { until: None },
+        2..=5 => ExecuteMsg::UnfreezeLeaderboard {},
+        6 => ExecuteMsg::SetPaused { paused: true },
+        26..=29 => ExecuteMsg::SetPaused { paused: false },
+        7 => {
+            let rounding = Some(Rounding::Down);
+            ExecuteMsg::RescaleScores { numerator: (rng.next() % 4) as u32, denominator: 1 + (rng.next() % 3) as u32, rounding, start_after: None, limit: None }
+        }
+        8 => ExecuteMsg::MergeIdentities { primary: user, secondary: rng.pick(&USERS).to_string() },
+        9..=10 => ExecuteMsg::Penalize { user, points: rng.points(), reason: "fuzz".to_string(), idempotency_key: None },
+        11 => ExecuteMsg::ResolveAppeal { user: rng.pick_from(model.penalties.keys(), user), uphold: rng.next() % 2 == 0 },
+        12 => ExecuteMsg::RemoveScore { user },
+        13 => ExecuteMsg::RestoreScore { user: rng.pick_from(model.removed.keys(), user) },
+        14 => {
+            let accepted = (0..model.wagers.len()).filter(|id| model.wagers[*id].state == WagerState::Accepted).collect::<Vec<_>>();
+            let wager_id = match accepted.len() {
+                0 => rng.next() % (model.wagers.len() as u64 + 1),
+                len => accepted[(rng.next() % len as u64) as usize] as u64,
+            };
+            let winner = model.wagers.get(wager_id as usize).map_or(user, |wager| match rng.next() % 2 {
+                0 => wager.proposer.clone(),
+                _ => wager.opponent.clone(),
+            });
+            ExecuteMsg::ReportWager { wager_id, winner }
+        }
+        15..=17 => return (user, ExecuteMsg::SpendScore { amount: rng.points(), memo: "fuzz".to_string() }, false),
+        18..=19 => {
+            let msg = ExecuteMsg::OpenWager { opponent: rng.pick(&USERS).to_string(), stake: rng.points(), expires_at: later };
+            return (user, msg, false);
+        }
+        20..=21 => {
+            let wager_id = rng.next() % (model.wagers.len() as u64 + 1);
+            let sender = match model.wagers.get(wager_id as usize) {
+                Some(wager) if rng.next() % 4 != 0 => wager.opponent.clone(),
+                _ => user,
+            };
+            return (sender, ExecuteMsg::AcceptWager { wager_id }, false);
+        }
+        22 => {
+            let wager_id = rng.next() % (model.wagers.len() as u64 + 1);
+            let sender = model.wagers.get(wager_id as usize).map_or(user, |wager| wager.proposer.clone());
+            return (sender, ExecuteMsg::RefundWager { wager_id }, false);
+        }
+        23 => return (rng.pick_from(model.penalties.keys(), user), ExecuteMsg::Appeal { reason: "fuzz".to_string() }, false),
+        24..=25 => {
+            let voucher = Voucher { id: rng.next() % 20, user, points: rng.points(), expires_at: later };
+            let payload = SignedVoucher { contract: mock_env().contract.address.to_string(), voucher: voucher.clone() };
+            let signature: Signature = voucher_key.sign(&to_vec(&payload).unwrap());
+            return (rng.pick(&USERS).to_string(), ExecuteMsg::RedeemVoucher { voucher, signature: Binary::from(signature.as_ref()) }, false);
+        }
+        _ => ExecuteMsg::UpdateScore { user: Addr::unchecked(user), score: rng.score(), expires_at: None, idempotency_key: None },
+    };
+    let sender = if rng.next() % 5 == 0 { rng.pick(&USERS) } else { OWNER };
+    (sender.to_string(), msg, true)
+}
+
+// A failed transaction reverts on chain, but the mock keeps whatever was written before
+// the error, so put the storage back by hand
+fn execute_or_revert(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+    let snapshot: Vec<_> = deps.storage.range(None, None, Order::Ascending).collect();
+    let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).map(|_| ());
+    if res.is_err() {
+        let mut storage = MockStorage::new();
+        for (key, value) in snapshot {
+            storage.set(&key, &value);
+        }
+        deps.storage = storage;
+    }
+    res
+}
+
+fn assert_invariants(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, model: &Model, step: usize) {
+    // Small batches, so the cursor gets carried across several of them
+    let mut cursor = None;
+    let mut checked = 0;
+    loop {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CheckInvariants { cursor, limit: Some(3) }).unwrap();
+        let report: InvariantsResponse = from_binary(&res).unwrap();
+        assert!(report.discrepancies.is_empty(), "step {}: {:?}", step, report.discrepancies);
+        checked += report.checked;
+        if report.complete {
+            break;
+        }
+        cursor = report.next_cursor;
+    }
+    let all_time = ALL_TIME_SCORES.keys(&deps.storage, None, None, Order::Ascending).count();
+    assert_eq!((model.scores.len() + all_time) as u32, checked, "step {}", step);
+
+    for user in USERS {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetScore { user: user.to_string(), auth: None }).unwrap();
+        let value: ScoreResponse = from_binary(&res).unwrap();
+        assert_eq!(model.scores.get(user).copied().unwrap_or_default(), value.score, "step {}", step);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn random_execute_sequences_keep_invariants(steps in vec(any::<[u64; DRAWS]>(), 1..=STEPS)) {
+        let voucher_key = SigningKey::from_bytes(&[1; 32]).unwrap();
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), InstantiateMsg::default()).unwrap();
+        let pubkey = Binary::from(voucher_key.verifying_key().to_bytes().as_slice());
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), ExecuteMsg::SetVoucherKey { pubkey: Some(pubkey) }).unwrap();
+        // Penalties stay open for appeal longer than any run, as the block time never moves
+        let msg = ExecuteMsg::UpdateConfig(ConfigUpdate { appeal_window: Some(3600), ..ConfigUpdate::default() });
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+        let mut model = Model::default();
+        for (step, draws) in steps.iter().enumerate() {
+            let (sender, msg, owner_only) = random_step(&mut Rng(draws.iter()), &model, &voucher_key);
+            let res = execute_or_revert(&mut deps, &sender, msg.clone());
+
+            match (owner_only && sender != OWNER, res) {
+                (true, Err(ContractError::Unauthorized {})) => {}
+                (true, res) => panic!("step {}: {} sent {:?}, got {:?}", step, sender, msg, res),
+                (false, Ok(())) => model.apply(&sender, &msg),
+                (false, Err(err)) => {
+                    let expected = model.expects_failure(&sender, &msg, &err);
+                    prop_assert!(expected, "step {}: {} sent {:?}, got {:?}", step, sender, msg, err);
+                }
+            }
+
+            assert_invariants(&deps, &model, step);
+        }
+    }
+}
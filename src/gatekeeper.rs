@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::QuerierWrapper;
+
+use crate::error::ContractError;
+use crate::state::Config;
+
+/// Query the configured gatekeeper contract must answer with an `EligibleResponse`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GatekeeperQueryMsg {
+    IsEligible { user: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EligibleResponse {
+    pub eligible: bool,
+}
+
+/// Rejects updates for users the gatekeeper, if any, doesn't approve. A gatekeeper that
+/// fails to answer rejects the update too.
+pub fn assert_eligible(querier: &QuerierWrapper, config: &Config, user: &str) -> Result<(), ContractError> {
+    let gatekeeper = match &config.gatekeeper {
+        Some(gatekeeper) => gatekeeper,
+        None => return Ok(()),
+    };
+
+    let res: EligibleResponse =
+        querier.query_wasm_smart(gatekeeper.to_string(), &GatekeeperQueryMsg::IsEligible { user: user.to_string() })?;
+    if !res.eligible {
+        return Err(ContractError::Ineligible { user: user.to_string() });
+    }
+    Ok(())
+}
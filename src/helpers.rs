@@ -2,10 +2,13 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, StdResult, WasmMsg
+    to_binary, Addr, Binary, Coin, CosmosMsg, QuerierWrapper, StdResult, Timestamp, Uint128, WasmMsg
 };
 
-use crate::msg::{ExecuteMsg};
+use crate::msg::{
+    BoardConfigUpdate, ConfigUpdate, Cw20FeeInit, ExecuteMsg, HasMinScoreResponse, PeerInit, QueryMsg, Rounding, ScoreResponse, ScoreUpdate, SourceInit,
+};
+use crate::state::{Bracket, CombineMode, EpochCap, Retention, Role};
 
 /// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 /// for working with this.
@@ -27,20 +30,451 @@ impl CwTemplateContract {
         .into())
     }
 
-    
-    // pub fn count<Q, T, CQ>(&self, querier: &Q) -> StdResult<CountResponse>
-    // where
-    //     Q: Querier,
-    //     T: Into<String>,
-    //     CQ: CustomQuery,
-    // {
-    //     let msg = QueryMsg::GetCount {};
-    //     let query = WasmQuery::Smart {
-    //         contract_addr: self.addr().into(),
-    //         msg: to_binary(&msg)?,
-    //     }
-    //     .into();
-    //     let res: CountResponse = QuerierWrapper::<CQ>::new(querier).query(&query)?;
-    //     Ok(res)
-    // }
+    // Admin actions, ready to embed in a cw3 proposal. Available to dependants
+    // built with the `library` feature.
+
+    pub fn update_score(&self, user: Addr, score: u32, expires_at: Option<Timestamp>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateScore { user, score, expires_at, idempotency_key: None })
+    }
+
+    pub fn set_mirror_enabled(&self, enabled: bool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetMirrorEnabled { enabled })
+    }
+
+    pub fn decouple_mirror(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::DecoupleMirror {})
+    }
+
+    pub fn update_config(&self, update: ConfigUpdate) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateConfig(update))
+    }
+
+    pub fn transfer_ownership<T: Into<String>>(&self, new_owner: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::TransferOwnership { new_owner: new_owner.into() })
+    }
+
+    pub fn set_paused(&self, paused: bool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetPaused { paused })
+    }
+
+    pub fn read_only_forwarding(&self, successor: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ReadOnlyForwarding { successor })
+    }
+
+    pub fn rescale_scores(
+        &self,
+        numerator: u32,
+        denominator: u32,
+        rounding: Option<Rounding>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RescaleScores { numerator, denominator, rounding, start_after, limit })
+    }
+
+    pub fn reset_all_scores(&self, confirmation: Option<String>, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ResetAllScores { confirmation, limit })
+    }
+
+    pub fn compact_zero_scores(&self, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CompactZeroScores { limit })
+    }
+
+    pub fn set_user_gain_cap(&self, cap: Option<EpochCap>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetUserGainCap { cap })
+    }
+
+    pub fn set_global_gain_cap(&self, cap: Option<EpochCap>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetGlobalGainCap { cap })
+    }
+
+    pub fn set_notifier(&self, addr: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetNotifier { addr })
+    }
+
+    pub fn set_aggregation(&self, sources: Vec<SourceInit>, mode: CombineMode) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetAggregation { sources, mode })
+    }
+
+    pub fn commit_scores(&self, hash: Binary) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CommitScores { hash })
+    }
+
+    pub fn reveal_scores(&self, updates: Vec<ScoreUpdate>, salt: Binary) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RevealScores { updates, salt })
+    }
+
+    pub fn start_new_season(&self, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::StartNewSeason { limit })
+    }
+
+    pub fn remove_score<T: Into<String>>(&self, user: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RemoveScore { user: user.into() })
+    }
+
+    pub fn restore_score<T: Into<String>>(&self, user: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RestoreScore { user: user.into() })
+    }
+
+    pub fn rollback<T: Into<String>>(&self, user: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Rollback { user: user.into() })
+    }
+
+    pub fn rollback_batch(&self, users: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RollbackBatch { users })
+    }
+
+    pub fn set_shadow(&self, addr: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetShadow { addr })
+    }
+
+    pub fn set_gatekeeper(&self, addr: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetGatekeeper { addr })
+    }
+
+    pub fn checkpoint(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Checkpoint {})
+    }
+
+    pub fn set_reward_stream(&self, per_second: Uint128) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetRewardStream { per_second })
+    }
+
+    pub fn set_faucet(&self, amount_per_claim: Uint128, min_score: u32, cooldown: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetFaucet { amount_per_claim, min_score, cooldown })
+    }
+
+    pub fn set_rebate(&self, amount: Uint128) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetRebate { amount })
+    }
+
+    pub fn set_shop(&self, addr: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetShop { addr })
+    }
+
+    pub fn set_certifier(&self, addr: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetCertifier { addr })
+    }
+
+    pub fn publish_weight_table(&self, top_n: u32) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::PublishWeightTable { top_n })
+    }
+
+    pub fn penalize<T: Into<String>>(&self, user: T, points: u32, reason: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Penalize { user: user.into(), points, reason: reason.into(), idempotency_key: None })
+    }
+
+    pub fn resolve_appeal<T: Into<String>>(&self, user: T, uphold: bool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ResolveAppeal { user: user.into(), uphold })
+    }
+
+    pub fn set_arbiter(&self, addr: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetArbiter { addr })
+    }
+
+    pub fn normalize_addresses(&self, start_after: Option<String>, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::NormalizeAddresses { start_after, limit })
+    }
+
+    pub fn clawback_stage(&self, stage: u32) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ClawbackStage { stage })
+    }
+
+    pub fn recover_cw20<T: Into<String>, U: Into<String>>(&self, token: T, amount: Uint128, recipient: U) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RecoverCw20 { token: token.into(), amount, recipient: recipient.into() })
+    }
+
+    pub fn recover_nft<T: Into<String>, U: Into<String>, V: Into<String>>(&self, contract: T, token_id: U, recipient: V) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RecoverNft { contract: contract.into(), token_id: token_id.into(), recipient: recipient.into() })
+    }
+
+    pub fn set_top_k(&self, k: u32) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetTopK { k })
+    }
+
+    pub fn rebuild_top_k(&self, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RebuildTopK { limit })
+    }
+
+    pub fn rebuild_index(&self, start_after: Option<String>, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RebuildIndex { start_after, limit })
+    }
+
+    pub fn set_update_fees(&self, native: Option<Coin>, cw20: Option<Cw20FeeInit>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetUpdateFees { native, cw20 })
+    }
+
+    pub fn review_anomaly<T: Into<String>>(&self, user: T, accept: bool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ReviewAnomaly { user: user.into(), accept })
+    }
+
+    pub fn add_hook<T: Into<String>>(&self, addr: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::AddHook { addr: addr.into() })
+    }
+
+    pub fn remove_hook<T: Into<String>>(&self, addr: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RemoveHook { addr: addr.into() })
+    }
+
+    pub fn update_operators(&self, add: Vec<String>, remove: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateOperators { add, remove })
+    }
+
+    pub fn merge_identities<T: Into<String>, U: Into<String>>(&self, primary: T, secondary: U) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::MergeIdentities { primary: primary.into(), secondary: secondary.into() })
+    }
+
+    pub fn attest<T: Into<String>, U: Into<String>>(&self, user: T, value: i64, tag: U) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Attest { user: user.into(), value, tag: tag.into() })
+    }
+
+    pub fn finalize_snapshot_digest<T: Into<String>>(&self, snapshot_id: u32, digest: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::FinalizeSnapshotDigest { snapshot_id, digest: digest.into() })
+    }
+
+    pub fn schedule_maintenance(&self, start: Timestamp, end: Timestamp) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ScheduleMaintenance { start, end })
+    }
+
+    pub fn cancel_maintenance(&self, start: Timestamp) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CancelMaintenance { start })
+    }
+
+    pub fn freeze_leaderboard(&self, until: Option<Timestamp>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::FreezeLeaderboard { until })
+    }
+
+    pub fn unfreeze_leaderboard(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UnfreezeLeaderboard {})
+    }
+
+    pub fn rotate_submission_key(&self, new_pubkey: Binary, effective_at: Timestamp) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RotateSubmissionKey { new_pubkey, effective_at })
+    }
+
+    pub fn set_voucher_key(&self, pubkey: Option<Binary>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetVoucherKey { pubkey })
+    }
+
+    pub fn set_journal(&self, enabled: bool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetJournal { enabled })
+    }
+
+    pub fn set_retention(&self, retention: Option<Retention>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetRetention { retention })
+    }
+
+    pub fn set_policy<T: Into<String>>(&self, msg_name: T, role: Role) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetPolicy { msg_name: msg_name.into(), role })
+    }
+
+    pub fn report_wager<T: Into<String>>(&self, wager_id: u64, winner: T) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ReportWager { wager_id, winner: winner.into() })
+    }
+
+    pub fn update_denylist(&self, add: Vec<String>, remove: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateDenylist { add, remove })
+    }
+
+    pub fn update_peers(&self, add: Vec<PeerInit>, remove: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdatePeers { add, remove })
+    }
+
+    pub fn update_board_admins<T: Into<String>>(&self, board: T, add: Vec<String>, remove: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateBoardAdmins { board: board.into(), add, remove })
+    }
+
+    pub fn update_board_config<T: Into<String>>(&self, board: T, update: BoardConfigUpdate) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateBoardConfig { board: board.into(), update })
+    }
+
+    pub fn set_brackets(&self, brackets: Vec<Bracket>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetBrackets { brackets })
+    }
+
+    // Queries, for contracts reading this one
+
+    /// The user's score as anyone may read it, i.e. rounded by Config::score_bucket.
+    pub fn score<T: Into<String>>(&self, querier: &QuerierWrapper, user: T) -> StdResult<u32> {
+        let msg = QueryMsg::GetScore { user: user.into(), auth: None };
+        let res: ScoreResponse = querier.query_wasm_smart(self.addr(), &msg)?;
+        Ok(res.score)
+    }
+
+    /// Whether the score as `score` reads it, but rounded down to Config::score_bucket, is
+    /// at least `min_score`; fails for hidden users.
+    pub fn has_min_score<T: Into<String>>(&self, querier: &QuerierWrapper, user: T, min_score: u32) -> StdResult<bool> {
+        let msg = QueryMsg::HasMinScore { user: user.into(), min_score, auth: None };
+        let res: HasMinScoreResponse = querier.query_wasm_smart(self.addr(), &msg)?;
+        Ok(res.has_min_score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{coin, from_binary, ContractResult, Decimal, SystemResult, WasmQuery};
+    use crate::state::CapPolicy;
+
+    fn contract() -> CwTemplateContract {
+        CwTemplateContract(Addr::unchecked("scores"))
+    }
+
+    // Unwraps the execute payload and compares it against the expected JSON
+    fn assert_json(msg: StdResult<CosmosMsg>, expected: &str) {
+        match msg.unwrap() {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+                assert_eq!("scores", contract_addr);
+                assert!(funds.is_empty());
+                assert_eq!(expected, String::from_utf8(msg.to_vec()).unwrap());
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn admin_messages_encode() {
+        let c = contract();
+        assert_json(
+            c.update_score(Addr::unchecked("player"), 10, None),
+            r#"{"update_score":{"user":"player","score":10,"expires_at":null,"idempotency_key":null}}"#,
+        );
+        assert_json(c.set_mirror_enabled(false), r#"{"set_mirror_enabled":{"enabled":false}}"#);
+        assert_json(c.decouple_mirror(), r#"{"decouple_mirror":{}}"#);
+        assert_json(
+            c.update_config(ConfigUpdate { prune_zero_scores: Some(true), ..ConfigUpdate::default() }),
+            r#"{"update_config":{"raw_queries":null,"prune_zero_scores":true,"min_delta":null,"small_delta_policy":null,"private_scores":null,"season_duration":null,"rollback_window":null,"event_prefix":null,"score_floor":null,"appeal_window":null,"reserved_addresses":null,"score_formula":null,"fee_routes":null,"anomaly_multiple":null,"merge_policy":null,"idempotency_ttl":null,"duplicate_key_policy":null,"removal_grace_period":null,"score_bucket":null,"max_query_limit":null,"max_query_scan":null}}"#,
+        );
+        assert_json(c.transfer_ownership("dao"), r#"{"transfer_ownership":{"new_owner":"dao"}}"#);
+        assert_json(c.set_paused(true), r#"{"set_paused":{"paused":true}}"#);
+        assert_json(c.read_only_forwarding(Some("next".to_string())), r#"{"read_only_forwarding":{"successor":"next"}}"#);
+        assert_json(
+            c.rescale_scores(1, 2, Some(Rounding::Nearest), None, Some(50)),
+            r#"{"rescale_scores":{"numerator":1,"denominator":2,"rounding":"nearest","start_after":null,"limit":50}}"#,
+        );
+        assert_json(
+            c.reset_all_scores(Some("abcd".to_string()), None),
+            r#"{"reset_all_scores":{"confirmation":"abcd","limit":null}}"#,
+        );
+        assert_json(c.compact_zero_scores(Some(10)), r#"{"compact_zero_scores":{"limit":10}}"#);
+        assert_json(
+            c.set_user_gain_cap(Some(EpochCap { amount: 100, epoch_seconds: 86400, policy: CapPolicy::Truncate })),
+            r#"{"set_user_gain_cap":{"cap":{"amount":100,"epoch_seconds":86400,"policy":"truncate"}}}"#,
+        );
+        assert_json(c.set_global_gain_cap(None), r#"{"set_global_gain_cap":{"cap":null}}"#);
+        assert_json(c.set_notifier(None), r#"{"set_notifier":{"addr":null}}"#);
+        assert_json(
+            c.set_aggregation(vec![SourceInit { addr: "season1".to_string(), weight: Decimal::percent(50) }], CombineMode::Max),
+            r#"{"set_aggregation":{"sources":[{"addr":"season1","weight":"0.5"}],"mode":"max"}}"#,
+        );
+        assert_json(c.commit_scores(Binary::from(b"hash".as_slice())), r#"{"commit_scores":{"hash":"aGFzaA=="}}"#);
+        assert_json(
+            c.reveal_scores(vec![ScoreUpdate { user: Addr::unchecked("player"), score: 5 }], Binary::from(b"salt".as_slice())),
+            r#"{"reveal_scores":{"updates":[{"user":"player","score":5}],"salt":"c2FsdA=="}}"#,
+        );
+        assert_json(c.start_new_season(Some(50)), r#"{"start_new_season":{"limit":50}}"#);
+        assert_json(c.remove_score("player"), r#"{"remove_score":{"user":"player"}}"#);
+        assert_json(c.restore_score("player"), r#"{"restore_score":{"user":"player"}}"#);
+        assert_json(c.rollback("player"), r#"{"rollback":{"user":"player"}}"#);
+        assert_json(c.rollback_batch(vec!["a".to_string(), "b".to_string()]), r#"{"rollback_batch":{"users":["a","b"]}}"#);
+        assert_json(c.set_shadow(Some("next".to_string())), r#"{"set_shadow":{"addr":"next"}}"#);
+        assert_json(c.set_gatekeeper(None), r#"{"set_gatekeeper":{"addr":null}}"#);
+        assert_json(c.checkpoint(), r#"{"checkpoint":{}}"#);
+        assert_json(c.set_reward_stream(Uint128::new(5)), r#"{"set_reward_stream":{"per_second":"5"}}"#);
+        assert_json(c.set_shop(Some("shop".to_string())), r#"{"set_shop":{"addr":"shop"}}"#);
+        assert_json(c.set_certifier(None), r#"{"set_certifier":{"addr":null}}"#);
+        assert_json(c.publish_weight_table(50), r#"{"publish_weight_table":{"top_n":50}}"#);
+        assert_json(
+            c.set_faucet(Uint128::new(100), 50, 3600),
+            r#"{"set_faucet":{"amount_per_claim":"100","min_score":50,"cooldown":3600}}"#,
+        );
+        assert_json(c.set_rebate(Uint128::new(20)), r#"{"set_rebate":{"amount":"20"}}"#);
+        assert_json(
+            c.penalize("player", 10, "cheating"),
+            r#"{"penalize":{"user":"player","points":10,"reason":"cheating","idempotency_key":null}}"#,
+        );
+        assert_json(c.resolve_appeal("player", false), r#"{"resolve_appeal":{"user":"player","uphold":false}}"#);
+        assert_json(c.set_arbiter(Some("court".to_string())), r#"{"set_arbiter":{"addr":"court"}}"#);
+        assert_json(c.normalize_addresses(None, Some(50)), r#"{"normalize_addresses":{"start_after":null,"limit":50}}"#);
+        assert_json(c.clawback_stage(2), r#"{"clawback_stage":{"stage":2}}"#);
+        assert_json(c.recover_cw20("token", Uint128::new(5), "bob"), r#"{"recover_cw20":{"token":"token","amount":"5","recipient":"bob"}}"#);
+        assert_json(c.recover_nft("nft", "7", "bob"), r#"{"recover_nft":{"contract":"nft","token_id":"7","recipient":"bob"}}"#);
+        assert_json(c.set_top_k(3), r#"{"set_top_k":{"k":3}}"#);
+        assert_json(c.rebuild_top_k(None), r#"{"rebuild_top_k":{"limit":null}}"#);
+        assert_json(c.rebuild_index(None, Some(20)), r#"{"rebuild_index":{"start_after":null,"limit":20}}"#);
+        assert_json(
+            c.set_update_fees(Some(coin(5, "uluna")), None),
+            r#"{"set_update_fees":{"native":{"denom":"uluna","amount":"5"},"cw20":null}}"#,
+        );
+        assert_json(c.review_anomaly("player", false), r#"{"review_anomaly":{"user":"player","accept":false}}"#);
+        assert_json(c.add_hook("listener"), r#"{"add_hook":{"addr":"listener"}}"#);
+        assert_json(c.remove_hook("listener"), r#"{"remove_hook":{"addr":"listener"}}"#);
+        assert_json(c.update_operators(vec!["relayer".to_string()], vec![]), r#"{"update_operators":{"add":["relayer"],"remove":[]}}"#);
+        assert_json(
+            c.set_brackets(vec![Bracket { name: "gold".to_string(), min_score: 100, max_score: 199, capacity: 8 }]),
+            r#"{"set_brackets":{"brackets":[{"name":"gold","min_score":100,"max_score":199,"capacity":8}]}}"#,
+        );
+        assert_json(c.attest("player", -3, "griefing"), r#"{"attest":{"user":"player","value":-3,"tag":"griefing"}}"#);
+        assert_json(
+            c.finalize_snapshot_digest(1, "bafybeigdyrzt"),
+            r#"{"finalize_snapshot_digest":{"snapshot_id":1,"digest":"bafybeigdyrzt"}}"#,
+        );
+        assert_json(
+            c.schedule_maintenance(Timestamp::from_seconds(100), Timestamp::from_seconds(200)),
+            r#"{"schedule_maintenance":{"start":"100000000000","end":"200000000000"}}"#,
+        );
+        assert_json(c.cancel_maintenance(Timestamp::from_seconds(100)), r#"{"cancel_maintenance":{"start":"100000000000"}}"#);
+        assert_json(c.freeze_leaderboard(Some(Timestamp::from_seconds(100))), r#"{"freeze_leaderboard":{"until":"100000000000"}}"#);
+        assert_json(c.unfreeze_leaderboard(), r#"{"unfreeze_leaderboard":{}}"#);
+        assert_json(
+            c.rotate_submission_key(Binary::from(vec![2; 33]), Timestamp::from_seconds(100)),
+            r#"{"rotate_submission_key":{"new_pubkey":"AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgIC","effective_at":"100000000000"}}"#,
+        );
+        assert_json(c.set_journal(true), r#"{"set_journal":{"enabled":true}}"#);
+        assert_json(c.set_voucher_key(None), r#"{"set_voucher_key":{"pubkey":null}}"#);
+        assert_json(
+            c.set_retention(Some(Retention { max_entries: Some(100), max_age: None })),
+            r#"{"set_retention":{"retention":{"max_entries":100,"max_age":null}}}"#,
+        );
+        assert_json(c.set_policy("purge_expired", Role::Operator), r#"{"set_policy":{"msg_name":"purge_expired","role":"operator"}}"#);
+        assert_json(c.update_denylist(vec!["scam".to_string()], vec![]), r#"{"update_denylist":{"add":["scam"],"remove":[]}}"#);
+        let peer = PeerInit { chain_id: "phoenix-1".to_string(), address: "relayer".to_string(), weight: Decimal::percent(50) };
+        assert_json(
+            c.update_peers(vec![peer], vec![]),
+            r#"{"update_peers":{"add":[{"chain_id":"phoenix-1","address":"relayer","weight":"0.5"}],"remove":[]}}"#,
+        );
+        assert_json(c.report_wager(3, "alice"), r#"{"report_wager":{"wager_id":3,"winner":"alice"}}"#);
+        assert_json(
+            c.update_board_admins("default", vec!["mod".to_string()], vec![]),
+            r#"{"update_board_admins":{"board":"default","add":["mod"],"remove":[]}}"#,
+        );
+        assert_json(
+            c.update_board_config("default", BoardConfigUpdate { min_delta: Some(2), ..BoardConfigUpdate::default() }),
+            r#"{"update_board_config":{"board":"default","update":{"min_delta":2,"small_delta_policy":null,"season_duration":null}}}"#,
+        );
+        assert_json(c.merge_identities("main", "alt"), r#"{"merge_identities":{"primary":"main","secondary":"alt"}}"#);
+    }
+
+    #[test]
+    fn queries_read_responses() {
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_wasm(|query| {
+            let res = match query {
+                WasmQuery::Smart { contract_addr, msg } if contract_addr == "scores" => match from_binary(msg).unwrap() {
+                    QueryMsg::GetScore { .. } => to_binary(&ScoreResponse { score: 42, expires_at: None }),
+                    QueryMsg::HasMinScore { min_score, .. } => to_binary(&HasMinScoreResponse { has_min_score: min_score <= 42 }),
+                    _ => panic!("unexpected query"),
+                },
+                _ => panic!("unexpected contract"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+        let querier = QuerierWrapper::new(&querier);
+
+        let c = contract();
+        assert_eq!(42, c.score(&querier, "alice").unwrap());
+        assert!(c.has_min_score(&querier, "alice", 42).unwrap());
+        assert!(!c.has_min_score(&querier, "alice", 43).unwrap());
+    }
 }
@@ -0,0 +1,58 @@
+use cosmwasm_std::{Deps, Env, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::msg::IdempotencyKeyResponse;
+use crate::state::{Config, DuplicateKeyPolicy, IdempotencyRecord, IDEMPOTENCY_EXPIRY, IDEMPOTENCY_KEYS};
+
+// expired keys dropped per keyed message, which keeps the map near its live size
+const PRUNE_BATCH: usize = 10;
+
+/// Records `key` for `sender` until Config::idempotency_ttl blocks from now. Returns
+/// false for a key still live, when the duplicate policy says to skip the message.
+pub fn claim_key(storage: &mut dyn Storage, env: &Env, config: &Config, sender: &str, key: &str) -> Result<bool, ContractError> {
+    let height = env.block.height;
+    prune_expired(storage, height)?;
+
+    let id = (sender.to_string(), key.to_string());
+    if IDEMPOTENCY_KEYS.may_load(storage, id.clone())?.map_or(false, |record| record.expires_at > height) {
+        return match config.duplicate_key_policy {
+            DuplicateKeyPolicy::Reject => Err(ContractError::DuplicateIdempotencyKey { key: key.to_string() }),
+            DuplicateKeyPolicy::Ignore => Ok(false),
+        };
+    }
+
+    let record = IdempotencyRecord { used_at: height, expires_at: height + config.idempotency_ttl };
+    IDEMPOTENCY_KEYS.save(storage, id.clone(), &record)?;
+    IDEMPOTENCY_EXPIRY.save(storage, (record.expires_at, id.0, id.1), &())?;
+    Ok(true)
+}
+
+fn prune_expired(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+    let end = Bound::exclusive((height + 1, String::new(), String::new()));
+    let expired = IDEMPOTENCY_EXPIRY
+        .keys(storage, None, Some(end), Order::Ascending)
+        .take(PRUNE_BATCH)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (expires_at, sender, key) in expired {
+        IDEMPOTENCY_EXPIRY.remove(storage, (expires_at, sender.clone(), key.clone()));
+        // A key reused after expiring has a newer record that must stay
+        let id = (sender, key);
+        if IDEMPOTENCY_KEYS.may_load(storage, id.clone())?.map_or(false, |record| record.expires_at == expires_at) {
+            IDEMPOTENCY_KEYS.remove(storage, id);
+        }
+    }
+    Ok(())
+}
+
+/// Whether `key` from `sender` is still live, so a retry carrying it would be a duplicate.
+pub fn query_idempotency_key(deps: Deps, env: Env, sender: String, key: String) -> StdResult<IdempotencyKeyResponse> {
+    let record = IDEMPOTENCY_KEYS
+        .may_load(deps.storage, (sender, key))?
+        .filter(|record| record.expires_at > env.block.height);
+    Ok(IdempotencyKeyResponse {
+        used: record.is_some(),
+        used_at: record.as_ref().map(|record| record.used_at),
+        expires_at: record.map(|record| record.expires_at),
+    })
+}
@@ -0,0 +1,138 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeSet;
+
+use cosmwasm_std::{from_binary, to_binary, Binary, Deps, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::leaderboard::{current_key, index_key};
+use crate::msg::InvariantsResponse;
+use crate::state::{ALL_TIME_INDEX, ALL_TIME_SCORES, RANK_BLOCK_COUNTS, RANK_COUNTS, SCORES, SCORE_INDEX, TOP_K, TOTALS};
+
+const DEFAULT_INVARIANT_LIMIT: u32 = 100;
+// Each entry costs a handful of reads, so batches stay well within query gas limits
+const MAX_INVARIANT_LIMIT: u32 = 500;
+
+// Decoded form of the opaque `cursor`: where the scan is, and the sums so far
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+struct Cursor {
+    // Done with the score map, now walking the all-time scores
+    all_time: bool,
+    // Last user checked in the current map
+    after: Option<String>,
+    total_score: u64,
+    users: u64,
+    all_time_users: u64,
+}
+
+/// Checks up to `limit` entries, first of the score map, then of the all-time scores,
+/// resuming after `cursor`. Every score must sit at its own key in the season index,
+/// with its tie group counted in the rank counts and an all-time score at least as high;
+/// every all-time score must sit at its key in the all-time index. The first batch also
+/// checks that the podium set is the head of the index. The sums the aggregates are
+/// compared against travel in the cursor, so writes between batches can show up as drift.
+pub fn query_check_invariants(deps: Deps, cursor: Option<Binary>, limit: Option<u32>) -> StdResult<InvariantsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_INVARIANT_LIMIT).min(MAX_INVARIANT_LIMIT) as usize;
+    let first = cursor.is_none();
+    let mut cursor: Cursor = match cursor {
+        Some(cursor) => from_binary(&cursor).map_err(|_| StdError::generic_err("Invalid cursor"))?,
+        None => Cursor::default(),
+    };
+    let totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let mut discrepancies = vec![];
+    if first {
+        check_podium(deps, &mut discrepancies)?;
+    }
+
+    let mut checked = 0;
+    if !cursor.all_time {
+        let start = cursor.after.take().map(Bound::exclusive);
+        let mut tie_groups = BTreeSet::new();
+        let mut done = true;
+        for item in SCORES.range(deps.storage, start, None, Order::Ascending) {
+            if checked == limit {
+                done = false;
+                break;
+            }
+            let (user, score) = item?;
+            checked += 1;
+            cursor.total_score += u64::from(score);
+            cursor.users += 1;
+            if let Some(key) = current_key(deps.storage, &user)? {
+                if !SCORE_INDEX.has(deps.storage, key.clone()) {
+                    discrepancies.push(format!("score_index: no entry for {}", user));
+                }
+                tie_groups.insert(key.0);
+            }
+            let all_time = ALL_TIME_SCORES.may_load(deps.storage, user.clone())?.map_or(0, |entry| entry.score);
+            if all_time < score {
+                discrepancies.push(format!("all_time_scores: {} has {}, below the live {}", user, all_time, score));
+            }
+            cursor.after = Some(user);
+        }
+        for inverted in tie_groups {
+            let indexed = SCORE_INDEX.sub_prefix(inverted).keys(deps.storage, None, None, Order::Ascending).count() as u32;
+            let counted = RANK_COUNTS.may_load(deps.storage, inverted)?.unwrap_or_default();
+            if indexed != counted {
+                discrepancies.push(format!("rank_counts: score {} counted {}, indexed {}", u32::MAX - inverted, counted, indexed));
+            }
+        }
+        if !done {
+            return finish(checked, cursor, discrepancies);
+        }
+
+        if totals.total_score != cursor.total_score {
+            discrepancies.push(format!("total_score: stored {}, computed {}", totals.total_score, cursor.total_score));
+        }
+        if totals.users != cursor.users {
+            discrepancies.push(format!("users: stored {}, computed {}", totals.users, cursor.users));
+        }
+        // Block counts sum to the size of the index without walking it
+        let mut indexed = 0u64;
+        for item in RANK_BLOCK_COUNTS.range(deps.storage, None, None, Order::Ascending) {
+            indexed += u64::from(item?.1);
+        }
+        if indexed != cursor.users {
+            discrepancies.push(format!("score_index: {} entries, {} scores", indexed, cursor.users));
+        }
+        cursor.all_time = true;
+        cursor.after = None;
+    }
+
+    let start = cursor.after.take().map(Bound::exclusive);
+    for item in ALL_TIME_SCORES.range(deps.storage, start, None, Order::Ascending) {
+        if checked == limit {
+            return finish(checked, cursor, discrepancies);
+        }
+        let (user, entry) = item?;
+        checked += 1;
+        cursor.all_time_users += 1;
+        if !ALL_TIME_INDEX.has(deps.storage, index_key(entry.score, entry.achieved_at, &user)) {
+            discrepancies.push(format!("all_time_index: no entry for {}", user));
+        }
+        cursor.after = Some(user);
+    }
+    if totals.all_time_users != cursor.all_time_users {
+        discrepancies.push(format!("all_time_users: stored {}, computed {}", totals.all_time_users, cursor.all_time_users));
+    }
+    Ok(InvariantsResponse { checked: checked as u32, complete: true, discrepancies, next_cursor: None })
+}
+
+fn finish(checked: usize, cursor: Cursor, discrepancies: Vec<String>) -> StdResult<InvariantsResponse> {
+    Ok(InvariantsResponse { checked: checked as u32, complete: false, discrepancies, next_cursor: Some(to_binary(&cursor)?) })
+}
+
+// TOP_K must be the head of the index, in order; while rebuilding it may be short
+fn check_podium(deps: Deps, discrepancies: &mut Vec<String>) -> StdResult<()> {
+    let top = TOP_K.may_load(deps.storage)?.unwrap_or_default();
+    if top.k == 0 {
+        return Ok(());
+    }
+    let wanted = if top.rebuilding { top.entries.len() } else { top.k as usize };
+    let head = SCORE_INDEX.keys(deps.storage, None, None, Order::Ascending).take(wanted).collect::<StdResult<Vec<_>>>()?;
+    if head != top.entries {
+        discrepancies.push(format!("top_k: {} entries, not the {} at the head of the index", top.entries.len(), head.len()));
+    }
+    Ok(())
+}
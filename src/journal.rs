@@ -0,0 +1,42 @@
+use cosmwasm_std::{Deps, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::limits::page_limit;
+use crate::msg::{ChangesResponse, JournalChange, SequenceResponse};
+use crate::state::{JournalEntry, CONFIG, JOURNAL, JOURNAL_ENTRIES};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Appends a score change to the journal under the next sequence number, when enabled.
+/// Called for every write to SCORES, so the sequence covers every mutation.
+pub fn record_change(storage: &mut dyn Storage, user: &str, old: u32, new: u32, height: u64) -> StdResult<()> {
+    let mut journal = match JOURNAL.may_load(storage)? {
+        Some(journal) if journal.enabled => journal,
+        _ => return Ok(()),
+    };
+    journal.sequence += 1;
+    JOURNAL.save(storage, &journal)?;
+    JOURNAL_ENTRIES.save(storage, journal.sequence, &JournalEntry { user: user.to_string(), old, new, height })
+}
+
+/// Last sequence number handed out; zero before the first journaled change.
+pub fn latest_sequence(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(JOURNAL.may_load(storage)?.map_or(0, |journal| journal.sequence))
+}
+
+pub fn query_latest_sequence(deps: Deps) -> StdResult<SequenceResponse> {
+    let journal = JOURNAL.may_load(deps.storage)?.unwrap_or_default();
+    Ok(SequenceResponse { sequence: journal.sequence, enabled: journal.enabled })
+}
+
+/// Journaled changes after sequence number `seq`, oldest first. Numbers are contiguous
+/// while the journal is on, so a reader can tell whether it missed anything.
+pub fn query_changes_since(deps: Deps, seq: u64, limit: Option<u32>) -> StdResult<ChangesResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let changes = JOURNAL_ENTRIES
+        .range(deps.storage, Some(Bound::exclusive(seq)), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(seq, entry)| JournalChange { seq, user: entry.user, old: entry.old, new: entry.new }))
+        .collect::<StdResult<_>>()?;
+    Ok(ChangesResponse { changes })
+}
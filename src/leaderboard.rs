@@ -0,0 +1,233 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{from_binary, to_binary, Binary, Deps, Env, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Map};
+
+use crate::addr::addr_info;
+use crate::all_time::all_time_key;
+use crate::boards::validate_board;
+use crate::limits::{page_limit, ScanBudget};
+use crate::ranks::rank_of;
+use crate::msg::{BoardRank, CrossRankResponse, LeaderboardEntry, LeaderboardPageResponse, LeaderboardResponse, Scope, ViewerRank};
+use crate::state::{Config, ACHIEVED_AT, ALL_TIME_INDEX, ARCHIVING, CONFIG, EXPIRIES, PROFILE_VISIBILITY, SCORES, SCORE_INDEX, TOTALS};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+// Ranks are counted by walking the index, so only the top of the board gets one
+pub const MAX_RANK_SCAN: u32 = 1000;
+// Boards per CrossRank query, each repeated in the response
+pub const MAX_CROSS_RANK_BOARDS: u32 = 5;
+
+/// Position of a user in the score index. Ascending key order is the leaderboard order:
+/// higher score first, then the score reached at the lower block height, then the lower
+/// address by byte order. Every key is unique, so pages never overlap or skip across ties.
+pub type IndexKey = (u32, u64, String);
+
+pub fn index_key(score: u32, achieved_at: u64, user: &str) -> IndexKey {
+    (u32::MAX - score, achieved_at, user.to_string())
+}
+
+/// Current index position of `user`, if they have a score.
+pub fn current_key(storage: &dyn Storage, user: &str) -> StdResult<Option<IndexKey>> {
+    let score = match SCORES.may_load(storage, user.to_string())? {
+        Some(score) => score,
+        None => return Ok(None),
+    };
+    let achieved_at = ACHIEVED_AT.may_load(storage, user.to_string())?.unwrap_or_default();
+    Ok(Some(index_key(score, achieved_at, user)))
+}
+
+/// The index a board is read from.
+fn board(scope: Scope) -> Map<'static, IndexKey, ()> {
+    match scope {
+        Scope::Season => SCORE_INDEX,
+        Scope::AllTime => ALL_TIME_INDEX,
+    }
+}
+
+/// Whether `user` appears on leaderboards and has a score anyone can read.
+pub fn is_public(storage: &dyn Storage, config: &Config, user: &str) -> StdResult<bool> {
+    Ok(PROFILE_VISIBILITY.may_load(storage, user.to_string())?.unwrap_or(!config.private_scores))
+}
+
+/// `score` as public queries show it, rounded to the nearest Config::score_bucket.
+pub fn redact(config: &Config, score: u32) -> u32 {
+    let bucket = u64::from(config.score_bucket);
+    if bucket <= 1 {
+        return score;
+    }
+    let rounded = (u64::from(score) + bucket / 2) / bucket * bucket;
+    rounded.min(u64::from(u32::MAX)) as u32
+}
+
+/// `score` rounded down to Config::score_bucket, for threshold checks on redacted scores.
+pub fn redact_down(config: &Config, score: u32) -> u32 {
+    let bucket = config.score_bucket.max(1);
+    score / bucket * bucket
+}
+
+/// Stored `points` as board rows show them: after Config::score_formula, like GetScore,
+/// then redacted. The formulas never reorder scores, so the index order still holds.
+pub fn shown_score(config: &Config, points: u32) -> u32 {
+    redact(config, config.score_formula.apply(points))
+}
+
+/// Whether `user`'s score has expired by `now`. Expired entries read as absent, as in
+/// GetScore, until PurgeExpired removes them.
+pub fn is_expired(storage: &dyn Storage, user: &str, now: Timestamp) -> StdResult<bool> {
+    Ok(matches!(EXPIRIES.may_load(storage, user.to_string())?, Some(expires_at) if expires_at <= now))
+}
+
+/// Lowest achievement height still on the season board. Live entries below it are left
+/// over from a season being archived and read as absent.
+pub fn season_floor(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(ARCHIVING.may_load(storage)?.map_or(0, |archiving| archiving.end_height))
+}
+
+// Decoded form of the opaque `cursor` handed out with each page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct Cursor {
+    // Last index position returned
+    after: IndexKey,
+    // Height the first page was read at
+    snapshot: u64,
+}
+
+/// Pages through the index, leaving out hidden users and, on the season board, entries
+/// that read as absent: expired, or left over from a season being archived. Continuation
+/// pages skip entries reached after the first page was read: a user who moves down the
+/// board mid-iteration can't show up twice, and users who changed show up again on the
+/// next fresh iteration.
+pub fn query_leaderboard(
+    deps: Deps,
+    env: Env,
+    cursor: Option<Binary>,
+    limit: Option<u32>,
+    scope: Scope,
+) -> StdResult<LeaderboardResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let limit = page_limit(&config, limit, DEFAULT_LIMIT)?;
+    let (start, snapshot) = match cursor {
+        Some(cursor) => {
+            let cursor: Cursor = from_binary(&cursor).map_err(|_| StdError::generic_err("Invalid cursor"))?;
+            (Some(Bound::exclusive(cursor.after)), cursor.snapshot)
+        }
+        None => (None, env.block.height),
+    };
+
+    let floor = match scope {
+        Scope::Season => season_floor(deps.storage)?,
+        Scope::AllTime => 0,
+    };
+    let mut keys = vec![];
+    let mut budget = ScanBudget::new(&config);
+    for key in board(scope).keys(deps.storage, start, None, Order::Ascending) {
+        budget.spend()?;
+        let key = key?;
+        if key.1 < floor || key.1 > snapshot || !is_public(deps.storage, &config, &key.2)? {
+            continue;
+        }
+        if scope == Scope::Season && is_expired(deps.storage, &key.2, env.block.time)? {
+            continue;
+        }
+        keys.push(key);
+        if keys.len() > limit {
+            break;
+        }
+    }
+    let more = keys.len() > limit;
+    keys.truncate(limit);
+    let next_cursor = match keys.last() {
+        Some(last) if more => Some(to_binary(&Cursor { after: last.clone(), snapshot })?),
+        _ => None,
+    };
+
+    let entries = keys
+        .into_iter()
+        .map(|(inverted, achieved_at, user)| {
+            Ok(LeaderboardEntry { user: addr_info(deps, user)?, score: shown_score(&config, u32::MAX - inverted), achieved_at })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(LeaderboardResponse { entries, next_cursor })
+}
+
+/// A leaderboard page plus the board size and the viewer's own standing, in one round trip.
+pub fn query_leaderboard_page(
+    deps: Deps,
+    env: Env,
+    cursor: Option<Binary>,
+    limit: Option<u32>,
+    viewer: Option<String>,
+    scope: Scope,
+) -> StdResult<LeaderboardPageResponse> {
+    let now = env.block.time;
+    let page = query_leaderboard(deps, env, cursor, limit, scope)?;
+    let config = CONFIG.load(deps.storage)?;
+    let totals = TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let total = match scope {
+        Scope::Season => totals.users,
+        Scope::AllTime => totals.all_time_users,
+    };
+
+    // Ranks count hidden users too; a hidden viewer's own standing isn't shown, nor one
+    // the board leaves out
+    let viewer = match viewer {
+        Some(user) if is_public(deps.storage, &config, &user)? => match viewer_key(deps.storage, &user, scope)? {
+            Some(key) if scope == Scope::Season && (key.1 < season_floor(deps.storage)? || is_expired(deps.storage, &user, now)?) => None,
+            Some(key) => Some(ViewerRank {
+                score: shown_score(&config, u32::MAX - key.0),
+                rank: rank(deps.storage, key, scope)?,
+                user: addr_info(deps, user)?,
+            }),
+            None => None,
+        },
+        _ => None,
+    };
+
+    Ok(LeaderboardPageResponse { entries: page.entries, next_cursor: page.next_cursor, total, viewer })
+}
+
+/// The user's standing on each requested board, scored as GetScore does. `exact` is
+/// whether the querier may see unredacted scores. Ranks count expired entries until
+/// PurgeExpired removes them, as RankSlice does, and are left out while a season is
+/// being archived.
+pub fn query_cross_rank(deps: Deps, now: Timestamp, user: String, boards: Vec<String>, exact: bool) -> StdResult<CrossRankResponse> {
+    if boards.len() > MAX_CROSS_RANK_BOARDS as usize {
+        return Err(StdError::generic_err(format!("At most {} boards per query", MAX_CROSS_RANK_BOARDS)));
+    }
+    for board in &boards {
+        validate_board(board).map_err(|err| StdError::generic_err(err.to_string()))?;
+    }
+    // Every board is the default board until boards are split, so one lookup covers them all
+    let config = CONFIG.load(deps.storage)?;
+    let archiving = ARCHIVING.may_load(deps.storage)?;
+    let floor = archiving.as_ref().map_or(0, |archiving| archiving.end_height);
+    let (score, rank) = match current_key(deps.storage, &user)? {
+        Some(key) if key.1 < floor || is_expired(deps.storage, &user, now)? => (0, None),
+        Some(key) if archiving.is_some() => (u32::MAX - key.0, None),
+        Some(key) => (u32::MAX - key.0, Some(rank_of(deps.storage, &key)?)),
+        None => (0, None),
+    };
+    let score = config.score_formula.apply(score);
+    let score = if exact { score } else { redact(&config, score) };
+    let boards = boards.into_iter().map(|board| BoardRank { board, score, rank }).collect();
+    Ok(CrossRankResponse { user, boards })
+}
+
+fn viewer_key(storage: &dyn Storage, user: &str, scope: Scope) -> StdResult<Option<IndexKey>> {
+    match scope {
+        Scope::Season => current_key(storage, user),
+        Scope::AllTime => all_time_key(storage, user),
+    }
+}
+
+/// 1-based position of `key` on the board, or None when it lies beyond MAX_RANK_SCAN.
+fn rank(storage: &dyn Storage, key: IndexKey, scope: Scope) -> StdResult<Option<u32>> {
+    let ahead = board(scope)
+        .keys(storage, None, Some(Bound::exclusive(key)), Order::Ascending)
+        .take(MAX_RANK_SCAN as usize)
+        .count() as u32;
+    Ok(if ahead < MAX_RANK_SCAN { Some(ahead + 1) } else { None })
+}
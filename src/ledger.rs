@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Coin, Deps, Env, Order, StdResult, Storage, Uint128};
+
+use crate::error::ContractError;
+use crate::limits::ScanBudget;
+use crate::msg::{DenomReconciliation, LedgerEntry, ReconcileResponse};
+use crate::state::{CONFIG, LEDGER};
+
+// Every coin the contract holds on someone's behalf is attributed to the purpose it was
+// paid in for, and every payout comes out of one purpose's share. Update fees never show
+// up here: they are forwarded in the transaction that pays them.
+pub const REWARD_POOL_FUNDS: &str = "reward_pool";
+pub const FAUCET_FUNDS: &str = "faucet";
+pub const REBATE_FUNDS: &str = "rebate_pool";
+
+/// The prize of one raffle, held until it is claimed or returned.
+pub fn raffle_funds(raffle_id: u64) -> String {
+    format!("raffle-{}", raffle_id)
+}
+
+/// What a merkle stage was funded with, less what has been claimed or clawed back.
+pub fn stage_funds(stage: u32) -> String {
+    format!("merkle-stage-{}", stage)
+}
+
+pub fn credit(storage: &mut dyn Storage, purpose: &str, coin: &Coin) -> StdResult<()> {
+    let key = (coin.denom.clone(), purpose.to_string());
+    let held = LEDGER.may_load(storage, key.clone())?.unwrap_or_default();
+    LEDGER.save(storage, key, &(held + coin.amount))
+}
+
+/// Takes `coin` out of `purpose`'s share, failing rather than paying out funds held for
+/// anything else. Entries are dropped once empty.
+pub fn debit(storage: &mut dyn Storage, purpose: &str, coin: &Coin) -> Result<(), ContractError> {
+    let key = (coin.denom.clone(), purpose.to_string());
+    let held = LEDGER.may_load(storage, key.clone())?.unwrap_or_default();
+    if held < coin.amount {
+        return Err(ContractError::LedgerShortfall {
+            purpose: purpose.to_string(),
+            denom: coin.denom.clone(),
+            held,
+            requested: coin.amount,
+        });
+    }
+    let left = held - coin.amount;
+    if left.is_zero() {
+        LEDGER.remove(storage, key);
+    } else {
+        LEDGER.save(storage, key, &left)?;
+    }
+    Ok(())
+}
+
+/// The ledger next to the contract's bank balances. A surplus is unattributed, e.g. sent
+/// without a message that accounts for it; a shortfall means purposes are owed more than
+/// the contract holds.
+pub fn query_reconcile(deps: Deps, env: Env) -> StdResult<ReconcileResponse> {
+    let mut totals: BTreeMap<String, (Uint128, Uint128)> = BTreeMap::new();
+    let mut entries = vec![];
+    let mut budget = ScanBudget::new(&CONFIG.load(deps.storage)?);
+    for item in LEDGER.range(deps.storage, None, None, Order::Ascending) {
+        budget.spend()?;
+        let ((denom, purpose), amount) = item?;
+        totals.entry(denom.clone()).or_default().0 += amount;
+        entries.push(LedgerEntry { denom, purpose, amount });
+    }
+    for coin in deps.querier.query_all_balances(&env.contract.address)? {
+        totals.entry(coin.denom).or_default().1 += coin.amount;
+    }
+
+    let denoms = totals
+        .into_iter()
+        .map(|(denom, (attributed, balance))| DenomReconciliation {
+            denom,
+            attributed,
+            balance,
+            surplus: balance.saturating_sub(attributed),
+            shortfall: attributed.saturating_sub(balance),
+        })
+        .collect();
+    Ok(ReconcileResponse { entries, denoms })
+}
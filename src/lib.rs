@@ -1,8 +1,67 @@
+pub mod addr;
+pub mod aggregate;
+pub mod all_time;
+pub mod anomaly;
+pub mod appeals;
+pub mod attestations;
+pub mod boards;
+pub mod brackets;
+pub mod certify;
 pub mod contract;
+pub mod controllers;
+pub mod custom;
+pub mod deltas;
+pub mod events;
 mod error;
+pub mod faucet;
+pub mod federation;
+pub mod gatekeeper;
 pub mod helpers;
+pub mod idempotency;
+pub mod invariants;
+pub mod journal;
 // pub mod integration_tests;
+// the benches run on a plain cw-multi-test App, which only routes Empty chain messages
+#[cfg(all(test, not(feature = "terra")))]
+mod bench_tests;
+#[cfg(test)]
+mod fuzz_tests;
+// debug-metrics appends storage counters to responses, so the golden files pin the default build
+#[cfg(all(test, not(feature = "debug-metrics")))]
+mod golden_tests;
+pub mod leaderboard;
+pub mod ledger;
+pub mod limits;
+pub mod maintenance;
+pub mod merkle;
+#[cfg(feature = "debug-metrics")]
+pub mod metrics;
+pub mod mirror;
 pub mod msg;
+pub mod notifier;
+pub mod operators;
+pub mod podium;
+pub mod policy;
+pub mod profiles;
+pub mod raffle;
+pub mod ranks;
+pub mod rebate;
+pub mod recovery;
+pub mod rescue;
+pub mod retention;
+pub mod rewards;
+pub mod season;
+pub mod shadow;
+pub mod shop;
+pub mod signing;
+pub mod simulate;
+pub mod splitter;
 pub mod state;
+pub mod timeline;
+pub mod twas;
+pub mod validation;
+pub mod vouchers;
+pub mod wagers;
+pub mod weights;
 
 pub use crate::error::ContractError;
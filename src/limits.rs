@@ -0,0 +1,36 @@
+use cosmwasm_std::{StdError, StdResult};
+
+use crate::state::Config;
+
+/// Page size for a list query: `limit`, or `default` without one. A limit above
+/// Config::max_query_limit fails instead of being cut down, so callers learn the cap.
+pub fn page_limit(config: &Config, limit: Option<u32>, default: u32) -> StdResult<usize> {
+    let max = config.max_query_limit;
+    match limit {
+        Some(limit) if limit > max => Err(StdError::generic_err(format!("Limit {} is above the maximum of {}", limit, max))),
+        Some(limit) => Ok(limit as usize),
+        None => Ok(default.min(max) as usize),
+    }
+}
+
+/// Entries a query may read while filtering, e.g. walking past hidden users to fill a
+/// page. Running out fails the query, however few entries it has found.
+pub struct ScanBudget {
+    max: u32,
+    scanned: u32,
+}
+
+impl ScanBudget {
+    pub fn new(config: &Config) -> Self {
+        ScanBudget { max: config.max_query_scan, scanned: 0 }
+    }
+
+    /// Counts one more entry read.
+    pub fn spend(&mut self) -> StdResult<()> {
+        if self.scanned == self.max {
+            return Err(StdError::generic_err(format!("Query would read more than {} entries", self.max)));
+        }
+        self.scanned += 1;
+        Ok(())
+    }
+}
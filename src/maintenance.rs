@@ -0,0 +1,83 @@
+use cosmwasm_std::{Deps, Env, StdResult, Storage, Timestamp};
+
+use crate::error::ContractError;
+use crate::msg::{FreezeResponse, MaintenanceResponse};
+use crate::state::{Freeze, MaintenanceWindow, FREEZE, MAINTENANCE_WINDOWS, PAUSED, SUCCESSOR};
+
+// upper bound on windows scheduled at once
+pub const MAX_MAINTENANCE_WINDOWS: u32 = 10;
+
+/// Whether mutations are blocked: paused by the owner, or inside a scheduled window.
+/// Windows start and end on their own, so no transaction is needed to resume.
+pub fn is_paused(storage: &dyn Storage, env: &Env) -> StdResult<bool> {
+    if PAUSED.may_load(storage)?.unwrap_or_default() {
+        return Ok(true);
+    }
+    let now = env.block.time;
+    let windows = MAINTENANCE_WINDOWS.may_load(storage)?.unwrap_or_default();
+    Ok(windows.iter().any(|window| window.start <= now && now < window.end))
+}
+
+/// Fails once writes have moved to a successor, for every message but the one lifting
+/// it, so a stale integration is told where to go instead of writing here unnoticed.
+pub fn assert_not_forwarding(storage: &dyn Storage, msg_name: &str) -> Result<(), ContractError> {
+    if msg_name == "read_only_forwarding" {
+        return Ok(());
+    }
+    match SUCCESSOR.may_load(storage)? {
+        Some(successor) => Err(ContractError::ReadOnly { successor: successor.to_string() }),
+        None => Ok(()),
+    }
+}
+
+/// Adds a window, dropping those already over. Windows may not overlap.
+pub fn schedule_window(storage: &mut dyn Storage, env: &Env, start: Timestamp, end: Timestamp) -> Result<(), ContractError> {
+    if end <= start || end <= env.block.time {
+        return Err(ContractError::InvalidMaintenanceWindow {});
+    }
+    let mut windows = MAINTENANCE_WINDOWS.may_load(storage)?.unwrap_or_default();
+    windows.retain(|window| window.end > env.block.time);
+    if windows.iter().any(|window| window.start < end && start < window.end) {
+        return Err(ContractError::InvalidMaintenanceWindow {});
+    }
+    if windows.len() >= MAX_MAINTENANCE_WINDOWS as usize {
+        return Err(ContractError::TooManyMaintenanceWindows { max: MAX_MAINTENANCE_WINDOWS });
+    }
+    let pos = windows.partition_point(|window| window.start < start);
+    windows.insert(pos, MaintenanceWindow { start, end });
+    MAINTENANCE_WINDOWS.save(storage, &windows)?;
+    Ok(())
+}
+
+/// Removes the window starting at `start`; cancelling an active window ends it now.
+pub fn cancel_window(storage: &mut dyn Storage, start: Timestamp) -> Result<(), ContractError> {
+    let mut windows = MAINTENANCE_WINDOWS.may_load(storage)?.unwrap_or_default();
+    let pos = windows
+        .iter()
+        .position(|window| window.start == start)
+        .ok_or(ContractError::MaintenanceNotFound {})?;
+    windows.remove(pos);
+    MAINTENANCE_WINDOWS.save(storage, &windows)?;
+    Ok(())
+}
+
+/// The window under way or coming up next, for frontends to announce.
+pub fn query_next_maintenance(deps: Deps, env: Env) -> StdResult<MaintenanceResponse> {
+    let now = env.block.time;
+    let windows = MAINTENANCE_WINDOWS.may_load(deps.storage)?.unwrap_or_default();
+    let window = windows.into_iter().find(|window| window.end > now);
+    let active = window.as_ref().map_or(false, |window| window.start <= now);
+    Ok(MaintenanceResponse { window, active })
+}
+
+/// The freeze in effect, if any. A freeze past its deadline no longer counts, so it
+/// lifts without a transaction.
+pub fn active_freeze(storage: &dyn Storage, env: &Env) -> StdResult<Option<Freeze>> {
+    let freeze = FREEZE.may_load(storage)?;
+    Ok(freeze.filter(|freeze| freeze.until.map_or(true, |until| env.block.time < until)))
+}
+
+pub fn query_freeze_status(deps: Deps, env: Env) -> StdResult<FreezeResponse> {
+    let freeze = active_freeze(deps.storage, &env)?;
+    Ok(FreezeResponse { frozen: freeze.is_some(), until: freeze.and_then(|freeze| freeze.until) })
+}
@@ -0,0 +1,66 @@
+use std::convert::TryInto;
+
+use cosmwasm_std::{Deps, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::Bound;
+use sha2::{Digest, Sha256};
+
+use crate::limits::page_limit;
+use crate::msg::{IsClaimedResponse, MerkleStagesResponse};
+use crate::state::{CONFIG, MERKLE_CLAIM_BITS, MERKLE_STAGES};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+// Claim flags are packed this many to a storage entry
+const BITS_PER_WORD: u64 = 64;
+
+/// Leaf committed to by a stage's tree: sha256 of the leaf's index, the claimer's address
+/// and the decimal amount, separated by colons, e.g. `sha256("7:terra1...:1000")`.
+pub fn leaf_hash(index: u64, user: &str, amount: Uint128) -> [u8; 32] {
+    Sha256::digest(format!("{}:{}:{}", index, user, amount).as_bytes()).into()
+}
+
+pub fn is_claimed(storage: &dyn Storage, stage: u32, index: u64) -> StdResult<bool> {
+    let word = MERKLE_CLAIM_BITS.may_load(storage, (stage, index / BITS_PER_WORD))?.unwrap_or_default();
+    Ok(word & (1 << (index % BITS_PER_WORD)) != 0)
+}
+
+pub fn set_claimed(storage: &mut dyn Storage, stage: u32, index: u64) -> StdResult<()> {
+    let key = (stage, index / BITS_PER_WORD);
+    let word = MERKLE_CLAIM_BITS.may_load(storage, key)?.unwrap_or_default();
+    MERKLE_CLAIM_BITS.save(storage, key, &(word | 1 << (index % BITS_PER_WORD)))
+}
+
+pub fn decode_hash(hex_hash: &str) -> StdResult<[u8; 32]> {
+    hex::decode(hex_hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| StdError::generic_err(format!("Not a hex-encoded sha256 hash: {}", hex_hash)))
+}
+
+/// Walks `proof` up from `leaf`, hashing each pair in sorted order, and compares the
+/// result against `root`.
+pub fn verify_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[String]) -> StdResult<bool> {
+    let mut hash = leaf;
+    for sibling in proof {
+        let sibling = decode_hash(sibling)?;
+        let (first, second) = if hash <= sibling { (hash, sibling) } else { (sibling, hash) };
+        hash = Sha256::new().chain(first).chain(second).finalize().into();
+    }
+    Ok(&hash == root)
+}
+
+pub fn query_merkle_stages(deps: Deps, start_after: Option<u32>, limit: Option<u32>) -> StdResult<MerkleStagesResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let stages = MERKLE_STAGES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, stage)| stage))
+        .collect::<StdResult<_>>()?;
+    Ok(MerkleStagesResponse { stages })
+}
+
+pub fn query_is_claimed(deps: Deps, stage: u32, index: u64) -> StdResult<IsClaimedResponse> {
+    MERKLE_STAGES.load(deps.storage, stage)?;
+    Ok(IsClaimedResponse { claimed: is_claimed(deps.storage, stage, index)? })
+}
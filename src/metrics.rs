@@ -0,0 +1,105 @@
+// Storage-op counts appended to every execute response, for profiling hot paths on a
+// testnet. Only compiled in with the `debug-metrics` feature.
+use std::cell::Cell;
+
+use cosmwasm_std::{DepsMut, Order, Record, Storage};
+
+use crate::custom::Response;
+use crate::error::ContractError;
+
+// Maps counted as index operations
+const INDEX_NAMESPACES: &[&[u8]] = &[b"score_index", b"season_index"];
+
+pub struct MeteredStorage<'a> {
+    inner: &'a mut dyn Storage,
+    reads: Cell<u64>,
+    writes: u64,
+    index_ops: Cell<u64>,
+}
+
+impl<'a> MeteredStorage<'a> {
+    pub fn new(inner: &'a mut dyn Storage) -> Self {
+        MeteredStorage { inner, reads: Cell::new(0), writes: 0, index_ops: Cell::new(0) }
+    }
+
+    fn touch(&self, key: &[u8]) {
+        if is_index_key(key) {
+            self.index_ops.set(self.index_ops.get() + 1);
+        }
+    }
+}
+
+// Map keys start with the namespace, prefixed by its length as two bytes
+fn is_index_key(key: &[u8]) -> bool {
+    INDEX_NAMESPACES.iter().any(|namespace| {
+        key.len() > namespace.len() + 2
+            && key[..2] == (namespace.len() as u16).to_be_bytes()
+            && key[2..].starts_with(namespace)
+    })
+}
+
+impl<'a> Storage for MeteredStorage<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.reads.set(self.reads.get() + 1);
+        self.touch(key);
+        self.inner.get(key)
+    }
+
+    fn range<'b>(&'b self, start: Option<&[u8]>, end: Option<&[u8]>, order: Order) -> Box<dyn Iterator<Item = Record> + 'b> {
+        // Every item pulled from the iterator costs a read
+        Box::new(self.inner.range(start, end, order).inspect(move |(key, _)| {
+            self.reads.set(self.reads.get() + 1);
+            self.touch(key);
+        }))
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.writes += 1;
+        self.touch(key);
+        self.inner.set(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.writes += 1;
+        self.touch(key);
+        self.inner.remove(key)
+    }
+}
+
+/// Runs `handler` against metered storage and reports what it did as attributes.
+pub fn metered<F>(deps: DepsMut, handler: F) -> Result<Response, ContractError>
+where
+    F: FnOnce(DepsMut) -> Result<Response, ContractError>,
+{
+    let mut storage = MeteredStorage::new(deps.storage);
+    let res = handler(DepsMut { storage: &mut storage, api: deps.api, querier: deps.querier })?;
+    Ok(res
+        .add_attribute("debug_storage_reads", storage.reads.get().to_string())
+        .add_attribute("debug_storage_writes", storage.writes.to_string())
+        .add_attribute("debug_index_ops", storage.index_ops.get().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::Addr;
+
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+
+    #[test]
+    // A new score adds exactly one index entry, and the counts show up on the response
+    fn reports_storage_ops() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg::default();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateScore { user: Addr::unchecked("player"), score: 10, expires_at: None, idempotency_key: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let value = |key: &str| res.attributes.iter().find(|attr| attr.key == key).unwrap().value.parse::<u64>().unwrap();
+        assert!(value("debug_storage_reads") > 0);
+        assert!(value("debug_storage_writes") > 0);
+        // The lookup of the old entry finds nothing, then the new key is written
+        assert_eq!(1, value("debug_index_ops"));
+    }
+}
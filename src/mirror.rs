@@ -0,0 +1,159 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    to_binary, Addr, Reply, StdError, StdResult, Storage, SubMsgResult, Uint128, WasmMsg,
+};
+
+use crate::custom::SubMsg;
+use crate::msg::MirrorTokenInit;
+use crate::state::{MIRROR, MIRROR_SHORTFALLS, PENDING_BURNS};
+
+pub const INSTANTIATE_MIRROR_REPLY_ID: u64 = 1;
+pub const MIRROR_BURN_REPLY_ID: u64 = 2;
+
+// Minimal subset of the cw20-base message types. They serialize identically to the
+// upstream definitions, which is all we need to instantiate and drive the token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub initial_balances: Vec<Cw20Coin>,
+    pub mint: Option<MinterResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20Coin {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterResponse {
+    pub minter: String,
+    pub cap: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20ExecuteMsg {
+    Mint { recipient: String, amount: Uint128 },
+    BurnFrom { owner: String, amount: Uint128 },
+    Transfer { recipient: String, amount: Uint128 },
+    Burn { amount: Uint128 },
+}
+
+/// Builds the submessage instantiating the mirror token with this contract as minter.
+pub fn instantiate_mirror_msg(contract: &Addr, init: MirrorTokenInit) -> StdResult<SubMsg> {
+    let msg = Cw20InstantiateMsg {
+        name: init.name,
+        symbol: init.symbol,
+        decimals: init.decimals,
+        initial_balances: vec![],
+        mint: Some(MinterResponse {
+            minter: contract.to_string(),
+            cap: None,
+        }),
+    };
+    let instantiate = WasmMsg::Instantiate {
+        admin: Some(contract.to_string()),
+        code_id: init.code_id,
+        msg: to_binary(&msg)?,
+        funds: vec![],
+        label: init.label,
+    };
+    Ok(SubMsg::reply_on_success(instantiate, INSTANTIATE_MIRROR_REPLY_ID))
+}
+
+/// Pulls the new token address out of the instantiate reply events.
+pub fn parse_mirror_address(msg: Reply) -> StdResult<String> {
+    let res = msg.result.into_result().map_err(StdError::generic_err)?;
+    res.events
+        .iter()
+        .filter(|event| event.ty == "instantiate")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "_contract_address")
+        .map(|attr| attr.value.clone())
+        .ok_or_else(|| StdError::generic_err("mirror token address not found in reply"))
+}
+
+/// Returns the messages needed to move a user's mirrored balance from `old` to `new`.
+///
+/// Increments are minted directly. Decrements use `BurnFrom`, which needs an allowance
+/// from the holder, so a revoked allowance can't block the score change: the burn replies
+/// either way, and a failed one is recorded as the holder's shortfall. The shortfall is
+/// burned along with their next decrement, or held back from their next mint.
+pub fn mirror_msgs(storage: &mut dyn Storage, user: &str, old: u32, new: u32) -> StdResult<Vec<SubMsg>> {
+    let mirror = match MIRROR.may_load(storage)? {
+        Some(mirror) if mirror.enabled => mirror,
+        _ => return Ok(vec![]),
+    };
+    let token = match mirror.token {
+        Some(token) => token,
+        None => return Ok(vec![]),
+    };
+
+    if new == old {
+        return Ok(vec![]);
+    }
+    let shortfall = MIRROR_SHORTFALLS.may_load(storage, user.to_string())?.unwrap_or_default();
+    let (msg, shortfall) = if new > old {
+        let increase = Uint128::from(new - old);
+        let settled = increase.min(shortfall);
+        let msg = Cw20ExecuteMsg::Mint {
+            recipient: user.to_string(),
+            amount: increase - settled,
+        };
+        (msg, shortfall - settled)
+    } else {
+        let amount = Uint128::from(old - new) + shortfall;
+        let mut pending = PENDING_BURNS.may_load(storage)?.unwrap_or_default();
+        pending.push((user.to_string(), amount));
+        PENDING_BURNS.save(storage, &pending)?;
+        let msg = Cw20ExecuteMsg::BurnFrom {
+            owner: user.to_string(),
+            amount,
+        };
+        (msg, Uint128::zero())
+    };
+    if shortfall.is_zero() {
+        MIRROR_SHORTFALLS.remove(storage, user.to_string());
+    } else {
+        MIRROR_SHORTFALLS.save(storage, user.to_string(), &shortfall)?;
+    }
+
+    let exec = WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    };
+    Ok(match msg {
+        // The whole increase went to settling the shortfall
+        Cw20ExecuteMsg::Mint { amount, .. } if amount.is_zero() => vec![],
+        Cw20ExecuteMsg::BurnFrom { .. } => vec![SubMsg::reply_always(exec, MIRROR_BURN_REPLY_ID)],
+        _ => vec![SubMsg::new(exec)],
+    })
+}
+
+/// Settles the oldest pending burn against its reply, recording it as a shortfall if it
+/// failed. Returns the holder and what they now owe.
+pub fn settle_burn(storage: &mut dyn Storage, result: &SubMsgResult) -> StdResult<(String, Uint128)> {
+    let mut pending = PENDING_BURNS.may_load(storage)?.unwrap_or_default();
+    if pending.is_empty() {
+        return Err(StdError::generic_err("no pending mirror burn"));
+    }
+    let (user, amount) = pending.remove(0);
+    if pending.is_empty() {
+        PENDING_BURNS.remove(storage);
+    } else {
+        PENDING_BURNS.save(storage, &pending)?;
+    }
+    let shortfall = MIRROR_SHORTFALLS.may_load(storage, user.clone())?.unwrap_or_default();
+    if result.is_ok() {
+        return Ok((user, shortfall));
+    }
+    let shortfall = shortfall + amount;
+    MIRROR_SHORTFALLS.save(storage, user.clone(), &shortfall)?;
+    Ok((user, shortfall))
+}
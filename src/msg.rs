@@ -1,14 +1,399 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr};
+use cosmwasm_std::{Addr, Attribute, Binary, Coin, Decimal, Timestamp, Uint128};
+
+use crate::state::{Bracket, Certification, CombineMode, Config, DuplicateKeyPolicy, EpochCap, EpochSummary, FeeRoute, MaintenanceWindow, MergePolicy, MerkleStage, OwnerChange, Penalty, Profile, ProvisionalUpdate, Raffle, Retention, Role, ScoreDelta, ScoreFormula, Season, SmallDeltaPolicy, SubmissionKey, TimelineEvent, Wager, WeightTable};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct InstantiateMsg {
+    // When set, a CW20 mirroring every user's score is instantiated alongside the contract
+    pub mirror: Option<MirrorTokenInit>,
+    // Prepended to emitted event types and attribute keys, e.g. "arcade1"
+    pub event_prefix: Option<String>,
+    // Fractional digits of every score, for fractional scoring models; zero by default.
+    // Integer scores in messages and responses are then in units of 10^-score_decimals.
+    pub score_decimals: Option<u32>,
+    // May write scores besides the owner, as if added with UpdateOperators
+    pub operators: Option<Vec<String>>,
+    // Further settings, applied before the contract takes its first message
+    pub config: Option<ConfigInit>,
+    // Preset turning subsystems off for simpler products; everything runs by default.
+    // Settings in `config` for a subsystem the profile turns off are rejected.
+    pub profile: Option<Profile>,
+}
+
+// Settings applied at instantiation, with the same checks as the messages that change
+// them later, so a production setup needs no follow-up transactions
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ConfigInit {
+    // As UpdateConfig
+    pub settings: Option<ConfigUpdate>,
+    // As SetUserGainCap and SetGlobalGainCap
+    pub user_gain_cap: Option<EpochCap>,
+    pub global_gain_cap: Option<EpochCap>,
+    // As SetUpdateFees
+    pub update_fee: Option<Coin>,
+    pub cw20_update_fee: Option<Cw20FeeInit>,
+    // As AddHook, in order
+    pub hooks: Option<Vec<String>>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct MirrorTokenInit {
+    // Code id of a cw20-base compatible token
+    pub code_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub label: String,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    UpdateScore { user: Addr, score: u32 }
+    // Set a user's score, optionally only valid until `expires_at`. A retry carrying the
+    // same `idempotency_key` within Config::idempotency_ttl blocks is a duplicate.
+    UpdateScore { user: Addr, score: u32, expires_at: Option<Timestamp>, idempotency_key: Option<String> },
+    // UpdateScore with a fractional score, which must fit Config::score_decimals exactly
+    UpdateScoreDecimal { user: Addr, score: Decimal, expires_at: Option<Timestamp>, idempotency_key: Option<String> },
+    // Anyone may relay a score signed by an active submission key, see SignedScore
+    SubmitSignedScore { user: String, score: u32, nonce: u64, signature: Binary },
+    // Owner-only: new_pubkey validates at once, the keys it replaces until effective_at
+    RotateSubmissionKey { new_pubkey: Binary, effective_at: Timestamp },
+    // Anyone may redeem a voucher signed with the voucher key, see SignedVoucher; its
+    // points go to voucher.user
+    RedeemVoucher { voucher: Voucher, signature: Binary },
+    // Owner-only: key vouchers must be signed with; None stops redemptions
+    SetVoucherKey { pubkey: Option<Binary> },
+    // Pause or resume minting/burning on the mirror token without forgetting it
+    SetMirrorEnabled { enabled: bool },
+    // Permanently stop mirroring; the token keeps existing balances
+    DecoupleMirror {},
+    // Owner-only config changes; unset fields are left untouched
+    UpdateConfig(ConfigUpdate),
+    // Hand the admin role to another address
+    TransferOwnership { new_owner: String },
+    // Block or allow score writes, e.g. during maintenance
+    SetPaused { paused: bool },
+    // Owner-only: after cutting over to `successor`, refuse every other message and point
+    // integrations at it, while queries keep answering; None lifts it
+    ReadOnlyForwarding { successor: Option<String> },
+    // Owner-only: pause automatically from `start` until `end`, without further transactions
+    ScheduleMaintenance { start: Timestamp, end: Timestamp },
+    // Owner-only: drop the window starting at `start`; an active window ends right away
+    CancelMaintenance { start: Timestamp },
+    // Owner-only: block mutations but keep queries live, e.g. while prizes are verified.
+    // Lifts by itself at `until`; without one it holds until UnfreezeLeaderboard
+    FreezeLeaderboard { until: Option<Timestamp> },
+    // Owner-only: lift a freeze early
+    UnfreezeLeaderboard {},
+    // Multiply every score by numerator / denominator, one page at a time while paused
+    RescaleScores {
+        numerator: u32,
+        denominator: u32,
+        rounding: Option<Rounding>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Season-end wipe. Call without a confirmation to receive a token, then echo it
+    // within the confirmation window; repeat with the token until the reset reports done.
+    ResetAllScores { confirmation: Option<String>, limit: Option<u32> },
+    // Delete stored zero scores, resuming where the previous call stopped
+    CompactZeroScores { limit: Option<u32> },
+    // Delete scores past their expiry; anyone may call this
+    PurgeExpired { limit: Option<u32> },
+    // Cap per-user gains per epoch; None removes the cap
+    SetUserGainCap { cap: Option<EpochCap> },
+    // Cap on the combined rise of all scores per epoch; None removes it
+    SetGlobalGainCap { cap: Option<EpochCap> },
+    // Contract told about every score change; None stops notifications
+    SetNotifier { addr: Option<String> },
+    // Configure the contracts combined by QueryMsg::AggregatedScore; empty sources disables it
+    SetAggregation { sources: Vec<SourceInit>, mode: CombineMode },
+    // Owner-only: peer deployments counted by QueryMsg::FederatedScore. Removes the
+    // chain ids in `remove`, then adds or replaces `add`.
+    UpdatePeers { add: Vec<PeerInit>, remove: Vec<String> },
+    // Registered peer address only: cache the peer's current scores for these users
+    SyncFromPeer { chain_id: String, updates: Vec<ScoreUpdate> },
+    // Set the sender's viewing key for reading scores in private mode
+    SetViewingKey { key: String },
+    // Lock in a score batch before publishing it; see contract::commitment_hash
+    CommitScores { hash: Binary },
+    // Apply the committed batch before the reveal window closes
+    RevealScores { updates: Vec<ScoreUpdate>, salt: Binary },
+    // End the current season and archive its scores, one batch per call until done.
+    // Scores written meanwhile already count for the new season.
+    StartNewSeason { limit: Option<u32> },
+    // Owner-only: delete the user's entry, restorable for Config::removal_grace_period
+    RemoveScore { user: String },
+    // Owner-only: bring back an entry deleted by RemoveScore within the grace period
+    RestoreScore { user: String },
+    // Undo the user's latest score change, whatever made it, within the rollback window
+    Rollback { user: String },
+    // Rollback for several users at once; fails as a whole if any can't be rolled back
+    RollbackBatch { users: Vec<String> },
+    // Contract sent a copy of every other execute message; None stops the copies
+    SetShadow { addr: Option<String> },
+    // Show or hide the sender on leaderboards; hidden scores need a viewing key to read
+    SetProfileVisibility { public: bool },
+    // Show the sender's address under another chain's bech32 prefix too, e.g. "osmo" for
+    // an account that came over IBC; None shows the local form only
+    SetAddrOrigin { prefix: Option<String> },
+    // Contract that must approve each updated user; None accepts everyone
+    SetGatekeeper { addr: Option<String> },
+    // Add the attached coin to the reward pool; anyone may fund it
+    FundPool {},
+    // Owner-only: share the funds added since the last checkpoint by current scores
+    Checkpoint {},
+    // Withdraw the sender's share of every checkpoint and of the stream so far
+    ClaimRewards {},
+    // Owner-only: pay the pool out continuously by score at this rate; zero stops streaming
+    SetRewardStream { per_second: Uint128 },
+    // Owner-only: add the attached coin to the faucet
+    FundFaucet {},
+    // Owner-only: pay `amount_per_claim` to users with at least `min_score`, once every
+    // `cooldown` seconds each
+    SetFaucet { amount_per_claim: Uint128, min_score: u32, cooldown: u64 },
+    // Withdraw one faucet payout for the sender
+    ClaimFaucet {},
+    // Owner-only: add the attached coin to the rebate pool
+    FundRebatePool {},
+    // Owner-only: pay `amount` back to each user on their first claim or profile setup,
+    // once per user while the pool lasts; zero stops rebates
+    SetRebate { amount: Uint128 },
+    // Owner-only: raffle the attached coin among the top scores, drawn at reveal_height
+    StartRaffle { reveal_height: u64 },
+    // Draw the winner once reveal_height is reached; anyone may send it
+    ResolveRaffle { raffle_id: u64 },
+    // Winner-only: withdraw the prize
+    ClaimRaffle { raffle_id: u64 },
+    // Spend `amount` of the sender's own points, e.g. on an in-game purchase
+    SpendScore { amount: u32, memo: String },
+    // Spend `amount` of the sender's points and forward `msg` to `contract` as a
+    // ReceiveSpend, in the same transaction; the contract rejecting it reverts the spend
+    SpendAndCall { amount: u32, contract: String, msg: Binary },
+    // Lock `stake` of the sender's points in a wager against `opponent`, who has until
+    // `expires_at` to accept; the stakes go back if it's still open or unreported then
+    OpenWager { opponent: String, stake: u32, expires_at: Timestamp },
+    // Opponent-only: lock the same stake, before the wager expires
+    AcceptWager { wager_id: u64 },
+    // Operator-only by default: give both stakes of an accepted wager to `winner`
+    ReportWager { wager_id: u64, winner: String },
+    // The proposer may take back an open wager; anyone may refund one that expired
+    RefundWager { wager_id: u64 },
+    // Owner-only: contract that must accept every spend; None stops the calls
+    SetShop { addr: Option<String> },
+    // Mint the sender a certificate NFT of their score, season and rank, once a season
+    CertifyScore {},
+    // Owner-only: cw721 contract minting score certificates; None stops certifying
+    SetCertifier { addr: Option<String> },
+    // Operator-only by default: publish the top `top_n` scores as the next version of the
+    // weight table, for partners reading reputation as normalized weights
+    PublishWeightTable { top_n: u32 },
+    // Owner-only: deduct points, escrowed for Config::appeal_window in case of an appeal.
+    // Fails for users without a score; does nothing at or below Config::score_floor
+    Penalize { user: String, points: u32, reason: String, idempotency_key: Option<String> },
+    // Contest the sender's latest penalty before its appeal window closes
+    Appeal { reason: String },
+    // Arbiter-only (owner-only while none is set): decide an appeal; overturning returns
+    // the escrowed points
+    ResolveAppeal { user: String, uphold: bool },
+    // Address deciding appeals in place of the owner; None hands the role back
+    SetArbiter { addr: Option<String> },
+    // Move scores stored under differently-cased addresses to the normalized form, one
+    // page at a time while paused
+    NormalizeAddresses { start_after: Option<String>, limit: Option<u32> },
+    // Owner-only: open a claim stage funded with the attached coin
+    RegisterMerkleStage { merkle_root: String, expires_at: Timestamp },
+    // Claim the sender's allocation, at leaf `index` of the stage's tree, before it expires
+    ClaimMerkle { stage: u32, index: u64, amount: Uint128, proof: Vec<String> },
+    // Owner-only: return what an expired stage has left unclaimed to the owner
+    ClawbackStage { stage: u32 },
+    // Owner-only: return cw20s sent here by mistake; tokens the contract manages itself,
+    // like the mirror token or the update fee token, are refused
+    RecoverCw20 { token: String, amount: Uint128, recipient: String },
+    // Owner-only: return an NFT sent here by mistake
+    RecoverNft { contract: String, token_id: String, recipient: String },
+    // Owner-only: how many leaders QueryMsg::Podium can return, at most 100; zero turns
+    // the maintained set off
+    SetTopK { k: u32 },
+    // Owner-only: refill the podium set from the leaderboard index, `limit` entries a call
+    RebuildTopK { limit: Option<u32> },
+    // Owner-only, while paused: re-derive the leaderboard index from the stored scores.
+    // Pass back `next_start_after` until `done`, then run RebuildTopK.
+    RebuildIndex { start_after: Option<String>, limit: Option<u32> },
+    // Owner-only: fees for UpdateScore, in native funds and/or a cw20 token; None or a zero
+    // amount waives
+    SetUpdateFees { native: Option<Coin>, cw20: Option<Cw20FeeInit> },
+    // Owner-only: settle an update flagged by the anomaly guard and unfreeze the user.
+    // Rejecting restores the score it replaced.
+    ReviewAnomaly { user: String, accept: bool },
+    // Owner-only: notify another contract of score changes, like the notifier
+    AddHook { addr: String },
+    RemoveHook { addr: String },
+    // Owner-only: addresses that may write scores besides the owner
+    UpdateOperators { add: Vec<String>, remove: Vec<String> },
+    // Owner-only: replace every bracket; ranges must not overlap and an empty list clears them
+    SetBrackets { brackets: Vec<Bracket> },
+    // Owner-only: fold the secondary address into the primary under Config::merge_policy.
+    // The secondary's entry is deleted and it can never hold a score again; refused while it
+    // has a penalty open for appeal.
+    MergeIdentities { primary: String, secondary: String },
+    // Anyone: record a signal about a user under a tag, kept apart from the official score.
+    // One value per attester and tag; attesting again replaces it.
+    Attest { user: String, value: i64, tag: String },
+    // Owner-only: number and record every score change for QueryMsg::ChangesSince. Off by
+    // default, as each change then costs an extra write.
+    SetJournal { enabled: bool },
+    // Owner-only: how much of the journal, owner history and deltas ring to keep; None keeps
+    // everything. Each write trims a little, and TrimHistory catches up after a change.
+    SetRetention { retention: Option<Retention> },
+    // Drop history entries the retention policy no longer keeps; anyone may call this
+    TrimHistory { limit: Option<u32> },
+    // Owner-only: the role needed to send `msg_name`, e.g. "purge_expired", in place of its
+    // default. SetPolicy and TransferOwnership always need the owner.
+    SetPolicy { msg_name: String, role: Role },
+    // Owner-only: who may manage `board` besides the owner. Board admins pass the
+    // board_admin policy, which by default covers StartNewSeason.
+    // Removes `remove`, then adds `add`.
+    UpdateBoardAdmins { board: String, add: Vec<String>, remove: Vec<String> },
+    // Owner-only: the config fields that only shape `board`, applied as UpdateConfig. The
+    // one board shares the contract-wide config, so its admins are refused until boards
+    // are split.
+    UpdateBoardConfig { board: String, update: BoardConfigUpdate },
+    // Owner-only: substrings rejected in reasons, memos and tags, matched ignoring case.
+    // Removes `remove`, then adds `add`.
+    UpdateDenylist { add: Vec<String>, remove: Vec<String> },
+    // Owner-only: record the content hash of an off-chain export of an archived season.
+    // `snapshot_id` is the season id; a digest can be recorded only once.
+    FinalizeSnapshotDigest { snapshot_id: u32, digest: String },
+    // cw20 hook: a token transfer paying Config::cw20_update_fee, carrying a ReceiveMsg
+    Receive(Cw20ReceiveMsg),
+}
+
+// What a game server signs for SubmitSignedScore: the secp256k1 signature is over the
+// sha256 of this struct's JSON, fields in this order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedScore {
+    // This contract, so a signature can't be replayed on another
+    pub contract: String,
+    pub user: String,
+    pub score: u32,
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20FeeInit {
+    pub token: String,
+    pub amount: Uint128,
+}
+
+// Same shape as the upstream cw20 Cw20ReceiveMsg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20ReceiveMsg {
+    pub sender: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+}
+
+// Payloads of Cw20ReceiveMsg::msg, executed on behalf of the token sender
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    UpdateScore { user: Addr, score: u32, expires_at: Option<Timestamp>, idempotency_key: Option<String> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScoreUpdate {
+    pub user: Addr,
+    pub score: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SourceInit {
+    pub addr: String,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PeerInit {
+    pub chain_id: String,
+    // Address allowed to send SyncFromPeer for the peer
+    pub address: String,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ConfigUpdate {
+    pub raw_queries: Option<bool>,
+    pub prune_zero_scores: Option<bool>,
+    pub min_delta: Option<u32>,
+    pub small_delta_policy: Option<SmallDeltaPolicy>,
+    pub private_scores: Option<bool>,
+    pub season_duration: Option<u64>,
+    pub rollback_window: Option<u64>,
+    // An empty string removes the prefix
+    pub event_prefix: Option<String>,
+    pub score_floor: Option<u32>,
+    pub appeal_window: Option<u64>,
+    // Replaces the whole list
+    pub reserved_addresses: Option<Vec<String>>,
+    pub score_formula: Option<ScoreFormula>,
+    // Replaces the whole table; shares must add up to at most 10000 basis points
+    pub fee_routes: Option<Vec<FeeRouteInit>>,
+    pub anomaly_multiple: Option<u32>,
+    pub merge_policy: Option<MergePolicy>,
+    // Blocks a used idempotency key is remembered; must be non-zero
+    pub idempotency_ttl: Option<u64>,
+    pub duplicate_key_policy: Option<DuplicateKeyPolicy>,
+    pub removal_grace_period: Option<u64>,
+    pub score_bucket: Option<u32>,
+    // Both must be non-zero
+    pub max_query_limit: Option<u32>,
+    pub max_query_scan: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BoardConfigUpdate {
+    pub min_delta: Option<u32>,
+    pub small_delta_policy: Option<SmallDeltaPolicy>,
+    pub season_duration: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRouteInit {
+    // None burns the share
+    pub addr: Option<String>,
+    pub bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    // Default
+    Down,
+    Up,
+    // Half rounds up
+    Nearest,
+}
+
+// Which board a leaderboard query reads
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    // Scores of the current season
+    Season,
+    // Every archived season's final score plus the current one
+    AllTime,
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Scope::Season
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -16,8 +401,156 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     // Fetch the owner of the contract
     GetOwner {},
-    // Fetch the score of a specific user
-    GetScore { user: String },
+    // Fetch the score of a specific user, after Config::score_formula
+    GetScore { user: String, auth: Option<ViewerAuth> },
+    // GetScore as a fractional value, for instances with Config::score_decimals set
+    GetScoreDecimal { user: String, auth: Option<ViewerAuth> },
+    // Whether the user's score, as GetScore reads it with the same `auth` but rounded down
+    // rather than to the nearest Config::score_bucket, is at least `min_score`, so other
+    // contracts can gate on it without reading the score itself
+    HasMinScore { user: String, min_score: u32, auth: Option<ViewerAuth> },
+    // Fetch the mirror token settings
+    GetMirror {},
+    // Fetch the contract config
+    GetConfig {},
+    // Dump raw storage between two keys, only for the owner and only when they enabled it
+    RawRange { start: Option<Binary>, end: Option<Binary>, limit: Option<u32>, auth: ViewerAuth },
+    // Recompute maintained aggregates and index entries from the score maps and report
+    // mismatches, `limit` entries a call. Pass back next_cursor until `complete`.
+    CheckInvariants { cursor: Option<Binary>, limit: Option<u32> },
+    // The funds held for each purpose next to the contract's bank balances, by denom
+    Reconcile {},
+    // Dry-run an execute message as `sender` sending `funds` against current state. `auth`
+    // must prove the querier is the sender or a writer.
+    Simulate { msg: Box<ExecuteMsg>, sender: String, funds: Vec<Coin>, auth: ViewerAuth },
+    // The user's score averaged over the last `window` seconds, weighted by how long
+    // each score was held; harder to move with a last-minute update than the score itself
+    AverageScore { user: String, window: u64, auth: Option<ViewerAuth> },
+    // Contract identity plus runtime-enabled features, for feature detection
+    Info {},
+    // Every ownership change, oldest first
+    OwnerHistory { start_after: Option<u64>, limit: Option<u32> },
+    // Fetch the contract notified about score changes
+    GetNotifier {},
+    // This contract's score combined with weighted scores from configured sources
+    AggregatedScore { user: String, auth: Option<ViewerAuth> },
+    // The local score plus each peer's last reported score for the user, by weight
+    FederatedScore { user: String, auth: Option<ViewerAuth> },
+    // Registered peer deployments, by chain id
+    Peers {},
+    // Scores from highest to lowest, after Config::score_formula as in GetScore; ties go to
+    // whoever reached the score first. Pass the previous page's next_cursor to continue.
+    // `scope` defaults to the season.
+    Leaderboard { cursor: Option<Binary>, limit: Option<u32>, scope: Option<Scope> },
+    // A leaderboard page with the board size and, optionally, the viewer's rank and score
+    LeaderboardPage { limit: Option<u32>, cursor: Option<Binary>, viewer: Option<String>, scope: Option<Scope> },
+    // The user's current score and rank on each of `boards`, in the order asked
+    CrossRank { user: String, boards: Vec<String>, auth: Option<ViewerAuth> },
+    // Final standings of an archived season
+    SeasonLeaderboard { season: u32, limit: Option<u32>, start_after: Option<String> },
+    // A user's final score in an archived season
+    SeasonScore { season: u32, user: String, auth: Option<ViewerAuth> },
+    // Every season with its start and end heights, oldest first
+    ListSeasons { start_after: Option<u32>, limit: Option<u32> },
+    // Rewards the user can withdraw with ClaimRewards
+    Claimable { user: String },
+    // The entry RestoreScore would bring back for the user, if any
+    RemovedScore { user: String },
+    // Whether the user can claim from the faucet, and their claims so far
+    FaucetStatus { user: String, auth: Option<ViewerAuth> },
+    // Whether the user's next claim or profile setup would be rebated
+    RebateStatus { user: String },
+    // A raffle's prize, draw and claim state
+    Raffle { raffle_id: u64 },
+    // A wager's users, stake and outcome
+    Wager { wager_id: u64 },
+    // The user's score certificate for `season`, the current one by default. Private users
+    // need `auth`, and the score is redacted as in GetScore without it.
+    Certification { user: String, season: Option<u32>, auth: Option<ViewerAuth> },
+    // A published weight table and its schema version, the latest table by default
+    WeightTable { version: Option<u64> },
+    // The user's spends, oldest first
+    SpendHistory { user: String, auth: Option<ViewerAuth>, start_after: Option<u64>, limit: Option<u32> },
+    // Everything that happened to the user's score, spends, rewards and penalties, oldest first
+    UserTimeline { user: String, auth: Option<ViewerAuth>, start_after: Option<u64>, limit: Option<u32> },
+    // Undecided appeals, oldest first
+    OpenAppeals { start_after: Option<u64>, limit: Option<u32> },
+    // One undecided appeal with its penalty
+    AppealCase { case_id: u64 },
+    // Merkle claim stages, oldest first
+    MerkleStages { start_after: Option<u32>, limit: Option<u32> },
+    // Whether the allocation at leaf `index` of a stage has been claimed
+    IsClaimed { stage: u32, index: u64 },
+    // The top `k` of the leaderboard in a single read, for `k` up to the configured size.
+    // Expired scores are left out, as in GetScore
+    Podium { k: u32 },
+    // The users at ranks `from_rank` through `to_rank`, without paging through the ones
+    // ahead. Expired scores are left out but keep their ranks until they are purged
+    RankSlice { from_rank: u32, to_rank: u32 },
+    // How collected funds are split between recipients
+    FeeRoutes {},
+    // The global gain cap and how much of it the current epoch has used
+    GlobalGain {},
+    // Updates held by the anomaly guard, by user
+    ProvisionalUpdates { start_after: Option<String>, limit: Option<u32> },
+    // cw-controllers compatible: the owner as admin
+    Admin {},
+    // cw-controllers compatible: contracts added with AddHook
+    Hooks {},
+    // Addresses that may write scores besides the owner
+    Operators {},
+    // Score writes by an address and what they added to the board, to spot unused keys
+    OperatorReport { addr: String },
+    // OperatorReport for every address that has written a score, by address
+    ListOperatorReports { start_after: Option<String>, limit: Option<u32> },
+    // Keys currently validating signed submissions, including those being rotated out
+    SubmissionKeys {},
+    // Whether a voucher id has been redeemed, and at what height
+    VoucherStatus { id: u64 },
+    // cw-controllers compatible: rewards the address can withdraw
+    Claims { address: String },
+    // The bracket the user's score places them in, if any
+    Bracket { user: String },
+    // Members of a bracket in leaderboard order; fails once the `start_after` user has left it
+    ListBracket { name: String, start_after: Option<String>, limit: Option<u32> },
+    // The maintenance window under way or coming up next
+    NextMaintenance {},
+    // How an address renders here and on the chain it came from
+    AddrInfo { address: String },
+    // Whether the leaderboard is frozen, and until when
+    FreezeStatus {},
+    // Whether a retry from `sender` carrying `key` would count as a duplicate
+    IdempotencyKey { sender: String, key: String },
+    // The digest recorded for an archived season's export, if any
+    SnapshotDigest { snapshot_id: u32 },
+    // Totals and top scores of an ended season, recorded at its rollover
+    EpochSummary { season: u32 },
+    // Sequence number of the latest journaled change
+    LatestSequence {},
+    // Journaled changes after sequence number `seq`, oldest first
+    ChangesSince { seq: u64, limit: Option<u32> },
+    // Recent score changes at heights above `height`, from a bounded ring, paged by sequence number
+    DeltasSince { height: u64, start_after: Option<u64>, limit: Option<u32> },
+    // The retention policy for history logs, if any
+    Retention {},
+    // The role needed to send `msg_name`, and whether the owner changed it from the default
+    Policy { msg_name: String },
+    // Addresses that may manage `board` besides the owner
+    BoardAdmins { board: String },
+    // Substrings rejected in user-supplied text, case-folded
+    Denylist {},
+    // The instantiation profile and the subsystems it left on; messages of the others
+    // fail with FeatureDisabled
+    EnabledFeatures {},
+    // Third-party attestations about a user under a tag, by attester
+    Attestations { user: String, tag: String, start_after: Option<String>, limit: Option<u32> },
+}
+
+// Proves the querier may read private scores: the user themselves or the owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ViewerAuth {
+    pub address: String,
+    pub key: String,
 }
 
 // We define a custom struct for each query response
@@ -29,4 +562,477 @@ pub struct OwnerResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ScoreResponse {
     pub score: u32,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HasMinScoreResponse {
+    pub has_min_score: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DecimalScoreResponse {
+    pub score: Decimal,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MirrorResponse {
+    pub token: Option<Addr>,
+    pub enabled: bool,
+}
+
+pub type ConfigResponse = Config;
+pub type RaffleResponse = Raffle;
+pub type WagerResponse = Wager;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CertificationResponse {
+    pub certification: Option<Certification>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WeightTableResponse {
+    // See weights::WEIGHT_TABLE_SCHEMA
+    pub schema: u32,
+    // None until a table is published, or for an unknown version
+    pub table: Option<WeightTable>,
+}
+pub type EpochSummaryResponse = EpochSummary;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RawEntry {
+    pub key: Binary,
+    pub value: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RawRangeResponse {
+    pub entries: Vec<RawEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InvariantsResponse {
+    // Number of score and all-time entries scanned in this batch
+    pub checked: u32,
+    // True on the last batch, once the aggregates have been compared
+    pub complete: bool,
+    pub discrepancies: Vec<String>,
+    // Opaque token for the next batch, None once complete
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AverageScoreResponse {
+    pub score: u32,
+    pub window: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateResponse {
+    pub success: bool,
+    // The error the real transaction would fail with
+    pub error: Option<String>,
+    // Resulting score of the UpdateScore target, otherwise of the sender, as GetScore
+    // would show it to the querier; None where they may not see it
+    pub score: Option<u32>,
+    // Update fee the message would take out of the simulated funds
+    pub fees: Vec<Coin>,
+    pub attributes: Vec<Attribute>,
+    // Number of submessages that would be dispatched
+    pub messages: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InfoResponse {
+    // cw2 contract name and semantic version
+    pub contract: String,
+    pub version: String,
+    // Optional subsystems switched on for this instance
+    pub features: Vec<String>,
+    // Message interfaces this code understands
+    pub interfaces: Vec<String>,
+    // Contract taking writes instead of this one, see ExecuteMsg::ReadOnlyForwarding
+    pub successor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerHistoryResponse {
+    pub changes: Vec<OwnerChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NotifierResponse {
+    pub notifier: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AggregatedScoreResponse {
+    pub score: Uint128,
+    // Local score first, then each source in configured order
+    pub breakdown: Vec<SourceScore>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SourceScore {
+    pub source: String,
+    pub raw: u32,
+    pub weighted: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardResponse {
+    pub entries: Vec<LeaderboardEntry>,
+    // Opaque token for the next page, None on the last page
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardEntry {
+    pub user: AddrInfo,
+    pub score: u32,
+    // Block height at which the score was reached
+    pub achieved_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardPageResponse {
+    pub entries: Vec<LeaderboardEntry>,
+    pub next_cursor: Option<Binary>,
+    // Number of users on the board
+    pub total: u64,
+    // None when no viewer was given, or the viewer has no score or is hidden
+    pub viewer: Option<ViewerRank>,
+}
+
+// An address as stored here, plus its form on the chain it came from when the user set one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddrInfo {
+    pub address: String,
+    pub origin: Option<OriginAddr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OriginAddr {
+    // Bech32 prefix of the originating chain
+    pub prefix: String,
+    // The same account encoded under that prefix
+    pub address: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CrossRankResponse {
+    pub user: String,
+    pub boards: Vec<BoardRank>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoardRank {
+    pub board: String,
+    pub score: u32,
+    // 1-based; None without a live score or while a season is being archived
+    pub rank: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ViewerRank {
+    pub user: AddrInfo,
+    pub score: u32,
+    // 1-based; None when further down than leaderboard::MAX_RANK_SCAN
+    pub rank: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRoutesResponse {
+    pub routes: Vec<FeeRoute>,
+    // Receives what the routes leave over
+    pub remainder: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GlobalGainResponse {
+    pub cap: Option<EpochCap>,
+    // Points gained so far this epoch; only tracked while a cap is set
+    pub gained: u32,
+    pub remaining: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProvisionalUpdatesResponse {
+    pub updates: Vec<ProvisionalEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProvisionalEntry {
+    pub user: String,
+    pub update: ProvisionalUpdate,
+}
+
+// A grant of `points` the owner signs off-chain, redeemable once until `expires_at`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Voucher {
+    pub id: u64,
+    pub user: String,
+    pub points: u32,
+    pub expires_at: Timestamp,
+}
+
+// What the owner signs for RedeemVoucher: the secp256k1 signature is over the sha256 of
+// this struct's JSON, fields in this order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedVoucher {
+    // This contract, so a voucher can't be redeemed on another
+    pub contract: String,
+    pub voucher: Voucher,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoucherStatusResponse {
+    pub id: u64,
+    pub redeemed_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubmissionKeysResponse {
+    pub keys: Vec<SubmissionKey>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FreezeResponse {
+    pub frozen: bool,
+    // When the freeze lifts; None while frozen means until lifted by the owner
+    pub until: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MaintenanceResponse {
+    pub window: Option<MaintenanceWindow>,
+    // Whether the window has started
+    pub active: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IdempotencyKeyResponse {
+    // False once the key has expired, after which it may be used again
+    pub used: bool,
+    pub used_at: Option<u64>,
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SnapshotDigestResponse {
+    pub snapshot_id: u32,
+    pub digest: Option<String>,
+    pub recorded_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SequenceResponse {
+    pub sequence: u64,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChangesResponse {
+    pub changes: Vec<JournalChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JournalChange {
+    pub seq: u64,
+    pub user: String,
+    pub old: u32,
+    pub new: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DeltasResponse {
+    pub deltas: Vec<ScoreDelta>,
+    // False when changes past the requested height may have been dropped from the ring
+    pub complete: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RetentionResponse {
+    pub retention: Option<Retention>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PolicyResponse {
+    pub msg_name: String,
+    pub role: Role,
+    pub overridden: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AttestationsResponse {
+    pub attestations: Vec<AttestationEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AttestationEntry {
+    pub attester: String,
+    pub value: i64,
+    pub height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BracketAssignmentResponse {
+    pub bracket: Option<String>,
+    // 1-based, within the bracket
+    pub position: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BracketMembersResponse {
+    pub name: String,
+    pub members: Vec<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PodiumResponse {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RankedEntry {
+    pub rank: u32,
+    pub user: AddrInfo,
+    pub score: u32,
+    pub achieved_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RankSliceResponse {
+    pub entries: Vec<RankedEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeasonsResponse {
+    pub seasons: Vec<Season>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeasonLeaderboardResponse {
+    pub season: u32,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemovedScoreResponse {
+    // None when there is nothing left to restore
+    pub score: Option<u32>,
+    pub restorable_until: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendHistoryResponse {
+    pub spends: Vec<SpendEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelineResponse {
+    pub entries: Vec<TimelineEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelineEntry {
+    // Numbers the user's entries from 1
+    pub id: u64,
+    pub height: u64,
+    pub event: TimelineEvent,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendEntry {
+    // Numbers the user's spends from 1
+    pub id: u64,
+    pub amount: u32,
+    pub memo: String,
+    pub height: u64,
+    // Contract called by SpendAndCall
+    pub contract: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FaucetStatusResponse {
+    // Whether a ClaimFaucet from the user would pay out now; None for a hidden user unless
+    // `auth` is theirs or the owner's
+    pub eligible: Option<bool>,
+    // None until the faucet is first funded
+    pub denom: Option<String>,
+    pub amount_per_claim: Uint128,
+    pub min_score: u32,
+    // None before the user's first claim
+    pub next_claim_at: Option<Timestamp>,
+    pub claims: u32,
+    pub claimed: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LedgerEntry {
+    pub denom: String,
+    // What the funds are held for, e.g. "reward_pool" or "raffle-3"
+    pub purpose: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomReconciliation {
+    pub denom: String,
+    // Sum of the ledger entries in this denom
+    pub attributed: Uint128,
+    pub balance: Uint128,
+    // Held but not attributed to anything
+    pub surplus: Uint128,
+    // Attributed but not held; non-zero means payouts will start failing
+    pub shortfall: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReconcileResponse {
+    // Ordered by denom, then purpose
+    pub entries: Vec<LedgerEntry>,
+    pub denoms: Vec<DenomReconciliation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RebateStatusResponse {
+    // Whether the user's next rebated message would pay out now
+    pub eligible: bool,
+    // None until the pool is first funded
+    pub denom: Option<String>,
+    pub amount: Uint128,
+    pub balance: Uint128,
+    // Height of the user's rebate, if they had one
+    pub rebated_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableResponse {
+    // None until the pool is first funded
+    pub denom: Option<String>,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AppealCase {
+    pub case_id: u64,
+    pub user: String,
+    pub penalty: Penalty,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OpenAppealsResponse {
+    pub cases: Vec<AppealCase>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MerkleStagesResponse {
+    pub stages: Vec<MerkleStage>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IsClaimedResponse {
+    pub claimed: bool,
 }
@@ -0,0 +1,38 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_binary, StdResult, Storage, WasmMsg};
+
+use crate::custom::SubMsg;
+use crate::state::{HOOKS, NOTIFIER};
+
+pub const NOTIFIER_REPLY_ID: u64 = 3;
+
+/// Message the configured notifier contract and hooks receive after every score change.
+/// It must accept `{"score_changed": {...}}` in its ExecuteMsg.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifierExecuteMsg {
+    ScoreChanged { user: String, old_score: u32, new_score: u32 },
+}
+
+/// Returns the fire-and-forget notifications for a change, to the notifier and every
+/// hook. Sent with `reply_on_error` so a failing listener can never block a write.
+pub fn notify_msgs(storage: &dyn Storage, user: &str, old: u32, new: u32) -> StdResult<Vec<SubMsg>> {
+    if old == new {
+        return Ok(vec![]);
+    }
+    let mut listeners = HOOKS.may_load(storage)?.unwrap_or_default();
+    if let Some(notifier) = NOTIFIER.may_load(storage)? {
+        listeners.insert(0, notifier);
+    }
+
+    let msg = to_binary(&NotifierExecuteMsg::ScoreChanged { user: user.to_string(), old_score: old, new_score: new })?;
+    Ok(listeners
+        .into_iter()
+        .map(|listener| {
+            let exec = WasmMsg::Execute { contract_addr: listener.to_string(), msg: msg.clone(), funds: vec![] };
+            SubMsg::reply_on_error(exec, NOTIFIER_REPLY_ID)
+        })
+        .collect())
+}
@@ -0,0 +1,96 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::limits::page_limit;
+use crate::state::{OperatorActivity, CONFIG, OPERATORS, OPERATOR_ACTIVITY, STATE};
+
+// upper bound on addresses allowed to write scores besides the owner
+pub const MAX_OPERATORS: u32 = 20;
+
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorReportResponse {
+    pub addr: String,
+    // Score writes that went through
+    pub updates: u64,
+    // What those writes added to the board in total; lowered scores count as nothing
+    pub points_issued: u64,
+    // None if the address never wrote a score
+    pub last_active_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorReportsResponse {
+    pub reports: Vec<OperatorReportResponse>,
+}
+
+/// Removes `remove`, then adds `add`; addresses already present are kept once.
+pub fn update_operators(storage: &mut dyn Storage, add: Vec<Addr>, remove: Vec<Addr>) -> Result<(), ContractError> {
+    let mut operators = OPERATORS.may_load(storage)?.unwrap_or_default();
+    operators.retain(|operator| !remove.contains(operator));
+    for addr in add {
+        if !operators.contains(&addr) {
+            operators.push(addr);
+        }
+    }
+    if operators.len() as u32 > MAX_OPERATORS {
+        return Err(ContractError::TooManyOperators { max: MAX_OPERATORS });
+    }
+    Ok(OPERATORS.save(storage, &operators)?)
+}
+
+/// Whether `addr` may write scores: the owner or an operator.
+pub fn is_writer(storage: &dyn Storage, addr: &Addr) -> StdResult<bool> {
+    if *addr == STATE.load(storage)?.owner {
+        return Ok(true);
+    }
+    Ok(OPERATORS.may_load(storage)?.unwrap_or_default().contains(addr))
+}
+
+/// Counts `updates` score writes by `writer`, together adding `points` to the board.
+pub fn record_activity(storage: &mut dyn Storage, writer: &Addr, updates: u64, points: u64, height: u64) -> StdResult<()> {
+    let mut activity = OPERATOR_ACTIVITY.may_load(storage, writer.to_string())?.unwrap_or_default();
+    activity.updates += updates;
+    activity.points_issued += points;
+    activity.last_active_height = height;
+    OPERATOR_ACTIVITY.save(storage, writer.to_string(), &activity)
+}
+
+fn report(addr: String, activity: Option<OperatorActivity>) -> OperatorReportResponse {
+    let activity = activity.unwrap_or_default();
+    let last_active_height = Some(activity.last_active_height).filter(|_| activity.updates > 0);
+    OperatorReportResponse { addr, updates: activity.updates, points_issued: activity.points_issued, last_active_height }
+}
+
+/// Activity of any address, whether or not it is still an operator.
+pub fn query_operator_report(deps: Deps, addr: String) -> StdResult<OperatorReportResponse> {
+    let activity = OPERATOR_ACTIVITY.may_load(deps.storage, addr.clone())?;
+    Ok(report(addr, activity))
+}
+
+/// Every address that has written a score, the owner included, by address.
+pub fn query_operator_reports(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<OperatorReportsResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let reports = OPERATOR_ACTIVITY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(addr, activity)| report(addr, Some(activity))))
+        .collect::<StdResult<_>>()?;
+    Ok(OperatorReportsResponse { reports })
+}
+
+pub fn query_operators(deps: Deps) -> StdResult<OperatorsResponse> {
+    let operators = OPERATORS.may_load(deps.storage)?.unwrap_or_default();
+    Ok(OperatorsResponse { operators: operators.into_iter().map(String::from).collect() })
+}
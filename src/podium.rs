@@ -0,0 +1,110 @@
+use cosmwasm_std::{Deps, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::Bound;
+
+use crate::addr::addr_info;
+use crate::leaderboard::{is_expired, is_public, shown_score, season_floor, IndexKey};
+use crate::msg::{LeaderboardEntry, PodiumResponse};
+use crate::state::{TopK, CONFIG, SCORE_INDEX, TOP_K};
+
+/// Keeps TOP_K in step with a change of one index key, after SCORE_INDEX has been
+/// updated. `old` is the key the user had, `new` the one they have now.
+///
+/// TOP_K always holds the first entries of the index. When it is shorter than `k` and
+/// not being rebuilt it holds the whole index, so new keys go straight in; otherwise a
+/// member leaving is replaced by the next key of the index.
+pub fn update_top_k(storage: &mut dyn Storage, old: Option<&IndexKey>, new: Option<&IndexKey>) -> StdResult<()> {
+    let mut top = match TOP_K.may_load(storage)? {
+        Some(top) if top.k > 0 => top,
+        _ => return Ok(()),
+    };
+    let k = top.k as usize;
+    let complete = !top.rebuilding && top.entries.len() < k;
+    let mut changed = false;
+
+    if let Some(pos) = old.and_then(|old| top.entries.binary_search(old).ok()) {
+        top.entries.remove(pos);
+        changed = true;
+        if !complete && !top.rebuilding {
+            let start = top.entries.last().cloned().map(Bound::exclusive);
+            if let Some(next) = SCORE_INDEX.keys(storage, start, None, Order::Ascending).next() {
+                top.entries.push(next?);
+            }
+        }
+    }
+    if let Some(new) = new {
+        let belongs = complete || top.entries.last().map_or(false, |last| new < last);
+        if let (true, Err(pos)) = (belongs, top.entries.binary_search(new)) {
+            top.entries.insert(pos, new.clone());
+            top.entries.truncate(k);
+            changed = true;
+        }
+    }
+
+    if changed {
+        TOP_K.save(storage, &top)?;
+    }
+    Ok(())
+}
+
+/// Changes the size of the set. Shrinking takes effect right away; growing a full set
+/// needs a RebuildTopK pass before the extra entries can be read.
+pub fn set_top_k(storage: &mut dyn Storage, k: u32) -> StdResult<()> {
+    let mut top = TOP_K.may_load(storage)?.unwrap_or_default();
+    let disabled = top.k == 0;
+    let full = top.entries.len() >= top.k as usize;
+    top.entries.truncate(k as usize);
+    if k > top.k && (disabled || full) {
+        top.rebuilding = true;
+    }
+    top.k = k;
+    TOP_K.save(storage, &top)
+}
+
+/// Refills TOP_K from the index, up to `limit` keys per call; the first call of a
+/// rebuild starts over from an empty set. Returns the keys added and whether it is done.
+pub fn rebuild_top_k(storage: &mut dyn Storage, limit: usize) -> StdResult<(usize, bool)> {
+    let mut top = TOP_K.may_load(storage)?.unwrap_or_default();
+    if !top.rebuilding {
+        top = TopK { k: top.k, entries: vec![], rebuilding: true };
+    }
+
+    let k = top.k as usize;
+    let wanted = k.saturating_sub(top.entries.len()).min(limit);
+    let start = top.entries.last().cloned().map(Bound::exclusive);
+    let keys = SCORE_INDEX
+        .keys(storage, start, None, Order::Ascending)
+        .take(wanted + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let added = keys.len().min(wanted);
+    let done = keys.len() <= wanted || top.entries.len() + added == k;
+    top.entries.extend(keys.into_iter().take(added));
+
+    top.rebuilding = !done;
+    TOP_K.save(storage, &top)?;
+    Ok((added, done))
+}
+
+/// The best `k` users on the board, read from the maintained set instead of the index.
+/// Hidden users and entries that read as absent at `now` are left out, so fewer than `k`
+/// entries may come back.
+pub fn query_podium(deps: Deps, now: Timestamp, k: u32) -> StdResult<PodiumResponse> {
+    let top = TOP_K.may_load(deps.storage)?.unwrap_or_default();
+    if k > top.k {
+        return Err(StdError::generic_err(format!("Podium holds at most {} entries", top.k)));
+    }
+    // The set is a correct prefix while being rebuilt, just possibly too short
+    if top.rebuilding && top.entries.len() < k as usize {
+        return Err(StdError::generic_err("Podium is being rebuilt"));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let floor = season_floor(deps.storage)?;
+    let mut entries = vec![];
+    for (inverted, achieved_at, user) in top.entries.into_iter().take(k as usize) {
+        if achieved_at < floor || !is_public(deps.storage, &config, &user)? || is_expired(deps.storage, &user, now)? {
+            continue;
+        }
+        entries.push(LeaderboardEntry { user: addr_info(deps, user)?, score: shown_score(&config, u32::MAX - inverted), achieved_at });
+    }
+    Ok(PodiumResponse { entries })
+}
@@ -0,0 +1,164 @@
+use cosmwasm_std::{to_vec, Addr, Deps, StdError, StdResult, Storage};
+
+use crate::boards::{is_board_admin, DEFAULT_BOARD};
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, PolicyResponse};
+use crate::operators::is_writer;
+use crate::state::{Role, POLICIES, SCORES, STATE};
+
+// Role each message needs unless the owner has set a policy for it. Messages missing here
+// need the owner, so a new message is locked down until it's listed. Handlers still apply
+// their own checks on top, e.g. the arbiter for ResolveAppeal or the signature for
+// SubmitSignedScore and RedeemVoucher.
+const DEFAULT_ROLES: &[(&str, Role)] = &[
+    ("update_score", Role::Operator),
+    ("update_score_decimal", Role::Operator),
+    ("submit_signed_score", Role::Anyone),
+    ("rotate_submission_key", Role::Owner),
+    ("redeem_voucher", Role::Anyone),
+    ("set_voucher_key", Role::Owner),
+    ("set_mirror_enabled", Role::Owner),
+    ("decouple_mirror", Role::Owner),
+    ("update_config", Role::Owner),
+    // The one board still shares the contract-wide config, so editing it stays with the owner
+    ("update_board_config", Role::Owner),
+    ("transfer_ownership", Role::Owner),
+    ("set_paused", Role::Owner),
+    ("read_only_forwarding", Role::Owner),
+    ("schedule_maintenance", Role::Owner),
+    ("cancel_maintenance", Role::Owner),
+    ("freeze_leaderboard", Role::Owner),
+    ("unfreeze_leaderboard", Role::Owner),
+    ("rescale_scores", Role::Owner),
+    ("reset_all_scores", Role::Owner),
+    ("compact_zero_scores", Role::Owner),
+    ("purge_expired", Role::Anyone),
+    ("set_user_gain_cap", Role::Owner),
+    ("set_global_gain_cap", Role::Owner),
+    ("set_notifier", Role::Owner),
+    ("set_aggregation", Role::Owner),
+    ("set_viewing_key", Role::Anyone),
+    ("commit_scores", Role::Owner),
+    ("reveal_scores", Role::Owner),
+    ("start_new_season", Role::BoardAdmin),
+    ("remove_score", Role::Owner),
+    ("restore_score", Role::Owner),
+    ("rollback", Role::Owner),
+    ("rollback_batch", Role::Owner),
+    ("set_shadow", Role::Owner),
+    ("set_profile_visibility", Role::Anyone),
+    ("set_addr_origin", Role::Anyone),
+    ("set_gatekeeper", Role::Owner),
+    ("fund_pool", Role::Anyone),
+    ("checkpoint", Role::Owner),
+    ("claim_rewards", Role::Anyone),
+    ("fund_faucet", Role::Owner),
+    ("set_faucet", Role::Owner),
+    ("start_raffle", Role::Owner),
+    ("resolve_raffle", Role::Anyone),
+    ("claim_raffle", Role::Anyone),
+    ("claim_faucet", Role::Anyone),
+    ("fund_rebate_pool", Role::Owner),
+    ("set_rebate", Role::Owner),
+    ("spend_score", Role::Anyone),
+    ("open_wager", Role::Anyone),
+    ("accept_wager", Role::Anyone),
+    ("report_wager", Role::Operator),
+    ("refund_wager", Role::Anyone),
+    ("spend_and_call", Role::Anyone),
+    ("set_shop", Role::Owner),
+    ("certify_score", Role::Anyone),
+    ("set_certifier", Role::Owner),
+    ("publish_weight_table", Role::Operator),
+    ("set_reward_stream", Role::Owner),
+    ("penalize", Role::Owner),
+    ("appeal", Role::Anyone),
+    ("resolve_appeal", Role::Anyone),
+    ("set_arbiter", Role::Owner),
+    ("normalize_addresses", Role::Owner),
+    ("register_merkle_stage", Role::Owner),
+    ("claim_merkle", Role::Anyone),
+    ("clawback_stage", Role::Owner),
+    ("recover_cw20", Role::Owner),
+    ("recover_nft", Role::Owner),
+    ("set_top_k", Role::Owner),
+    ("rebuild_top_k", Role::Owner),
+    ("rebuild_index", Role::Owner),
+    ("set_update_fees", Role::Owner),
+    ("review_anomaly", Role::Owner),
+    ("add_hook", Role::Owner),
+    ("update_operators", Role::Owner),
+    ("remove_hook", Role::Owner),
+    ("set_brackets", Role::Owner),
+    ("merge_identities", Role::Owner),
+    ("attest", Role::Anyone),
+    ("set_journal", Role::Owner),
+    ("set_retention", Role::Owner),
+    ("trim_history", Role::Anyone),
+    ("finalize_snapshot_digest", Role::Owner),
+    ("set_policy", Role::Owner),
+    ("update_board_admins", Role::Owner),
+    ("update_denylist", Role::Owner),
+    ("update_peers", Role::Owner),
+    ("sync_from_peer", Role::Anyone),
+    ("receive", Role::Anyone),
+];
+
+// Policies the owner can't change, so control of the contract can't be given away or lost
+const LOCKED: &[&str] = &["set_policy", "transfer_ownership", "update_board_admins"];
+
+/// The message's name as sent, e.g. "update_score".
+pub fn msg_name(msg: &ExecuteMsg) -> StdResult<String> {
+    let json = to_vec(msg)?;
+    Ok(json.iter().skip(2).take_while(|c| **c != b'"').map(|c| *c as char).collect())
+}
+
+fn default_role(msg_name: &str) -> Role {
+    DEFAULT_ROLES.iter().find(|(name, _)| *name == msg_name).map_or(Role::Owner, |(_, role)| *role)
+}
+
+pub fn validate_policy(msg_name: &str) -> Result<(), ContractError> {
+    if !DEFAULT_ROLES.iter().any(|(name, _)| *name == msg_name) {
+        return Err(ContractError::UnknownMessage { name: msg_name.to_string() });
+    }
+    if LOCKED.contains(&msg_name) {
+        return Err(ContractError::PolicyLocked {});
+    }
+    Ok(())
+}
+
+/// Fails unless `sender` holds the role the policy for `msg_name` asks for.
+pub fn authorize(storage: &dyn Storage, sender: &Addr, msg_name: &str) -> Result<(), ContractError> {
+    let role = POLICIES.may_load(storage, msg_name.to_string())?.unwrap_or_else(|| default_role(msg_name));
+    let allowed = match role {
+        Role::Owner => *sender == STATE.load(storage)?.owner,
+        Role::Operator => is_writer(storage, sender)?,
+        // Every message manages the one board there is
+        Role::BoardAdmin => is_board_admin(storage, DEFAULT_BOARD, sender)?,
+        Role::AnyRegistered => SCORES.has(storage, sender.to_string()) || is_writer(storage, sender)?,
+        Role::Anyone => true,
+    };
+    if !allowed {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// Sets the role `msg_name` needs; setting its default drops the override.
+pub fn set_policy(storage: &mut dyn Storage, msg_name: &str, role: Role) -> Result<(), ContractError> {
+    validate_policy(msg_name)?;
+    if role == default_role(msg_name) {
+        POLICIES.remove(storage, msg_name.to_string());
+    } else {
+        POLICIES.save(storage, msg_name.to_string(), &role)?;
+    }
+    Ok(())
+}
+
+pub fn query_policy(deps: Deps, msg_name: String) -> StdResult<PolicyResponse> {
+    if !DEFAULT_ROLES.iter().any(|(name, _)| *name == msg_name) {
+        return Err(StdError::generic_err(ContractError::UnknownMessage { name: msg_name }.to_string()));
+    }
+    let stored = POLICIES.may_load(deps.storage, msg_name.clone())?;
+    Ok(PolicyResponse { overridden: stored.is_some(), role: stored.unwrap_or_else(|| default_role(&msg_name)), msg_name })
+}
@@ -0,0 +1,79 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Deps, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::state::{Feature, Features, Profile, FEATURES};
+
+pub const ALL_FEATURES: [Feature; 3] = [Feature::Seasons, Feature::Fees, Feature::Hooks];
+
+// Messages that only make sense while their subsystem runs. Messages left out belong to
+// none and always go through.
+const FEATURE_MSGS: &[(&str, Feature)] = &[
+    ("start_new_season", Feature::Seasons),
+    ("set_update_fees", Feature::Fees),
+    ("add_hook", Feature::Hooks),
+    ("remove_hook", Feature::Hooks),
+    ("set_notifier", Feature::Hooks),
+];
+
+impl Feature {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Feature::Seasons => "seasons",
+            Feature::Fees => "fees",
+            Feature::Hooks => "hooks",
+        }
+    }
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Features { profile: Profile::Full, enabled_features: ALL_FEATURES.to_vec() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EnabledFeaturesResponse {
+    pub profile: Profile,
+    pub enabled_features: Vec<Feature>,
+}
+
+/// The subsystems `profile` runs with.
+pub fn profile_features(profile: Profile) -> Features {
+    let enabled_features = match profile {
+        Profile::Full => ALL_FEATURES.to_vec(),
+        // Plain scores: no rollovers, no paid updates, nothing notified
+        Profile::Simple => vec![],
+        // Seasons with paid entries; results are read back rather than pushed
+        Profile::Tournament => vec![Feature::Seasons, Feature::Fees],
+        // Lasting, free scores that other contracts follow through hooks
+        Profile::Reputation => vec![Feature::Hooks],
+    };
+    Features { profile, enabled_features }
+}
+
+/// Fails if the contract's profile turned `feature` off. Contracts instantiated before
+/// profiles existed run with everything on.
+pub fn assert_enabled(storage: &dyn Storage, feature: Feature) -> Result<(), ContractError> {
+    let features = FEATURES.may_load(storage)?.unwrap_or_default();
+    if !features.enabled_features.contains(&feature) {
+        return Err(ContractError::FeatureDisabled { feature: feature.as_str().to_string() });
+    }
+    Ok(())
+}
+
+/// Fails if `msg_name` belongs to a subsystem that is turned off. Only those messages
+/// load the flags.
+pub fn assert_msg_enabled(storage: &dyn Storage, msg_name: &str) -> Result<(), ContractError> {
+    match FEATURE_MSGS.iter().find(|(name, _)| *name == msg_name) {
+        Some((_, feature)) => assert_enabled(storage, *feature),
+        None => Ok(()),
+    }
+}
+
+pub fn query_enabled_features(deps: Deps) -> StdResult<EnabledFeaturesResponse> {
+    let features = FEATURES.may_load(deps.storage)?.unwrap_or_default();
+    Ok(EnabledFeaturesResponse { profile: features.profile, enabled_features: features.enabled_features })
+}
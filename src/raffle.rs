@@ -0,0 +1,74 @@
+use cosmwasm_std::{Binary, Deps, Env, Order, StdResult, Storage, Timestamp};
+use sha2::{Digest, Sha256};
+
+use crate::leaderboard::{is_expired, season_floor};
+use crate::state::{Raffle, RAFFLES, SCORE_INDEX};
+
+// Only the top of the board enters, which bounds the work of a draw
+pub const MAX_RAFFLE_ENTRANTS: u32 = 100;
+// Index keys a draw walks looking for live entrants; past this many expired or stale
+// entries at the top, fewer than MAX_RAFFLE_ENTRANTS enter
+const MAX_RAFFLE_SCAN: usize = 1000;
+
+#[derive(Debug, PartialEq)]
+pub struct Draw {
+    pub winner: Option<String>,
+    pub seed: Binary,
+    // Sum of the entrants' scores; a user's chance is their score over this
+    pub total_weight: u64,
+    pub entrants: u32,
+}
+
+/// Entropy for a draw. CosmWasm doesn't expose block hashes to contracts, so this is the
+/// sha256 of what the resolving block does show: chain id, contract, raffle id, height,
+/// time and transaction index. That is deterministic and auditable from the event trail,
+/// but not secret: validators have some sway over block time, and whoever resolves picks
+/// the block. Keep prizes small enough that steering a draw isn't worth it.
+pub fn raffle_seed(env: &Env, raffle_id: u64) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(env.block.chain_id.as_bytes());
+    hasher.update(env.contract.address.as_bytes());
+    hasher.update(raffle_id.to_be_bytes());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    hasher.update(env.transaction.as_ref().map_or(u32::MAX, |tx| tx.index).to_be_bytes());
+    Binary::from(hasher.finalize().as_slice())
+}
+
+/// Picks a winner among the top entrants, each weighted by score. Entries that read as
+/// absent at `now`, expired or left over from a season being archived, don't enter. No
+/// one wins when every entrant has a zero score.
+pub fn draw(storage: &dyn Storage, now: Timestamp, seed: Binary) -> StdResult<Draw> {
+    let floor = season_floor(storage)?;
+    let mut entrants = vec![];
+    for key in SCORE_INDEX.keys(storage, None, None, Order::Ascending).take(MAX_RAFFLE_SCAN) {
+        let (inverted, achieved_at, user) = key?;
+        if achieved_at < floor || is_expired(storage, &user, now)? {
+            continue;
+        }
+        entrants.push((user, u64::from(u32::MAX - inverted)));
+        if entrants.len() == MAX_RAFFLE_ENTRANTS as usize {
+            break;
+        }
+    }
+    let total_weight: u64 = entrants.iter().map(|(_, weight)| weight).sum();
+
+    let mut winner = None;
+    if total_weight > 0 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&seed[..8]);
+        let mut ticket = u64::from_be_bytes(bytes) % total_weight;
+        for (user, weight) in entrants.iter() {
+            if ticket < *weight {
+                winner = Some(user.clone());
+                break;
+            }
+            ticket -= weight;
+        }
+    }
+    Ok(Draw { winner, seed, total_weight, entrants: entrants.len() as u32 })
+}
+
+pub fn query_raffle(deps: Deps, raffle_id: u64) -> StdResult<Raffle> {
+    RAFFLES.load(deps.storage, raffle_id)
+}
@@ -0,0 +1,123 @@
+use cosmwasm_std::{Deps, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Map};
+
+use crate::addr::addr_info;
+use crate::leaderboard::{is_expired, is_public, shown_score, IndexKey};
+use crate::msg::{RankSliceResponse, RankedEntry};
+use crate::state::{ARCHIVING, CONFIG, RANK_BLOCK_COUNTS, RANK_COUNTS, SCORE_INDEX};
+
+// Scores are counted singly and in blocks of 2^RANK_BLOCK_BITS, so finding a rank sums
+// block counts, then the counts of one block, then walks the keys of a single score
+const RANK_BLOCK_BITS: u32 = 12;
+
+// Widest interval RankSlice returns
+pub const MAX_RANK_SLICE: u32 = 30;
+
+/// Adds `key` to the season board, keeping the rank counts in step. The key must not be
+/// on the board yet.
+pub fn index_add(storage: &mut dyn Storage, key: &IndexKey) -> StdResult<()> {
+    SCORE_INDEX.save(storage, key.clone(), &())?;
+    adjust_counts(storage, key.0, true)
+}
+
+/// Removes `key` from the season board, keeping the rank counts in step.
+pub fn index_remove(storage: &mut dyn Storage, key: &IndexKey) -> StdResult<()> {
+    if SCORE_INDEX.may_load(storage, key.clone())?.is_none() {
+        return Ok(());
+    }
+    SCORE_INDEX.remove(storage, key.clone());
+    adjust_counts(storage, key.0, false)
+}
+
+fn adjust_counts(storage: &mut dyn Storage, inverted: u32, added: bool) -> StdResult<()> {
+    adjust(storage, RANK_COUNTS, inverted, added)?;
+    adjust(storage, RANK_BLOCK_COUNTS, inverted >> RANK_BLOCK_BITS, added)
+}
+
+// Entries are dropped at zero, so walking a count map only visits occupied scores
+fn adjust(storage: &mut dyn Storage, counts: Map<u32, u32>, at: u32, added: bool) -> StdResult<()> {
+    let count = counts.may_load(storage, at)?.unwrap_or_default();
+    let count = if added { count + 1 } else { count.saturating_sub(1) };
+    if count == 0 {
+        counts.remove(storage, at);
+        return Ok(());
+    }
+    counts.save(storage, at, &count)
+}
+
+/// 1-based rank of `key` on the season board, which must hold it. Ties are ordered as on
+/// the leaderboard.
+pub fn rank_of(storage: &dyn Storage, key: &IndexKey) -> StdResult<u32> {
+    let block = key.0 >> RANK_BLOCK_BITS;
+    let mut ahead = 0u32;
+    for item in RANK_BLOCK_COUNTS.range(storage, None, Some(Bound::exclusive(block)), Order::Ascending) {
+        ahead += item?.1;
+    }
+    let (start, end) = (Bound::inclusive(block << RANK_BLOCK_BITS), Bound::exclusive(key.0));
+    for item in RANK_COUNTS.range(storage, Some(start), Some(end), Order::Ascending) {
+        ahead += item?.1;
+    }
+    let start = Bound::inclusive((key.0, 0u64, String::new()));
+    ahead += SCORE_INDEX.keys(storage, Some(start), Some(Bound::exclusive(key.clone())), Order::Ascending).count() as u32;
+    Ok(ahead + 1)
+}
+
+// The inverted score holding rank `skip + 1`, and how many of its keys come before it
+fn locate(storage: &dyn Storage, mut skip: u32) -> StdResult<Option<(u32, u32)>> {
+    let mut block = None;
+    for item in RANK_BLOCK_COUNTS.range(storage, None, None, Order::Ascending) {
+        let (at, count) = item?;
+        if skip < count {
+            block = Some(at);
+            break;
+        }
+        skip -= count;
+    }
+    let block = match block {
+        Some(block) => block,
+        None => return Ok(None),
+    };
+
+    let start = Bound::inclusive(block << RANK_BLOCK_BITS);
+    for item in RANK_COUNTS.range(storage, Some(start), None, Order::Ascending) {
+        let (inverted, count) = item?;
+        if skip < count {
+            return Ok(Some((inverted, skip)));
+        }
+        skip -= count;
+    }
+    Err(StdError::generic_err("Rank counts are out of step with the index"))
+}
+
+/// The users at ranks `from_rank` through `to_rank` of the season board, both 1-based and
+/// inclusive. Hidden users keep their ranks but are left out, and so do scores expired by
+/// `now`: ranks count them until PurgeExpired removes them.
+pub fn query_rank_slice(deps: Deps, now: Timestamp, from_rank: u32, to_rank: u32) -> StdResult<RankSliceResponse> {
+    if from_rank == 0 || to_rank < from_rank || to_rank - from_rank >= MAX_RANK_SLICE {
+        return Err(StdError::generic_err(format!("Ranks must run from 1 up, at most {} at a time", MAX_RANK_SLICE)));
+    }
+    // Entries of a season still being archived are counted but no longer on the board
+    if ARCHIVING.may_load(deps.storage)?.is_some() {
+        return Err(StdError::generic_err("Season is being archived"));
+    }
+
+    let (inverted, skip) = match locate(deps.storage, from_rank - 1)? {
+        Some(found) => found,
+        None => return Ok(RankSliceResponse { entries: vec![] }),
+    };
+    let config = CONFIG.load(deps.storage)?;
+    let start = Bound::inclusive((inverted, 0u64, String::new()));
+    let keys = SCORE_INDEX
+        .keys(deps.storage, Some(start), None, Order::Ascending)
+        .skip(skip as usize)
+        .take((to_rank - from_rank + 1) as usize);
+    let mut entries = vec![];
+    for (rank, key) in (from_rank..).zip(keys) {
+        let (inverted, achieved_at, user) = key?;
+        if !is_public(deps.storage, &config, &user)? || is_expired(deps.storage, &user, now)? {
+            continue;
+        }
+        entries.push(RankedEntry { rank, user: addr_info(deps, user)?, score: shown_score(&config, u32::MAX - inverted), achieved_at });
+    }
+    Ok(RankSliceResponse { entries })
+}
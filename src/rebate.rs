@@ -0,0 +1,48 @@
+use cosmwasm_std::{coin, Addr, Coin, Deps, Env, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::ledger::{debit, REBATE_FUNDS};
+use crate::msg::RebateStatusResponse;
+use crate::state::{REBATED, REBATE_POOL};
+
+// Messages a user sends while onboarding: setting up their profile, or a first claim.
+// The first of them each user sends gets the rebate, while the pool lasts.
+const REBATED_MSGS: &[&str] = &[
+    "set_viewing_key",
+    "set_profile_visibility",
+    "set_addr_origin",
+    "claim_rewards",
+    "claim_faucet",
+    "claim_raffle",
+    "claim_merkle",
+];
+
+/// Takes the rebate owed to `user` out of the pool, if `msg_name` qualifies, the pool
+/// covers it and they never had one. An empty pool skips the rebate rather than failing
+/// the message.
+pub fn take_rebate(storage: &mut dyn Storage, env: &Env, user: &Addr, msg_name: &str) -> Result<Option<Coin>, ContractError> {
+    if !REBATED_MSGS.contains(&msg_name) {
+        return Ok(None);
+    }
+    let mut pool = REBATE_POOL.may_load(storage)?.unwrap_or_default();
+    let denom = match &pool.denom {
+        Some(denom) if !pool.amount.is_zero() && pool.balance >= pool.amount => denom.clone(),
+        _ => return Ok(None),
+    };
+    if REBATED.has(storage, user.to_string()) {
+        return Ok(None);
+    }
+    let rebate = coin(pool.amount.u128(), denom);
+    debit(storage, REBATE_FUNDS, &rebate)?;
+    pool.balance -= pool.amount;
+    REBATE_POOL.save(storage, &pool)?;
+    REBATED.save(storage, user.to_string(), &env.block.height)?;
+    Ok(Some(rebate))
+}
+
+pub fn query_rebate_status(deps: Deps, user: String) -> StdResult<RebateStatusResponse> {
+    let pool = REBATE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let rebated_at = REBATED.may_load(deps.storage, user)?;
+    let funded = pool.denom.is_some() && !pool.amount.is_zero() && pool.balance >= pool.amount;
+    Ok(RebateStatusResponse { eligible: funded && rebated_at.is_none(), denom: pool.denom, amount: pool.amount, balance: pool.balance, rebated_at })
+}
@@ -0,0 +1,53 @@
+use cosmwasm_std::{Deps, Env, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::msg::RemovedScoreResponse;
+use crate::state::{RemovedScore, REMOVAL_QUEUE, REMOVED_SCORES};
+
+// tombstones past their grace period dropped per removal or restore
+const PURGE_BATCH: usize = 10;
+
+/// Keeps a removed entry restorable until `removed.restorable_until`.
+pub fn record_tombstone(storage: &mut dyn Storage, user: &str, removed: &RemovedScore) -> StdResult<()> {
+    // A user removed again replaces their older tombstone
+    if let Some(old) = REMOVED_SCORES.may_load(storage, user.to_string())? {
+        REMOVAL_QUEUE.remove(storage, (old.restorable_until.nanos(), user.to_string()));
+    }
+    REMOVED_SCORES.save(storage, user.to_string(), removed)?;
+    REMOVAL_QUEUE.save(storage, (removed.restorable_until.nanos(), user.to_string()), &())
+}
+
+/// Takes `user`'s tombstone out, whether or not it can still be restored.
+pub fn take_tombstone(storage: &mut dyn Storage, user: &str) -> StdResult<Option<RemovedScore>> {
+    let removed = REMOVED_SCORES.may_load(storage, user.to_string())?;
+    if let Some(removed) = &removed {
+        REMOVED_SCORES.remove(storage, user.to_string());
+        REMOVAL_QUEUE.remove(storage, (removed.restorable_until.nanos(), user.to_string()));
+    }
+    Ok(removed)
+}
+
+/// Drops a batch of tombstones whose grace period has ended.
+pub fn purge_tombstones(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let end = Bound::exclusive((env.block.time.nanos(), String::new()));
+    let expired = REMOVAL_QUEUE
+        .keys(storage, None, Some(end), Order::Ascending)
+        .take(PURGE_BATCH)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (restorable_until, user) in expired {
+        REMOVAL_QUEUE.remove(storage, (restorable_until, user.clone()));
+        REMOVED_SCORES.remove(storage, user);
+    }
+    Ok(())
+}
+
+/// The score RestoreScore would bring back for `user`, while it still can.
+pub fn query_removed_score(deps: Deps, env: Env, user: String) -> StdResult<RemovedScoreResponse> {
+    let removed = REMOVED_SCORES
+        .may_load(deps.storage, user)?
+        .filter(|removed| env.block.time < removed.restorable_until);
+    Ok(RemovedScoreResponse {
+        score: removed.as_ref().map(|removed| removed.score),
+        restorable_until: removed.map(|removed| removed.restorable_until),
+    })
+}
@@ -0,0 +1,36 @@
+use cosmwasm_std::{to_binary, Addr, StdResult, Storage, Uint128, WasmMsg};
+
+use crate::certify::Cw721ExecuteMsg;
+use crate::error::ContractError;
+use crate::mirror::Cw20ExecuteMsg;
+use crate::state::{CONFIG, FAUCET, MIRROR, REBATE_POOL, REWARD_POOL};
+
+// Returns tokens sent to the contract by mistake. Nothing here touches what the contract
+// accounts for itself, so recovering can never shortchange a pool or a claim.
+
+/// Fails for cw20s the contract handles itself: the mirror token, the update fee token,
+/// and any token a pool is denominated in.
+pub fn assert_unmanaged_cw20(storage: &dyn Storage, token: &Addr) -> Result<(), ContractError> {
+    let config = CONFIG.load(storage)?;
+    let mut managed = vec![];
+    managed.extend(MIRROR.may_load(storage)?.and_then(|mirror| mirror.token).map(String::from));
+    managed.extend(config.cw20_update_fee.map(|fee| fee.token.to_string()));
+    managed.extend(REWARD_POOL.may_load(storage)?.and_then(|pool| pool.denom));
+    managed.extend(FAUCET.may_load(storage)?.and_then(|faucet| faucet.denom));
+    managed.extend(REBATE_POOL.may_load(storage)?.and_then(|pool| pool.denom));
+    if managed.iter().any(|managed| managed == token.as_str()) {
+        return Err(ContractError::ManagedAsset { asset: token.to_string() });
+    }
+    Ok(())
+}
+
+pub fn recover_cw20_msg(token: &Addr, amount: Uint128, recipient: &Addr) -> StdResult<WasmMsg> {
+    let msg = Cw20ExecuteMsg::Transfer { recipient: recipient.to_string(), amount };
+    Ok(WasmMsg::Execute { contract_addr: token.to_string(), msg: to_binary(&msg)?, funds: vec![] })
+}
+
+/// The contract never holds NFTs of its own; certificates are minted straight to users.
+pub fn recover_nft_msg(contract: &Addr, token_id: String, recipient: &Addr) -> StdResult<WasmMsg> {
+    let msg = Cw721ExecuteMsg::TransferNft { recipient: recipient.to_string(), token_id };
+    Ok(WasmMsg::Execute { contract_addr: contract.to_string(), msg: to_binary(&msg)?, funds: vec![] })
+}
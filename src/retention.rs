@@ -0,0 +1,64 @@
+use cosmwasm_std::{Deps, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::deltas::trim_deltas;
+use crate::error::ContractError;
+use crate::msg::RetentionResponse;
+use crate::state::{Retention, JOURNAL_ENTRIES, OWNER_HISTORY, RETENTION};
+
+// Oldest entries each log may shed per write; more than one so a log catches up after
+// the policy is tightened
+pub const TRIM_PER_WRITE: u32 = 2;
+
+pub fn validate_retention(retention: &Retention) -> Result<(), ContractError> {
+    if retention.max_entries == Some(0) || retention.max_age == Some(0) {
+        return Err(ContractError::InvalidRetention {});
+    }
+    Ok(())
+}
+
+/// Drops up to `limit` of the oldest entries of each history log that the retention policy
+/// no longer keeps: the journal, the owner history and the deltas ring. The newest entry of
+/// a log is always kept, so numbering carries on and readers can tell what was dropped.
+pub fn trim_history(storage: &mut dyn Storage, height: u64, limit: u32) -> StdResult<u32> {
+    let retention = match RETENTION.may_load(storage)? {
+        Some(retention) => retention,
+        None => return Ok(0),
+    };
+    let mut trimmed = trim_log(storage, &JOURNAL_ENTRIES, &retention, height, limit, |entry| entry.height)?;
+    trimmed += trim_log(storage, &OWNER_HISTORY, &retention, height, limit, |change| change.height)?;
+    trimmed += trim_deltas(storage, &retention, height, limit)?;
+    Ok(trimmed)
+}
+
+// Both logs number entries contiguously, so an entry's distance from the newest is the
+// difference of their keys
+fn trim_log<T: Serialize + DeserializeOwned>(
+    storage: &mut dyn Storage,
+    log: &Map<u64, T>,
+    retention: &Retention,
+    height: u64,
+    limit: u32,
+    recorded_at: fn(&T) -> u64,
+) -> StdResult<u32> {
+    let newest = match log.keys(storage, None, None, Order::Descending).next().transpose()? {
+        Some(newest) => newest,
+        None => return Ok(0),
+    };
+    let expired = log
+        .range(storage, None, Some(Bound::exclusive(newest)), Order::Ascending)
+        .take(limit as usize)
+        .take_while(|item| item.as_ref().map_or(true, |(id, entry)| retention.expired(newest - id, recorded_at(entry), height)))
+        .map(|item| item.map(|(id, _)| id))
+        .collect::<StdResult<Vec<_>>>()?;
+    for id in expired.iter() {
+        log.remove(storage, *id);
+    }
+    Ok(expired.len() as u32)
+}
+
+pub fn query_retention(deps: Deps) -> StdResult<RetentionResponse> {
+    Ok(RetentionResponse { retention: RETENTION.may_load(deps.storage)? })
+}
@@ -0,0 +1,73 @@
+use cosmwasm_std::{Decimal, Deps, Env, StdResult, Storage, Timestamp, Uint128};
+
+use crate::msg::ClaimableResponse;
+use crate::state::{RewardPool, UserReward, REWARD_POOL, SCORES, TOTALS, USER_REWARDS};
+
+/// Releases what the stream has paid out since the last accrual into the per-point index.
+/// Runs at the start of every execute, so scores are always settled against an index
+/// that is current for the block.
+pub fn accrue(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let mut pool = match REWARD_POOL.may_load(storage)? {
+        Some(pool) if !pool.stream_per_second.is_zero() && pool.last_accrual < env.block.time => pool,
+        _ => return Ok(()),
+    };
+    let total_score = TOTALS.may_load(storage)?.unwrap_or_default().total_score;
+    stream(&mut pool, total_score, env.block.time);
+    REWARD_POOL.save(storage, &pool)
+}
+
+/// Credits `user` with what their `score` earned since they were last settled. Must run
+/// before every change to a stored score, so that rewards are paid out against the
+/// scores held while they accrued.
+pub fn settle(storage: &mut dyn Storage, user: &str, score: u32) -> StdResult<()> {
+    let per_point = match REWARD_POOL.may_load(storage)? {
+        Some(pool) if !pool.per_point.is_zero() => pool.per_point,
+        _ => return Ok(()),
+    };
+    let reward = pending(storage, user, score, per_point)?;
+    USER_REWARDS.save(storage, user.to_string(), &reward)
+}
+
+/// The user's reward entry brought up to `per_point`, without saving it.
+pub fn pending(storage: &dyn Storage, user: &str, score: u32, per_point: Decimal) -> StdResult<UserReward> {
+    let mut reward = USER_REWARDS.may_load(storage, user.to_string())?.unwrap_or_default();
+    reward.pending += Uint128::from(score) * (per_point - reward.index);
+    reward.index = per_point;
+    Ok(reward)
+}
+
+pub fn query_claimable(deps: Deps, env: Env, user: String) -> StdResult<ClaimableResponse> {
+    let mut pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+    if !pool.stream_per_second.is_zero() && pool.last_accrual < env.block.time {
+        let total_score = TOTALS.may_load(deps.storage)?.unwrap_or_default().total_score;
+        stream(&mut pool, total_score, env.block.time);
+    }
+    let score = SCORES.may_load(deps.storage, user.clone())?.unwrap_or_default();
+    let reward = pending(deps.storage, &user, score, pool.per_point)?;
+    Ok(ClaimableResponse { denom: pool.denom, amount: reward.pending })
+}
+
+/// Spreads all of the pool's unallocated funds over `total_score` points at once.
+/// Returns the amount handed out.
+pub fn checkpoint(pool: &mut RewardPool, total_score: u64) -> Uint128 {
+    allocate(pool, pool.unallocated, total_score)
+}
+
+// Streams nothing while nobody holds a score; those funds stay in the pool
+fn stream(pool: &mut RewardPool, total_score: u64, now: Timestamp) {
+    let elapsed = now.seconds() - pool.last_accrual.seconds();
+    let released = pool.stream_per_second.saturating_mul(Uint128::from(elapsed)).min(pool.unallocated);
+    if total_score > 0 && !released.is_zero() {
+        allocate(pool, released, total_score);
+    }
+    pool.last_accrual = now;
+}
+
+// Rounding leftovers stay unallocated for later
+fn allocate(pool: &mut RewardPool, amount: Uint128, total_score: u64) -> Uint128 {
+    let increase = Decimal::from_ratio(amount, total_score);
+    let allocated = Uint128::from(total_score) * increase;
+    pool.per_point += increase;
+    pool.unallocated -= allocated;
+    allocated
+}
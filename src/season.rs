@@ -0,0 +1,128 @@
+use cosmwasm_std::{Deps, Event, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::Bound;
+
+use crate::addr::addr_info;
+use crate::events::{evt_epoch_summary, evt_epoch_top};
+use crate::leaderboard::{index_key, is_public, redact};
+use crate::limits::{page_limit, ScanBudget};
+use crate::msg::{LeaderboardEntry, ScoreResponse, SeasonLeaderboardResponse, SeasonsResponse, SnapshotDigestResponse};
+use crate::state::{
+    EpochSummary, Season, SummaryEntry, Totals, CONFIG, EPOCH_SUMMARIES, SCORE_INDEX, SEASONS, SEASON_INDEX, SEASON_SCORES,
+    SNAPSHOT_DIGESTS,
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+// Users listed by name in an epoch summary
+pub const SUMMARY_TOP_N: u32 = 10;
+
+// Archive queries only answer once a season is completely archived
+fn archived_season(deps: Deps, season: u32) -> StdResult<Season> {
+    match SEASONS.may_load(deps.storage, season)? {
+        Some(record) if record.archived => Ok(record),
+        _ => Err(StdError::generic_err(format!("Season {} is not archived", season))),
+    }
+}
+
+/// Final standings of `season`, in the same order as the live leaderboard. The archive
+/// never changes, so paging by the last user returned is stable.
+pub fn query_season_leaderboard(
+    deps: Deps,
+    season: u32,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<SeasonLeaderboardResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    archived_season(deps, season)?;
+
+    let limit = page_limit(&config, limit, DEFAULT_LIMIT)?;
+    let start = match start_after {
+        Some(user) => {
+            let archived = SEASON_SCORES
+                .may_load(deps.storage, (season, user.clone()))?
+                .ok_or_else(|| StdError::generic_err(format!("No score for start_after user {}", user)))?;
+            Some(Bound::exclusive(index_key(archived.score, archived.achieved_at, &user)))
+        }
+        None => None,
+    };
+
+    // Hidden users are left out by their current visibility
+    let mut entries = vec![];
+    let mut budget = ScanBudget::new(&config);
+    for key in SEASON_INDEX.prefix(season).keys(deps.storage, start, None, Order::Ascending) {
+        budget.spend()?;
+        let (inverted, achieved_at, user) = key?;
+        if !is_public(deps.storage, &config, &user)? {
+            continue;
+        }
+        entries.push(LeaderboardEntry { user: addr_info(deps, user)?, score: redact(&config, u32::MAX - inverted), achieved_at });
+        if entries.len() == limit {
+            break;
+        }
+    }
+    Ok(SeasonLeaderboardResponse { season, entries })
+}
+
+/// A user's final score in `season`; users without one read as zero.
+pub fn query_season_score(deps: Deps, season: u32, user: String) -> StdResult<ScoreResponse> {
+    archived_season(deps, season)?;
+    let score = SEASON_SCORES.may_load(deps.storage, (season, user))?.map_or(0, |archived| archived.score);
+    Ok(ScoreResponse { score, expires_at: None })
+}
+
+pub fn query_list_seasons(deps: Deps, start_after: Option<u32>, limit: Option<u32>) -> StdResult<SeasonsResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let seasons = SEASONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, season)| season))
+        .collect::<StdResult<_>>()?;
+    Ok(SeasonsResponse { seasons })
+}
+
+/// Content hash the owner recorded for the export of an archived season, if any.
+pub fn query_snapshot_digest(deps: Deps, snapshot_id: u32) -> StdResult<SnapshotDigestResponse> {
+    let recorded = SNAPSHOT_DIGESTS.may_load(deps.storage, snapshot_id)?;
+    Ok(SnapshotDigestResponse {
+        snapshot_id,
+        digest: recorded.as_ref().map(|recorded| recorded.digest.clone()),
+        recorded_at: recorded.map(|recorded| recorded.recorded_at),
+    })
+}
+
+/// Records the summary of `ended` at its rollover, while all live scores still belong to
+/// it, and returns the events announcing it: one with the totals, then one per top user.
+pub fn summarize_season(storage: &mut dyn Storage, ended: &Season, totals: &Totals, end_time: Timestamp) -> StdResult<Vec<Event>> {
+    let top = SCORE_INDEX
+        .keys(storage, None, None, Order::Ascending)
+        .take(SUMMARY_TOP_N as usize)
+        .map(|key| key.map(|(inverted, _, user)| SummaryEntry { user, score: u32::MAX - inverted }))
+        .collect::<StdResult<Vec<_>>>()?;
+    let summary = EpochSummary {
+        season: ended.id,
+        start_height: ended.start_height,
+        end_height: ended.end_height.unwrap_or_default(),
+        end_time,
+        total_score: totals.total_score,
+        users: totals.users,
+        top,
+    };
+    EPOCH_SUMMARIES.save(storage, ended.id, &summary)?;
+
+    let mut events = vec![evt_epoch_summary(&summary)];
+    events.extend(summary.top.iter().enumerate().map(|(i, entry)| evt_epoch_top(summary.season, i + 1, entry)));
+    Ok(events)
+}
+
+/// The summary recorded when `season` ended, with top scores redacted like any public query.
+pub fn query_epoch_summary(deps: Deps, season: u32) -> StdResult<EpochSummary> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut summary = EPOCH_SUMMARIES
+        .may_load(deps.storage, season)?
+        .ok_or_else(|| StdError::generic_err(format!("Season {} has not ended", season)))?;
+    for entry in summary.top.iter_mut() {
+        entry.score = redact(&config, entry.score);
+    }
+    Ok(summary)
+}
@@ -0,0 +1,28 @@
+use cosmwasm_std::{to_binary, StdResult, WasmMsg};
+
+use crate::custom::SubMsg;
+use crate::msg::ExecuteMsg;
+use crate::state::Config;
+
+pub const SHADOW_REPLY_ID: u64 = 4;
+
+/// Returns a copy of `msg` for the shadow contract, if one is set. The shadow sees this
+/// contract as the sender, so it should be instantiated with this contract as owner.
+/// Sent with `reply_on_error` so the shadow can never change local behavior.
+pub fn shadow_msg(config: &Config, msg: &ExecuteMsg) -> StdResult<Option<SubMsg>> {
+    // Wiring up the shadow is local configuration, not traffic
+    if let ExecuteMsg::SetShadow { .. } = msg {
+        return Ok(None);
+    }
+    let shadow = match &config.shadow {
+        Some(shadow) => shadow,
+        None => return Ok(None),
+    };
+
+    let exec = WasmMsg::Execute {
+        contract_addr: shadow.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    };
+    Ok(Some(SubMsg::reply_on_error(exec, SHADOW_REPLY_ID)))
+}
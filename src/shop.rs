@@ -0,0 +1,73 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_binary, Addr, Binary, Deps, Order, StdResult, WasmMsg};
+use cw_storage_plus::Bound;
+
+use crate::custom::SubMsg;
+use crate::limits::page_limit;
+use crate::msg::{SpendEntry, SpendHistoryResponse};
+use crate::state::{CONFIG, SPENDS};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Message the configured shop contract receives for every SpendScore. It must accept
+/// `{"score_spent": {...}}` in its ExecuteMsg.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShopExecuteMsg {
+    ScoreSpent { user: String, spend_id: u64, amount: u32, memo: String },
+}
+
+/// What a SpendAndCall target receives, like cw20's Cw20ReceiveMsg. The call comes from
+/// this contract, and `spend_id` can be checked against QueryMsg::SpendHistory. The
+/// target must accept `{"receive_spend": {...}}` in its ExecuteMsg.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendReceiveMsg {
+    pub sender: String,
+    pub spend_id: u64,
+    pub amount: u32,
+    pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendReceiverExecuteMsg {
+    ReceiveSpend(SpendReceiveMsg),
+}
+
+/// Tells the shop about a spend. Unlike notifications this is not fire-and-forget: a
+/// shop that rejects the purchase reverts the spend with it.
+pub fn shop_msg(shop: &Addr, user: &str, spend_id: u64, amount: u32, memo: &str) -> StdResult<SubMsg> {
+    let msg = ShopExecuteMsg::ScoreSpent { user: user.to_string(), spend_id, amount, memo: memo.to_string() };
+    let exec = WasmMsg::Execute { contract_addr: shop.to_string(), msg: to_binary(&msg)?, funds: vec![] };
+    Ok(SubMsg::new(exec))
+}
+
+/// Forwards a SpendAndCall to its target. Failure propagates, reverting the spend.
+pub fn receive_spend_msg(contract: &Addr, user: &str, spend_id: u64, amount: u32, msg: Binary) -> StdResult<SubMsg> {
+    let receive = SpendReceiverExecuteMsg::ReceiveSpend(SpendReceiveMsg { sender: user.to_string(), spend_id, amount, msg });
+    let exec = WasmMsg::Execute { contract_addr: contract.to_string(), msg: to_binary(&receive)?, funds: vec![] };
+    Ok(SubMsg::new(exec))
+}
+
+/// The user's spends, oldest first.
+pub fn query_spend_history(deps: Deps, user: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<SpendHistoryResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let spends = SPENDS
+        .prefix(user)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(id, spend)| SpendEntry {
+                id,
+                amount: spend.amount,
+                memo: spend.memo,
+                height: spend.height,
+                contract: spend.contract,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(SpendHistoryResponse { spends })
+}
@@ -0,0 +1,70 @@
+use cosmwasm_std::{to_vec, Binary, Deps, Env, StdResult, Storage, Timestamp};
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::{SignedScore, SubmissionKeysResponse};
+use crate::state::{SubmissionKey, SUBMISSION_KEYS, SUBMISSION_NONCES};
+
+// upper bound on keys validating at once, counting those still in their overlap
+pub const MAX_SUBMISSION_KEYS: u32 = 5;
+
+/// Keys that validate submissions now.
+pub fn active_keys(storage: &dyn Storage, env: &Env) -> StdResult<Vec<SubmissionKey>> {
+    let keys = SUBMISSION_KEYS.may_load(storage)?.unwrap_or_default();
+    Ok(keys.into_iter().filter(|key| key.retires_at.map_or(true, |retires_at| env.block.time < retires_at)).collect())
+}
+
+/// Adds `pubkey`, valid right away. The keys it replaces keep validating until
+/// `effective_at`, so a server can switch over without downtime; `effective_at` of now
+/// drops them at once, for a compromised key.
+pub fn rotate_key(storage: &mut dyn Storage, env: &Env, pubkey: Binary, effective_at: Timestamp) -> Result<(), ContractError> {
+    if !is_pubkey(&pubkey) {
+        return Err(ContractError::InvalidPubkey {});
+    }
+    if effective_at < env.block.time {
+        return Err(ContractError::InvalidKeyRotation {});
+    }
+    let mut keys = active_keys(storage, env)?;
+    if keys.iter().any(|key| key.pubkey == pubkey) {
+        return Err(ContractError::InvalidKeyRotation {});
+    }
+    for key in keys.iter_mut() {
+        key.retires_at = Some(key.retires_at.map_or(effective_at, |retires_at| retires_at.min(effective_at)));
+    }
+    keys.retain(|key| key.retires_at.map_or(true, |retires_at| env.block.time < retires_at));
+    if keys.len() >= MAX_SUBMISSION_KEYS as usize {
+        return Err(ContractError::TooManySubmissionKeys { max: MAX_SUBMISSION_KEYS });
+    }
+    keys.push(SubmissionKey { pubkey, added_at: env.block.time, retires_at: None });
+    Ok(SUBMISSION_KEYS.save(storage, &keys)?)
+}
+
+/// SEC1 encoded secp256k1 point, compressed or not.
+pub fn is_pubkey(pubkey: &Binary) -> bool {
+    match pubkey.len() {
+        33 => pubkey[0] == 0x02 || pubkey[0] == 0x03,
+        65 => pubkey[0] == 0x04,
+        _ => false,
+    }
+}
+
+/// Checks `signature` over the JSON of `payload` against the active keys. Nonces must
+/// rise per user, so a submission can't be replayed; the caller records the new one.
+pub fn verify_submission(deps: Deps, env: &Env, payload: &SignedScore, signature: &Binary) -> Result<(), ContractError> {
+    if let Some(last) = SUBMISSION_NONCES.may_load(deps.storage, payload.user.clone())? {
+        if payload.nonce <= last {
+            return Err(ContractError::StaleNonce { last });
+        }
+    }
+    let hash = Sha256::digest(&to_vec(payload)?);
+    for key in active_keys(deps.storage, env)? {
+        if deps.api.secp256k1_verify(&hash, signature, &key.pubkey).unwrap_or(false) {
+            return Ok(());
+        }
+    }
+    Err(ContractError::InvalidSignature {})
+}
+
+pub fn query_submission_keys(deps: Deps, env: Env) -> StdResult<SubmissionKeysResponse> {
+    Ok(SubmissionKeysResponse { keys: active_keys(deps.storage, &env)? })
+}
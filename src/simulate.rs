@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Addr, Coin, Deps, DepsMut, Env, MessageInfo, Order, Record, StdError, StdResult, Storage};
+
+use crate::contract::{assert_can_view, execute, is_authorized_viewer, normalize_addr, query_score};
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, SimulateResponse, ViewerAuth};
+use crate::operators::is_writer;
+use crate::state::{Config, CONFIG};
+
+/// Copy-on-write view over read-only storage. Writes land in memory and are
+/// dropped with the overlay, so the real handlers can run inside a query.
+pub struct OverlayStorage<'a> {
+    base: &'a dyn Storage,
+    // None marks a key removed in the overlay
+    changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> OverlayStorage<'a> {
+    pub fn new(base: &'a dyn Storage) -> Self {
+        OverlayStorage { base, changes: BTreeMap::new() }
+    }
+}
+
+impl<'a> Storage for OverlayStorage<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.changes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    fn range<'b>(&'b self, start: Option<&[u8]>, end: Option<&[u8]>, order: Order) -> Box<dyn Iterator<Item = Record> + 'b> {
+        let in_bounds = |key: &[u8]| start.map_or(true, |s| key >= s) && end.map_or(true, |e| key < e);
+
+        let mut merged: BTreeMap<Vec<u8>, Vec<u8>> = self.base.range(start, end, Order::Ascending).collect();
+        for (key, value) in self.changes.iter().filter(|(key, _)| in_bounds(key)) {
+            match value {
+                Some(value) => merged.insert(key.clone(), value.clone()),
+                None => merged.remove(key),
+            };
+        }
+
+        match order {
+            Order::Ascending => Box::new(merged.into_iter()),
+            Order::Descending => Box::new(merged.into_iter().rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.changes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.changes.insert(key.to_vec(), None);
+    }
+}
+
+/// Runs `msg` as if `sender` had sent it with `funds` and reports the outcome without
+/// committing. `auth` must prove the querier is the sender or a writer, or simulation
+/// would read out anyone's private score.
+pub fn query_simulate(deps: Deps, env: Env, msg: ExecuteMsg, sender: String, funds: Vec<Coin>, auth: ViewerAuth) -> StdResult<SimulateResponse> {
+    let sender = normalize_addr(deps.api, &sender)?;
+    if !is_authorized_viewer(deps, &sender, Some(auth.clone()))? {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+    let querier = Addr::unchecked(normalize_addr(deps.api, &auth.address)?);
+    let exact_errors = is_writer(deps.storage, &querier)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let (user, fees) = match &msg {
+        ExecuteMsg::UpdateScore { user, .. } | ExecuteMsg::UpdateScoreDecimal { user, .. } => {
+            (user.to_string(), update_fee_paid(&config, &funds).into_iter().collect())
+        }
+        _ => (sender.clone(), vec![]),
+    };
+
+    let mut overlay = OverlayStorage::new(deps.storage);
+    let info = MessageInfo { sender: Addr::unchecked(sender), funds };
+    let result = execute(
+        DepsMut { storage: &mut overlay, api: deps.api, querier: deps.querier },
+        env.clone(),
+        info,
+        msg,
+    );
+
+    Ok(match result {
+        Ok(res) => {
+            // Read back the way GetScore would show it to this querier
+            let deps = Deps { storage: &overlay, api: deps.api, querier: deps.querier };
+            let score = match assert_can_view(deps, &user, Some(auth)) {
+                Ok(exact) => Some(query_score(deps, env, user, exact)?.score),
+                Err(_) => None,
+            };
+            SimulateResponse {
+                success: true,
+                error: None,
+                score,
+                fees,
+                attributes: res.attributes,
+                messages: res.messages.len() as u32,
+            }
+        }
+        Err(err) => SimulateResponse {
+            success: false,
+            error: Some(describe_error(&err, exact_errors)),
+            score: None,
+            fees: vec![],
+            attributes: vec![],
+            messages: 0,
+        },
+    })
+}
+
+/// The native update fee taken from `funds`: everything sent in the fee's denom.
+fn update_fee_paid(config: &Config, funds: &[Coin]) -> Option<Coin> {
+    let fee = config.update_fee.as_ref()?;
+    funds.iter().find(|paid| paid.denom == fee.denom).cloned()
+}
+
+/// The error text, with score-derived values left out for queriers other than writers.
+fn describe_error(err: &ContractError, exact: bool) -> String {
+    if exact {
+        return err.to_string();
+    }
+    match err {
+        ContractError::InsufficientScore { amount, .. } => format!("Not enough spendable score to spend {} points", amount),
+        ContractError::ScoreTooLow { min_score, .. } => format!("A score of at least {} is needed to claim", min_score),
+        ContractError::DeltaTooSmall { min_delta, .. } => format!("Score change is below the minimum of {}", min_delta),
+        ContractError::EpochCapExceeded { .. } => "Gain exceeds the per-epoch cap".to_string(),
+        err => err.to_string(),
+    }
+}
@@ -0,0 +1,60 @@
+use cosmwasm_std::{coin, to_binary, Addr, BankMsg, Coin, Deps, StdResult, Uint128, WasmMsg};
+
+use crate::mirror::Cw20ExecuteMsg;
+use crate::msg::FeeRoutesResponse;
+use crate::state::{Config, CONFIG, STATE};
+
+pub const TOTAL_BPS: u16 = 10_000;
+
+/// Each route's share of `amount`, rounded down, with None for burning. Whatever is left,
+/// rounding dust included, goes to `remainder`.
+fn shares<'a>(config: &'a Config, remainder: &'a Addr, amount: Uint128) -> Vec<(Option<&'a Addr>, Uint128)> {
+    let mut left = amount;
+    let mut shares = vec![];
+    for route in &config.fee_routes {
+        let share = amount.multiply_ratio(route.bps, TOTAL_BPS);
+        if share.is_zero() {
+            continue;
+        }
+        left -= share;
+        shares.push((route.addr.as_ref(), share));
+    }
+    if !left.is_zero() {
+        shares.push((Some(remainder), left));
+    }
+    shares
+}
+
+/// Splits native funds the contract collects across Config::fee_routes.
+pub fn split_msgs(config: &Config, remainder: &Addr, funds: Coin) -> Vec<BankMsg> {
+    shares(config, remainder, funds.amount)
+        .into_iter()
+        .map(|(addr, share)| {
+            let amount = vec![coin(share.u128(), &funds.denom)];
+            match addr {
+                Some(addr) => BankMsg::Send { to_address: addr.to_string(), amount },
+                None => BankMsg::Burn { amount },
+            }
+        })
+        .collect()
+}
+
+/// Splits cw20 tokens the contract received the same way, with cw20 transfers and burns.
+pub fn split_cw20_msgs(config: &Config, remainder: &Addr, token: &Addr, amount: Uint128) -> StdResult<Vec<WasmMsg>> {
+    shares(config, remainder, amount)
+        .into_iter()
+        .map(|(addr, share)| {
+            let msg = match addr {
+                Some(addr) => Cw20ExecuteMsg::Transfer { recipient: addr.to_string(), amount: share },
+                None => Cw20ExecuteMsg::Burn { amount: share },
+            };
+            Ok(WasmMsg::Execute { contract_addr: token.to_string(), msg: to_binary(&msg)?, funds: vec![] })
+        })
+        .collect()
+}
+
+pub fn query_fee_routes(deps: Deps) -> StdResult<FeeRoutesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    Ok(FeeRoutesResponse { routes: config.fee_routes, remainder: state.owner })
+}
@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -9,5 +9,880 @@ pub struct State {
     pub owner: Addr
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Config {
+    // Exposes QueryMsg::RawRange; off by default since it dumps arbitrary storage
+    pub raw_queries: bool,
+    // Delete entries instead of storing zero scores
+    pub prune_zero_scores: bool,
+    // Updates moving a score by less than this are filtered; zero disables the filter
+    pub min_delta: u32,
+    pub small_delta_policy: SmallDeltaPolicy,
+    // Limit on how much a single user's score may rise per epoch
+    pub user_gain_cap: Option<EpochCap>,
+    // Limit on how much all scores together may rise per epoch, however many writers
+    pub global_gain_cap: Option<EpochCap>,
+    // Users are hidden unless they opt in with SetProfileVisibility. Hidden users are left
+    // off leaderboards, and reading their score needs a viewing key for them, the owner or
+    // an operator. This only gates queries; raw chain state stays readable.
+    pub private_scores: bool,
+    // Seconds after which a season rolls over on the next execute; zero means manual only
+    pub season_duration: u64,
+    // Blocks during which the owner can undo a score change; zero disables undo
+    pub rollback_window: u64,
+    // Contract receiving a copy of every execute message, e.g. the next version under test
+    pub shadow: Option<Addr>,
+    // Namespaces events and attributes so several instances can be told apart; empty for none
+    pub event_prefix: String,
+    // Lowest score any write may store; lower results are clamped up to it. Existing
+    // entries below a newly raised floor are clamped on their next write.
+    pub score_floor: u32,
+    // Contract asked `IsEligible { user }` before each score update, e.g. for KYC checks
+    pub gatekeeper: Option<Addr>,
+    // Seconds a penalized user has to appeal; zero makes penalties final right away
+    pub appeal_window: u64,
+    // Decides appeals instead of the owner, without any other admin rights
+    pub arbiter: Option<Addr>,
+    // Addresses that may never hold a score, e.g. the treasury or a burn address. The
+    // contract itself is always treated as reserved.
+    pub reserved_addresses: Vec<Addr>,
+    // Turns stored points into the score GetScore reports
+    pub score_formula: ScoreFormula,
+    // Fixed at instantiation: stored scores are in units of 10^-score_decimals, so that
+    // ordering and all integer arithmetic on them stay exact
+    pub score_decimals: u32,
+    // Where funds the contract collects go, e.g. treasury, dev fund and burn; the owner
+    // receives whatever the routes leave
+    pub fee_routes: Vec<FeeRoute>,
+    // Charged for each UpdateScore, attached as native funds
+    pub update_fee: Option<Coin>,
+    // Alternative fee in a cw20 token, paid by sending it with an embedded UpdateScore
+    pub cw20_update_fee: Option<Cw20Fee>,
+    // Updates above this multiple of the user's previous score or the mean score are
+    // written provisionally and freeze the user until reviewed; zero disables the guard
+    pub anomaly_multiple: u32,
+    // How MergeIdentities combines the scores of two linked addresses
+    pub merge_policy: MergePolicy,
+    // Blocks an idempotency key stays live after the message that used it
+    pub idempotency_ttl: u64,
+    // What a message repeating a live idempotency key does
+    pub duplicate_key_policy: DuplicateKeyPolicy,
+    // Seconds during which RestoreScore can undo a RemoveScore; zero makes removals final
+    pub removal_grace_period: u64,
+    // Public queries round scores to the nearest multiple of this; a viewing key for the
+    // user or the owner reads exact values. Zero or one shows exact scores to everyone.
+    pub score_bucket: u32,
+    // Largest `limit` a list query accepts; asking for more is an error
+    pub max_query_limit: u32,
+    // Entries a query may read while filtering before it fails, see limits::ScanBudget
+    pub max_query_scan: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20Fee {
+    pub token: Addr,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRoute {
+    // None burns the share
+    pub addr: Option<Addr>,
+    // Share in basis points of each collection
+    pub bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochCap {
+    pub amount: u32,
+    // Epochs are consecutive windows of this many seconds of block time
+    pub epoch_seconds: u64,
+    pub policy: CapPolicy,
+}
+
+impl EpochCap {
+    pub fn epoch(&self, time: Timestamp) -> u64 {
+        time.seconds() / self.epoch_seconds
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CapPolicy {
+    // Apply as much of the gain as the cap allows
+    Truncate,
+    // Fail the transaction with ContractError::EpochCapExceeded
+    Reject,
+}
+
+// Gains a user, or everyone together, accumulated in the most recent epoch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct EpochGain {
+    pub epoch: u64,
+    pub gained: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SmallDeltaPolicy {
+    // Fail the transaction with ContractError::DeltaTooSmall
+    Reject,
+    // Succeed without writing anything
+    Ignore,
+}
+
+impl Default for SmallDeltaPolicy {
+    fn default() -> Self {
+        SmallDeltaPolicy::Reject
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKeyPolicy {
+    // Fail the transaction with ContractError::DuplicateIdempotencyKey
+    Reject,
+    // Succeed without doing anything; attached funds are kept like any other fee
+    Ignore,
+}
+
+impl Default for DuplicateKeyPolicy {
+    fn default() -> Self {
+        DuplicateKeyPolicy::Reject
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IdempotencyRecord {
+    pub used_at: u64,
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    // Add both scores, saturating at u32::MAX
+    Sum,
+    // Take the higher of the two
+    Max,
+    // Keep the primary's score and drop the secondary's
+    KeepPrimary,
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        MergePolicy::Sum
+    }
+}
+
+impl MergePolicy {
+    pub fn merge(&self, primary: Option<u32>, secondary: Option<u32>) -> Option<u32> {
+        match (self, primary, secondary) {
+            (MergePolicy::KeepPrimary, primary, _) => primary,
+            (_, None, secondary) => secondary,
+            (_, primary, None) => primary,
+            (MergePolicy::Sum, Some(primary), Some(secondary)) => Some(primary.saturating_add(secondary)),
+            (MergePolicy::Max, Some(primary), Some(secondary)) => Some(primary.max(secondary)),
+        }
+    }
+}
+
+// Integer-only, so every node derives the same score
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreFormula {
+    // Report stored points as they are
+    Raw,
+    // Integer square root of the points
+    Sqrt,
+    // `scale` for every doubling of points, i.e. scale * floor(log2(points + 1))
+    Log2 { scale: u32 },
+    // Points * numerator / denominator, rounded down and capped at u32::MAX
+    Multiplier { numerator: u32, denominator: u32 },
+}
+
+impl Default for ScoreFormula {
+    fn default() -> Self {
+        ScoreFormula::Raw
+    }
+}
+
+impl ScoreFormula {
+    pub fn apply(&self, points: u32) -> u32 {
+        match *self {
+            ScoreFormula::Raw => points,
+            ScoreFormula::Sqrt => isqrt(points),
+            ScoreFormula::Log2 { scale } => scale.saturating_mul(63 - (u64::from(points) + 1).leading_zeros()),
+            ScoreFormula::Multiplier { numerator, denominator } => {
+                (u64::from(points) * u64::from(numerator) / u64::from(denominator.max(1))).min(u64::from(u32::MAX)) as u32
+            }
+        }
+    }
+}
+
+fn isqrt(n: u32) -> u32 {
+    // Newton's method from above; converges in a handful of steps for 32-bit inputs
+    let n = u64::from(n);
+    let mut x = n;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x as u32
+}
+
+// The first `k` keys of SCORE_INDEX, maintained on every score write so the podium can
+// be read without walking the index
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct TopK {
+    // Zero turns the set off
+    pub k: u32,
+    pub entries: Vec<(u32, u64, String)>,
+    // A RebuildTopK pass is under way; entries is a correct but possibly short prefix
+    pub rebuilding: bool,
+}
+
+// Mutations are blocked from `start` until `end`, as if paused
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MaintenanceWindow {
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+// Mutations are blocked, queries stay live; lifts on its own at `until` if set
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Freeze {
+    pub until: Option<Timestamp>,
+}
+
+// Game server key whose signatures admit SubmitSignedScore
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubmissionKey {
+    // SEC1 encoded secp256k1 public key
+    pub pubkey: Binary,
+    pub added_at: Timestamp,
+    // Set once a rotation replaces the key; it validates until then
+    pub retires_at: Option<Timestamp>,
+}
+
+// Sequence numbering of score changes, see journal::record_change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Journal {
+    pub enabled: bool,
+    // Last number handed out; kept while the journal is off so numbers are never reused
+    pub sequence: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JournalEntry {
+    pub user: String,
+    pub old: u32,
+    pub new: u32,
+    // Zero for entries journaled before heights were recorded
+    #[serde(default)]
+    pub height: u64,
+}
+
+// Who may send a message, from most to least restricted
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Owner,
+    // The owner or an operator
+    Operator,
+    // The owner or an admin of the board the message manages, see boards::is_board_admin
+    BoardAdmin,
+    // Anyone holding a live score, besides the owner and operators
+    AnyRegistered,
+    Anyone,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct OperatorActivity {
+    pub updates: u64,
+    pub points_issued: u64,
+    pub last_active_height: u64,
+}
+
+// Preset chosen at instantiation, deciding which subsystems run, see profiles::profile_features
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    // Everything on
+    Full,
+    Simple,
+    Tournament,
+    Reputation,
+}
+
+// A subsystem a profile can turn off
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    // StartNewSeason and timed rollovers
+    Seasons,
+    // Fees on score updates
+    Fees,
+    // The notifier and score hooks
+    Hooks,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Features {
+    pub profile: Profile,
+    pub enabled_features: Vec<Feature>,
+}
+
+// How much of the history logs to keep; an entry goes once either limit is passed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Retention {
+    pub max_entries: Option<u64>,
+    // In blocks
+    pub max_age: Option<u64>,
+}
+
+impl Retention {
+    /// Whether an entry `behind` places before the newest of its log, recorded at height
+    /// `recorded_at`, is past keeping at `height`.
+    pub fn expired(&self, behind: u64, recorded_at: u64, height: u64) -> bool {
+        self.max_entries.map_or(false, |max| behind >= max) || self.max_age.map_or(false, |max| recorded_at.saturating_add(max) < height)
+    }
+}
+
+// One change in the recent-deltas ring; `delta` is the new score minus the old one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScoreDelta {
+    pub seq: u64,
+    pub user: String,
+    pub delta: i64,
+    pub height: u64,
+}
+
+// Content hash of an off-chain export of an archived season, e.g. an IPFS CID
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SnapshotDigest {
+    pub digest: String,
+    // Block height it was recorded at
+    pub recorded_at: u64,
+}
+
+// A third party's signal about a user, separate from the official score
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Attestation {
+    pub value: i64,
+    // Block height of the latest Attest
+    pub height: u64,
+}
+
+// A named score range for matchmaking, holding at most `capacity` users in leaderboard order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Bracket {
+    pub name: String,
+    pub min_score: u32,
+    pub max_score: u32,
+    pub capacity: u32,
+}
+
+// Aggregates maintained on every score write
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Totals {
+    pub total_score: u64,
+    pub users: u64,
+    // Users on the all-time board
+    #[serde(default)]
+    pub all_time_users: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerChange {
+    pub id: u64,
+    // None for the entry recorded at instantiation
+    pub old: Option<Addr>,
+    pub new: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+// A requested wipe of every score, waiting for or undergoing confirmation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingReset {
+    pub token: String,
+    // Last block height at which the token can be confirmed
+    pub expires_at: u64,
+    // Once confirmed, further calls keep deleting batches until nothing is left
+    pub confirmed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Season {
+    pub id: u32,
+    pub start_height: u64,
+    pub start_time: Timestamp,
+    // Set when the next season starts
+    pub end_height: Option<u64>,
+    // Whether every score of an ended season has been moved to the archive
+    pub archived: bool,
+}
+
+// A season's totals at its rollover, kept for off-chain settlement
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochSummary {
+    pub season: u32,
+    pub start_height: u64,
+    pub end_height: u64,
+    pub end_time: Timestamp,
+    // Sum of the season's final scores
+    pub total_score: u64,
+    // Users who held a score when the season ended
+    pub users: u64,
+    // The best season::SUMMARY_TOP_N, in leaderboard order
+    pub top: Vec<SummaryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SummaryEntry {
+    pub user: String,
+    pub score: u32,
+}
+
+// An ended season whose scores are still being moved into the archive
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Archiving {
+    pub season: u32,
+    // Live entries achieved below this height still belong to the ended season
+    pub end_height: u64,
+    // Last user the archiver looked at
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArchivedScore {
+    pub score: u32,
+    pub achieved_at: u64,
+}
+
+// Something that happened to a user, as kept in their timeline
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEvent {
+    // Any change to the live score, whatever caused it
+    ScoreChanged { old: u32, new: u32 },
+    // See SPENDS for the memo
+    Spent { spend_id: u64, amount: u32 },
+    RewardsClaimed { amount: Coin },
+    // Points actually deducted, which the score floor may have cut short
+    Penalized { points: u32, reason: String },
+    AppealResolved { case_id: u64, upheld: bool },
+}
+
+// A score as held from the observation's time on, see twas::record_observation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScoreObservation {
+    pub score: u32,
+    // Score-seconds held before this observation
+    pub cumulative: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelineRecord {
+    pub height: u64,
+    pub event: TimelineEvent,
+}
+
+// Points a user spent with SpendScore
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Spend {
+    pub amount: u32,
+    pub memo: String,
+    pub height: u64,
+    // Contract called by SpendAndCall
+    #[serde(default)]
+    pub contract: Option<Addr>,
+}
+
+// Sum of a user's final scores in archived seasons plus their live score
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllTimeScore {
+    pub score: u32,
+    // Height the all-time score last changed at, for ordering ties
+    pub achieved_at: u64,
+}
+
+// An entry deleted by RemoveScore, kept until its grace period ends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemovedScore {
+    pub score: u32,
+    pub achieved_at: u64,
+    pub expires_at: Option<Timestamp>,
+    pub restorable_until: Timestamp,
+}
+
+// A user's entry as it was before their latest score change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PreviousScore {
+    // None when the change created the entry
+    pub score: Option<u32>,
+    pub achieved_at: u64,
+    pub expires_at: Option<Timestamp>,
+    // Height of the change this undoes
+    pub recorded_at: u64,
+}
+
+// Hash of a score batch to be revealed later, see ExecuteMsg::CommitScores
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScoreCommitment {
+    pub hash: Binary,
+    // Last block height at which the batch can be revealed
+    pub expires_at: u64,
+}
+
+// Other score contracts folded into QueryMsg::AggregatedScore
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AggregationConfig {
+    pub sources: Vec<ScoreSource>,
+    pub mode: CombineMode,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScoreSource {
+    pub addr: Addr,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Peer {
+    // The only address that may push the peer's scores here: the peer contract itself
+    // when on this chain, or the relayer forwarding its reports
+    pub address: Addr,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PeerScore {
+    pub score: u32,
+    pub synced_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CombineMode {
+    Sum,
+    Max,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MirrorConfig {
+    // Set once the token instantiation reply comes back, cleared when decoupled
+    pub token: Option<Addr>,
+    pub enabled: bool,
+}
+
+// An update flagged by the anomaly guard, with what it replaced so review can undo it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProvisionalUpdate {
+    pub score: u32,
+    // None when the user had no entry
+    pub previous: Option<u32>,
+    pub previous_achieved_at: u64,
+    pub flagged_at: u64,
+}
+
+// Points taken by a penalty, held in escrow while the user may appeal or the appeal is open
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Penalty {
+    pub points: u32,
+    pub reason: String,
+    pub applied_at: Timestamp,
+    pub appeal_deadline: Timestamp,
+    // Set once the user appeals
+    pub appeal: Option<Appeal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Appeal {
+    // Position in the arbiter's queue, in filing order
+    pub case_id: u64,
+    pub reason: String,
+    pub filed_at: Timestamp,
+}
+
+// One Merkle claim campaign; several may run side by side
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MerkleStage {
+    pub id: u32,
+    // Hex-encoded root over merkle::leaf_hash leaves
+    pub merkle_root: String,
+    pub denom: String,
+    // Funds attached when the stage was registered
+    pub total: Uint128,
+    pub claimed: Uint128,
+    // Claims are accepted up to and including this time
+    pub expires_at: Timestamp,
+    // Set once the unclaimed remainder went back to the owner
+    pub clawed_back: bool,
+}
+
+// Funds waiting to be claimed, see the rewards module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardPool {
+    // Fixed by the first FundPool
+    pub denom: Option<String>,
+    // Funded but not yet handed out by a checkpoint
+    pub unallocated: Uint128,
+    // Reward earned by one score point over all checkpoints and streaming so far
+    pub per_point: Decimal,
+    // Released from `unallocated` to current scores every second; zero for checkpoints only
+    pub stream_per_second: Uint128,
+    // Block time streaming was last accounted up to
+    pub last_accrual: Timestamp,
+}
+
+// Fixed payouts for users with at least `min_score`, see the faucet module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Faucet {
+    // Fixed by the first FundFaucet
+    pub denom: Option<String>,
+    // Funded and not yet claimed
+    pub balance: Uint128,
+    // Zero until the owner configures the faucet
+    pub amount_per_claim: Uint128,
+    pub min_score: u32,
+    // Seconds a user waits between claims
+    pub cooldown: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RebatePool {
+    // Fixed by the first FundRebatePool
+    pub denom: Option<String>,
+    // Funded and not yet paid out
+    pub balance: Uint128,
+    // Paid once per user; zero turns rebates off
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct FaucetClaim {
+    pub last_claimed: Option<Timestamp>,
+    pub claims: u32,
+    pub claimed: Uint128,
+}
+
+// A prize drawn among the top scores once reveal_height is reached, see raffle::draw
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Raffle {
+    pub id: u64,
+    pub prize: Coin,
+    pub reveal_height: u64,
+    // Height of the resolving block, once drawn
+    pub resolved_at: Option<u64>,
+    pub seed: Option<Binary>,
+    // None after a draw with no eligible entrant, when the prize went back to the owner
+    pub winner: Option<Addr>,
+    pub claimed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WagerStatus {
+    // Waiting for the opponent; only the proposer's stake is locked
+    Open,
+    // Both stakes locked, waiting for the result
+    Accepted,
+    // The winner took both stakes
+    Settled,
+    // Cancelled or timed out, with every locked stake returned
+    Refunded,
+}
+
+// Points two users locked against each other until an operator reports the winner.
+// Anything not accepted or reported by expires_at can be refunded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Wager {
+    pub id: u64,
+    pub proposer: Addr,
+    pub opponent: Addr,
+    // Points each side locks
+    pub stake: u32,
+    pub expires_at: Timestamp,
+    pub status: WagerStatus,
+    pub winner: Option<Addr>,
+}
+
+// A minted score certificate, as read when it was requested
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Certification {
+    pub token_id: String,
+    pub score: u32,
+    pub season: u32,
+    pub rank: u32,
+    pub height: u64,
+}
+
+// One published export of the top of the board, see the weights module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WeightTable {
+    // Counts publishes from 1
+    pub version: u64,
+    pub season: u32,
+    // Block the scores were read at
+    pub height: u64,
+    pub time: Timestamp,
+    // Sum of the entries' scores, which the weights are shares of
+    pub total_score: u64,
+    // In leaderboard order
+    pub entries: Vec<WeightEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WeightEntry {
+    pub user: String,
+    // As public queries show it
+    pub score: u32,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UserReward {
+    // RewardPool::per_point when the user was last settled
+    pub index: Decimal,
+    // Earned and not yet claimed
+    pub pending: Uint128,
+}
+
 pub const STATE: Item<State> = Item::new("state");
-pub const SCORES: Map<String, u32> = Map::new("scores");
\ No newline at end of file
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const PAUSED: Item<bool> = Item::new("paused");
+// Set once writes have moved to a successor contract
+pub const SUCCESSOR: Item<Addr> = Item::new("successor");
+// Scheduled maintenance, sorted by start and never overlapping
+pub const MAINTENANCE_WINDOWS: Item<Vec<MaintenanceWindow>> = Item::new("maintenance_windows");
+// Keys validating signed submissions, see signing::rotate_key
+pub const SUBMISSION_KEYS: Item<Vec<SubmissionKey>> = Item::new("submission_keys");
+// Last nonce used in each user's signed submissions
+pub const SUBMISSION_NONCES: Map<String, u64> = Map::new("submission_nonces");
+// Key the owner signs vouchers with, and the height each voucher id was redeemed at
+pub const VOUCHER_KEY: Item<Binary> = Item::new("voucher_key");
+pub const REDEEMED_VOUCHERS: Map<u64, u64> = Map::new("redeemed_vouchers");
+// Set while the leaderboard is frozen, e.g. for a prize verification window
+pub const FREEZE: Item<Freeze> = Item::new("freeze");
+pub const PENDING_RESET: Item<PendingReset> = Item::new("pending_reset");
+pub const SCORE_COMMITMENT: Item<ScoreCommitment> = Item::new("score_commitment");
+pub const COMPACT_CURSOR: Item<String> = Item::new("compact_cursor");
+pub const SCORES: Map<String, u32> = Map::new("scores");
+// Block height at which each user's current score was set, for tie-breaking
+pub const ACHIEVED_AT: Map<String, u64> = Map::new("achieved_at");
+// Leaderboard order over SCORES, see leaderboard::index_key
+pub const SCORE_INDEX: Map<(u32, u64, String), ()> = Map::new("score_index");
+// Index keys per inverted score, and per block of inverted scores, see ranks.rs
+pub const RANK_COUNTS: Map<u32, u32> = Map::new("rank_counts");
+pub const RANK_BLOCK_COUNTS: Map<u32, u32> = Map::new("rank_block_counts");
+// Last index key checked by an ongoing RebuildIndex sweep
+pub const REINDEX_CURSOR: Item<(u32, u64, String)> = Item::new("reindex_cursor");
+pub const TOP_K: Item<TopK> = Item::new("top_k");
+// Sorted by min_score, ranges never overlap
+pub const BRACKETS: Item<Vec<Bracket>> = Item::new("brackets");
+pub const CURRENT_SEASON: Item<u32> = Item::new("current_season");
+pub const ARCHIVING: Item<Archiving> = Item::new("archiving");
+pub const SEASONS: Map<u32, Season> = Map::new("seasons");
+pub const SEASON_SCORES: Map<(u32, String), ArchivedScore> = Map::new("season_scores");
+pub const SEASON_INDEX: Map<(u32, (u32, u64, String)), ()> = Map::new("season_index");
+// All-time scores by user, and the all-time board ordered like SCORE_INDEX
+pub const ALL_TIME_SCORES: Map<String, AllTimeScore> = Map::new("all_time_scores");
+pub const ALL_TIME_INDEX: Map<(u32, u64, String), ()> = Map::new("all_time_index");
+// (sender, key) of idempotency keys, and the same keys by expiry height for pruning
+pub const IDEMPOTENCY_KEYS: Map<(String, String), IdempotencyRecord> = Map::new("idempotency_keys");
+pub const IDEMPOTENCY_EXPIRY: Map<(u64, String, String), ()> = Map::new("idempotency_expiry");
+pub const JOURNAL: Item<Journal> = Item::new("journal");
+pub const JOURNAL_ENTRIES: Map<u64, JournalEntry> = Map::new("journal_entries");
+pub const RETENTION: Item<Retention> = Item::new("retention");
+// Next sequence number for the deltas ring, and the ring itself by sequence modulo its capacity
+pub const DELTA_HEAD: Item<u64> = Item::new("delta_head");
+// First sequence number not yet trimmed under the retention policy
+pub const DELTA_TAIL: Item<u64> = Item::new("delta_tail");
+pub const DELTAS: Map<u64, ScoreDelta> = Map::new("deltas");
+// (user, tag, attester); attesting again replaces the attester's earlier value
+pub const ATTESTATIONS: Map<(String, String, String), Attestation> = Map::new("attestations");
+// Addresses merged into another by MergeIdentities, to the address that absorbed them
+pub const MERGED: Map<String, String> = Map::new("merged");
+// By season id; recorded once, never replaced
+pub const SNAPSHOT_DIGESTS: Map<u32, SnapshotDigest> = Map::new("snapshot_digests");
+pub const EPOCH_SUMMARIES: Map<u32, EpochSummary> = Map::new("epoch_summaries");
+pub const PENALTIES: Map<String, Penalty> = Map::new("penalties");
+// Case id of each undecided appeal to the appealing user
+pub const OPEN_APPEALS: Map<u64, String> = Map::new("open_appeals");
+pub const NEXT_CASE_ID: Item<u64> = Item::new("next_case_id");
+// Users frozen by the anomaly guard until the owner reviews their update
+pub const PROVISIONAL: Map<String, ProvisionalUpdate> = Map::new("provisional");
+pub const PREVIOUS_SCORES: Map<String, PreviousScore> = Map::new("previous_scores");
+// Tombstones of removed entries, and the same users by end of grace period for purging
+pub const REMOVED_SCORES: Map<String, RemovedScore> = Map::new("removed_scores");
+pub const REMOVAL_QUEUE: Map<(u64, String), ()> = Map::new("removal_queue");
+pub const USER_EPOCH_GAINS: Map<String, EpochGain> = Map::new("user_epoch_gains");
+pub const GLOBAL_EPOCH_GAIN: Item<EpochGain> = Item::new("global_epoch_gain");
+// Optional expiry per score entry, plus a queue ordered by expiry time (nanos) for purging
+pub const EXPIRIES: Map<String, Timestamp> = Map::new("expiries");
+pub const EXPIRY_QUEUE: Map<(u64, String), ()> = Map::new("expiry_queue");
+pub const TOTALS: Item<Totals> = Item::new("totals");
+pub const MERKLE_STAGES: Map<u32, MerkleStage> = Map::new("merkle_stages");
+// Claimed flags per stage, 64 leaf indexes to an entry; see merkle::is_claimed
+pub const MERKLE_CLAIM_BITS: Map<(u32, u64), u64> = Map::new("merkle_claim_bits");
+pub const REWARD_POOL: Item<RewardPool> = Item::new("reward_pool");
+pub const USER_REWARDS: Map<String, UserReward> = Map::new("user_rewards");
+pub const FAUCET: Item<Faucet> = Item::new("faucet");
+// Contract told about every SpendScore, and each user's spends by per-user id
+pub const SHOP: Item<Addr> = Item::new("shop");
+// cw721 contract minting score certificates
+pub const CERTIFIER: Item<Addr> = Item::new("certifier");
+// (season, user) of every certificate minted
+pub const CERTIFICATIONS: Map<(u32, String), Certification> = Map::new("certifications");
+// Every published weight table by version
+pub const WEIGHT_TABLES: Map<u64, WeightTable> = Map::new("weight_tables");
+pub const SPENDS: Map<(String, u64), Spend> = Map::new("spends");
+// Each user's score changes, spends, reward claims and penalties by per-user id, never trimmed
+pub const TIMELINE: Map<(String, u64), TimelineRecord> = Map::new("timeline");
+// Each user's score changes by block time in seconds, for time-weighted averages
+pub const SCORE_OBSERVATIONS: Map<(String, u64), ScoreObservation> = Map::new("score_observations");
+pub const FAUCET_CLAIMS: Map<String, FaucetClaim> = Map::new("faucet_claims");
+// Owner-funded onboarding rebates, and the height each user got theirs, see rebate::take_rebate
+pub const REBATE_POOL: Item<RebatePool> = Item::new("rebate_pool");
+pub const REBATED: Map<String, u64> = Map::new("rebated");
+// Coins held by (denom, purpose), see the ledger module
+pub const LEDGER: Map<(String, String), Uint128> = Map::new("ledger");
+pub const RAFFLES: Map<u64, Raffle> = Map::new("raffles");
+pub const WAGERS: Map<u64, Wager> = Map::new("wagers");
+pub const NEXT_WAGER_ID: Item<u64> = Item::new("next_wager_id");
+pub const NEXT_RAFFLE_ID: Item<u64> = Item::new("next_raffle_id");
+pub const OWNER_HISTORY: Map<u64, OwnerChange> = Map::new("owner_history");
+pub const NOTIFIER: Item<Addr> = Item::new("notifier");
+// Further contracts notified of score changes, managed like cw-controllers Hooks
+pub const HOOKS: Item<Vec<Addr>> = Item::new("hooks");
+// May write scores besides the owner, see operators::is_writer
+pub const OPERATORS: Item<Vec<Addr>> = Item::new("operators");
+// Score writes by sender, kept after an operator is removed, see operators::record_activity
+pub const OPERATOR_ACTIVITY: Map<String, OperatorActivity> = Map::new("operator_activity");
+// Admins by board name, who manage that board besides the owner
+pub const BOARD_ADMINS: Map<String, Vec<Addr>> = Map::new("board_admins");
+// Case-folded substrings no user-supplied text may contain, see validation::validate_text
+pub const DENYLIST: Item<Vec<String>> = Item::new("denylist");
+// Role required by message name, where the owner has changed it from the default
+pub const POLICIES: Map<String, Role> = Map::new("policies");
+// Subsystems the instantiation profile left on; absent means all of them
+pub const FEATURES: Item<Features> = Item::new("features");
+pub const AGGREGATION: Item<AggregationConfig> = Item::new("aggregation");
+// Peer deployments by chain id, and the last score each reported per user, see federation
+pub const PEERS: Map<String, Peer> = Map::new("peers");
+pub const PEER_SCORES: Map<(String, String), PeerScore> = Map::new("peer_scores");
+// Explicit SetProfileVisibility choices; users without one follow Config::private_scores
+pub const PROFILE_VISIBILITY: Map<String, bool> = Map::new("profile_visibility");
+// Bech32 prefix of the chain a user's account came from, for display, see addr::addr_info
+pub const ADDR_ORIGINS: Map<String, String> = Map::new("addr_origins");
+// sha256 of each address's viewing key
+pub const VIEWING_KEYS: Map<String, Binary> = Map::new("viewing_keys");
+pub const MIRROR: Item<MirrorConfig> = Item::new("mirror");
+// Mirror burns dispatched by the current transaction, in order, until their replies arrive
+pub const PENDING_BURNS: Item<Vec<(String, Uint128)>> = Item::new("pending_burns");
+// Points taken off a score but still held as mirror tokens, because the burn failed;
+// settled against the holder's next mirror change
+pub const MIRROR_SHORTFALLS: Map<String, Uint128> = Map::new("mirror_shortfalls");
@@ -0,0 +1,32 @@
+use cosmwasm_std::{Deps, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::limits::page_limit;
+use crate::msg::{TimelineEntry, TimelineResponse};
+use crate::state::{TimelineEvent, TimelineRecord, CONFIG, TIMELINE};
+
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Appends to the user's timeline, numbering their entries from 1.
+pub fn record_timeline(storage: &mut dyn Storage, user: &str, height: u64, event: TimelineEvent) -> StdResult<()> {
+    let id = TIMELINE
+        .prefix(user.to_string())
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |last| last + 1);
+    TIMELINE.save(storage, (user.to_string(), id), &TimelineRecord { height, event })
+}
+
+/// The user's activity, oldest first.
+pub fn query_user_timeline(deps: Deps, user: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<TimelineResponse> {
+    let limit = page_limit(&CONFIG.load(deps.storage)?, limit, DEFAULT_LIMIT)?;
+    let start = start_after.map(Bound::exclusive);
+    let entries = TIMELINE
+        .prefix(user)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, record)| TimelineEntry { id, height: record.height, event: record.event }))
+        .collect::<StdResult<_>>()?;
+    Ok(TimelineResponse { entries })
+}
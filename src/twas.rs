@@ -0,0 +1,44 @@
+use cosmwasm_std::{Deps, Env, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::Bound;
+
+use crate::leaderboard::redact;
+use crate::msg::AverageScoreResponse;
+use crate::state::{ScoreObservation, CONFIG, SCORE_OBSERVATIONS};
+
+// Longest window AverageScore accepts, 90 days
+pub const MAX_AVERAGE_WINDOW: u64 = 90 * 24 * 60 * 60;
+
+/// Records that `user` holds `score` from `time` (in seconds) on, closing off the score
+/// held before it into the running score-seconds total.
+pub fn record_observation(storage: &mut dyn Storage, user: &str, score: u32, time: u64) -> StdResult<()> {
+    let cumulative = cumulative_at(storage, user, time)?;
+    SCORE_OBSERVATIONS.save(storage, (user.to_string(), time), &ScoreObservation { score, cumulative })
+}
+
+// Score-seconds the user held up to `time`; zero before their first score
+fn cumulative_at(storage: &dyn Storage, user: &str, time: u64) -> StdResult<Uint128> {
+    let last = SCORE_OBSERVATIONS
+        .prefix(user.to_string())
+        .range(storage, None, Some(Bound::inclusive(time)), Order::Descending)
+        .next()
+        .transpose()?;
+    Ok(match last {
+        Some((since, observation)) => observation.cumulative + Uint128::from(observation.score) * Uint128::from(time - since),
+        None => Uint128::zero(),
+    })
+}
+
+/// The user's score averaged over the last `window` seconds, each score weighted by how
+/// long it was held. Time before their first score counts as zero.
+pub fn query_average_score(deps: Deps, env: Env, user: String, window: u64, exact: bool) -> StdResult<AverageScoreResponse> {
+    if window == 0 || window > MAX_AVERAGE_WINDOW {
+        return Err(StdError::generic_err(format!("window must be 1 to {} seconds", MAX_AVERAGE_WINDOW)));
+    }
+    let now = env.block.time.seconds();
+    let from = now.saturating_sub(window);
+    let held = cumulative_at(deps.storage, &user, now)? - cumulative_at(deps.storage, &user, from)?;
+    let average = (held / Uint128::from(now - from).max(Uint128::new(1))).u128() as u32;
+    let config = CONFIG.load(deps.storage)?;
+    let score = if exact { average } else { redact(&config, average) };
+    Ok(AverageScoreResponse { score, window })
+}
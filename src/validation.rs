@@ -0,0 +1,98 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Deps, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::msg::ExecuteMsg;
+use crate::state::DENYLIST;
+
+// upper bounds on the owner's denylist
+pub const MAX_DENYLIST_ENTRIES: u32 = 50;
+pub const MAX_DENYLIST_ENTRY_LEN: u32 = 32;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenylistResponse {
+    pub substrings: Vec<String>,
+}
+
+// Characters that render as nothing or reorder what follows: zero-width joiners and
+// spaces, the byte order mark and bidi overrides. Let through, they can make one string
+// display as another in explorers and UIs.
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2060}'..='\u{2069}' | '\u{feff}')
+}
+
+// Denylist matching ignores case
+fn fold(value: &str) -> String {
+    value.to_lowercase()
+}
+
+/// Fails if `value` holds control or invisible characters, or any denylisted substring.
+/// Lengths are checked with the message, before anything is loaded.
+pub fn validate_text(denylist: &[String], field: &str, value: &str) -> Result<(), ContractError> {
+    if value.chars().any(|c| c.is_control() || is_invisible(c)) {
+        return Err(ContractError::InvalidText { field: field.to_string() });
+    }
+    let folded = fold(value);
+    if denylist.iter().any(|denied| folded.contains(denied.as_str())) {
+        return Err(ContractError::DeniedText { field: field.to_string() });
+    }
+    Ok(())
+}
+
+/// Checks every user-supplied string `msg` carries, e.g. penalty reasons, memos and tags.
+pub fn validate_msg_text(storage: &dyn Storage, msg: &ExecuteMsg) -> Result<(), ContractError> {
+    let fields = match msg {
+        ExecuteMsg::Penalize { reason, .. } | ExecuteMsg::Appeal { reason } => vec![("reason", reason)],
+        ExecuteMsg::SpendScore { memo, .. } => vec![("memo", memo)],
+        ExecuteMsg::Attest { tag, .. } => vec![("tag", tag)],
+        _ => return Ok(()),
+    };
+    let denylist = DENYLIST.may_load(storage)?.unwrap_or_default();
+    for (field, value) in fields {
+        validate_text(&denylist, field, value)?;
+    }
+    Ok(())
+}
+
+/// Removes `remove` from the denylist, then adds `add`. Entries are stored case-folded.
+pub fn update_denylist(storage: &mut dyn Storage, add: Vec<String>, remove: Vec<String>) -> Result<(), ContractError> {
+    let mut denylist = DENYLIST.may_load(storage)?.unwrap_or_default();
+    let remove: Vec<_> = remove.iter().map(|entry| fold(entry)).collect();
+    denylist.retain(|entry| !remove.contains(entry));
+    for entry in add {
+        if entry.is_empty() || entry.len() > MAX_DENYLIST_ENTRY_LEN as usize {
+            return Err(ContractError::InvalidDenylistEntry { max: MAX_DENYLIST_ENTRY_LEN });
+        }
+        validate_text(&[], "denylist", &entry)?;
+        let entry = fold(&entry);
+        if !denylist.contains(&entry) {
+            denylist.push(entry);
+        }
+    }
+    if denylist.len() as u32 > MAX_DENYLIST_ENTRIES {
+        return Err(ContractError::TooManyDenylistEntries { max: MAX_DENYLIST_ENTRIES });
+    }
+    Ok(DENYLIST.save(storage, &denylist)?)
+}
+
+pub fn query_denylist(deps: Deps) -> StdResult<DenylistResponse> {
+    Ok(DenylistResponse { substrings: DENYLIST.may_load(deps.storage)?.unwrap_or_default() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_text;
+
+    #[test]
+    fn rejects_hidden_and_denied_text() {
+        let denylist = vec!["scam".to_string()];
+        assert!(validate_text(&denylist, "memo", "sword of dawn").is_ok());
+        assert!(validate_text(&denylist, "memo", "line\nbreak").is_err());
+        assert!(validate_text(&denylist, "memo", "admin\u{202e}nimda").is_err());
+        assert!(validate_text(&denylist, "memo", "zero\u{200b}width").is_err());
+        assert!(validate_text(&denylist, "memo", "Free SCAM here").is_err());
+        assert!(validate_text(&[], "memo", "Free SCAM here").is_ok());
+    }
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::{to_vec, Binary, Deps, Env, StdResult, Storage};
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::{SignedVoucher, Voucher, VoucherStatusResponse};
+use crate::signing::is_pubkey;
+use crate::state::{REDEEMED_VOUCHERS, VOUCHER_KEY};
+
+/// Sets the key vouchers must be signed with; None stops redemptions. Vouchers signed
+/// with a replaced key can no longer be redeemed.
+pub fn set_voucher_key(storage: &mut dyn Storage, pubkey: Option<Binary>) -> Result<(), ContractError> {
+    match pubkey {
+        Some(pubkey) if !is_pubkey(&pubkey) => Err(ContractError::InvalidPubkey {}),
+        Some(pubkey) => Ok(VOUCHER_KEY.save(storage, &pubkey)?),
+        None => {
+            VOUCHER_KEY.remove(storage);
+            Ok(())
+        }
+    }
+}
+
+/// Checks that `voucher` is unexpired, not yet redeemed, and signed with the voucher key
+/// over the JSON of its SignedVoucher. The caller records the redemption.
+pub fn verify_voucher(deps: Deps, env: &Env, voucher: &Voucher, signature: &Binary) -> Result<(), ContractError> {
+    let pubkey = VOUCHER_KEY.may_load(deps.storage)?.ok_or(ContractError::VouchersNotConfigured {})?;
+    if env.block.time >= voucher.expires_at {
+        return Err(ContractError::VoucherExpired { expired_at: voucher.expires_at });
+    }
+    if let Some(height) = REDEEMED_VOUCHERS.may_load(deps.storage, voucher.id)? {
+        return Err(ContractError::VoucherRedeemed { id: voucher.id, height });
+    }
+    let payload = SignedVoucher { contract: env.contract.address.to_string(), voucher: voucher.clone() };
+    let hash = Sha256::digest(&to_vec(&payload)?);
+    if !deps.api.secp256k1_verify(&hash, signature, &pubkey).unwrap_or(false) {
+        return Err(ContractError::InvalidVoucherSignature {});
+    }
+    Ok(())
+}
+
+pub fn query_voucher_status(deps: Deps, id: u64) -> StdResult<VoucherStatusResponse> {
+    Ok(VoucherStatusResponse { id, redeemed_at: REDEEMED_VOUCHERS.may_load(deps.storage, id)? })
+}
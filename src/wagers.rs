@@ -0,0 +1,17 @@
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::error::ContractError;
+use crate::state::{Wager, WagerStatus, WAGERS};
+
+/// Fails unless the wager is in one of the `expected` states.
+pub fn assert_status(wager: &Wager, expected: &[WagerStatus]) -> Result<(), ContractError> {
+    if !expected.contains(&wager.status) {
+        let expected = expected.iter().map(|status| format!("{:?}", status).to_lowercase()).collect::<Vec<_>>().join(" or ");
+        return Err(ContractError::WagerNotInState { wager_id: wager.id, expected });
+    }
+    Ok(())
+}
+
+pub fn query_wager(deps: Deps, wager_id: u64) -> StdResult<Wager> {
+    WAGERS.load(deps.storage, wager_id)
+}
@@ -0,0 +1,84 @@
+use cosmwasm_std::{Decimal, Deps, Env, Order, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::leaderboard::{is_public, redact};
+use crate::msg::WeightTableResponse;
+use crate::state::{WeightEntry, WeightTable, ARCHIVING, CONFIG, CURRENT_SEASON, EXPIRIES, SCORE_INDEX, WEIGHT_TABLES};
+
+// Layout of WeightTable as consumers read it. Bumped on any change to its fields or to
+// how weights are computed, so partners can refuse a schema they don't know.
+pub const WEIGHT_TABLE_SCHEMA: u32 = 1;
+
+// Largest table PublishWeightTable writes
+pub const MAX_WEIGHT_TABLE_SIZE: u32 = 100;
+// Index keys a publish walks looking for public, unexpired scores; with more hidden users
+// than this at the top, the table comes out short
+const MAX_WEIGHT_TABLE_SCAN: usize = 1000;
+
+/// Snapshots the top `top_n` public, unexpired scores of the season board as the next
+/// version of the weight table. Weights are each score's share of the table's total, as
+/// public queries show the scores, rounded down; they add up to at most one.
+pub fn publish_weight_table(storage: &mut dyn Storage, env: &Env, top_n: u32) -> Result<WeightTable, ContractError> {
+    if top_n == 0 || top_n > MAX_WEIGHT_TABLE_SIZE {
+        return Err(ContractError::InvalidWeightTableSize { max: MAX_WEIGHT_TABLE_SIZE });
+    }
+    // Entries of a season still being archived are no longer on the board
+    if let Some(archiving) = ARCHIVING.may_load(storage)? {
+        return Err(ContractError::SeasonArchiving { season: archiving.season });
+    }
+
+    let config = CONFIG.load(storage)?;
+    let mut scores = vec![];
+    for key in SCORE_INDEX.keys(storage, None, None, Order::Ascending).take(MAX_WEIGHT_TABLE_SCAN) {
+        let (inverted, _, user) = key?;
+        let score = redact(&config, u32::MAX - inverted);
+        // Zero scores carry no weight, and every key after one is zero too
+        if score == 0 {
+            break;
+        }
+        let expired = EXPIRIES.may_load(storage, user.clone())?.map_or(false, |expires_at| expires_at <= env.block.time);
+        if expired || !is_public(storage, &config, &user)? {
+            continue;
+        }
+        scores.push((user, score));
+        if scores.len() == top_n as usize {
+            break;
+        }
+    }
+    let total: u64 = scores.iter().map(|(_, score)| u64::from(*score)).sum();
+    if total == 0 {
+        return Err(ContractError::EmptyWeightTable {});
+    }
+
+    let version = WEIGHT_TABLES
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |last| last + 1);
+    let table = WeightTable {
+        version,
+        season: CURRENT_SEASON.load(storage)?,
+        height: env.block.height,
+        time: env.block.time,
+        total_score: total,
+        entries: scores
+            .into_iter()
+            .map(|(user, score)| WeightEntry { weight: Decimal::from_ratio(u64::from(score), total), user, score })
+            .collect(),
+    };
+    WEIGHT_TABLES.save(storage, version, &table)?;
+    Ok(table)
+}
+
+/// A published weight table, the latest by default; None before the first publish.
+pub fn query_weight_table(deps: Deps, version: Option<u64>) -> StdResult<WeightTableResponse> {
+    let table = match version {
+        Some(version) => WEIGHT_TABLES.may_load(deps.storage, version)?,
+        None => WEIGHT_TABLES
+            .range(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+            .map(|(_, table)| table),
+    };
+    Ok(WeightTableResponse { schema: WEIGHT_TABLE_SCHEMA, table })
+}